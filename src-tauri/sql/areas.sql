@@ -3,9 +3,12 @@ CREATE TABLE IF NOT EXISTS areas (
     user_id TEXT NOT NULL,
     name TEXT NOT NULL,
     image_url TEXT,
+    archived_at INTEGER, -- NULL unless archived
+    deleted_at INTEGER, -- NULL unless in the trash
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
 );
 CREATE INDEX IF NOT EXISTS idx_areas_user_id ON areas(user_id);
+CREATE INDEX IF NOT EXISTS idx_areas_deleted ON areas(deleted_at) WHERE deleted_at IS NOT NULL;
 
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS event_exceptions (
+    event_id TEXT NOT NULL,
+    occurrence_start INTEGER NOT NULL, -- Start the occurrence would have had
+    PRIMARY KEY (event_id, occurrence_start),
+    FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS event_overrides (
+    event_id TEXT NOT NULL,
+    occurrence_start INTEGER NOT NULL, -- Start the occurrence would have had
+    title TEXT NOT NULL,
+    description TEXT,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER,
+    location TEXT,
+    all_day INTEGER DEFAULT 0, -- 0 = false, 1 = true
+    time_zone TEXT, -- IANA zone of start/end, NULL for floating all-day times
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (event_id, occurrence_start),
+    FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_event_overrides_start_time ON event_overrides(start_time);
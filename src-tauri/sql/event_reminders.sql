@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS event_reminders (
+    event_id TEXT NOT NULL,
+    minutes_before INTEGER NOT NULL,
+    PRIMARY KEY (event_id, minutes_before),
+    FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+);
@@ -8,12 +8,19 @@ CREATE TABLE IF NOT EXISTS events (
     end_time INTEGER,
     location TEXT,
     all_day INTEGER DEFAULT 0, -- 0 = false, 1 = true
+    time_zone TEXT, -- IANA zone of start/end, NULL for floating all-day times
+    recurrence_rule TEXT, -- RFC 5545 RRULE value, NULL for one-off events
+    recurrence_end INTEGER, -- Latest possible occurrence start, NULL if open-ended
+    ical_uid TEXT, -- UID of the imported VEVENT, NULL for events created here
+    deleted_at INTEGER, -- NULL unless in the trash
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
     FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE SET NULL
 );
-CREATE INDEX IF NOT EXISTS idx_events_user_id ON events(user_id);
 CREATE INDEX IF NOT EXISTS idx_events_project_id ON events(project_id);
-CREATE INDEX IF NOT EXISTS idx_events_start_time ON events(start_time);
-
+CREATE INDEX IF NOT EXISTS idx_events_user_start ON events(user_id, start_time);
+CREATE INDEX IF NOT EXISTS idx_events_user_project_start ON events(user_id, project_id, start_time);
+CREATE INDEX IF NOT EXISTS idx_events_user_recurring ON events(user_id, recurrence_rule);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_user_ical_uid ON events(user_id, ical_uid);
+CREATE INDEX IF NOT EXISTS idx_events_deleted ON events(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,17 @@
+-- Foreign keys were not enforced before this version, so deleting an area or project
+-- left its children behind. Remove rows whose parent is gone.
+
+DELETE FROM sessions WHERE user_id NOT IN (SELECT id FROM users);
+DELETE FROM areas WHERE user_id NOT IN (SELECT id FROM users);
+DELETE FROM projects
+WHERE user_id NOT IN (SELECT id FROM users)
+   OR area_id NOT IN (SELECT id FROM areas);
+DELETE FROM resources
+WHERE user_id NOT IN (SELECT id FROM users)
+   OR project_id NOT IN (SELECT id FROM projects);
+DELETE FROM events WHERE user_id NOT IN (SELECT id FROM users);
+
+-- Events outlive their project (ON DELETE SET NULL)
+UPDATE events SET project_id = NULL
+WHERE project_id IS NOT NULL
+  AND project_id NOT IN (SELECT id FROM projects);
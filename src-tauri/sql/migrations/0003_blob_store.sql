@@ -0,0 +1,36 @@
+-- Content-addressed storage for resource files, replacing base64 resources.file_data
+
+CREATE TABLE IF NOT EXISTS blobs (
+    hash TEXT PRIMARY KEY, -- Hex encoded SHA-256 of data
+    data BLOB NOT NULL,
+    size INTEGER NOT NULL, -- Size in bytes
+    ref_count INTEGER NOT NULL DEFAULT 0, -- Number of resources pointing at this blob
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_blobs_ref_count ON blobs(ref_count);
+
+ALTER TABLE resources ADD COLUMN blob_hash TEXT REFERENCES blobs(hash);
+CREATE INDEX IF NOT EXISTS idx_resources_blob_hash ON resources(blob_hash);
+
+-- Keep ref_count in step with resources.blob_hash, including cascaded deletes
+CREATE TRIGGER IF NOT EXISTS trg_resources_blob_insert
+AFTER INSERT ON resources
+WHEN NEW.blob_hash IS NOT NULL
+BEGIN
+    UPDATE blobs SET ref_count = ref_count + 1 WHERE hash = NEW.blob_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_blob_update
+AFTER UPDATE OF blob_hash ON resources
+WHEN OLD.blob_hash IS NOT NEW.blob_hash
+BEGIN
+    UPDATE blobs SET ref_count = ref_count - 1 WHERE hash = OLD.blob_hash;
+    UPDATE blobs SET ref_count = ref_count + 1 WHERE hash = NEW.blob_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_blob_delete
+AFTER DELETE ON resources
+WHEN OLD.blob_hash IS NOT NULL
+BEGIN
+    UPDATE blobs SET ref_count = ref_count - 1 WHERE hash = OLD.blob_hash;
+END;
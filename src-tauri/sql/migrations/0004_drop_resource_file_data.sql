@@ -0,0 +1,2 @@
+-- File bodies now live in blobs; migration 3 copied every file_data value across
+ALTER TABLE resources DROP COLUMN file_data;
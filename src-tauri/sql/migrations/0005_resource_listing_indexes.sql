@@ -0,0 +1,3 @@
+-- Support sorted, paged resource listings per user
+CREATE INDEX IF NOT EXISTS idx_resources_user_created ON resources(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_resources_user_updated ON resources(user_id, updated_at);
@@ -0,0 +1,31 @@
+-- Recurring events: an RRULE on the series row, plus cancelled and edited occurrences
+ALTER TABLE events ADD COLUMN recurrence_rule TEXT;
+-- Latest possible occurrence start; NULL for one-off events and series without an end
+ALTER TABLE events ADD COLUMN recurrence_end INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_events_user_recurring ON events(user_id, recurrence_rule);
+
+-- EXDATEs: occurrences of a series that have been removed
+CREATE TABLE IF NOT EXISTS event_exceptions (
+    event_id TEXT NOT NULL,
+    occurrence_start INTEGER NOT NULL, -- Start the occurrence would have had
+    PRIMARY KEY (event_id, occurrence_start),
+    FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+);
+
+-- Single occurrences edited independently of their series
+CREATE TABLE IF NOT EXISTS event_overrides (
+    event_id TEXT NOT NULL,
+    occurrence_start INTEGER NOT NULL, -- Start the occurrence would have had
+    title TEXT NOT NULL,
+    description TEXT,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER,
+    location TEXT,
+    all_day INTEGER DEFAULT 0, -- 0 = false, 1 = true
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (event_id, occurrence_start),
+    FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_event_overrides_start_time ON event_overrides(start_time);
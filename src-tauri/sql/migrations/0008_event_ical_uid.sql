@@ -0,0 +1,4 @@
+-- UID of the iCalendar VEVENT an event was imported from, so re-imports update it in place
+ALTER TABLE events ADD COLUMN ical_uid TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_user_ical_uid ON events(user_id, ical_uid);
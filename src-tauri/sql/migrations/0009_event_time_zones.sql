@@ -0,0 +1,6 @@
+-- Time zones: timed events keep the IANA zone they were scheduled in, all-day events
+-- store floating wall-clock times (time_zone NULL), and each user has a display zone.
+-- The backfill pins existing rows to the machine's zone.
+ALTER TABLE events ADD COLUMN time_zone TEXT;
+ALTER TABLE event_overrides ADD COLUMN time_zone TEXT;
+ALTER TABLE users ADD COLUMN time_zone TEXT;
@@ -0,0 +1,6 @@
+-- Window queries scan one user's events by start time, optionally within some projects.
+-- The composite indexes cover the single-column ones on user_id and start_time.
+CREATE INDEX IF NOT EXISTS idx_events_user_start ON events(user_id, start_time);
+CREATE INDEX IF NOT EXISTS idx_events_user_project_start ON events(user_id, project_id, start_time);
+DROP INDEX IF EXISTS idx_events_user_id;
+DROP INDEX IF EXISTS idx_events_start_time;
@@ -0,0 +1,13 @@
+-- Archive: areas and projects can be set aside without deleting them. An archived area
+-- hides its projects, and archived projects hide their resources and events, from the
+-- default listings. completed_at records when a project was last moved to Done, for
+-- users who have projects archived automatically after auto_archive_days.
+ALTER TABLE areas ADD COLUMN archived_at INTEGER;
+ALTER TABLE projects ADD COLUMN archived_at INTEGER;
+ALTER TABLE projects ADD COLUMN completed_at INTEGER;
+ALTER TABLE users ADD COLUMN auto_archive_days INTEGER;
+
+-- Best guess for projects that were already done
+UPDATE projects SET completed_at = updated_at WHERE status = 'Done';
+
+CREATE INDEX IF NOT EXISTS idx_projects_completed ON projects(status, completed_at);
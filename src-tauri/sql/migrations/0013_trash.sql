@@ -0,0 +1,13 @@
+-- Trash: deleting an area, project, resource or event only stamps deleted_at. Trashed
+-- rows, and everything under a trashed area or project, are hidden until restored, then
+-- removed for good once older than the owner's trash_retention_days (30 when NULL).
+ALTER TABLE areas ADD COLUMN deleted_at INTEGER;
+ALTER TABLE projects ADD COLUMN deleted_at INTEGER;
+ALTER TABLE resources ADD COLUMN deleted_at INTEGER;
+ALTER TABLE events ADD COLUMN deleted_at INTEGER;
+ALTER TABLE users ADD COLUMN trash_retention_days INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_areas_deleted ON areas(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_projects_deleted ON projects(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_resources_deleted ON resources(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_events_deleted ON events(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,42 @@
+-- Tags a user can put on any of their areas, projects, resources and events, across the
+-- area and project hierarchy. Names are unique per user regardless of case.
+CREATE TABLE IF NOT EXISTS tags (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    color TEXT, -- Hex color such as #f59e0b, NULL for the default
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tags_user_name ON tags(user_id, name COLLATE NOCASE);
+
+CREATE TABLE IF NOT EXISTS tag_assignments (
+    tag_id TEXT NOT NULL,
+    kind TEXT NOT NULL, -- 'area', 'project', 'resource' or 'event'
+    item_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (tag_id, kind, item_id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_tag_assignments_item ON tag_assignments(kind, item_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_area_tag_assignments_delete AFTER DELETE ON areas
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'area' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_project_tag_assignments_delete AFTER DELETE ON projects
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'project' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resource_tag_assignments_delete AFTER DELETE ON resources
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'resource' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_event_tag_assignments_delete AFTER DELETE ON events
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'event' AND item_id = OLD.id;
+END;
@@ -0,0 +1,34 @@
+-- [[Links]] written in resource documents, pointing at an area, project, resource or event
+-- by its title. target_id is NULL while the user has nothing by that title; such a link
+-- counts for whatever later takes the title.
+CREATE TABLE IF NOT EXISTS resource_links (
+    source_id TEXT NOT NULL, -- Resource whose document has the link
+    target_kind TEXT NOT NULL, -- 'area', 'project', 'resource' or 'event'
+    target_title TEXT NOT NULL COLLATE NOCASE, -- Title as written in the link
+    target_id TEXT,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (source_id, target_kind, target_title),
+    FOREIGN KEY (source_id) REFERENCES resources(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_resource_links_target ON resource_links(target_kind, target_id);
+
+-- A purged target leaves its links dangling rather than removing them from the documents
+CREATE TRIGGER IF NOT EXISTS trg_area_resource_links_delete AFTER DELETE ON areas
+BEGIN
+    UPDATE resource_links SET target_id = NULL WHERE target_kind = 'area' AND target_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_project_resource_links_delete AFTER DELETE ON projects
+BEGIN
+    UPDATE resource_links SET target_id = NULL WHERE target_kind = 'project' AND target_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resource_resource_links_delete AFTER DELETE ON resources
+BEGIN
+    UPDATE resource_links SET target_id = NULL WHERE target_kind = 'resource' AND target_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_event_resource_links_delete AFTER DELETE ON events
+BEGIN
+    UPDATE resource_links SET target_id = NULL WHERE target_kind = 'event' AND target_id = OLD.id;
+END;
@@ -0,0 +1,20 @@
+-- Notes with their own Inbox -> Draft -> Final workflow. A note may belong to a project or
+-- an area, or to neither and wait in the inbox.
+CREATE TABLE IF NOT EXISTS notes (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    project_id TEXT, -- At most one of project_id and area_id is set
+    area_id TEXT,
+    title TEXT NOT NULL,
+    description TEXT,
+    content TEXT,
+    status TEXT NOT NULL DEFAULT 'Inbox',
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (area_id) REFERENCES areas(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_notes_user_status ON notes(user_id, status);
+CREATE INDEX IF NOT EXISTS idx_notes_project ON notes(project_id);
+CREATE INDEX IF NOT EXISTS idx_notes_area ON notes(area_id);
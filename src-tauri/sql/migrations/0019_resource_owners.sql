@@ -0,0 +1,99 @@
+-- Resources may belong to a project, an area, or neither. SQLite cannot relax NOT NULL in
+-- place, so the table is rebuilt and its indexes and triggers recreated; foreign keys are
+-- off while migrations run.
+CREATE TABLE resources_new (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    project_id TEXT, -- At most one of project_id and area_id is set
+    area_id TEXT,
+    name TEXT NOT NULL,
+    content TEXT, -- For text documents
+    file_type TEXT, -- MIME type or file extension
+    file_size INTEGER, -- Size in bytes
+    blob_hash TEXT, -- File contents, see blobs.sql
+    deleted_at INTEGER, -- NULL unless in the trash
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (area_id) REFERENCES areas(id) ON DELETE CASCADE,
+    FOREIGN KEY (blob_hash) REFERENCES blobs(hash),
+    CHECK (project_id IS NULL OR area_id IS NULL)
+);
+
+INSERT INTO resources_new (id, user_id, project_id, area_id, name, content, file_type, file_size, blob_hash, deleted_at, created_at, updated_at)
+SELECT id, user_id, project_id, NULL, name, content, file_type, file_size, blob_hash, deleted_at, created_at, updated_at
+FROM resources;
+
+DROP TABLE resources;
+ALTER TABLE resources_new RENAME TO resources;
+
+CREATE INDEX IF NOT EXISTS idx_resources_user_id ON resources(user_id);
+CREATE INDEX IF NOT EXISTS idx_resources_project_id ON resources(project_id);
+CREATE INDEX IF NOT EXISTS idx_resources_area_id ON resources(area_id);
+CREATE INDEX IF NOT EXISTS idx_resources_blob_hash ON resources(blob_hash);
+CREATE INDEX IF NOT EXISTS idx_resources_user_created ON resources(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_resources_user_updated ON resources(user_id, updated_at);
+CREATE INDEX IF NOT EXISTS idx_resources_deleted ON resources(deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_blob_insert
+AFTER INSERT ON resources
+WHEN NEW.blob_hash IS NOT NULL
+BEGIN
+    UPDATE blobs SET ref_count = ref_count + 1 WHERE hash = NEW.blob_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_blob_update
+AFTER UPDATE OF blob_hash ON resources
+WHEN OLD.blob_hash IS NOT NEW.blob_hash
+BEGIN
+    UPDATE blobs SET ref_count = ref_count - 1 WHERE hash = OLD.blob_hash;
+    UPDATE blobs SET ref_count = ref_count + 1 WHERE hash = NEW.blob_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_blob_delete
+AFTER DELETE ON resources
+WHEN OLD.blob_hash IS NOT NULL
+BEGIN
+    UPDATE blobs SET ref_count = ref_count - 1 WHERE hash = OLD.blob_hash;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_fts_insert AFTER INSERT ON resources
+BEGIN
+    INSERT INTO resources_fts (id, user_id, name, content)
+    VALUES (
+        NEW.id, NEW.user_id, NEW.name,
+        CASE WHEN json_valid(NEW.content)
+            THEN (SELECT group_concat(value, ' ') FROM json_tree(NEW.content) WHERE key = 'text')
+            ELSE NEW.content
+        END
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_fts_update AFTER UPDATE OF name, content ON resources
+BEGIN
+    DELETE FROM resources_fts WHERE id = OLD.id;
+    INSERT INTO resources_fts (id, user_id, name, content)
+    VALUES (
+        NEW.id, NEW.user_id, NEW.name,
+        CASE WHEN json_valid(NEW.content)
+            THEN (SELECT group_concat(value, ' ') FROM json_tree(NEW.content) WHERE key = 'text')
+            ELSE NEW.content
+        END
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_fts_delete AFTER DELETE ON resources
+BEGIN
+    DELETE FROM resources_fts WHERE id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resource_tag_assignments_delete AFTER DELETE ON resources
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'resource' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resource_resource_links_delete AFTER DELETE ON resources
+BEGIN
+    UPDATE resource_links SET target_id = NULL WHERE target_kind = 'resource' AND target_id = OLD.id;
+END;
@@ -0,0 +1,14 @@
+-- Order of projects within their board column, an area and status. Positions are REAL so
+-- a card can be dropped between two others without renumbering the column. Existing
+-- projects keep the newest-first order they were shown in.
+ALTER TABLE projects ADD COLUMN position REAL NOT NULL DEFAULT 0;
+
+UPDATE projects SET position = (
+    SELECT ranked.rn * 1024.0 FROM (
+        SELECT id, ROW_NUMBER() OVER (PARTITION BY area_id, status ORDER BY created_at DESC, id) AS rn
+        FROM projects
+    ) ranked
+    WHERE ranked.id = projects.id
+);
+
+CREATE INDEX IF NOT EXISTS idx_projects_column ON projects(area_id, status, position);
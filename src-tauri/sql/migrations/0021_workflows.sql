@@ -0,0 +1,35 @@
+-- Project statuses come from workflows instead of a fixed list. A user may define their own
+-- workflow and override it per area; without one the built-in Inbox, Planned, Progress,
+-- Done applies, so existing projects need no changes.
+CREATE TABLE IF NOT EXISTS workflows (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    area_id TEXT, -- NULL for the user's own workflow, else the area's override
+    name TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (area_id) REFERENCES areas(id) ON DELETE CASCADE
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflows_user ON workflows(user_id) WHERE area_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflows_area ON workflows(area_id) WHERE area_id IS NOT NULL;
+
+-- Board columns of a workflow, in order. Projects refer to them by name.
+CREATE TABLE IF NOT EXISTS workflow_statuses (
+    workflow_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    done INTEGER NOT NULL DEFAULT 0, -- 1 for finished statuses: completed_at, auto-archive, no reminders
+    PRIMARY KEY (workflow_id, name),
+    FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+);
+
+-- Moves allowed between statuses. A workflow without any allows every move.
+CREATE TABLE IF NOT EXISTS workflow_transitions (
+    workflow_id TEXT NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    PRIMARY KEY (workflow_id, from_status, to_status),
+    FOREIGN KEY (workflow_id, from_status) REFERENCES workflow_statuses(workflow_id, name) ON DELETE CASCADE,
+    FOREIGN KEY (workflow_id, to_status) REFERENCES workflow_statuses(workflow_id, name) ON DELETE CASCADE
+);
@@ -8,6 +8,10 @@ CREATE TABLE IF NOT EXISTS projects (
     priority TEXT,
     start_date INTEGER,
     end_date INTEGER,
+    archived_at INTEGER, -- NULL unless archived
+    completed_at INTEGER, -- When last moved to Done
+    position REAL NOT NULL DEFAULT 0, -- Order within the area and status board column
+    deleted_at INTEGER, -- NULL unless in the trash
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
@@ -16,4 +20,7 @@ CREATE TABLE IF NOT EXISTS projects (
 CREATE INDEX IF NOT EXISTS idx_projects_user_id ON projects(user_id);
 CREATE INDEX IF NOT EXISTS idx_projects_area_id ON projects(area_id);
 CREATE INDEX IF NOT EXISTS idx_projects_status ON projects(status);
+CREATE INDEX IF NOT EXISTS idx_projects_completed ON projects(status, completed_at);
+CREATE INDEX IF NOT EXISTS idx_projects_column ON projects(area_id, status, position);
+CREATE INDEX IF NOT EXISTS idx_projects_deleted ON projects(deleted_at) WHERE deleted_at IS NOT NULL;
 
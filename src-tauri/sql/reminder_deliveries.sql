@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS reminder_deliveries (
+    user_id TEXT NOT NULL,
+    kind TEXT NOT NULL, -- 'event' or 'project'
+    source_id TEXT NOT NULL,
+    occurrence_start INTEGER NOT NULL, -- Start of the occurrence, or the project's end date
+    minutes_before INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    status TEXT NOT NULL, -- 'fired', 'snoozed' or 'dismissed'
+    due_at INTEGER NOT NULL, -- When it was last shown, or is next to be shown if snoozed
+    PRIMARY KEY (kind, source_id, occurrence_start, minutes_before),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_reminder_deliveries_user_status ON reminder_deliveries(user_id, status);
+CREATE INDEX IF NOT EXISTS idx_reminder_deliveries_status_due ON reminder_deliveries(status, due_at);
+
+CREATE TRIGGER IF NOT EXISTS trg_event_reminder_deliveries_delete AFTER DELETE ON events
+BEGIN
+    DELETE FROM reminder_deliveries WHERE kind = 'event' AND source_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_project_reminder_deliveries_delete AFTER DELETE ON projects
+BEGIN
+    DELETE FROM reminder_deliveries WHERE kind = 'project' AND source_id = OLD.id;
+END;
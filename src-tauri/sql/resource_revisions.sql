@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS resource_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT, -- Larger for newer revisions
+    resource_id TEXT NOT NULL,
+    name TEXT NOT NULL, -- Name of the resource at the time
+    content TEXT NOT NULL,
+    content_size INTEGER NOT NULL, -- Size of content in bytes
+    saved_at INTEGER NOT NULL, -- When this content was saved
+    created_at INTEGER NOT NULL, -- When it was replaced
+    FOREIGN KEY (resource_id) REFERENCES resources(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_resource_revisions_resource ON resource_revisions(resource_id, id);
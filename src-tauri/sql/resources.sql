@@ -1,17 +1,26 @@
 CREATE TABLE IF NOT EXISTS resources (
     id TEXT PRIMARY KEY,
     user_id TEXT NOT NULL,
-    project_id TEXT NOT NULL,
+    project_id TEXT, -- At most one of project_id and area_id is set
+    area_id TEXT,
     name TEXT NOT NULL,
     content TEXT, -- For text documents
-    file_data TEXT, -- Base64 encoded file data
     file_type TEXT, -- MIME type or file extension
     file_size INTEGER, -- Size in bytes
+    blob_hash TEXT, -- File contents, see blobs.sql
+    deleted_at INTEGER, -- NULL unless in the trash
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
-    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (area_id) REFERENCES areas(id) ON DELETE CASCADE,
+    FOREIGN KEY (blob_hash) REFERENCES blobs(hash),
+    CHECK (project_id IS NULL OR area_id IS NULL)
 );
 CREATE INDEX IF NOT EXISTS idx_resources_user_id ON resources(user_id);
 CREATE INDEX IF NOT EXISTS idx_resources_project_id ON resources(project_id);
-
+CREATE INDEX IF NOT EXISTS idx_resources_area_id ON resources(area_id);
+CREATE INDEX IF NOT EXISTS idx_resources_blob_hash ON resources(blob_hash);
+CREATE INDEX IF NOT EXISTS idx_resources_user_created ON resources(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_resources_user_updated ON resources(user_id, updated_at);
+CREATE INDEX IF NOT EXISTS idx_resources_deleted ON resources(deleted_at) WHERE deleted_at IS NOT NULL;
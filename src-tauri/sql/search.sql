@@ -0,0 +1,120 @@
+-- Full-text indexes. Each keeps its own copy of the searchable text, written by triggers.
+-- Resource content is TipTap JSON, so only its "text" nodes are indexed.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS resources_fts USING fts5(
+    id UNINDEXED,
+    user_id UNINDEXED,
+    name,
+    content,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS projects_fts USING fts5(
+    id UNINDEXED,
+    user_id UNINDEXED,
+    title,
+    description,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS areas_fts USING fts5(
+    id UNINDEXED,
+    user_id UNINDEXED,
+    name,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS events_fts USING fts5(
+    id UNINDEXED,
+    user_id UNINDEXED,
+    title,
+    description,
+    location,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_fts_insert AFTER INSERT ON resources
+BEGIN
+    INSERT INTO resources_fts (id, user_id, name, content)
+    VALUES (
+        NEW.id, NEW.user_id, NEW.name,
+        CASE WHEN json_valid(NEW.content)
+            THEN (SELECT group_concat(value, ' ') FROM json_tree(NEW.content) WHERE key = 'text')
+            ELSE NEW.content
+        END
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_fts_update AFTER UPDATE OF name, content ON resources
+BEGIN
+    DELETE FROM resources_fts WHERE id = OLD.id;
+    INSERT INTO resources_fts (id, user_id, name, content)
+    VALUES (
+        NEW.id, NEW.user_id, NEW.name,
+        CASE WHEN json_valid(NEW.content)
+            THEN (SELECT group_concat(value, ' ') FROM json_tree(NEW.content) WHERE key = 'text')
+            ELSE NEW.content
+        END
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resources_fts_delete AFTER DELETE ON resources
+BEGIN
+    DELETE FROM resources_fts WHERE id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_projects_fts_insert AFTER INSERT ON projects
+BEGIN
+    INSERT INTO projects_fts (id, user_id, title, description)
+    VALUES (NEW.id, NEW.user_id, NEW.title, NEW.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_projects_fts_update AFTER UPDATE OF title, description ON projects
+BEGIN
+    DELETE FROM projects_fts WHERE id = OLD.id;
+    INSERT INTO projects_fts (id, user_id, title, description)
+    VALUES (NEW.id, NEW.user_id, NEW.title, NEW.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_projects_fts_delete AFTER DELETE ON projects
+BEGIN
+    DELETE FROM projects_fts WHERE id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_areas_fts_insert AFTER INSERT ON areas
+BEGIN
+    INSERT INTO areas_fts (id, user_id, name) VALUES (NEW.id, NEW.user_id, NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_areas_fts_update AFTER UPDATE OF name ON areas
+BEGIN
+    DELETE FROM areas_fts WHERE id = OLD.id;
+    INSERT INTO areas_fts (id, user_id, name) VALUES (NEW.id, NEW.user_id, NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_areas_fts_delete AFTER DELETE ON areas
+BEGIN
+    DELETE FROM areas_fts WHERE id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_events_fts_insert AFTER INSERT ON events
+BEGIN
+    INSERT INTO events_fts (id, user_id, title, description, location)
+    VALUES (NEW.id, NEW.user_id, NEW.title, NEW.description, NEW.location);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_events_fts_update AFTER UPDATE OF title, description, location ON events
+BEGIN
+    DELETE FROM events_fts WHERE id = OLD.id;
+    INSERT INTO events_fts (id, user_id, title, description, location)
+    VALUES (NEW.id, NEW.user_id, NEW.title, NEW.description, NEW.location);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_events_fts_delete AFTER DELETE ON events
+BEGIN
+    DELETE FROM events_fts WHERE id = OLD.id;
+END;
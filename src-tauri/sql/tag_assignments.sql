@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS tag_assignments (
+    tag_id TEXT NOT NULL,
+    kind TEXT NOT NULL, -- 'area', 'project', 'resource' or 'event'
+    item_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (tag_id, kind, item_id),
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_tag_assignments_item ON tag_assignments(kind, item_id);
+
+CREATE TRIGGER IF NOT EXISTS trg_area_tag_assignments_delete AFTER DELETE ON areas
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'area' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_project_tag_assignments_delete AFTER DELETE ON projects
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'project' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_resource_tag_assignments_delete AFTER DELETE ON resources
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'resource' AND item_id = OLD.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_event_tag_assignments_delete AFTER DELETE ON events
+BEGIN
+    DELETE FROM tag_assignments WHERE kind = 'event' AND item_id = OLD.id;
+END;
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS tasks (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    parent_id TEXT, -- NULL for a task directly under the project
+    title TEXT NOT NULL,
+    notes TEXT,
+    done INTEGER NOT NULL DEFAULT 0, -- 0 = false, 1 = true
+    due_date INTEGER,
+    priority TEXT,
+    position INTEGER NOT NULL DEFAULT 0,
+    completed_at INTEGER, -- When last marked done
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_tasks_project ON tasks(project_id, parent_id, position);
+CREATE INDEX IF NOT EXISTS idx_tasks_parent ON tasks(parent_id);
@@ -3,6 +3,9 @@ CREATE TABLE IF NOT EXISTS users (
     email TEXT UNIQUE NOT NULL,
     name TEXT NOT NULL,
     password_hash TEXT NOT NULL,
+    time_zone TEXT, -- IANA zone name, NULL to use the machine's zone
+    auto_archive_days INTEGER, -- Archive projects Done for this many days, NULL for never
+    trash_retention_days INTEGER, -- Days deleted items are kept, NULL for the default 30
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL
 );
@@ -0,0 +1,9 @@
+-- Board columns of a workflow, in order. Projects refer to them by name.
+CREATE TABLE IF NOT EXISTS workflow_statuses (
+    workflow_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    done INTEGER NOT NULL DEFAULT 0, -- 1 for finished statuses: completed_at, auto-archive, no reminders
+    PRIMARY KEY (workflow_id, name),
+    FOREIGN KEY (workflow_id) REFERENCES workflows(id) ON DELETE CASCADE
+);
@@ -0,0 +1,9 @@
+-- Moves allowed between statuses. A workflow without any allows every move.
+CREATE TABLE IF NOT EXISTS workflow_transitions (
+    workflow_id TEXT NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    PRIMARY KEY (workflow_id, from_status, to_status),
+    FOREIGN KEY (workflow_id, from_status) REFERENCES workflow_statuses(workflow_id, name) ON DELETE CASCADE,
+    FOREIGN KEY (workflow_id, to_status) REFERENCES workflow_statuses(workflow_id, name) ON DELETE CASCADE
+);
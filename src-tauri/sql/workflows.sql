@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS workflows (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    area_id TEXT, -- NULL for the user's own workflow, else the area's override
+    name TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (area_id) REFERENCES areas(id) ON DELETE CASCADE
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflows_user ON workflows(user_id) WHERE area_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflows_area ON workflows(area_id) WHERE area_id IS NOT NULL;
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::areas::{AreaInfo, AreaResponse};
+use crate::error::{RecallError, respond};
+use crate::projects::{ProjectInfo, ProjectResponse};
+use crate::service;
+use crate::{AppState, get_user_id_from_token};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchiveListing {
+    pub areas: Vec<AreaInfo>,
+    // Includes projects hidden only because their area is archived; their own
+    // archived_at is null
+    pub projects: Vec<ProjectInfo>,
+    pub auto_archive_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutoArchiveResponse {
+    pub auto_archive_days: Option<i64>,
+}
+
+// Hides the area with its projects, and their resources and events, from default listings
+#[tauri::command]
+pub fn archive_area(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let area = service::archive::archive_area(&conn, &user_id, &id)?;
+    state.scheduler.wake();
+
+    respond(AreaResponse { area: Some(area) })
+}
+
+#[tauri::command]
+pub fn unarchive_area(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let area = service::archive::unarchive_area(&conn, &user_id, &id)?;
+    state.scheduler.wake();
+
+    respond(AreaResponse { area: Some(area) })
+}
+
+#[tauri::command]
+pub fn archive_project(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let project = service::archive::archive_project(&conn, &user_id, &id)?;
+    state.scheduler.wake();
+
+    respond(ProjectResponse {
+        project: Some(project),
+    })
+}
+
+#[tauri::command]
+pub fn unarchive_project(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let project = service::archive::unarchive_project(&conn, &user_id, &id)?;
+    state.scheduler.wake();
+
+    respond(ProjectResponse {
+        project: Some(project),
+    })
+}
+
+#[tauri::command]
+pub fn get_archive(
+    token: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let archive = service::archive::list(&conn, &user_id)?;
+
+    respond(archive)
+}
+
+// Archive projects once they have been Done for `days` days; null turns it off.
+// The background scheduler applies it.
+#[tauri::command]
+pub fn set_auto_archive(
+    token: String,
+    days: Option<i64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let auto_archive_days = service::archive::set_auto_archive_days(&conn, &user_id, days)?;
+    state.scheduler.wake();
+
+    respond(AutoArchiveResponse { auto_archive_days })
+}
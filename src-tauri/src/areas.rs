@@ -1,10 +1,9 @@
-use anyhow::Result;
-use chrono::Utc;
 use exemplar::Model;
-use rusqlite::params;
 use serde::{Deserialize, Serialize};
 
-use crate::{AppState, HasId, generate_id, get_user_id_from_token, try_insert_thing};
+use crate::error::{RecallError, parse_request, respond, respond_with_message};
+use crate::service;
+use crate::{AppState, HasId, get_user_id_from_token};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Model)]
 #[table("areas")]
@@ -14,6 +13,8 @@ pub struct Area {
     pub user_id: String,
     pub name: String,
     pub image_url: Option<String>,
+    pub archived_at: Option<i64>,
+    pub deleted_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -38,8 +39,6 @@ pub struct UpdateAreaRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AreaResponse {
-    pub success: bool,
-    pub message: Option<String>,
     pub area: Option<AreaInfo>,
 }
 
@@ -48,6 +47,7 @@ pub struct AreaInfo {
     pub id: String,
     pub name: String,
     pub image_url: Option<String>,
+    pub archived_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -57,86 +57,24 @@ pub fn create_area(
     token: String,
     json: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
-
-    // Get user_id from token
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: CreateAreaRequest = parse_request(&json)?;
 
-    // Parse request
-    let request: CreateAreaRequest = serde_json::from_str(&json).map_err(|e| {
-        println!("JSON parse error: {}", e);
-        println!("JSON: {}", json);
-        anyhow::Error::from(e)
-    })?;
-
-    // Validate name
-    if request.name.trim().is_empty() {
-        let error_response = AreaResponse {
-            success: false,
-            message: Some("Area name cannot be empty".to_string()),
-            area: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
-
-    // Create area
-    let now = Utc::now().timestamp();
-    let mut area = Area {
-        id: None,
-        user_id: user_id.clone(),
-        name: request.name.trim().to_string(),
-        image_url: request.image_url,
-        created_at: now,
-        updated_at: now,
-    };
+    let area = service::areas::create(&conn, &user_id, request)?;
 
-    let area_id = try_insert_thing(&mut area, &conn)?;
-    println!("inserted Area id: {:?}", area_id);
-
-    let response = AreaResponse {
-        success: true,
-        message: None,
-        area: Some(AreaInfo {
-            id: area_id,
-            name: area.name,
-            image_url: area.image_url,
-            created_at: area.created_at,
-            updated_at: area.updated_at,
-        }),
-    };
-
-    Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
+    respond(AreaResponse { area: Some(area) })
 }
 
 #[tauri::command]
-pub fn get_areas(token: String, state: tauri::State<'_, AppState>) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
-
-    // Get user_id from token
+pub fn get_areas(token: String, state: tauri::State<'_, AppState>) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    // Get all areas for user
-    let mut stmt = conn.prepare(
-        "SELECT id, name, image_url, created_at, updated_at FROM areas WHERE user_id = ?1 ORDER BY created_at DESC"
-    ).map_err(anyhow::Error::from)?;
+    let areas = service::areas::list(&conn, &user_id)?;
 
-    let areas: Result<Vec<AreaInfo>, _> = stmt
-        .query_map(params![user_id], |row| {
-            Ok(AreaInfo {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                image_url: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        })
-        .map_err(anyhow::Error::from)?
-        .collect();
-
-    let areas = areas.map_err(anyhow::Error::from)?;
-
-    Ok(serde_json::to_string(&areas).map_err(anyhow::Error::from)?)
+    Ok(serde_json::to_string(&areas)?)
 }
 
 #[tauri::command]
@@ -145,79 +83,14 @@ pub fn update_area(
     id: String,
     json: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
-
-    // Get user_id from token
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: UpdateAreaRequest = parse_request(&json)?;
 
-    // Parse request
-    let request: UpdateAreaRequest = serde_json::from_str(&json).map_err(|e| {
-        println!("JSON parse error: {}", e);
-        println!("JSON: {}", json);
-        anyhow::Error::from(e)
-    })?;
-
-    // Validate name
-    if request.name.trim().is_empty() {
-        let error_response = AreaResponse {
-            success: false,
-            message: Some("Area name cannot be empty".to_string()),
-            area: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
-
-    // Check if area exists and belongs to user
-    let area_user_id: String = conn
-        .query_row(
-            "SELECT user_id FROM areas WHERE id = ?1",
-            params![id],
-            |row| row.get(0),
-        )
-        .map_err(|_| tauri::Error::Anyhow(anyhow::anyhow!("Area not found")))?;
+    let area = service::areas::update(&conn, &user_id, &id, request)?;
 
-    if area_user_id != user_id {
-        let error_response = AreaResponse {
-            success: false,
-            message: Some("You don't have permission to update this area".to_string()),
-            area: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
-
-    // Update area
-    let now = Utc::now().timestamp();
-    conn.execute(
-        "UPDATE areas SET name = ?1, image_url = ?2, updated_at = ?3 WHERE id = ?4",
-        params![request.name.trim(), request.image_url, now, id],
-    )
-    .map_err(anyhow::Error::from)?;
-
-    // Get updated area
-    let area_info: AreaInfo = conn
-        .query_row(
-            "SELECT id, name, image_url, created_at, updated_at FROM areas WHERE id = ?1",
-            params![id],
-            |row| {
-                Ok(AreaInfo {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    image_url: row.get(2)?,
-                    created_at: row.get(3)?,
-                    updated_at: row.get(4)?,
-                })
-            },
-        )
-        .map_err(anyhow::Error::from)?;
-
-    let response = AreaResponse {
-        success: true,
-        message: None,
-        area: Some(area_info),
-    };
-
-    Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
+    respond(AreaResponse { area: Some(area) })
 }
 
 #[tauri::command]
@@ -225,31 +98,13 @@ pub fn get_area_by_id(
     token: String,
     id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
-
-    // Get user_id from token
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    // Get area by id
-    let area_info: Result<AreaInfo, _> = conn.query_row(
-        "SELECT id, name, image_url, created_at, updated_at FROM areas WHERE id = ?1 AND user_id = ?2",
-        params![id, user_id],
-        |row| {
-            Ok(AreaInfo {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                image_url: row.get(2)?,
-                created_at: row.get(3)?,
-                updated_at: row.get(4)?,
-            })
-        },
-    );
+    let area = service::areas::get(&conn, &user_id, &id)?;
 
-    match area_info {
-        Ok(area) => Ok(serde_json::to_string(&area).map_err(anyhow::Error::from)?),
-        Err(_) => Err(tauri::Error::Anyhow(anyhow::anyhow!("Area not found"))),
-    }
+    Ok(serde_json::to_string(&area)?)
 }
 
 #[tauri::command]
@@ -257,40 +112,11 @@ pub fn delete_area(
     token: String,
     id: String,
     state: tauri::State<'_, AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
-
-    // Get user_id from token
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    // Check if area exists and belongs to user
-    let area_user_id: String = conn
-        .query_row(
-            "SELECT user_id FROM areas WHERE id = ?1",
-            params![id],
-            |row| row.get(0),
-        )
-        .map_err(|_| tauri::Error::Anyhow(anyhow::anyhow!("Area not found")))?;
-
-    if area_user_id != user_id {
-        let error_response = AreaResponse {
-            success: false,
-            message: Some("You don't have permission to delete this area".to_string()),
-            area: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
-
-    // Delete area
-    conn.execute("DELETE FROM areas WHERE id = ?1", params![id])
-        .map_err(anyhow::Error::from)
-        .map_err(tauri::Error::from)?;
-
-    let response = AreaResponse {
-        success: true,
-        message: Some("Area deleted successfully".to_string()),
-        area: None,
-    };
+    service::areas::delete(&conn, &user_id, &id)?;
 
-    Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
+    respond_with_message("Area deleted successfully", AreaResponse { area: None })
 }
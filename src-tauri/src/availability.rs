@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, parse_request, respond};
+use crate::events::EventInfo;
+use crate::service;
+use crate::{AppState, get_user_id_from_token};
+
+// [start, end) in Unix seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeSlot {
+    pub start: i64,
+    pub end: i64,
+}
+
+// Daily window to look for free time in, as "HH:MM" in the user's zone
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkingHours {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FreeBusyRequest {
+    pub start_date: i64,
+    pub end_date: i64,
+    pub duration_minutes: i64,
+    // The whole range when absent
+    #[serde(default)]
+    pub working_hours: Option<WorkingHours>,
+    // All-day events block their whole days unless this is set
+    #[serde(default)]
+    pub ignore_all_day: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct FreeBusy {
+    pub busy: Vec<TimeSlot>,
+    pub free: Vec<TimeSlot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConflictsResponse {
+    pub conflicts: Vec<EventInfo>,
+}
+
+// Events that would overlap a proposed slot, e.g. before saving an event.
+// Pass the event's own id as exclude_id when rescheduling it.
+#[tauri::command(rename_all = "snake_case")]
+pub fn check_event_conflicts(
+    token: String,
+    start_time: i64,
+    end_time: Option<i64>,
+    exclude_id: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let conflicts = service::availability::conflicts(
+        &conn,
+        &user_id,
+        start_time,
+        end_time,
+        exclude_id.as_deref(),
+    )?;
+
+    respond(ConflictsResponse { conflicts })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_free_busy(
+    token: String,
+    json: String,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: FreeBusyRequest = parse_request(&json)?;
+
+    let free_busy = service::availability::free_busy(&conn, &user_id, &request)?;
+
+    respond(free_busy)
+}
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use chrono::Utc;
 use rand::Rng;
+use recall_lib::blobs;
+use recall_lib::time_zone;
 use rusqlite::Connection;
 use std::env;
 
@@ -188,7 +190,7 @@ fn main() -> Result<()> {
         let name = resource_names[i % resource_names.len()];
 
         // Mix of text and file resources
-        let (content, file_data, file_type, file_size) = if rng.random_bool(0.6) {
+        let (content, blob_hash, file_type, file_size) = if rng.random_bool(0.6) {
             // Text resource
             (
                 Some(format!(
@@ -201,11 +203,12 @@ fn main() -> Result<()> {
             )
         } else {
             // File resource (simulated)
+            let file_data = format!("Simulated file contents for {} #{}", name, i).into_bytes();
             (
                 None::<String>,
-                Some("base64encodedfiledata".to_string()),
-                Some("application/pdf".to_string()),
-                Some(rng.random_range(1000..5000000)),
+                Some(blobs::put_blob(&conn, &file_data)?),
+                Some("text/plain".to_string()),
+                Some(file_data.len() as i64),
             )
         };
 
@@ -213,14 +216,15 @@ fn main() -> Result<()> {
         let updated_at = created_at + rng.random_range(0..30) * 24 * 3600;
 
         conn.execute(
-            "INSERT INTO resources (id, user_id, project_id, name, content, file_data, file_type, file_size, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            rusqlite::params![resource_id, user_id, project_id, name, content, file_data, file_type, file_size, created_at, updated_at],
+            "INSERT INTO resources (id, user_id, project_id, name, content, blob_hash, file_type, file_size, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![resource_id, user_id, project_id, name, content, blob_hash, file_type, file_size, created_at, updated_at],
         )?;
         println!("  ✓ Created resource: {}", name);
     }
 
     // Create Events
     println!("\nCreating events...");
+    let zone = time_zone::system_zone();
     for i in 0..20 {
         let event_id = generate_id();
         let title = event_titles[i % event_titles.len()];
@@ -269,9 +273,17 @@ fn main() -> Result<()> {
         let created_at = start_time - rng.random_range(0..7) * 24 * 3600;
         let updated_at = created_at;
 
+        // All-day events store floating dates, timed ones the zone they were scheduled in
+        let (start_time, end_time, time_zone) = if all_day {
+            let floating = |t: i64| time_zone::to_floating(zone, t);
+            (floating(start_time), end_time.map(floating), None)
+        } else {
+            (start_time, end_time, Some(zone.name()))
+        };
+
         conn.execute(
-            "INSERT INTO events (id, user_id, project_id, title, description, start_time, end_time, location, all_day, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
-            rusqlite::params![event_id, user_id, project_id, title, description, start_time, end_time, location, if all_day { 1 } else { 0 }, created_at, updated_at],
+            "INSERT INTO events (id, user_id, project_id, title, description, start_time, end_time, location, all_day, time_zone, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            rusqlite::params![event_id, user_id, project_id, title, description, start_time, end_time, location, if all_day { 1 } else { 0 }, time_zone, created_at, updated_at],
         )?;
         println!(
             "  ✓ Created event: {} ({})",
@@ -0,0 +1,164 @@
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use rusqlite::{Connection, MAIN_DB, OptionalExtension, params};
+use sha2::{Digest, Sha256};
+
+use crate::generate_id;
+
+// Largest file a single resource may hold
+pub const MAX_BLOB_SIZE: i64 = 256 * 1024 * 1024; // 256MB in bytes
+
+// Unit of streaming I/O between files on disk and the blobs table
+const CHUNK_SIZE: usize = 1024 * 1024; // 1MB
+
+pub fn hash_bytes(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+// Store data under its SHA-256 and return the hash. Identical uploads share one row.
+// A new blob starts with ref_count 0; the resources triggers count references once a
+// resource points at it, so call this in the same transaction as that insert or update.
+pub fn put_blob(conn: &Connection, data: &[u8]) -> Result<String> {
+    let hash = hash_bytes(data);
+    conn.execute(
+        "INSERT OR IGNORE INTO blobs (hash, data, size, ref_count, created_at) VALUES (?1, ?2, ?3, 0, ?4)",
+        params![hash, data, data.len() as i64, Utc::now().timestamp()],
+    )?;
+    Ok(hash)
+}
+
+pub fn get_blob(conn: &Connection, hash: &str) -> Result<Option<Vec<u8>>> {
+    let data = conn
+        .query_row(
+            "SELECT data FROM blobs WHERE hash = ?1",
+            params![hash],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(data)
+}
+
+// Delete blobs no resource refers to any more. Returns the number of blobs removed.
+pub fn collect_garbage(conn: &Connection) -> Result<usize> {
+    let removed = conn.execute("DELETE FROM blobs WHERE ref_count <= 0", [])?;
+    if removed > 0 {
+        println!("removed {} unreferenced blobs", removed);
+    }
+    Ok(removed)
+}
+
+// Stream a file from disk into the blob store without holding it in memory.
+// Returns (hash, size). Run inside a transaction: the row is written under a placeholder
+// key first and only renamed to its SHA-256 once the whole file has been copied.
+// `on_progress` receives (bytes copied, total bytes) after every chunk.
+pub fn import_blob_from_path(
+    conn: &Connection,
+    path: &Path,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<(String, i64)> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let total = file.metadata()?.len();
+    if total as i64 > MAX_BLOB_SIZE {
+        bail!(
+            "File size exceeds maximum of {}MB",
+            MAX_BLOB_SIZE / (1024 * 1024)
+        );
+    }
+
+    // Placeholder keys cannot collide with hex digests
+    let pending_key = format!("pending-{}", generate_id());
+    conn.execute(
+        "INSERT INTO blobs (hash, data, size, ref_count, created_at) VALUES (?1, zeroblob(?2), ?2, 0, ?3)",
+        params![pending_key, total as i64, Utc::now().timestamp()],
+    )?;
+    let row_id = conn.last_insert_rowid();
+
+    let mut hasher = Sha256::new();
+    let mut copied: u64 = 0;
+    {
+        let mut blob = conn.blob_open(MAIN_DB, "blobs", "data", row_id, false)?;
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            if copied + read as u64 > total {
+                bail!("{} changed while it was being imported", path.display());
+            }
+            blob.write_all(&buffer[..read])?;
+            hasher.update(&buffer[..read]);
+            copied += read as u64;
+            on_progress(copied, total);
+        }
+    }
+    if copied != total {
+        bail!("{} changed while it was being imported", path.display());
+    }
+
+    let hash = format!("{:x}", hasher.finalize());
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM blobs WHERE hash = ?1)",
+        params![hash],
+        |row| row.get(0),
+    )?;
+    if exists {
+        // Identical content is already stored; drop the copy we just made
+        conn.execute("DELETE FROM blobs WHERE rowid = ?1", params![row_id])?;
+    } else {
+        conn.execute(
+            "UPDATE blobs SET hash = ?1 WHERE rowid = ?2",
+            params![hash, row_id],
+        )?;
+    }
+
+    Ok((hash, total as i64))
+}
+
+// Stream a stored blob out to a file on disk. Returns the number of bytes written.
+// A partially written file is removed if the copy fails.
+pub fn export_blob_to_path(
+    conn: &Connection,
+    hash: &str,
+    path: &Path,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<u64> {
+    let (row_id, total): (i64, i64) = conn
+        .query_row(
+            "SELECT rowid, size FROM blobs WHERE hash = ?1",
+            params![hash],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .with_context(|| format!("Blob {} not found", hash))?;
+    let total = total as u64;
+
+    let mut copy = || -> Result<u64> {
+        let mut blob = conn.blob_open(MAIN_DB, "blobs", "data", row_id, true)?;
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut written: u64 = 0;
+        loop {
+            let read = blob.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read])?;
+            written += read as u64;
+            on_progress(written, total);
+        }
+        writer.flush()?;
+        Ok(written)
+    };
+
+    copy().inspect_err(|_| {
+        let _ = std::fs::remove_file(path);
+    })
+}
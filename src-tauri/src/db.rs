@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+use rusqlite::Connection;
+
+// How long a connection waits on a locked database before giving up with SQLITE_BUSY
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Settings applied to every connection the pool opens. Apart from journal_mode, which is
+// stored in the database file, SQLite keeps these per connection, so they cannot be set once.
+pub fn configure_connection(conn: &mut Connection) -> rusqlite::Result<()> {
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    // Required for the ON DELETE CASCADE / SET NULL clauses in the schema to take effect
+    conn.pragma_update(None, "foreign_keys", true)?;
+    // WAL lets readers keep going while a command writes; in-memory databases report "memory"
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+    // NORMAL is durable across application crashes in WAL mode and avoids an fsync per commit
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    Ok(())
+}
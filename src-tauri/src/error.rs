@@ -0,0 +1,182 @@
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+
+// Stable, machine-readable error codes. The frontend switches on these, never on messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NotFound,
+    Forbidden,
+    Validation,
+    Conflict,
+    Unauthenticated,
+    Storage,
+}
+
+// Every failure a command can report. Messages are meant for display.
+#[derive(Debug)]
+pub enum RecallError {
+    NotFound(String),
+    Forbidden(String),
+    Validation(String),
+    Conflict(String),
+    Unauthenticated(String),
+    Storage(anyhow::Error),
+}
+
+impl RecallError {
+    pub fn not_found(what: &str) -> Self {
+        RecallError::NotFound(format!("{} not found", what))
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        RecallError::Forbidden(message.into())
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        RecallError::Validation(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        RecallError::Conflict(message.into())
+    }
+
+    pub fn unauthenticated(message: impl Into<String>) -> Self {
+        RecallError::Unauthenticated(message.into())
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            RecallError::NotFound(_) => ErrorCode::NotFound,
+            RecallError::Forbidden(_) => ErrorCode::Forbidden,
+            RecallError::Validation(_) => ErrorCode::Validation,
+            RecallError::Conflict(_) => ErrorCode::Conflict,
+            RecallError::Unauthenticated(_) => ErrorCode::Unauthenticated,
+            RecallError::Storage(_) => ErrorCode::Storage,
+        }
+    }
+}
+
+impl fmt::Display for RecallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecallError::NotFound(message)
+            | RecallError::Forbidden(message)
+            | RecallError::Validation(message)
+            | RecallError::Conflict(message)
+            | RecallError::Unauthenticated(message) => f.write_str(message),
+            RecallError::Storage(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RecallError {}
+
+// Anything we don't classify explicitly is a storage/infrastructure failure
+impl From<anyhow::Error> for RecallError {
+    fn from(e: anyhow::Error) -> Self {
+        RecallError::Storage(e)
+    }
+}
+
+impl From<rusqlite::Error> for RecallError {
+    fn from(e: rusqlite::Error) -> Self {
+        RecallError::Storage(e.into())
+    }
+}
+
+impl From<r2d2::Error> for RecallError {
+    fn from(e: r2d2::Error) -> Self {
+        RecallError::Storage(e.into())
+    }
+}
+
+impl From<serde_json::Error> for RecallError {
+    fn from(e: serde_json::Error) -> Self {
+        RecallError::Storage(e.into())
+    }
+}
+
+impl From<std::io::Error> for RecallError {
+    fn from(e: std::io::Error) -> Self {
+        RecallError::Storage(e.into())
+    }
+}
+
+impl From<tauri::Error> for RecallError {
+    fn from(e: tauri::Error) -> Self {
+        RecallError::Storage(e.into())
+    }
+}
+
+// The single response shape shared by every command.
+// `data` is flattened so payload fields sit next to success/code/message.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Envelope<T> {
+    pub success: bool,
+    pub code: Option<ErrorCode>,
+    pub message: Option<String>,
+    #[serde(flatten)]
+    pub data: T,
+}
+
+impl<T> Envelope<T> {
+    pub fn ok(data: T) -> Self {
+        Envelope {
+            success: true,
+            code: None,
+            message: None,
+            data,
+        }
+    }
+
+    pub fn ok_with_message(message: impl Into<String>, data: T) -> Self {
+        Envelope {
+            success: true,
+            code: None,
+            message: Some(message.into()),
+            data,
+        }
+    }
+}
+
+// Commands reject with the envelope, so callers see { success: false, code, message }
+impl Serialize for RecallError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let RecallError::Storage(e) = self {
+            println!("storage error: {:#}", e);
+        }
+        Envelope {
+            success: false,
+            code: Some(self.code()),
+            message: Some(self.to_string()),
+            data: (),
+        }
+        .serialize(serializer)
+    }
+}
+
+// Serialize a successful payload into the envelope
+pub fn respond<T: Serialize>(data: T) -> Result<String, RecallError> {
+    Ok(serde_json::to_string(&Envelope::ok(data))?)
+}
+
+pub fn respond_with_message<T: Serialize>(
+    message: impl Into<String>,
+    data: T,
+) -> Result<String, RecallError> {
+    Ok(serde_json::to_string(&Envelope::ok_with_message(
+        message, data,
+    ))?)
+}
+
+// Parse a command's JSON request body; malformed input is a validation error
+pub fn parse_request<T: DeserializeOwned>(json: &str) -> Result<T, RecallError> {
+    serde_json::from_str(json).map_err(|e| {
+        println!("JSON parse error: {}", e);
+        println!("JSON: {}", json);
+        RecallError::Validation(format!("Invalid request: {}", e))
+    })
+}
@@ -1,10 +1,9 @@
-use anyhow::Result;
-use chrono::Utc;
 use exemplar::Model;
-use rusqlite::params;
 use serde::{Deserialize, Serialize};
 
-use crate::{AppState, HasId, get_user_id_from_token, try_insert_thing};
+use crate::error::{RecallError, parse_request, respond, respond_with_message};
+use crate::service;
+use crate::{AppState, HasId, get_user_id_from_token};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Model)]
 #[table("events")]
@@ -18,7 +17,12 @@ pub struct Event {
     pub start_time: i64,
     pub end_time: Option<i64>,
     pub location: Option<String>,
-    pub all_day: i64, // 0 = false, 1 = true
+    pub all_day: i64,                    // 0 = false, 1 = true
+    pub time_zone: Option<String>,       // IANA zone; NULL for floating all-day times
+    pub recurrence_rule: Option<String>, // RFC 5545 RRULE value
+    pub recurrence_end: Option<i64>,     // Latest possible occurrence start
+    pub ical_uid: Option<String>,        // UID of the VEVENT this was imported from
+    pub deleted_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -38,6 +42,16 @@ pub struct CreateEventRequest {
     pub end_time: Option<i64>,
     pub location: Option<String>,
     pub all_day: bool,
+    // Zone of a timed event, and of the dates of an all-day one; defaults to the user's
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    #[serde(default)]
+    pub recurrence_rule: Option<String>,
+    #[serde(default)]
+    pub exception_dates: Vec<i64>,
+    // Minutes before the event, or each occurrence, to remind the user
+    #[serde(default)]
+    pub reminders: Vec<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,16 +63,42 @@ pub struct UpdateEventRequest {
     pub end_time: Option<i64>,
     pub location: Option<String>,
     pub all_day: bool,
+    // Zone of a timed event, and of the dates of an all-day one; defaults to the user's
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    #[serde(default)]
+    pub recurrence_rule: Option<String>,
+    // Replaces the series' exception dates when present
+    #[serde(default)]
+    pub exception_dates: Option<Vec<i64>>,
+    // Replaces the event's reminders when present
+    #[serde(default)]
+    pub reminders: Option<Vec<i64>>,
+}
+
+// New details for one occurrence of a recurring event
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OccurrenceRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub start_time: i64,
+    pub end_time: Option<i64>,
+    pub location: Option<String>,
+    pub all_day: bool,
+    #[serde(default)]
+    pub time_zone: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EventResponse {
-    pub success: bool,
-    pub message: Option<String>,
     pub event: Option<EventInfo>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// A one-off event, a recurring series, or one occurrence expanded from a series.
+// Occurrences keep the series' id and carry the start they had before any override.
+// Times are instants; all-day events are placed on their dates in the user's zone,
+// and `day` is the date the event starts on there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventInfo {
     pub id: String,
     pub project_id: Option<String>,
@@ -69,133 +109,71 @@ pub struct EventInfo {
     pub end_time: Option<i64>,
     pub location: Option<String>,
     pub all_day: bool,
+    pub time_zone: Option<String>,
+    pub day: String,
+    pub recurrence_rule: Option<String>,
+    pub exception_dates: Vec<i64>,
+    pub reminders: Vec<i64>,
+    pub occurrence_start: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+// Filters for listing events; every field is optional and they combine with AND.
+// An event matches the window if any part of it overlaps [start_date, end_date].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct EventQuery {
+    #[serde(default)]
+    pub start_date: Option<i64>,
+    #[serde(default)]
+    pub end_date: Option<i64>,
+    // Events in any of these projects; empty means all projects
+    #[serde(default)]
+    pub project_ids: Vec<String>,
+    // Events carrying every one of these tags
+    #[serde(default)]
+    pub tag_ids: Vec<String>,
+    // Words that must all prefix-match the title, description or location
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub all_day_only: bool,
+    // Without a project filter, events of archived projects are left out unless this is set
+    #[serde(default)]
+    pub include_archived: bool,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EventsListResponse {
-    pub success: bool,
-    pub message: Option<String>,
     pub events: Vec<EventInfo>,
 }
 
+// What an .ics import did. Edited or cancelled occurrences count as updates.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CalendarImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub fn create_event(
     token: String,
     json: String,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: CreateEventRequest = parse_request(&json)?;
 
-    // Parse request
-    let request: CreateEventRequest = serde_json::from_str(&json).map_err(|e| {
-        println!("JSON parse error: {}", e);
-        println!("JSON: {}", json);
-        anyhow::Error::from(e)
-    })?;
-
-    // Validate title
-    if request.title.trim().is_empty() {
-        let error_response = EventResponse {
-            success: false,
-            message: Some("Event title cannot be empty".to_string()),
-            event: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
-
-    // Validate end_time is after start_time if provided
-    if let Some(end_time) = request.end_time {
-        if end_time <= request.start_time {
-            let error_response = EventResponse {
-                success: false,
-                message: Some("End time must be after start time".to_string()),
-                event: None,
-            };
-            return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-        }
-    }
-
-    // Validate that project belongs to user if provided
-    if let Some(ref project_id) = request.project_id {
-        let project_user_id: String = conn
-            .query_row(
-                "SELECT user_id FROM projects WHERE id = ?1",
-                params![project_id],
-                |row| row.get(0),
-            )
-            .map_err(|_| tauri::Error::Anyhow(anyhow::anyhow!("Project not found")))?;
-
-        if project_user_id != user_id {
-            let error_response = EventResponse {
-                success: false,
-                message: Some(
-                    "You don't have permission to create events for this project".to_string(),
-                ),
-                event: None,
-            };
-            return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-        }
-    }
-
-    // Create event
-    let now = Utc::now().timestamp();
-    let mut event = Event {
-        id: None,
-        user_id: user_id.clone(),
-        project_id: request.project_id,
-        title: request.title.trim().to_string(),
-        description: request
-            .description
-            .map(|d| d.trim().to_string())
-            .filter(|d| !d.is_empty()),
-        start_time: request.start_time,
-        end_time: request.end_time,
-        location: request
-            .location
-            .map(|l| l.trim().to_string())
-            .filter(|l| !l.is_empty()),
-        all_day: if request.all_day { 1 } else { 0 },
-        created_at: now,
-        updated_at: now,
-    };
-
-    let event_id = try_insert_thing(&mut event, &conn)?;
-    println!("inserted Event id: {:?}", event_id);
-
-    // Get project name for response if project_id exists
-    let project_name: Option<String> = if let Some(ref pid) = event.project_id {
-        conn.query_row(
-            "SELECT title FROM projects WHERE id = ?1",
-            params![pid],
-            |row| row.get(0),
-        )
-        .ok()
-    } else {
-        None
-    };
-
-    let response = EventResponse {
-        success: true,
-        message: None,
-        event: Some(EventInfo {
-            id: event_id,
-            project_id: event.project_id,
-            project_name,
-            title: event.title,
-            description: event.description,
-            start_time: event.start_time,
-            end_time: event.end_time,
-            location: event.location,
-            all_day: event.all_day == 1,
-            created_at: event.created_at,
-            updated_at: event.updated_at,
-        }),
-    };
+    let event = service::events::create(&conn, &user_id, request)?;
+    state.scheduler.wake();
 
-    Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
+    respond(EventResponse { event: Some(event) })
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -204,237 +182,38 @@ pub fn get_events(
     start_date: Option<i64>,
     end_date: Option<i64>,
     project_id: Option<String>,
+    tag_ids: Option<Vec<String>>,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    // Build query dynamically based on filters
-    let events_result: Result<Vec<EventInfo>, anyhow::Error> = match (
-        start_date, end_date, project_id,
-    ) {
-        (None, None, None) => {
-            let query = "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.user_id = ?1 ORDER BY e.start_time ASC".to_string();
-            let mut stmt = conn.prepare(&query).map_err(anyhow::Error::from)?;
-            let event_iter = stmt
-                .query_map(
-                    params![user_id],
-                    |row| -> Result<EventInfo, rusqlite::Error> {
-                        Ok(EventInfo {
-                            id: row.get(0)?,
-                            project_id: row.get(1)?,
-                            project_name: row.get(10)?,
-                            title: row.get(2)?,
-                            description: row.get(3)?,
-                            start_time: row.get(4)?,
-                            end_time: row.get(5)?,
-                            location: row.get(6)?,
-                            all_day: row.get::<_, i64>(7)? == 1,
-                            created_at: row.get(8)?,
-                            updated_at: row.get(9)?,
-                        })
-                    },
-                )
-                .map_err(anyhow::Error::from)?;
-            let mut events = Vec::new();
-            for event_result in event_iter {
-                events.push(event_result.map_err(anyhow::Error::from)?);
-            }
-            Ok(events)
-        }
-        (Some(start), None, None) => {
-            let query = "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.user_id = ?1 AND e.start_time >= ?2 ORDER BY e.start_time ASC".to_string();
-            let mut stmt = conn.prepare(&query).map_err(anyhow::Error::from)?;
-            let event_iter = stmt
-                .query_map(params![user_id, start], |row| {
-                    Ok(EventInfo {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        project_name: row.get(10)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        start_time: row.get(4)?,
-                        end_time: row.get(5)?,
-                        location: row.get(6)?,
-                        all_day: row.get::<_, i64>(7)? == 1,
-                        created_at: row.get(8)?,
-                        updated_at: row.get(9)?,
-                    })
-                })
-                .map_err(anyhow::Error::from)?;
-            let mut events = Vec::new();
-            for event_result in event_iter {
-                events.push(event_result.map_err(anyhow::Error::from)?);
-            }
-            Ok(events)
-        }
-        (Some(start), Some(end), None) => {
-            let query = "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.user_id = ?1 AND e.start_time >= ?2 AND e.start_time <= ?3 ORDER BY e.start_time ASC".to_string();
-            let mut stmt = conn.prepare(&query).map_err(anyhow::Error::from)?;
-            let event_iter = stmt
-                .query_map(params![user_id, start, end], |row| {
-                    Ok(EventInfo {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        project_name: row.get(10)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        start_time: row.get(4)?,
-                        end_time: row.get(5)?,
-                        location: row.get(6)?,
-                        all_day: row.get::<_, i64>(7)? == 1,
-                        created_at: row.get(8)?,
-                        updated_at: row.get(9)?,
-                    })
-                })
-                .map_err(anyhow::Error::from)?;
-            let mut events = Vec::new();
-            for event_result in event_iter {
-                events.push(event_result.map_err(anyhow::Error::from)?);
-            }
-            Ok(events)
-        }
-        (Some(start), Some(end), Some(ref pid)) => {
-            let query = "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.user_id = ?1 AND e.start_time >= ?2 AND e.start_time <= ?3 AND e.project_id = ?4 ORDER BY e.start_time ASC".to_string();
-            let mut stmt = conn.prepare(&query).map_err(anyhow::Error::from)?;
-            let event_iter = stmt
-                .query_map(params![user_id, start, end, pid], |row| {
-                    Ok(EventInfo {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        project_name: row.get(10)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        start_time: row.get(4)?,
-                        end_time: row.get(5)?,
-                        location: row.get(6)?,
-                        all_day: row.get::<_, i64>(7)? == 1,
-                        created_at: row.get(8)?,
-                        updated_at: row.get(9)?,
-                    })
-                })
-                .map_err(anyhow::Error::from)?;
-            let mut events = Vec::new();
-            for event_result in event_iter {
-                events.push(event_result.map_err(anyhow::Error::from)?);
-            }
-            Ok(events)
-        }
-        (Some(start), None, Some(ref pid)) => {
-            let query = "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.user_id = ?1 AND e.start_time >= ?2 AND e.project_id = ?3 ORDER BY e.start_time ASC".to_string();
-            let mut stmt = conn.prepare(&query).map_err(anyhow::Error::from)?;
-            let event_iter = stmt
-                .query_map(params![user_id, start, pid], |row| {
-                    Ok(EventInfo {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        project_name: row.get(10)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        start_time: row.get(4)?,
-                        end_time: row.get(5)?,
-                        location: row.get(6)?,
-                        all_day: row.get::<_, i64>(7)? == 1,
-                        created_at: row.get(8)?,
-                        updated_at: row.get(9)?,
-                    })
-                })
-                .map_err(anyhow::Error::from)?;
-            let mut events = Vec::new();
-            for event_result in event_iter {
-                events.push(event_result.map_err(anyhow::Error::from)?);
-            }
-            Ok(events)
-        }
-        (None, Some(end), None) => {
-            let query = "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.user_id = ?1 AND e.start_time <= ?2 ORDER BY e.start_time ASC".to_string();
-            let mut stmt = conn.prepare(&query).map_err(anyhow::Error::from)?;
-            let event_iter = stmt
-                .query_map(params![user_id, end], |row| {
-                    Ok(EventInfo {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        project_name: row.get(10)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        start_time: row.get(4)?,
-                        end_time: row.get(5)?,
-                        location: row.get(6)?,
-                        all_day: row.get::<_, i64>(7)? == 1,
-                        created_at: row.get(8)?,
-                        updated_at: row.get(9)?,
-                    })
-                })
-                .map_err(anyhow::Error::from)?;
-            let mut events = Vec::new();
-            for event_result in event_iter {
-                events.push(event_result.map_err(anyhow::Error::from)?);
-            }
-            Ok(events)
-        }
-        (None, Some(end), Some(ref pid)) => {
-            let query = "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.user_id = ?1 AND e.start_time <= ?2 AND e.project_id = ?3 ORDER BY e.start_time ASC".to_string();
-            let mut stmt = conn.prepare(&query).map_err(anyhow::Error::from)?;
-            let event_iter = stmt
-                .query_map(params![user_id, end, pid], |row| {
-                    Ok(EventInfo {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        project_name: row.get(10)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        start_time: row.get(4)?,
-                        end_time: row.get(5)?,
-                        location: row.get(6)?,
-                        all_day: row.get::<_, i64>(7)? == 1,
-                        created_at: row.get(8)?,
-                        updated_at: row.get(9)?,
-                    })
-                })
-                .map_err(anyhow::Error::from)?;
-            let mut events = Vec::new();
-            for event_result in event_iter {
-                events.push(event_result.map_err(anyhow::Error::from)?);
-            }
-            Ok(events)
-        }
-        (None, None, Some(ref pid)) => {
-            let query = "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.user_id = ?1 AND e.project_id = ?2 ORDER BY e.start_time ASC".to_string();
-            let mut stmt = conn.prepare(&query).map_err(anyhow::Error::from)?;
-            let event_iter = stmt
-                .query_map(params![user_id, pid], |row| {
-                    Ok(EventInfo {
-                        id: row.get(0)?,
-                        project_id: row.get(1)?,
-                        project_name: row.get(10)?,
-                        title: row.get(2)?,
-                        description: row.get(3)?,
-                        start_time: row.get(4)?,
-                        end_time: row.get(5)?,
-                        location: row.get(6)?,
-                        all_day: row.get::<_, i64>(7)? == 1,
-                        created_at: row.get(8)?,
-                        updated_at: row.get(9)?,
-                    })
-                })
-                .map_err(anyhow::Error::from)?;
-            let mut events = Vec::new();
-            for event_result in event_iter {
-                events.push(event_result.map_err(anyhow::Error::from)?);
-            }
-            Ok(events)
-        }
+    let query = EventQuery {
+        start_date,
+        end_date,
+        project_ids: project_id.into_iter().collect(),
+        tag_ids: tag_ids.unwrap_or_default(),
+        ..EventQuery::default()
     };
+    let events = service::events::query(&conn, &user_id, &query)?;
 
-    let events = events_result.map_err(anyhow::Error::from)?;
+    respond(EventsListResponse { events })
+}
 
-    let response = EventsListResponse {
-        success: true,
-        message: None,
-        events,
-    };
+// get_events with every filter, taking an EventQuery as JSON
+#[tauri::command(rename_all = "snake_case")]
+pub fn query_events(
+    token: String,
+    json: String,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let query: EventQuery = parse_request(&json)?;
 
-    Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
+    let events = service::events::query(&conn, &user_id, &query)?;
+
+    respond(EventsListResponse { events })
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -442,49 +221,13 @@ pub fn get_event_by_id(
     token: String,
     id: String,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    let event_result: Result<EventInfo, rusqlite::Error> = conn.query_row(
-        "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.id = ?1 AND e.user_id = ?2",
-        params![id, user_id],
-        |row| {
-            Ok(EventInfo {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                project_name: row.get(10)?,
-                title: row.get(2)?,
-                description: row.get(3)?,
-                start_time: row.get(4)?,
-                end_time: row.get(5)?,
-                location: row.get(6)?,
-                all_day: row.get::<_, i64>(7)? == 1,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        },
-    );
-
-    match event_result {
-        Ok(event) => {
-            let response = EventResponse {
-                success: true,
-                message: None,
-                event: Some(event),
-            };
-            Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
-        }
-        Err(rusqlite::Error::QueryReturnedNoRows) => {
-            let response = EventResponse {
-                success: false,
-                message: Some("Event not found".to_string()),
-                event: None,
-            };
-            Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
-        }
-        Err(e) => Err(tauri::Error::Anyhow(anyhow::Error::from(e))),
-    }
+    let event = service::events::get(&conn, &user_id, &id)?;
+
+    respond(EventResponse { event: Some(event) })
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -493,171 +236,125 @@ pub fn update_event(
     id: String,
     json: String,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: UpdateEventRequest = parse_request(&json)?;
 
-    // Parse request
-    let request: UpdateEventRequest = serde_json::from_str(&json).map_err(|e| {
-        println!("JSON parse error: {}", e);
-        println!("JSON: {}", json);
-        anyhow::Error::from(e)
-    })?;
-
-    // Validate title
-    if request.title.trim().is_empty() {
-        let error_response = EventResponse {
-            success: false,
-            message: Some("Event title cannot be empty".to_string()),
-            event: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
+    let event = service::events::update(&conn, &user_id, &id, request)?;
+    state.scheduler.wake();
 
-    // Validate end_time is after start_time if provided
-    if let Some(end_time) = request.end_time {
-        if end_time <= request.start_time {
-            let error_response = EventResponse {
-                success: false,
-                message: Some("End time must be after start time".to_string()),
-                event: None,
-            };
-            return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-        }
-    }
+    respond(EventResponse { event: Some(event) })
+}
 
-    // Check if event exists and belongs to user
-    let event_user_id: String = conn
-        .query_row(
-            "SELECT user_id FROM events WHERE id = ?1",
-            params![id],
-            |row| row.get(0),
-        )
-        .map_err(|_| tauri::Error::Anyhow(anyhow::anyhow!("Event not found")))?;
-
-    if event_user_id != user_id {
-        let error_response = EventResponse {
-            success: false,
-            message: Some("You don't have permission to update this event".to_string()),
-            event: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
+#[tauri::command(rename_all = "snake_case")]
+pub fn delete_event(
+    token: String,
+    id: String,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
 
-    // Validate that project belongs to user if provided
-    if let Some(ref project_id) = request.project_id {
-        let project_user_id: String = conn
-            .query_row(
-                "SELECT user_id FROM projects WHERE id = ?1",
-                params![project_id],
-                |row| row.get(0),
-            )
-            .map_err(|_| tauri::Error::Anyhow(anyhow::anyhow!("Project not found")))?;
-
-        if project_user_id != user_id {
-            let error_response = EventResponse {
-                success: false,
-                message: Some(
-                    "You don't have permission to assign events to this project".to_string(),
-                ),
-                event: None,
-            };
-            return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-        }
-    }
+    service::events::delete(&conn, &user_id, &id)?;
 
-    // Update event
-    let now = Utc::now().timestamp();
-    conn.execute(
-        "UPDATE events SET project_id = ?1, title = ?2, description = ?3, start_time = ?4, end_time = ?5, location = ?6, all_day = ?7, updated_at = ?8 WHERE id = ?9 AND user_id = ?10",
-        params![
-            request.project_id,
-            request.title.trim(),
-            request.description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()),
-            request.start_time,
-            request.end_time,
-            request.location.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
-            if request.all_day { 1 } else { 0 },
-            now,
-            id,
-            user_id
-        ],
-    )
-    .map_err(anyhow::Error::from)?;
-
-    // Get updated event
-    let event_result: Result<EventInfo, rusqlite::Error> = conn.query_row(
-        "SELECT e.id, e.project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name FROM events e LEFT JOIN projects p ON e.project_id = p.id WHERE e.id = ?1",
-        params![id],
-        |row| {
-            Ok(EventInfo {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                project_name: row.get(10)?,
-                title: row.get(2)?,
-                description: row.get(3)?,
-                start_time: row.get(4)?,
-                end_time: row.get(5)?,
-                location: row.get(6)?,
-                all_day: row.get::<_, i64>(7)? == 1,
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        },
-    );
-
-    match event_result {
-        Ok(event) => {
-            let response = EventResponse {
-                success: true,
-                message: None,
-                event: Some(event),
-            };
-            Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
-        }
-        Err(e) => Err(tauri::Error::Anyhow(anyhow::Error::from(e))),
-    }
+    respond_with_message("Event deleted successfully", EventResponse { event: None })
 }
 
+// Change a single occurrence of a recurring event, leaving the rest of the series alone
 #[tauri::command(rename_all = "snake_case")]
-pub fn delete_event(
+pub fn update_event_occurrence(
     token: String,
     id: String,
+    occurrence_start: i64,
+    json: String,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: OccurrenceRequest = parse_request(&json)?;
 
-    // Check if event exists and belongs to user
-    let event_user_id: String = conn
-        .query_row(
-            "SELECT user_id FROM events WHERE id = ?1",
-            params![id],
-            |row| row.get(0),
-        )
-        .map_err(|_| tauri::Error::Anyhow(anyhow::anyhow!("Event not found")))?;
-
-    if event_user_id != user_id {
-        let error_response = EventResponse {
-            success: false,
-            message: Some("You don't have permission to delete this event".to_string()),
-            event: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
+    let event =
+        service::events::update_occurrence(&conn, &user_id, &id, occurrence_start, request)?;
+    state.scheduler.wake();
 
-    // Delete event
-    conn.execute(
-        "DELETE FROM events WHERE id = ?1 AND user_id = ?2",
-        params![id, user_id],
-    )
-    .map_err(anyhow::Error::from)?;
+    respond(EventResponse { event: Some(event) })
+}
 
-    let response = EventResponse {
-        success: true,
-        message: Some("Event deleted successfully".to_string()),
-        event: None,
+// Remove a single occurrence of a recurring event
+#[tauri::command(rename_all = "snake_case")]
+pub fn cancel_event_occurrence(
+    token: String,
+    id: String,
+    occurrence_start: i64,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    service::events::cancel_occurrence(&conn, &user_id, &id, occurrence_start)?;
+
+    respond_with_message("Occurrence removed", EventResponse { event: None })
+}
+
+// Write the user's events, filtered like get_events, to an .ics file.
+// Shows a save dialog when no path is given. Returns the path written.
+#[tauri::command(rename_all = "snake_case")]
+pub fn export_events_ics(
+    token: String,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    project_id: Option<String>,
+    path: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let calendar =
+        service::calendar::export(&conn, &user_id, start_date, end_date, project_id.as_deref())?;
+
+    let path = match path {
+        Some(path) => path.into(),
+        None => rfd::FileDialog::new()
+            .set_file_name("recall.ics")
+            .add_filter("iCalendar", &["ics"])
+            .save_file()
+            .ok_or_else(|| RecallError::validation("User cancelled file save dialog"))?,
+    };
+    std::fs::write(&path, calendar)?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+// Import an .ics file, optionally into one project. Shows an open dialog when no path is given.
+#[tauri::command(rename_all = "snake_case")]
+pub fn import_events_ics(
+    token: String,
+    project_id: Option<String>,
+    path: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let path = match path {
+        Some(path) => path.into(),
+        None => rfd::FileDialog::new()
+            .add_filter("iCalendar", &["ics", "ical", "ifb"])
+            .pick_file()
+            .ok_or_else(|| RecallError::validation("User cancelled file open dialog"))?,
     };
+    let text = std::fs::read_to_string(&path)?;
 
-    Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
+    let summary = service::calendar::import(&conn, &user_id, &text, project_id.as_deref())?;
+    state.scheduler.wake();
+
+    respond_with_message(
+        format!(
+            "Imported {} new and {} updated events",
+            summary.created, summary.updated
+        ),
+        summary,
+    )
 }
@@ -0,0 +1,464 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+
+use crate::error::RecallError;
+use crate::time_zone;
+
+// Longest content line we write, in octets, before folding (RFC 5545 section 3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+pub const PRODID: &str = "-//Recall//Recall Calendar//EN";
+
+// A DTSTART/DTEND/EXDATE/RECURRENCE-ID value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcsTime {
+    // VALUE=DATE, as used by all-day events
+    Date(NaiveDate),
+    // A fixed instant, written with a trailing Z
+    Utc(i64),
+    // Wall-clock time in a named zone, or floating (local to whoever reads it) when None
+    Local(NaiveDateTime, Option<Tz>),
+}
+
+impl IcsTime {
+    // Unix seconds, reading dates and floating times in `floating_zone`
+    pub fn timestamp(&self, floating_zone: Tz) -> i64 {
+        match *self {
+            IcsTime::Date(date) => time_zone::instant(floating_zone, date.and_time(NaiveTime::MIN)),
+            IcsTime::Utc(ts) => ts,
+            IcsTime::Local(datetime, tz) => {
+                time_zone::instant(tz.unwrap_or(floating_zone), datetime)
+            }
+        }
+    }
+
+    pub fn is_date(&self) -> bool {
+        matches!(self, IcsTime::Date(_))
+    }
+
+    // The IANA zone of a zoned wall-clock time
+    pub fn zone(&self) -> Option<Tz> {
+        match *self {
+            IcsTime::Local(_, tz) => tz,
+            _ => None,
+        }
+    }
+}
+
+// One VEVENT. Overridden occurrences of a series share its UID and carry a recurrence_id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsEvent {
+    pub uid: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub categories: Vec<String>,
+    pub start: IcsTime,
+    pub end: Option<IcsTime>,
+    pub rrule: Option<String>,
+    pub exdates: Vec<IcsTime>,
+    pub recurrence_id: Option<IcsTime>,
+    pub cancelled: bool,
+    pub last_modified: Option<i64>,
+}
+
+impl IcsEvent {
+    pub fn new(uid: impl Into<String>, start: IcsTime) -> Self {
+        IcsEvent {
+            uid: uid.into(),
+            summary: None,
+            description: None,
+            location: None,
+            categories: Vec::new(),
+            start,
+            end: None,
+            rrule: None,
+            exdates: Vec::new(),
+            recurrence_id: None,
+            cancelled: false,
+            last_modified: None,
+        }
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+// Append a content line, folded so no physical line exceeds 75 octets
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+    out.push_str("\r\n");
+}
+
+fn format_utc(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+// ";VALUE=DATE:20250101", ":20250101T090000Z" or ";TZID=Europe/Paris:20250101T100000"
+fn format_time(time: &IcsTime) -> String {
+    match time {
+        IcsTime::Date(date) => format!(";VALUE=DATE:{}", date.format("%Y%m%d")),
+        IcsTime::Utc(ts) => format!(":{}", format_utc(*ts)),
+        IcsTime::Local(datetime, Some(tz)) => {
+            format!(";TZID={}:{}", tz.name(), datetime.format("%Y%m%dT%H%M%S"))
+        }
+        IcsTime::Local(datetime, None) => format!(":{}", datetime.format("%Y%m%dT%H%M%S")),
+    }
+}
+
+fn format_time_list(times: &[IcsTime]) -> String {
+    let Some(first) = times.first() else {
+        return String::new();
+    };
+    let head = format_time(first);
+    let (params, _) = head.split_once(':').unwrap_or(("", ""));
+    let values: Vec<String> = times
+        .iter()
+        .map(|t| {
+            format_time(t)
+                .split_once(':')
+                .map_or(String::new(), |(_, v)| v.to_string())
+        })
+        .collect();
+    format!("{}:{}", params, values.join(","))
+}
+
+// Serialize events as a VCALENDAR with CRLF line endings
+pub fn write_calendar(events: &[IcsEvent]) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, &format!("PRODID:{}", PRODID));
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+
+    let stamp = format_utc(Utc::now().timestamp());
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", escape_text(&event.uid)));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        if let Some(modified) = event.last_modified {
+            push_line(&mut out, &format!("LAST-MODIFIED:{}", format_utc(modified)));
+        }
+        if let Some(ref recurrence_id) = event.recurrence_id {
+            push_line(
+                &mut out,
+                &format!("RECURRENCE-ID{}", format_time(recurrence_id)),
+            );
+        }
+        push_line(&mut out, &format!("DTSTART{}", format_time(&event.start)));
+        if let Some(ref end) = event.end {
+            push_line(&mut out, &format!("DTEND{}", format_time(end)));
+        }
+        if let Some(ref rrule) = event.rrule {
+            push_line(&mut out, &format!("RRULE:{}", rrule));
+        }
+        if !event.exdates.is_empty() {
+            push_line(
+                &mut out,
+                &format!("EXDATE{}", format_time_list(&event.exdates)),
+            );
+        }
+        if let Some(ref summary) = event.summary {
+            push_line(&mut out, &format!("SUMMARY:{}", escape_text(summary)));
+        }
+        if let Some(ref description) = event.description {
+            push_line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape_text(description)),
+            );
+        }
+        if let Some(ref location) = event.location {
+            push_line(&mut out, &format!("LOCATION:{}", escape_text(location)));
+        }
+        if !event.categories.is_empty() {
+            let categories: Vec<String> = event.categories.iter().map(|c| escape_text(c)).collect();
+            push_line(&mut out, &format!("CATEGORIES:{}", categories.join(",")));
+        }
+        if event.cancelled {
+            push_line(&mut out, "STATUS:CANCELLED");
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+// A parsed content line: NAME;PARAM=VALUE:value
+struct ContentLine {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl ContentLine {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// Join folded lines back together
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(rest) = raw.strip_prefix([' ', '\t'])
+            && let Some(last) = lines.last_mut()
+        {
+            last.push_str(rest);
+            continue;
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+fn parse_content_line(line: &str) -> Option<ContentLine> {
+    // The value starts at the first colon outside a quoted parameter value
+    let mut in_quotes = false;
+    let mut split = None;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                split = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let split = split?;
+    let (head, value) = (&line[..split], &line[split + 1..]);
+
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| {
+            (
+                k.trim().to_ascii_uppercase(),
+                v.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+
+    Some(ContentLine {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+fn parse_time_value(value: &str, params: &ContentLine) -> Option<IcsTime> {
+    let value = value.trim();
+    if params.param("VALUE") == Some("DATE") || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(IcsTime::Date);
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .ok()
+            .map(|t| IcsTime::Utc(t.and_utc().timestamp()));
+    }
+    let datetime = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    // Zones outside the IANA database (e.g. Windows names) are read as local time
+    let tz = params.param("TZID").and_then(|id| id.parse::<Tz>().ok());
+    Some(IcsTime::Local(datetime, tz))
+}
+
+// A DURATION such as P1D, PT1H30M or P2W, in seconds. None if malformed or out of range.
+fn parse_duration(value: &str) -> Option<i64> {
+    let (sign, rest) = match value.trim().strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.trim().trim_start_matches('+')),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let mut total: i64 = 0;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                let seconds = n.checked_mul(match unit {
+                    'W' => 7 * 24 * 3600,
+                    'D' => 24 * 3600,
+                    'H' => 3600,
+                    'M' => 60,
+                    'S' => 1,
+                    _ => return None,
+                })?;
+                total = total.checked_add(seconds)?;
+            }
+        }
+    }
+    Some(sign * total)
+}
+
+// None when the result falls outside the representable range
+fn add_seconds(time: IcsTime, seconds: i64) -> Option<IcsTime> {
+    match time {
+        IcsTime::Date(date) => Duration::try_days(seconds / (24 * 3600))
+            .and_then(|days| date.checked_add_signed(days))
+            .map(IcsTime::Date),
+        IcsTime::Utc(ts) => ts.checked_add(seconds).map(IcsTime::Utc),
+        IcsTime::Local(datetime, tz) => Duration::try_seconds(seconds)
+            .and_then(|duration| datetime.checked_add_signed(duration))
+            .map(|datetime| IcsTime::Local(datetime, tz)),
+    }
+}
+
+// Split a TEXT list on the commas escape_text left unescaped
+fn split_text_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                item.push(c);
+                if let Some(next) = chars.next() {
+                    item.push(next);
+                }
+            }
+            ',' => items.push(std::mem::take(&mut item)),
+            _ => item.push(c),
+        }
+    }
+    items.push(item);
+    items
+}
+
+// Read the VEVENTs of an iCalendar file. Events without a UID or DTSTART are skipped.
+pub fn parse_calendar(text: &str) -> Result<Vec<IcsEvent>, RecallError> {
+    let lines = unfold(text.trim_start_matches('\u{feff}'));
+    if !lines
+        .first()
+        .is_some_and(|l| l.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err(RecallError::validation(
+            "Not an iCalendar file: missing BEGIN:VCALENDAR",
+        ));
+    }
+
+    let mut events = Vec::new();
+    // Properties of the VEVENT being read; None outside one
+    let mut current: Option<Vec<ContentLine>> = None;
+    // Depth of components nested in the VEVENT, such as VALARM
+    let mut nested = 0;
+
+    for line in &lines {
+        let Some(line) = parse_content_line(line) else {
+            continue;
+        };
+        match (line.name.as_str(), line.value.to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") if current.is_none() => current = Some(Vec::new()),
+            ("BEGIN", _) if current.is_some() => nested += 1,
+            ("END", "VEVENT") if nested == 0 => {
+                if let Some(event) = current.take().and_then(build_event) {
+                    events.push(event);
+                }
+            }
+            ("END", _) if nested > 0 => nested -= 1,
+            _ => {
+                if nested == 0
+                    && let Some(props) = current.as_mut()
+                {
+                    props.push(line);
+                }
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+fn build_event(props: Vec<ContentLine>) -> Option<IcsEvent> {
+    let find = |name: &str| props.iter().find(|p| p.name == name);
+    let uid = find("UID")?.value.trim().to_string();
+    let start_line = find("DTSTART")?;
+    let start = parse_time_value(&start_line.value, start_line)?;
+
+    let mut event = IcsEvent::new(unescape_text(&uid), start);
+    event.summary = find("SUMMARY").map(|p| unescape_text(&p.value));
+    event.description = find("DESCRIPTION").map(|p| unescape_text(&p.value));
+    event.location = find("LOCATION").map(|p| unescape_text(&p.value));
+    event.end = match (find("DTEND"), find("DURATION")) {
+        (Some(end), _) => parse_time_value(&end.value, end),
+        // A DURATION that cannot be read or applied leaves the event unusable
+        (None, Some(duration)) => Some(add_seconds(start, parse_duration(&duration.value)?)?),
+        (None, None) => None,
+    };
+    event.rrule = find("RRULE").map(|p| p.value.trim().to_string());
+    event.recurrence_id = find("RECURRENCE-ID").and_then(|p| parse_time_value(&p.value, p));
+    event.cancelled = find("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED"));
+    event.last_modified = find("LAST-MODIFIED")
+        .and_then(|p| parse_time_value(&p.value, p))
+        .map(|t| t.timestamp(Tz::UTC));
+    for prop in props.iter().filter(|p| p.name == "EXDATE") {
+        event.exdates.extend(
+            prop.value
+                .split(',')
+                .filter_map(|value| parse_time_value(value, prop)),
+        );
+    }
+    for prop in props.iter().filter(|p| p.name == "CATEGORIES") {
+        event.categories.extend(
+            split_text_list(&prop.value)
+                .iter()
+                .map(|c| unescape_text(c.trim())),
+        );
+    }
+
+    Some(event)
+}
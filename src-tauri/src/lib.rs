@@ -1,8 +1,28 @@
-mod areas;
-mod auth;
-mod events;
-mod projects;
-mod resources;
+pub mod archive;
+pub mod areas;
+pub mod auth;
+pub mod availability;
+pub mod blobs;
+pub mod db;
+pub mod error;
+pub mod events;
+pub mod ical;
+pub mod links;
+pub mod migrations;
+pub mod notes;
+pub mod projects;
+pub mod recurrence;
+pub mod reminders;
+pub mod resources;
+pub mod revisions;
+pub mod scheduler;
+pub mod search;
+pub mod service;
+pub mod tags;
+pub mod tasks;
+pub mod time_zone;
+pub mod trash;
+pub mod workflows;
 
 use anyhow::Result;
 use chrono::Utc;
@@ -12,6 +32,9 @@ use r2d2_sqlite::SqliteConnectionManager;
 use rand::Rng;
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::error::RecallError;
 
 // Trait for safe access to id field
 pub trait HasId {
@@ -43,13 +66,14 @@ pub fn generate_id() -> String {
 
 pub struct AppState {
     pub pool: Pool<SqliteConnectionManager>,
+    pub scheduler: scheduler::Scheduler,
 }
 
 // Helper function to get user_id from token
 pub fn get_user_id_from_token(
     token: &str,
     conn: &rusqlite::Connection,
-) -> Result<String, tauri::Error> {
+) -> Result<String, RecallError> {
     let now = Utc::now().timestamp();
     let user_id: String = conn
         .query_row(
@@ -57,15 +81,15 @@ pub fn get_user_id_from_token(
             params![token, now],
             |row| row.get(0),
         )
-        .map_err(|_| tauri::Error::Anyhow(anyhow::anyhow!("Invalid or expired token")))?;
+        .map_err(|_| RecallError::unauthenticated("Invalid or expired token"))?;
     Ok(user_id)
 }
 
-// Helper function to insert a model instance with retry logic for UNIQUE constraint failures
+// Helper function to insert a model instance, retrying with a fresh id when the id is taken
 pub fn try_insert_thing<T: exemplar::Model + HasId>(
     thing: &mut T,
     conn: &rusqlite::Connection,
-) -> Result<String, tauri::Error> {
+) -> Result<String, RecallError> {
     let attempts = 3;
     for i in 0..attempts {
         // Only call id_mut() once per loop iteration
@@ -82,28 +106,46 @@ pub fn try_insert_thing<T: exemplar::Model + HasId>(
                 if let Some(id) = id_ref.as_ref() {
                     return Ok(id.clone());
                 } else {
-                    return Err(tauri::Error::Anyhow(anyhow::anyhow!(
-                        "ID is None after insert"
-                    )));
+                    return Err(anyhow::anyhow!("ID is None after insert").into());
                 }
             }
-            Err(e) if e.to_string().contains("UNIQUE constraint failed") => {
+            Err(e) => {
+                let message = e.to_string();
+                let Some(columns) = message.strip_prefix("UNIQUE constraint failed: ") else {
+                    return Err(RecallError::from(e));
+                };
+                let meta = T::metadata();
+                if columns != format!("{}.id", meta.table) {
+                    // Another id cannot fix a duplicate value; name the field instead
+                    let fields: Vec<&str> = columns
+                        .split(", ")
+                        .map(|column| column.rsplit('.').next().unwrap_or(column))
+                        .collect();
+                    return Err(RecallError::conflict(format!(
+                        "{} with this {} already exists",
+                        meta.model,
+                        fields.join(" and ")
+                    )));
+                }
                 println!("UNIQUE constraint failed on attempt {}: {}", i + 1, e);
-                continue;
+                // Draw a fresh id next time; retrying the same one can only fail again
+                *thing.id_mut() = None;
             }
-            Err(e) => return Err(tauri::Error::Anyhow(anyhow::Error::from(e))),
         }
     }
-    Err(tauri::Error::Anyhow(anyhow::anyhow!(
-        "Failed to insert thing after {} attempts due to repeated UNIQUE constraint violations",
+    Err(RecallError::conflict(format!(
+        "Failed to insert thing after {} attempts due to repeated id collisions",
         attempts
     )))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run(pool: Pool<SqliteConnectionManager>) {
+    let scheduler_pool = pool.clone();
     tauri::Builder::default()
-        .setup(|app| {
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(move |app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
@@ -111,11 +153,16 @@ pub fn run(pool: Pool<SqliteConnectionManager>) {
                         .build(),
                 )?;
             }
-            tauri::async_runtime::spawn(async move {});
+            app.state::<AppState>()
+                .scheduler
+                .start(app.handle().clone(), scheduler_pool);
 
             Ok(())
         })
-        .manage(AppState { pool })
+        .manage(AppState {
+            pool,
+            scheduler: scheduler::Scheduler::new(),
+        })
         .invoke_handler(tauri::generate_handler![
             auth::signup,
             auth::signin,
@@ -123,6 +170,7 @@ pub fn run(pool: Pool<SqliteConnectionManager>) {
             auth::update_user,
             auth::change_password_with_token,
             auth::delete_session,
+            auth::set_time_zone,
             areas::create_area,
             areas::get_areas,
             areas::get_area_by_id,
@@ -133,18 +181,74 @@ pub fn run(pool: Pool<SqliteConnectionManager>) {
             projects::get_project_by_id,
             projects::update_project,
             projects::move_project,
+            projects::reorder_project,
             projects::delete_project,
+            tasks::create_task,
+            tasks::get_tasks,
+            tasks::get_task_by_id,
+            tasks::update_task,
+            tasks::set_task_done,
+            tasks::reorder_tasks,
+            tasks::delete_task,
+            notes::create_note,
+            notes::get_notes,
+            notes::get_note_by_id,
+            notes::update_note,
+            notes::move_note,
+            notes::delete_note,
+            notes::promote_note_to_resource,
+            notes::promote_note_to_project,
+            archive::archive_area,
+            archive::unarchive_area,
+            archive::archive_project,
+            archive::unarchive_project,
+            archive::get_archive,
+            archive::set_auto_archive,
+            trash::get_trash,
+            trash::restore_item,
+            trash::empty_trash,
+            trash::set_trash_retention,
+            workflows::get_workflow,
+            workflows::set_workflow,
+            workflows::delete_workflow,
+            tags::get_tags,
+            tags::create_tag,
+            tags::update_tag,
+            tags::merge_tags,
+            tags::delete_tag,
+            tags::get_item_tags,
+            tags::set_item_tags,
+            tags::get_tagged_items,
             resources::create_resource,
             resources::get_resources,
+            resources::list_resources,
             resources::get_resource_by_id,
             resources::update_resource,
+            resources::move_resource,
             resources::delete_resource,
-            resources::download_resource_file,
+            revisions::get_resource_revisions,
+            revisions::get_resource_revision,
+            revisions::diff_resource_revisions,
+            revisions::restore_resource_revision,
+            links::get_backlinks,
+            resources::export_resource_file,
+            resources::import_resource_file,
             events::create_event,
             events::get_events,
+            events::query_events,
             events::get_event_by_id,
             events::update_event,
-            events::delete_event
+            events::delete_event,
+            events::update_event_occurrence,
+            events::cancel_event_occurrence,
+            events::export_events_ics,
+            events::import_events_ics,
+            availability::check_event_conflicts,
+            availability::get_free_busy,
+            reminders::get_pending_reminders,
+            reminders::snooze_reminder,
+            reminders::dismiss_reminder,
+            search::search
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, respond};
+use crate::service::{self, Owned};
+use crate::{AppState, get_user_id_from_token};
+
+// A resource whose document has a [[link]] to the item asked about
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Backlink {
+    pub id: String,
+    pub name: String,
+    pub project_id: Option<String>,
+    pub project_title: Option<String>,
+    pub snippet: Option<String>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BacklinksResponse {
+    pub backlinks: Vec<Backlink>,
+}
+
+// Resources linking to an area, project, resource or event
+#[tauri::command]
+pub fn get_backlinks(
+    token: String,
+    kind: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let kind = Owned::parse(&kind)?;
+
+    let backlinks = service::links::backlinks(&conn, &user_id, kind, &id)?;
+
+    respond(BacklinksResponse { backlinks })
+}
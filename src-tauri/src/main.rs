@@ -6,27 +6,6 @@ use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::Connection;
 
-fn create_tables(conn: &Connection) -> Result<()> {
-    let users_schema = include_str!("../sql/users.sql");
-    let sessions_schema = include_str!("../sql/sessions.sql");
-    let areas_schema = include_str!("../sql/areas.sql");
-    let projects_schema = include_str!("../sql/projects.sql");
-    let resources_schema = include_str!("../sql/resources.sql");
-    let events_schema = include_str!("../sql/events.sql");
-    let schema = format!(
-        "{}\n{}\n{}\n{}\n{}\n{}",
-        users_schema,
-        sessions_schema,
-        areas_schema,
-        projects_schema,
-        resources_schema,
-        events_schema
-    );
-
-    conn.execute_batch(&schema)?;
-    Ok(())
-}
-
 fn main() -> Result<()> {
     let current_dir = std::env::current_dir().unwrap();
     let db_path = current_dir.join("recall.db");
@@ -36,11 +15,16 @@ fn main() -> Result<()> {
         std::fs::File::create(&db_path)?;
     }
 
+    // Bring the schema up to date once, before any pooled connection is handed out
+    let mut conn = Connection::open(&db_path)?;
+    recall_lib::db::configure_connection(&mut conn)?;
+    recall_lib::migrations::run_migrations(&mut conn)?;
+    recall_lib::blobs::collect_garbage(&conn)?;
+    drop(conn);
+
     // Use sqlite3 database
-    let manager = SqliteConnectionManager::file(db_path).with_init(move |conn| {
-        let _ = create_tables(conn).map_err(|e| anyhow::anyhow!("Failed to create tables: {}", e));
-        Ok(())
-    });
+    let manager =
+        SqliteConnectionManager::file(db_path).with_init(recall_lib::db::configure_connection);
     let pool = Pool::new(manager).expect("Failed to create pool.");
     recall_lib::run(pool);
     Ok(())
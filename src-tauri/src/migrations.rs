@@ -0,0 +1,474 @@
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::{Connection, OptionalExtension, params};
+use sha2::{Digest, Sha256};
+
+// A single forward-only schema change. `version` is what PRAGMA user_version is set to
+// once the migration has been applied, so versions must be contiguous and never reused.
+// `backfill` runs after `sql` in the same transaction, for data changes SQL cannot express.
+// Backfills are frozen like the SQL files: they see the schema as of their own version,
+// so they carry their own queries instead of calling into code that tracks the latest one.
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+    pub backfill: Option<fn(&Connection) -> Result<()>>,
+}
+
+// Ordered list of every migration. Files in sql/migrations are frozen once released:
+// change the schema by appending a new migration, never by editing an old one.
+// The per-table files in sql/ mirror the resulting schema for the exemplar model checks,
+// so a migration updates them too; tests/migrations.rs compares the two.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("../sql/migrations/0001_initial_schema.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 2,
+        name: "remove_orphaned_rows",
+        sql: include_str!("../sql/migrations/0002_remove_orphaned_rows.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 3,
+        name: "blob_store",
+        sql: include_str!("../sql/migrations/0003_blob_store.sql"),
+        backfill: Some(move_file_data_to_blobs),
+    },
+    Migration {
+        version: 4,
+        name: "drop_resource_file_data",
+        sql: include_str!("../sql/migrations/0004_drop_resource_file_data.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 5,
+        name: "resource_listing_indexes",
+        sql: include_str!("../sql/migrations/0005_resource_listing_indexes.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 6,
+        name: "full_text_search",
+        sql: include_str!("../sql/migrations/0006_full_text_search.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 7,
+        name: "event_recurrence",
+        sql: include_str!("../sql/migrations/0007_event_recurrence.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 8,
+        name: "event_ical_uid",
+        sql: include_str!("../sql/migrations/0008_event_ical_uid.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 9,
+        name: "event_time_zones",
+        sql: include_str!("../sql/migrations/0009_event_time_zones.sql"),
+        backfill: Some(pin_event_time_zones),
+    },
+    Migration {
+        version: 10,
+        name: "event_range_indexes",
+        sql: include_str!("../sql/migrations/0010_event_range_indexes.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 11,
+        name: "reminders",
+        sql: include_str!("../sql/migrations/0011_reminders.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 12,
+        name: "archive",
+        sql: include_str!("../sql/migrations/0012_archive.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 13,
+        name: "trash",
+        sql: include_str!("../sql/migrations/0013_trash.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 14,
+        name: "resource_revisions",
+        sql: include_str!("../sql/migrations/0014_resource_revisions.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 15,
+        name: "tags",
+        sql: include_str!("../sql/migrations/0015_tags.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 16,
+        name: "resource_links",
+        sql: include_str!("../sql/migrations/0016_resource_links.sql"),
+        backfill: Some(index_resource_links),
+    },
+    Migration {
+        version: 17,
+        name: "tasks",
+        sql: include_str!("../sql/migrations/0017_tasks.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 18,
+        name: "notes",
+        sql: include_str!("../sql/migrations/0018_notes.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 19,
+        name: "resource_owners",
+        sql: include_str!("../sql/migrations/0019_resource_owners.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 20,
+        name: "project_positions",
+        sql: include_str!("../sql/migrations/0020_project_positions.sql"),
+        backfill: None,
+    },
+    Migration {
+        version: 21,
+        name: "workflows",
+        sql: include_str!("../sql/migrations/0021_workflows.sql"),
+        backfill: None,
+    },
+];
+
+// Schema version this binary was built for
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+pub fn current_version(conn: &Connection) -> Result<i64> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    Ok(version)
+}
+
+// Bring the database up to the latest schema version.
+// Each migration runs in its own transaction together with the user_version bump,
+// so a failure leaves the database at the last fully applied version.
+pub fn run_migrations(conn: &mut Connection) -> Result<()> {
+    // Table rebuilds need foreign keys off, and the pragma is ignored inside a transaction,
+    // so turn them off around the whole run and verify integrity per migration instead.
+    conn.pragma_update(None, "foreign_keys", false)?;
+    let result = apply_pending(conn);
+    conn.pragma_update(None, "foreign_keys", true)?;
+    result
+}
+
+fn apply_pending(conn: &mut Connection) -> Result<()> {
+    let current = current_version(conn)?;
+    let latest = latest_version();
+
+    if current > latest {
+        bail!(
+            "Database schema version {} is newer than this build supports ({}). Please update Recall.",
+            current,
+            latest
+        );
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql).with_context(|| {
+            format!(
+                "Failed to apply migration {} ({})",
+                migration.version, migration.name
+            )
+        })?;
+        if let Some(backfill) = migration.backfill {
+            backfill(&tx).with_context(|| {
+                format!(
+                    "Failed to backfill migration {} ({})",
+                    migration.version, migration.name
+                )
+            })?;
+        }
+
+        let violations: i64 =
+            tx.query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| {
+                row.get(0)
+            })?;
+        if violations > 0 {
+            bail!(
+                "Migration {} ({}) left {} foreign key violations",
+                migration.version,
+                migration.name,
+                violations
+            );
+        }
+
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+        println!(
+            "applied migration {} ({})",
+            migration.version, migration.name
+        );
+    }
+
+    Ok(())
+}
+
+// Migration 3: decode the base64 resources.file_data payloads into the blob store.
+// Blobs are written with the version 3 blobs SQL rather than through crate::blobs, which
+// follows the current schema.
+fn move_file_data_to_blobs(conn: &Connection) -> Result<()> {
+    let mut stmt =
+        conn.prepare("SELECT id, file_data FROM resources WHERE file_data IS NOT NULL")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let now = Utc::now().timestamp();
+    for (id, file_data) in rows {
+        // Keep payloads that were never valid base64 as-is rather than losing them
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&file_data)
+            .unwrap_or_else(|_| file_data.into_bytes());
+        let hash = format!("{:x}", Sha256::digest(&bytes));
+        conn.execute(
+            "INSERT OR IGNORE INTO blobs (hash, data, size, ref_count, created_at) VALUES (?1, ?2, ?3, 0, ?4)",
+            params![hash, bytes, bytes.len() as i64, now],
+        )?;
+        conn.execute(
+            "UPDATE resources SET blob_hash = ?1, file_size = ?2 WHERE id = ?3",
+            params![hash, bytes.len() as i64, id],
+        )?;
+    }
+
+    Ok(())
+}
+
+// Migration 9 widens recurrence_end by this, more than a series' last occurrence can move
+// when it is expanded in its zone rather than in UTC
+const RECURRENCE_END_SLACK: i64 = 2 * 24 * 3600;
+
+// Migration 9: times were instants that the frontend read in the machine's zone. Pin timed
+// events to that zone, and turn all-day events and their occurrence keys into floating times.
+fn pin_event_time_zones(conn: &Connection) -> Result<()> {
+    let zone: Tz = iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC);
+    // The wall-clock reading in `zone`, encoded as if it were UTC
+    let floating = |instant: i64| {
+        DateTime::from_timestamp(instant, 0)
+            .unwrap_or_default()
+            .with_timezone(&zone)
+            .naive_local()
+            .and_utc()
+            .timestamp()
+    };
+
+    for table in ["events", "event_overrides"] {
+        conn.execute(
+            &format!("UPDATE {} SET time_zone = ?1 WHERE all_day = 0", table),
+            params![zone.name()],
+        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT rowid, start_time, end_time FROM {} WHERE all_day = 1",
+            table
+        ))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (rowid, start, end) in rows {
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET start_time = ?1, end_time = ?2 WHERE rowid = ?3",
+                    table
+                ),
+                params![floating(start), end.map(floating), rowid],
+            )?;
+        }
+    }
+
+    // Occurrences of all-day series are keyed by their floating starts
+    for table in ["event_exceptions", "event_overrides"] {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT t.rowid, t.occurrence_start FROM {} t
+             JOIN events e ON e.id = t.event_id WHERE e.all_day = 1",
+            table
+        ))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (rowid, key) in rows {
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET occurrence_start = ?1 WHERE rowid = ?2",
+                    table
+                ),
+                params![floating(key), rowid],
+            )?;
+        }
+    }
+
+    // Series now expand in their own zone or over floating readings instead of in UTC, which
+    // moves each occurrence by less than a day. recurrence_end only bounds window queries, so
+    // widening it keeps every occurrence in range without expanding the rules here; the next
+    // save of a series stores its exact end again.
+    conn.execute(
+        "UPDATE events SET recurrence_end = recurrence_end + ?1 WHERE recurrence_end IS NOT NULL",
+        params![RECURRENCE_END_SLACK],
+    )?;
+
+    Ok(())
+}
+
+// Migration 16: index the links in documents written before links were tracked. The parsing
+// and lookups below are how links worked at version 16; service::links has since moved on.
+fn index_resource_links(conn: &Connection) -> Result<()> {
+    let mut stmt =
+        conn.prepare("SELECT id, user_id, content FROM resources WHERE content IS NOT NULL")?;
+    let documents = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let now = Utc::now().timestamp();
+    for (id, user_id, content) in documents {
+        for (kind, title) in document_links(&content) {
+            let target_id = resolve_link(conn, &user_id, kind, &title)?;
+            // A document mentioning itself is not a backlink
+            if kind == "resource" && target_id.as_deref() == Some(id.as_str()) {
+                continue;
+            }
+            conn.execute(
+                "INSERT OR IGNORE INTO resource_links (source_id, target_kind, target_title, target_id, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, kind, title, target_id, now],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// Every [[link]] in a document as (kind, title). TipTap JSON documents are searched text
+// node by text node, anything else as plain text.
+fn document_links(content: &str) -> Vec<(&'static str, String)> {
+    fn text_nodes<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(serde_json::Value::String(text)) = map.get("text") {
+                    out.push(text);
+                }
+                if let Some(children) = map.get("content") {
+                    text_nodes(children, out);
+                }
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|item| text_nodes(item, out)),
+            _ => {}
+        }
+    }
+
+    let document: Option<serde_json::Value> = serde_json::from_str(content)
+        .ok()
+        .filter(|value: &serde_json::Value| value.is_object() || value.is_array());
+    let mut texts = Vec::new();
+    match &document {
+        Some(value) => text_nodes(value, &mut texts),
+        None => texts.push(content),
+    }
+
+    let mut links = Vec::new();
+    for text in texts {
+        let mut from = 0;
+        while let Some(start) = text[from..].find("[[").map(|i| from + i) {
+            let Some(end) = text[start + 2..].find("]]").map(|i| start + 2 + i) else {
+                break;
+            };
+            let inner = &text[start + 2..end];
+            if inner.contains(['[', ']', '\n']) {
+                from = start + 1;
+                continue;
+            }
+
+            // `[[project:Title]]` and the like name the kind; `[[Title]]` is a resource
+            let (kind, title) = match inner.split_once(':') {
+                Some((kind, title)) => match kind.trim().to_lowercase().as_str() {
+                    "area" => ("area", title),
+                    "project" => ("project", title),
+                    "resource" => ("resource", title),
+                    "event" => ("event", title),
+                    _ => ("resource", inner),
+                },
+                None => ("resource", inner),
+            };
+            let title = title.trim();
+            if !title.is_empty() && title.chars().count() <= 200 {
+                links.push((kind, title.to_string()));
+            }
+            from = end + 2;
+        }
+    }
+    links
+}
+
+// The user's item of `kind` called `title`, the oldest if several are. Items deleted, or
+// under a deleted area or project, are in the trash and not linked to.
+fn resolve_link(
+    conn: &Connection,
+    user_id: &str,
+    kind: &str,
+    title: &str,
+) -> Result<Option<String>> {
+    let sql = match kind {
+        "area" => {
+            "SELECT t.id FROM areas t
+             WHERE t.user_id = ?1 AND t.name = ?2 COLLATE NOCASE AND t.deleted_at IS NULL
+             ORDER BY t.created_at LIMIT 1"
+        }
+        "project" => {
+            "SELECT t.id FROM projects t JOIN areas a ON a.id = t.area_id
+             WHERE t.user_id = ?1 AND t.title = ?2 COLLATE NOCASE
+             AND t.deleted_at IS NULL AND a.deleted_at IS NULL
+             ORDER BY t.created_at LIMIT 1"
+        }
+        "event" => {
+            "SELECT t.id FROM events t
+             WHERE t.user_id = ?1 AND t.title = ?2 COLLATE NOCASE AND t.deleted_at IS NULL
+             ORDER BY t.created_at LIMIT 1"
+        }
+        _ => {
+            "SELECT t.id FROM resources t
+             LEFT JOIN projects p ON p.id = t.project_id LEFT JOIN areas a ON a.id = p.area_id
+             WHERE t.user_id = ?1 AND t.name = ?2 COLLATE NOCASE
+             AND t.deleted_at IS NULL AND p.deleted_at IS NULL AND a.deleted_at IS NULL
+             ORDER BY t.created_at LIMIT 1"
+        }
+    };
+    Ok(conn
+        .query_row(sql, params![user_id, title], |row| row.get(0))
+        .optional()?)
+}
@@ -0,0 +1,226 @@
+use exemplar::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, parse_request, respond, respond_with_message};
+use crate::projects::ProjectResponse;
+use crate::resources::ResourceResponse;
+use crate::service;
+use crate::{AppState, HasId, get_user_id_from_token};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Model)]
+#[table("notes")]
+#[check("../sql/notes.sql")]
+pub struct Note {
+    pub id: Option<String>,
+    pub user_id: String,
+    pub project_id: Option<String>,
+    pub area_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub status: String, // "Inbox", "Draft" or "Final"
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl HasId for Note {
+    fn id_mut(&mut self) -> &mut Option<String> {
+        &mut self.id
+    }
+}
+
+// Set at most one of project_id and area_id; with neither the note stays in the inbox
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateNoteRequest {
+    pub project_id: Option<String>,
+    pub area_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub status: Option<String>, // Inbox when not given
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateNoteRequest {
+    pub project_id: Option<String>,
+    pub area_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub status: String,
+}
+
+// Filters for listing notes; they combine with AND
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct NoteQuery {
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    // Notes of the area itself and of its projects
+    #[serde(default)]
+    pub area_id: Option<String>,
+    // Only notes belonging to neither a project nor an area
+    #[serde(default)]
+    pub unattached: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct NoteInfo {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub project_title: Option<String>,
+    pub area_id: Option<String>,
+    pub area_name: Option<String>, // The note's area, or its project's
+    pub title: String,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub status: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NoteResponse {
+    pub note: Option<NoteInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotesResponse {
+    pub notes: Vec<NoteInfo>,
+}
+
+#[tauri::command]
+pub fn create_note(
+    token: String,
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: CreateNoteRequest = parse_request(&json)?;
+
+    let note = service::notes::create(&conn, &user_id, request)?;
+
+    respond(NoteResponse { note: Some(note) })
+}
+
+// Notes matching a NoteQuery given as JSON, most recently updated first
+#[tauri::command]
+pub fn get_notes(
+    token: String,
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let query: NoteQuery = parse_request(&json)?;
+
+    let notes = service::notes::list(&conn, &user_id, &query)?;
+
+    respond(NotesResponse { notes })
+}
+
+#[tauri::command]
+pub fn get_note_by_id(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let note = service::notes::get(&conn, &user_id, &id)?;
+
+    respond(NoteResponse { note: Some(note) })
+}
+
+#[tauri::command]
+pub fn update_note(
+    token: String,
+    id: String,
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: UpdateNoteRequest = parse_request(&json)?;
+
+    let note = service::notes::update(&conn, &user_id, &id, request)?;
+
+    respond(NoteResponse { note: Some(note) })
+}
+
+// Change only the status, as when dragging a note between columns
+#[tauri::command(rename_all = "snake_case")]
+pub fn move_note(
+    token: String,
+    id: String,
+    new_status: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let note = service::notes::move_to(&conn, &user_id, &id, &new_status)?;
+
+    respond(NoteResponse { note: Some(note) })
+}
+
+#[tauri::command]
+pub fn delete_note(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    service::notes::delete(&conn, &user_id, &id)?;
+
+    respond_with_message("Note deleted successfully", NoteResponse { note: None })
+}
+
+// Turn the note into a resource of project_id, or of wherever the note is
+#[tauri::command(rename_all = "snake_case")]
+pub fn promote_note_to_resource(
+    token: String,
+    id: String,
+    project_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let resource =
+        service::notes::promote_to_resource(&conn, &user_id, &id, project_id.as_deref())?;
+
+    respond_with_message(
+        "Note turned into a resource",
+        ResourceResponse {
+            resource: Some(resource),
+        },
+    )
+}
+
+// Turn the note into a project of area_id, or of the note's own area
+#[tauri::command(rename_all = "snake_case")]
+pub fn promote_note_to_project(
+    token: String,
+    id: String,
+    area_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let project = service::notes::promote_to_project(&conn, &user_id, &id, area_id.as_deref())?;
+    state.scheduler.wake();
+
+    respond_with_message(
+        "Note turned into a project",
+        ProjectResponse {
+            project: Some(project),
+        },
+    )
+}
@@ -0,0 +1,562 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use chrono_tz::Tz;
+
+use crate::error::RecallError;
+use crate::time_zone;
+
+// Longest COUNT we accept, so a series' last occurrence can always be worked out
+pub const MAX_COUNT: u32 = 10_000;
+
+// Periods in a row that may produce no occurrence before expansion gives up, e.g. for
+// FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30
+const MAX_EMPTY_PERIODS: u32 = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+// A BYDAY entry such as MO, 2TU or -1FR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+// When a series ends. A bare date is a calendar day in the series' own zone, or in the
+// wall clock of a floating series (RFC 5545 section 3.3.10).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    Instant(i64),
+    Date(NaiveDate),
+}
+
+// The subset of an RFC 5545 RRULE we support: FREQ, INTERVAL, COUNT, UNTIL, BYDAY,
+// BYMONTHDAY and BYMONTH. Weeks start on Monday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+}
+
+fn invalid(message: impl Into<String>) -> RecallError {
+    RecallError::validation(format!("Invalid recurrence rule: {}", message.into()))
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    match code {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, RecallError> {
+    value
+        .parse()
+        .map_err(|_| invalid(format!("{} must be a number", key)))
+}
+
+// UNTIL as a UTC timestamp, or a bare date covering the whole of that day
+fn parse_until(value: &str) -> Result<Until, RecallError> {
+    let value = value.trim_end_matches('Z');
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Ok(Until::Instant(datetime.and_utc().timestamp()));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Ok(Until::Date(date));
+    }
+    Err(invalid("UNTIL must look like 20250131 or 20250131T090000Z"))
+}
+
+impl RecurrenceRule {
+    // Parse an RRULE value, with or without the leading "RRULE:"
+    pub fn parse(rule: &str) -> Result<Self, RecallError> {
+        let rule = rule.trim();
+        let rule = rule
+            .strip_prefix("RRULE:")
+            .or_else(|| rule.strip_prefix("rrule:"))
+            .unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = Vec::new();
+        let mut by_month_day = Vec::new();
+        let mut by_month = Vec::new();
+
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected KEY=VALUE, got {}", part)))?;
+            let key = key.trim().to_ascii_uppercase();
+            let value = value.trim().to_ascii_uppercase();
+
+            match key.as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid(format!("unsupported FREQ {}", value))),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = parse_number(&key, &value)?;
+                    if interval == 0 {
+                        return Err(invalid("INTERVAL must be at least 1"));
+                    }
+                }
+                "COUNT" => {
+                    let n: u32 = parse_number(&key, &value)?;
+                    if n == 0 || n > MAX_COUNT {
+                        return Err(invalid(format!(
+                            "COUNT must be between 1 and {}",
+                            MAX_COUNT
+                        )));
+                    }
+                    count = Some(n);
+                }
+                "UNTIL" => until = Some(parse_until(&value)?),
+                "BYDAY" => {
+                    for item in value.split(',') {
+                        let split = item.len().saturating_sub(2);
+                        let (ordinal, code) = item.split_at(split);
+                        let weekday = parse_weekday(code)
+                            .ok_or_else(|| invalid(format!("unknown weekday {}", item)))?;
+                        let ordinal = if ordinal.is_empty() {
+                            None
+                        } else {
+                            let n: i32 = parse_number(&key, ordinal)?;
+                            if n == 0 || n.abs() > 53 {
+                                return Err(invalid(format!("bad BYDAY ordinal {}", item)));
+                            }
+                            Some(n)
+                        };
+                        by_day.push(WeekdayNum { ordinal, weekday });
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for item in value.split(',') {
+                        let day: i32 = parse_number(&key, item)?;
+                        if day == 0 || day.abs() > 31 {
+                            return Err(invalid(
+                                "BYMONTHDAY must be between 1 and 31, or -31 and -1",
+                            ));
+                        }
+                        by_month_day.push(day);
+                    }
+                }
+                "BYMONTH" => {
+                    for item in value.split(',') {
+                        let month: u32 = parse_number(&key, item)?;
+                        if !(1..=12).contains(&month) {
+                            return Err(invalid("BYMONTH must be between 1 and 12"));
+                        }
+                        by_month.push(month);
+                    }
+                }
+                // Monday is the only week start we expand with
+                "WKST" if value == "MO" => {}
+                _ => return Err(invalid(format!("{} is not supported", part))),
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| invalid("FREQ is required"))?;
+        if count.is_some() && until.is_some() {
+            return Err(invalid("COUNT and UNTIL cannot both be set"));
+        }
+        let ordinals = by_day.iter().any(|d| d.ordinal.is_some());
+        if ordinals && !matches!(frequency, Frequency::Monthly | Frequency::Yearly) {
+            return Err(invalid(
+                "numbered BYDAY values need FREQ=MONTHLY or FREQ=YEARLY",
+            ));
+        }
+        if !by_month_day.is_empty() && frequency == Frequency::Weekly {
+            return Err(invalid("BYMONTHDAY cannot be used with FREQ=WEEKLY"));
+        }
+
+        Ok(RecurrenceRule {
+            frequency,
+            interval,
+            count,
+            until,
+            by_day,
+            by_month_day,
+            by_month,
+        })
+    }
+
+    // Occurrence starts from `start` onwards, in order. `start` is always the first.
+    // Wall-clock times are read in UTC; see iter_from_in.
+    pub fn iter_from(&self, start: NaiveDateTime) -> Occurrences<'_> {
+        self.iter_from_in(Tz::UTC, start)
+    }
+
+    // Occurrences keep the wall-clock time of `start` in `tz`, across DST changes.
+    // An UNTIL instant is compared against each occurrence's instant in `tz`, and an UNTIL
+    // date against its date there.
+    pub fn iter_from_in(&self, tz: Tz, start: NaiveDateTime) -> Occurrences<'_> {
+        Occurrences {
+            rule: self,
+            tz,
+            start,
+            period: 0,
+            pending: VecDeque::from([start]),
+            emitted: 0,
+            empty_periods: 0,
+            done: false,
+        }
+    }
+
+    // Occurrence starts of the series beginning at `start` that fall within [from, to]
+    pub fn occurrences_between(&self, start: i64, from: i64, to: i64, limit: usize) -> Vec<i64> {
+        self.occurrences_between_in(Tz::UTC, start, from, to, limit)
+    }
+
+    pub fn occurrences_between_in(
+        &self,
+        tz: Tz,
+        start: i64,
+        from: i64,
+        to: i64,
+        limit: usize,
+    ) -> Vec<i64> {
+        self.iter_from_in(tz, time_zone::wall_clock(tz, start))
+            .map(|occurrence| time_zone::instant(tz, occurrence))
+            .skip_while(|&occurrence| occurrence < from)
+            .take_while(|&occurrence| occurrence <= to)
+            .take(limit)
+            .collect()
+    }
+
+    pub fn is_occurrence(&self, start: i64, occurrence: i64) -> bool {
+        self.is_occurrence_in(Tz::UTC, start, occurrence)
+    }
+
+    pub fn is_occurrence_in(&self, tz: Tz, start: i64, occurrence: i64) -> bool {
+        self.occurrences_between_in(tz, start, occurrence, occurrence, 1)
+            .first()
+            .is_some_and(|&found| found == occurrence)
+    }
+
+    // The rule as written into an .ics file for a series in `tz`. UNTIL must be a DATE for
+    // series of all-day events and a UTC time otherwise (RFC 5545 section 3.3.10).
+    pub fn to_ical(&self, date_only: bool, tz: Tz) -> String {
+        let until = match self.until {
+            Some(Until::Instant(instant)) if date_only => {
+                Some(Until::Date(time_zone::day(Tz::UTC, instant)))
+            }
+            Some(Until::Date(date)) if !date_only => {
+                Some(Until::Instant(time_zone::instant(tz, end_of_day(date))))
+            }
+            until => until,
+        };
+        RecurrenceRule {
+            until,
+            ..self.clone()
+        }
+        .to_string()
+    }
+
+    // Latest possible occurrence start, or None when the series never ends
+    pub fn last_occurrence(&self, start: i64) -> Option<i64> {
+        self.last_occurrence_in(Tz::UTC, start)
+    }
+
+    pub fn last_occurrence_in(&self, tz: Tz, start: i64) -> Option<i64> {
+        if self.count.is_some() {
+            return self
+                .iter_from_in(tz, time_zone::wall_clock(tz, start))
+                .last()
+                .map(|last| time_zone::instant(tz, last));
+        }
+        match self.until? {
+            Until::Instant(instant) => Some(instant),
+            Until::Date(date) => Some(time_zone::instant(tz, end_of_day(date))),
+        }
+    }
+
+    // Dates the period `index` periods after the one holding `start` could fall on
+    fn period_dates(&self, start: NaiveDate, index: u32) -> Vec<NaiveDate> {
+        let step = index as i64 * self.interval as i64;
+        let mut dates = match self.frequency {
+            Frequency::Daily => {
+                let date = start + Duration::days(step);
+                let weekday_ok = self.by_day.is_empty()
+                    || self.by_day.iter().any(|d| d.weekday == date.weekday());
+                let day_ok = self.by_month_day.is_empty()
+                    || self
+                        .by_month_day
+                        .iter()
+                        .any(|&d| resolve_month_day(date.year(), date.month(), d) == Some(date));
+                if weekday_ok && day_ok {
+                    vec![date]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let monday = start - Duration::days(start.weekday().num_days_from_monday() as i64)
+                    + Duration::weeks(step);
+                let weekdays: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|d| d.weekday).collect()
+                };
+                weekdays
+                    .into_iter()
+                    .map(|w| monday + Duration::days(w.num_days_from_monday() as i64))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let months = start.year() as i64 * 12 + start.month0() as i64 + step;
+                let (year, month) = (
+                    months.div_euclid(12) as i32,
+                    months.rem_euclid(12) as u32 + 1,
+                );
+                self.month_dates(year, month, start.day())
+            }
+            Frequency::Yearly => {
+                let year = start.year() + step as i32;
+                if self.by_month.is_empty()
+                    && self.by_month_day.is_empty()
+                    && !self.by_day.is_empty()
+                {
+                    // BYDAY ordinals count through the whole year when no month is given
+                    self.year_weekday_dates(year)
+                } else {
+                    // Month days repeat in every month unless BYMONTH narrows them
+                    let months = if !self.by_month.is_empty() {
+                        self.by_month.clone()
+                    } else if !self.by_month_day.is_empty() {
+                        (1..=12).collect()
+                    } else {
+                        vec![start.month()]
+                    };
+                    months
+                        .into_iter()
+                        .flat_map(|month| self.month_dates(year, month, start.day()))
+                        .collect()
+                }
+            }
+        };
+
+        if !self.by_month.is_empty() {
+            dates.retain(|d| self.by_month.contains(&d.month()));
+        }
+        dates.sort();
+        dates.dedup();
+        dates
+    }
+
+    fn month_dates(&self, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+        if !self.by_month_day.is_empty() {
+            return self
+                .by_month_day
+                .iter()
+                .filter_map(|&d| resolve_month_day(year, month, d))
+                .filter(|date| {
+                    self.by_day.is_empty()
+                        || self.by_day.iter().any(|d| d.weekday == date.weekday())
+                })
+                .collect();
+        }
+        if !self.by_day.is_empty() {
+            let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+                return Vec::new();
+            };
+            let days: Vec<NaiveDate> = first
+                .iter_days()
+                .take_while(|d| d.month() == month)
+                .collect();
+            return pick_weekdays(&days, &self.by_day);
+        }
+        NaiveDate::from_ymd_opt(year, month, default_day)
+            .into_iter()
+            .collect()
+    }
+
+    fn year_weekday_dates(&self, year: i32) -> Vec<NaiveDate> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+            return Vec::new();
+        };
+        let days: Vec<NaiveDate> = first.iter_days().take_while(|d| d.year() == year).collect();
+        pick_weekdays(&days, &self.by_day)
+    }
+}
+
+// Last second of a date's wall-clock day
+fn end_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap())
+}
+
+// Day `day` of a month, counting from the end when negative
+fn resolve_month_day(year: i32, month: u32, day: i32) -> Option<NaiveDate> {
+    if day > 0 {
+        return NaiveDate::from_ymd_opt(year, month, day as u32);
+    }
+    let next_month = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }?;
+    let date = next_month + Duration::days(day as i64);
+    (date.month() == month).then_some(date)
+}
+
+// Days from `days` matching the BYDAY entries, honouring ordinals like 2TU or -1FR
+fn pick_weekdays(days: &[NaiveDate], by_day: &[WeekdayNum]) -> Vec<NaiveDate> {
+    let mut picked = Vec::new();
+    for entry in by_day {
+        let matching: Vec<NaiveDate> = days
+            .iter()
+            .copied()
+            .filter(|d| d.weekday() == entry.weekday)
+            .collect();
+        match entry.ordinal {
+            None => picked.extend(matching),
+            Some(n) if n > 0 => picked.extend(matching.get(n as usize - 1)),
+            Some(n) => picked.extend(
+                matching
+                    .len()
+                    .checked_sub(n.unsigned_abs() as usize)
+                    .and_then(|i| matching.get(i)),
+            ),
+        }
+    }
+    picked
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        match self.until {
+            Some(Until::Instant(instant)) => {
+                if let Some(until) = DateTime::from_timestamp(instant, 0) {
+                    write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+                }
+            }
+            Some(Until::Date(date)) => write!(f, ";UNTIL={}", date.format("%Y%m%d"))?,
+            None => {}
+        }
+        if !self.by_month.is_empty() {
+            let months: Vec<String> = self.by_month.iter().map(|m| m.to_string()).collect();
+            write!(f, ";BYMONTH={}", months.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(|d| d.to_string()).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|d| match d.ordinal {
+                    Some(n) => format!("{}{}", n, weekday_code(d.weekday)),
+                    None => weekday_code(d.weekday).to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+// Iterator over a rule's occurrence starts, see RecurrenceRule::iter_from
+pub struct Occurrences<'a> {
+    rule: &'a RecurrenceRule,
+    tz: Tz,
+    start: NaiveDateTime,
+    period: u32,
+    pending: VecDeque<NaiveDateTime>,
+    emitted: u32,
+    empty_periods: u32,
+    done: bool,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<NaiveDateTime> {
+        while !self.done {
+            if let Some(next) = self.pending.pop_front() {
+                if self.rule.count.is_some_and(|count| self.emitted >= count)
+                    || self.rule.until.is_some_and(|until| match until {
+                        Until::Instant(instant) => time_zone::instant(self.tz, next) > instant,
+                        Until::Date(date) => next.date() > date,
+                    })
+                {
+                    self.done = true;
+                    break;
+                }
+                self.emitted += 1;
+                return Some(next);
+            }
+
+            let time = self.start.time();
+            let candidates: Vec<NaiveDateTime> = self
+                .rule
+                .period_dates(self.start.date(), self.period)
+                .into_iter()
+                .map(|date| date.and_time(time))
+                // The start was already produced; anything before it is not part of the series
+                .filter(|candidate| *candidate > self.start)
+                .collect();
+            self.period += 1;
+
+            if candidates.is_empty() {
+                self.empty_periods += 1;
+                if self.empty_periods > MAX_EMPTY_PERIODS {
+                    self.done = true;
+                }
+            } else {
+                self.empty_periods = 0;
+                self.pending.extend(candidates);
+            }
+        }
+        None
+    }
+}
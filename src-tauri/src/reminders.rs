@@ -0,0 +1,89 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, parse_request, respond, respond_with_message};
+use crate::service;
+use crate::{AppState, get_user_id_from_token};
+
+// A reminder for one occurrence of an event, or for a project's end date.
+// `due_at` is when it was, or will next be, shown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReminderInfo {
+    pub user_id: String,
+    pub kind: String, // "event" or "project"
+    pub source_id: String,
+    pub occurrence_start: i64,
+    pub minutes_before: i64,
+    pub title: String,
+    pub due_at: i64,
+}
+
+// Identifies a reminder that has been shown, for snoozing or dismissing it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReminderKey {
+    pub kind: String,
+    pub source_id: String,
+    pub occurrence_start: i64,
+    pub minutes_before: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemindersResponse {
+    pub reminders: Vec<ReminderInfo>,
+}
+
+// Reminders already shown that are still waiting for the user, e.g. after a restart
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_pending_reminders(
+    token: String,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let reminders = service::reminders::pending(&conn, &user_id)?;
+
+    respond(RemindersResponse { reminders })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn snooze_reminder(
+    token: String,
+    json: String,
+    minutes: i64,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let key: ReminderKey = parse_request(&json)?;
+
+    service::reminders::snooze(&conn, &user_id, &key, minutes, Utc::now().timestamp())?;
+    state.scheduler.wake();
+
+    respond_with_message(
+        "Reminder snoozed",
+        RemindersResponse {
+            reminders: Vec::new(),
+        },
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn dismiss_reminder(
+    token: String,
+    json: String,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let key: ReminderKey = parse_request(&json)?;
+
+    service::reminders::dismiss(&conn, &user_id, &key)?;
+
+    respond_with_message(
+        "Reminder dismissed",
+        RemindersResponse {
+            reminders: Vec::new(),
+        },
+    )
+}
@@ -1,11 +1,12 @@
 use anyhow::Result;
-use base64::Engine;
-use chrono::Utc;
 use exemplar::Model;
-use rusqlite::params;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::Emitter;
 
-use crate::{AppState, HasId, generate_id, get_user_id_from_token, try_insert_thing};
+use crate::error::{RecallError, parse_request, respond_with_message};
+use crate::service;
+use crate::{AppState, HasId, generate_id, get_user_id_from_token};
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Model)]
 #[table("resources")]
@@ -13,12 +14,14 @@ use crate::{AppState, HasId, generate_id, get_user_id_from_token, try_insert_thi
 pub struct Resource {
     pub id: Option<String>,
     pub user_id: String,
-    pub project_id: String,
+    pub project_id: Option<String>,
+    pub area_id: Option<String>,
     pub name: String,
     pub content: Option<String>,   // For text documents
-    pub file_data: Option<String>, // Base64 encoded file data
     pub file_type: Option<String>, // MIME type or file extension
     pub file_size: Option<i64>,    // Size in bytes
+    pub blob_hash: Option<String>, // SHA-256 of the file contents in blobs
+    pub deleted_at: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -29,9 +32,11 @@ impl HasId for Resource {
     }
 }
 
+// Set at most one of project_id and area_id; with neither the resource stands on its own
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateResourceRequest {
-    pub project_id: String,
+    pub project_id: Option<String>,
+    pub area_id: Option<String>,
     pub name: String,
     pub content: Option<String>,
     pub file_data: Option<String>,
@@ -39,6 +44,8 @@ pub struct CreateResourceRequest {
     pub file_size: Option<i64>,
 }
 
+// Without content the stored document is kept, and without file_data the stored file
+// unless remove_file is set. Listings carry neither, so a rename can leave both out.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateResourceRequest {
     pub name: String,
@@ -46,231 +53,163 @@ pub struct UpdateResourceRequest {
     pub file_data: Option<String>,
     pub file_type: Option<String>,
     pub file_size: Option<i64>,
+    #[serde(default)]
+    pub remove_file: bool,
+}
+
+// A file on disk to turn into a resource
+#[derive(Debug)]
+pub struct ImportResourceRequest {
+    pub project_id: Option<String>,
+    pub area_id: Option<String>,
+    pub path: String,
+    pub name: Option<String>,
+    pub file_type: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResourceResponse {
-    pub success: bool,
-    pub message: Option<String>,
     pub resource: Option<ResourceInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResourceInfo {
     pub id: String,
-    pub project_id: String,
+    pub project_id: Option<String>,
     pub project_name: Option<String>,
+    pub area_id: Option<String>,
+    pub area_name: Option<String>, // The resource's area, or its project's
     pub name: String,
     pub content: Option<String>,
     pub file_data: Option<String>,
     pub file_type: Option<String>,
     pub file_size: Option<i64>,
+    pub blob_hash: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Metadata-only view of a resource for listings; bodies come from get_resource_by_id
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceSummary {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub project_name: Option<String>,
+    pub area_id: Option<String>,
+    pub area_name: Option<String>, // The resource's area, or its project's
+    pub name: String,
+    pub snippet: Option<String>,
+    pub has_file: bool,
+    pub file_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub blob_hash: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-const MAX_FILE_SIZE: i64 = 5 * 1024 * 1024; // 5MB in bytes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceSummaryPage {
+    pub resources: Vec<ResourceSummary>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+// Payload of the resource-export-progress and resource-import-progress events
+#[derive(Debug, Clone, Serialize)]
+pub struct FileTransferProgress {
+    pub resource_id: Option<String>,
+    pub path: String,
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+}
+
+const EXPORT_PROGRESS_EVENT: &str = "resource-export-progress";
+const IMPORT_PROGRESS_EVENT: &str = "resource-import-progress";
 
 #[tauri::command(rename_all = "snake_case")]
 pub fn create_resource(
     token: String,
     json: String,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    // Parse request
-    let request: CreateResourceRequest = serde_json::from_str(&json).map_err(|e| {
-        println!("JSON parse error: {}", e);
-        println!("JSON: {}", json);
-        anyhow::Error::from(e)
-    })?;
-
-    // Validate file size if provided
-    if let Some(size) = request.file_size {
-        if size > MAX_FILE_SIZE {
-            let error_response = ResourceResponse {
-                success: false,
-                message: Some("File size exceeds maximum of 5MB".to_string()),
-                resource: None,
-            };
-            return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-        }
-    }
-
-    // Validate that project belongs to user
-    let project_exists: bool = conn
-        .query_row(
-            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND user_id = ?2)",
-            params![request.project_id, user_id],
-            |row| row.get(0),
-        )
-        .map_err(anyhow::Error::from)?;
-
-    if !project_exists {
-        let error_response = ResourceResponse {
-            success: false,
-            message: Some("Project not found".to_string()),
-            resource: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
-
-    let now = Utc::now().timestamp();
-    let mut resource = Resource {
-        id: None,
-        user_id: user_id.clone(),
-        project_id: request.project_id,
-        name: request.name,
-        content: request.content,
-        file_data: request.file_data,
-        file_type: request.file_type,
-        file_size: request.file_size,
-        created_at: now,
-        updated_at: now,
-    };
+    let request: CreateResourceRequest = parse_request(&json)?;
+    let resource = service::resources::create(&conn, &user_id, request)?;
 
-    let resource_id = try_insert_thing(&mut resource, &conn)?;
-
-    let response = ResourceResponse {
-        success: true,
-        message: Some("Resource created successfully".to_string()),
-        resource: Some(ResourceInfo {
-            id: resource_id,
-            project_id: resource.project_id,
-            project_name: None,
-            name: resource.name,
-            content: resource.content,
-            file_data: resource.file_data,
-            file_type: resource.file_type,
-            file_size: resource.file_size,
-            created_at: resource.created_at,
-            updated_at: resource.updated_at,
-        }),
-    };
-
-    Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
+    respond_with_message(
+        "Resource created successfully",
+        ResourceResponse {
+            resource: Some(resource),
+        },
+    )
 }
 
+// An area_id covers the area's own resources and those of its projects
 #[tauri::command(rename_all = "snake_case")]
 pub fn get_resources(
     token: String,
     project_id: Option<String>,
+    area_id: Option<String>,
+    tag_ids: Option<Vec<String>>,
     state: tauri::State<AppState>,
-) -> Result<Vec<ResourceInfo>, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<Vec<ResourceInfo>, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    let resources = if let Some(pid) = project_id {
-        // Get resources for a specific project
-        let mut stmt = conn
-            .prepare(
-                "SELECT r.id, r.project_id, r.name, r.content, r.file_data, r.file_type, r.file_size, r.created_at, r.updated_at, p.title as project_name
-                 FROM resources r
-                 LEFT JOIN projects p ON r.project_id = p.id
-                 WHERE r.user_id = ?1 AND r.project_id = ?2
-                 ORDER BY r.created_at DESC",
-            )
-            .map_err(anyhow::Error::from)?;
-
-        let rows = stmt
-            .query_map(params![user_id, pid], |row| {
-                Ok(ResourceInfo {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    project_name: row.get(9)?,
-                    name: row.get(2)?,
-                    content: row.get(3)?,
-                    file_data: row.get(4)?,
-                    file_type: row.get(5)?,
-                    file_size: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })
-            .map_err(anyhow::Error::from)?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(anyhow::Error::from)?;
-
-        rows
-    } else {
-        // Get all resources for the user
-        let mut stmt = conn
-            .prepare(
-                "SELECT r.id, r.project_id, r.name, r.content, r.file_data, r.file_type, r.file_size, r.created_at, r.updated_at, p.title as project_name
-                 FROM resources r
-                 LEFT JOIN projects p ON r.project_id = p.id
-                 WHERE r.user_id = ?1
-                 ORDER BY r.created_at DESC",
-            )
-            .map_err(anyhow::Error::from)?;
-
-        let rows = stmt
-            .query_map(params![user_id], |row| {
-                Ok(ResourceInfo {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    project_name: row.get(9)?,
-                    name: row.get(2)?,
-                    content: row.get(3)?,
-                    file_data: row.get(4)?,
-                    file_type: row.get(5)?,
-                    file_size: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            })
-            .map_err(anyhow::Error::from)?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(anyhow::Error::from)?;
-
-        rows
-    };
+    service::resources::list_tagged(
+        &conn,
+        &user_id,
+        project_id.as_deref(),
+        area_id.as_deref(),
+        &tag_ids.unwrap_or_default(),
+    )
+}
+
+// Paged resource listing without file bodies or full content, filtered like get_resources
+#[tauri::command(rename_all = "snake_case")]
+#[allow(clippy::too_many_arguments)]
+pub fn list_resources(
+    token: String,
+    project_id: Option<String>,
+    area_id: Option<String>,
+    tag_ids: Option<Vec<String>>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    state: tauri::State<AppState>,
+) -> Result<ResourceSummaryPage, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
 
-    Ok(resources)
+    service::resources::list_summaries(
+        &conn,
+        &user_id,
+        project_id.as_deref(),
+        area_id.as_deref(),
+        &tag_ids.unwrap_or_default(),
+        sort_by.as_deref(),
+        sort_order.as_deref(),
+        limit,
+        offset,
+    )
 }
 
+// Full resource including content and file body; listings leave the body out
 #[tauri::command(rename_all = "snake_case")]
 pub fn get_resource_by_id(
     token: String,
     id: String,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    let resource: ResourceInfo = conn
-        .query_row(
-            "SELECT r.id, r.project_id, r.name, r.content, r.file_data, r.file_type, r.file_size, r.created_at, r.updated_at, p.title as project_name
-             FROM resources r
-             LEFT JOIN projects p ON r.project_id = p.id
-             WHERE r.id = ?1 AND r.user_id = ?2",
-            params![id, user_id],
-            |row| {
-                Ok(ResourceInfo {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    project_name: row.get(9)?,
-                    name: row.get(2)?,
-                    content: row.get(3)?,
-                    file_data: row.get(4)?,
-                    file_type: row.get(5)?,
-                    file_size: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            },
-        )
-        .map_err(|e| {
-            if e.to_string().contains("Query returned no rows") {
-                tauri::Error::Anyhow(anyhow::anyhow!("Resource not found"))
-            } else {
-                tauri::Error::Anyhow(anyhow::Error::from(e))
-            }
-        })?;
-
-    Ok(serde_json::to_string(&resource).map_err(anyhow::Error::from)?)
+    let resource = service::resources::get(&conn, &user_id, &id)?;
+    Ok(serde_json::to_string(&resource)?)
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -279,95 +218,47 @@ pub fn update_resource(
     id: String,
     json: String,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    // Parse request
-    let request: UpdateResourceRequest = serde_json::from_str(&json).map_err(|e| {
-        println!("JSON parse error: {}", e);
-        println!("JSON: {}", json);
-        anyhow::Error::from(e)
-    })?;
-
-    // Validate file size if provided
-    if let Some(size) = request.file_size {
-        if size > MAX_FILE_SIZE {
-            let error_response = ResourceResponse {
-                success: false,
-                message: Some("File size exceeds maximum of 5MB".to_string()),
-                resource: None,
-            };
-            return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-        }
-    }
+    let request: UpdateResourceRequest = parse_request(&json)?;
+    let resource = service::resources::update(&conn, &user_id, &id, request)?;
 
-    // Check if resource exists and belongs to user
-    let resource_exists: bool = conn
-        .query_row(
-            "SELECT EXISTS(SELECT 1 FROM resources WHERE id = ?1 AND user_id = ?2)",
-            params![id, user_id],
-            |row| row.get(0),
-        )
-        .map_err(anyhow::Error::from)?;
-
-    if !resource_exists {
-        let error_response = ResourceResponse {
-            success: false,
-            message: Some("Resource not found".to_string()),
-            resource: None,
-        };
-        return Ok(serde_json::to_string(&error_response).map_err(anyhow::Error::from)?);
-    }
-
-    let now = Utc::now().timestamp();
-    conn.execute(
-        "UPDATE resources SET name = ?1, content = ?2, file_data = ?3, file_type = ?4, file_size = ?5, updated_at = ?6 WHERE id = ?7 AND user_id = ?8",
-        params![
-            request.name,
-            request.content,
-            request.file_data,
-            request.file_type,
-            request.file_size,
-            now,
-            id,
-            user_id
-        ],
+    respond_with_message(
+        "Resource updated successfully",
+        ResourceResponse {
+            resource: Some(resource),
+        },
     )
-    .map_err(anyhow::Error::from)?;
-
-    // Fetch updated resource
-    let resource: ResourceInfo = conn
-        .query_row(
-            "SELECT r.id, r.project_id, r.name, r.content, r.file_data, r.file_type, r.file_size, r.created_at, r.updated_at, p.title as project_name
-             FROM resources r
-             LEFT JOIN projects p ON r.project_id = p.id
-             WHERE r.id = ?1 AND r.user_id = ?2",
-            params![id, user_id],
-            |row| {
-                Ok(ResourceInfo {
-                    id: row.get(0)?,
-                    project_id: row.get(1)?,
-                    project_name: row.get(9)?,
-                    name: row.get(2)?,
-                    content: row.get(3)?,
-                    file_data: row.get(4)?,
-                    file_type: row.get(5)?,
-                    file_size: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                })
-            },
-        )
-        .map_err(anyhow::Error::from)?;
+}
 
-    let response = ResourceResponse {
-        success: true,
-        message: Some("Resource updated successfully".to_string()),
-        resource: Some(resource),
-    };
+// Put the resource in a project, in an area, or with neither on its own
+#[tauri::command(rename_all = "snake_case")]
+pub fn move_resource(
+    token: String,
+    id: String,
+    project_id: Option<String>,
+    area_id: Option<String>,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
 
-    Ok(serde_json::to_string(&response).map_err(anyhow::Error::from)?)
+    let resource = service::resources::move_to(
+        &conn,
+        &user_id,
+        &id,
+        project_id.as_deref(),
+        area_id.as_deref(),
+    )?;
+
+    respond_with_message(
+        "Resource moved successfully",
+        ResourceResponse {
+            resource: Some(resource),
+        },
+    )
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -375,82 +266,95 @@ pub fn delete_resource(
     token: String,
     id: String,
     state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    let rows_affected = conn
-        .execute(
-            "DELETE FROM resources WHERE id = ?1 AND user_id = ?2",
-            params![id, user_id],
-        )
-        .map_err(anyhow::Error::from)?;
-
-    if rows_affected == 0 {
-        return Err(tauri::Error::Anyhow(anyhow::anyhow!("Resource not found")));
-    }
+    service::resources::delete(&conn, &user_id, &id)?;
 
     Ok(id)
 }
 
+// Stream a resource's file to path, reporting progress to the frontend
+fn export_resource_to_path(
+    app: &tauri::AppHandle,
+    conn: &rusqlite::Connection,
+    id: &str,
+    user_id: &str,
+    path: &Path,
+) -> Result<u64, RecallError> {
+    let path_display = path.to_string_lossy().to_string();
+    service::resources::export_to_path(conn, user_id, id, path, |done, total| {
+        let _ = app.emit(
+            EXPORT_PROGRESS_EVENT,
+            FileTransferProgress {
+                resource_id: Some(id.to_string()),
+                path: path_display.clone(),
+                bytes_transferred: done,
+                total_bytes: total,
+            },
+        );
+    })
+}
+
+// Write a resource's file to a caller-chosen path. Emits resource-export-progress events.
 #[tauri::command(rename_all = "snake_case")]
-pub fn download_resource_file(
+pub async fn export_resource_file(
+    app: tauri::AppHandle,
     token: String,
     id: String,
-    state: tauri::State<AppState>,
-) -> Result<String, tauri::Error> {
-    let conn = state.pool.get().map_err(anyhow::Error::from)?;
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
     let user_id = get_user_id_from_token(&token, &conn)?;
 
-    let (file_data, file_name, file_type): (Option<String>, String, Option<String>) = conn
-        .query_row(
-            "SELECT file_data, name, file_type FROM resources WHERE id = ?1 AND user_id = ?2",
-            params![id, user_id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )
-        .map_err(|e| {
-            if e.to_string().contains("Query returned no rows") {
-                tauri::Error::Anyhow(anyhow::anyhow!("Resource not found"))
-            } else {
-                tauri::Error::Anyhow(anyhow::Error::from(e))
-            }
+    export_resource_to_path(&app, &conn, &id, &user_id, Path::new(&path))?;
+
+    Ok(path)
+}
+
+// Create a resource in a project, an area or neither from a file on disk. Emits
+// resource-import-progress events.
+#[tauri::command(rename_all = "snake_case")]
+#[allow(clippy::too_many_arguments)]
+pub async fn import_resource_file(
+    app: tauri::AppHandle,
+    token: String,
+    project_id: Option<String>,
+    area_id: Option<String>,
+    path: String,
+    name: Option<String>,
+    file_type: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let request = ImportResourceRequest {
+        project_id,
+        area_id,
+        path: path.clone(),
+        name,
+        file_type,
+    };
+    let resource =
+        service::resources::import_from_path(&conn, &user_id, request, |done, total| {
+            let _ = app.emit(
+                IMPORT_PROGRESS_EVENT,
+                FileTransferProgress {
+                    resource_id: None,
+                    path: path.clone(),
+                    bytes_transferred: done,
+                    total_bytes: total,
+                },
+            );
         })?;
 
-    if let Some(base64_data) = file_data {
-        // Show save dialog using rfd
-        let mut dialog = rfd::FileDialog::new().set_file_name(&file_name);
-
-        // Add file filter if we have a file type
-        if let Some(ft) = &file_type {
-            if let Some(ext) = ft.split('/').last() {
-                if !ext.is_empty() {
-                    dialog = dialog.add_filter("File", &[ext]);
-                }
-            }
-        }
-
-        if let Some(file_path) = dialog.save_file() {
-            // Decode base64
-            let decoded = base64::engine::general_purpose::STANDARD
-                .decode(&base64_data)
-                .map_err(|e| {
-                    tauri::Error::Anyhow(anyhow::anyhow!("Failed to decode base64: {}", e))
-                })?;
-
-            // Write to file
-            std::fs::write(&file_path, decoded).map_err(|e| {
-                tauri::Error::Anyhow(anyhow::anyhow!("Failed to write file: {}", e))
-            })?;
-
-            Ok(file_path.to_string_lossy().to_string())
-        } else {
-            Err(tauri::Error::Anyhow(anyhow::anyhow!(
-                "User cancelled file save dialog"
-            )))
-        }
-    } else {
-        Err(tauri::Error::Anyhow(anyhow::anyhow!(
-            "No file data available"
-        )))
-    }
+    respond_with_message(
+        "Resource imported successfully",
+        ResourceResponse {
+            resource: Some(resource),
+        },
+    )
 }
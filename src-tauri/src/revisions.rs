@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, respond, respond_with_message};
+use crate::resources::ResourceResponse;
+use crate::service;
+use crate::{AppState, get_user_id_from_token};
+
+// Content a resource had before a save replaced it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Revision {
+    pub id: i64,
+    pub resource_id: String,
+    pub name: String,
+    pub content: String,
+    pub content_size: i64,
+    pub saved_at: i64,   // When this content was saved
+    pub created_at: i64, // When it was replaced
+}
+
+// A revision without its content, for listings
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionSummary {
+    pub id: i64,
+    pub resource_id: String,
+    pub name: String,
+    pub snippet: Option<String>,
+    pub content_size: i64,
+    pub saved_at: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub change: String, // "equal", "insert" or "delete"
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub from: i64,
+    pub to: Option<i64>, // None for the current content
+    pub changes: Vec<DiffLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionsResponse {
+    pub revisions: Vec<RevisionSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionResponse {
+    pub revision: Revision,
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_resource_revisions(
+    token: String,
+    resource_id: String,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let revisions = service::revisions::list(&conn, &user_id, &resource_id)?;
+
+    respond(RevisionsResponse { revisions })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_resource_revision(
+    token: String,
+    resource_id: String,
+    id: i64,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let revision = service::revisions::get(&conn, &user_id, &resource_id, id)?;
+
+    respond(RevisionResponse { revision })
+}
+
+// Compare two revisions, or a revision with the current content when `to` is null
+#[tauri::command(rename_all = "snake_case")]
+pub fn diff_resource_revisions(
+    token: String,
+    resource_id: String,
+    from: i64,
+    to: Option<i64>,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let diff = service::revisions::diff(&conn, &user_id, &resource_id, from, to)?;
+
+    respond(diff)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn restore_resource_revision(
+    token: String,
+    resource_id: String,
+    id: i64,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let resource = service::revisions::restore(&conn, &user_id, &resource_id, id)?;
+
+    respond_with_message(
+        "Revision restored",
+        ResourceResponse {
+            resource: Some(resource),
+        },
+    )
+}
@@ -0,0 +1,128 @@
+// Background reminder delivery. A thread sleeps until the next reminder is due, or until
+// it is woken because events or reminders changed, then emits each due reminder to the
+// frontend and shows it as a desktop notification. Deliveries are recorded in the database,
+// so reminders missed while the app was closed are shown on the next start, and none twice.
+// Each check also archives projects that have been Done for their owner's chosen time, and
+// purges items that have been in the trash longer than their owner keeps them.
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use chrono::Utc;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::error::RecallError;
+use crate::reminders::ReminderInfo;
+use crate::service;
+
+// Frontend event carrying a ReminderInfo
+pub const REMINDER_EVENT: &str = "reminder";
+
+// Longest sleep between checks, so clock changes and missed wakes are caught up with
+const MAX_SLEEP: i64 = 5 * 60;
+
+// Wakes the scheduler thread early. Without a running thread, waking does nothing.
+pub struct Scheduler {
+    wake: Mutex<Option<Sender<()>>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Scheduler {
+            wake: Mutex::new(None),
+        }
+    }
+
+    // Check for due reminders now, e.g. after an event was created or a reminder snoozed
+    pub fn wake(&self) {
+        if let Some(sender) = self.wake.lock().unwrap().as_ref() {
+            let _ = sender.send(());
+        }
+    }
+
+    pub fn start(&self, app: AppHandle, pool: Pool<SqliteConnectionManager>) {
+        let (sender, receiver) = mpsc::channel();
+        *self.wake.lock().unwrap() = Some(sender);
+        std::thread::Builder::new()
+            .name("reminders".to_string())
+            .spawn(move || run(app, pool, receiver))
+            .expect("failed to start the reminder scheduler");
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Deliver what is due, returning how long to sleep before the next check
+fn tick(app: &AppHandle, pool: &Pool<SqliteConnectionManager>) -> Result<i64, RecallError> {
+    let conn = pool.get()?;
+    let now = Utc::now().timestamp();
+    let archived = service::archive::auto_archive(&conn, now)?;
+    if archived > 0 {
+        log::info!("Archived {} finished projects", archived);
+    }
+    let purged = service::trash::purge_expired(&conn, now)?;
+    if purged > 0 {
+        log::info!("Purged {} items from the trash", purged);
+    }
+    for reminder in service::reminders::take_due(&conn, now)? {
+        deliver(app, &reminder);
+    }
+    let next = service::reminders::next_due(&conn, now, now + MAX_SLEEP)?;
+    Ok(next.map_or(MAX_SLEEP, |due| (due - now).clamp(1, MAX_SLEEP)))
+}
+
+// "15 minutes", "2 hours", "1 day": the largest whole unit
+fn lead_time(minutes: i64) -> String {
+    let (amount, unit) = if minutes % (24 * 60) == 0 {
+        (minutes / (24 * 60), "day")
+    } else if minutes % 60 == 0 {
+        (minutes / 60, "hour")
+    } else {
+        (minutes, "minute")
+    };
+    if amount == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", amount, unit)
+    }
+}
+
+fn deliver(app: &AppHandle, reminder: &ReminderInfo) {
+    if let Err(err) = app.emit(REMINDER_EVENT, reminder.clone()) {
+        log::warn!("Failed to emit reminder: {}", err);
+    }
+    let body = match reminder.kind.as_str() {
+        "project" => format!("Project ends in {}", lead_time(reminder.minutes_before)),
+        _ if reminder.minutes_before == 0 => "Starting now".to_string(),
+        _ => format!("Starts in {}", lead_time(reminder.minutes_before)),
+    };
+    if let Err(err) = app
+        .notification()
+        .builder()
+        .title(&reminder.title)
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show notification: {}", err);
+    }
+}
+
+fn run(app: AppHandle, pool: Pool<SqliteConnectionManager>, wake: Receiver<()>) {
+    loop {
+        let sleep = tick(&app, &pool).unwrap_or_else(|err| {
+            log::error!("Reminder check failed: {}", err);
+            MAX_SLEEP
+        });
+        match wake.recv_timeout(Duration::from_secs(sleep as u64)) {
+            Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
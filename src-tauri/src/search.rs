@@ -0,0 +1,201 @@
+use anyhow::Result;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::error::RecallError;
+use crate::service::Owned;
+use crate::{AppState, get_user_id_from_token, service, time_zone};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub id: String,
+    pub title: String,             // Title with matches wrapped in <mark>
+    pub snippet: Option<String>,   // Best matching excerpt of the body, if any
+    pub parent_id: Option<String>, // Project or area of a resource, project of an event, area of a project
+    pub start_time: Option<i64>,   // Events only, as an instant in the user's zone
+    pub score: f64,                // bm25, lower is better
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResults {
+    pub query: String,
+    pub resources: Vec<SearchHit>,
+    pub projects: Vec<SearchHit>,
+    pub areas: Vec<SearchHit>,
+    pub events: Vec<SearchHit>,
+}
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+// Titles weigh more than bodies; unindexed id/user_id columns get 0
+const RESOURCES_QUERY: &str = "SELECT resources_fts.id,
+        highlight(resources_fts, 2, '<mark>', '</mark>'),
+        snippet(resources_fts, 3, '<mark>', '</mark>', '…', 16),
+        COALESCE(r.project_id, r.area_id), NULL,
+        bm25(resources_fts, 0, 0, 10.0, 1.0) AS score
+    FROM resources_fts
+    JOIN resources r ON r.id = resources_fts.id
+    WHERE resources_fts MATCH ?1 AND resources_fts.user_id = ?2";
+
+const PROJECTS_QUERY: &str = "SELECT projects_fts.id,
+        highlight(projects_fts, 2, '<mark>', '</mark>'),
+        snippet(projects_fts, 3, '<mark>', '</mark>', '…', 16),
+        p.area_id, NULL,
+        bm25(projects_fts, 0, 0, 10.0, 1.0) AS score
+    FROM projects_fts
+    JOIN projects p ON p.id = projects_fts.id
+    WHERE projects_fts MATCH ?1 AND projects_fts.user_id = ?2";
+
+const AREAS_QUERY: &str = "SELECT areas_fts.id,
+        highlight(areas_fts, 2, '<mark>', '</mark>'),
+        NULL, NULL, NULL,
+        bm25(areas_fts) AS score
+    FROM areas_fts
+    WHERE areas_fts MATCH ?1 AND areas_fts.user_id = ?2";
+
+const EVENTS_QUERY: &str = "SELECT events_fts.id,
+        highlight(events_fts, 2, '<mark>', '</mark>'),
+        snippet(events_fts, -1, '<mark>', '</mark>', '…', 16),
+        e.project_id, e.start_time,
+        bm25(events_fts, 0, 0, 10.0, 1.0, 2.0) AS score
+    FROM events_fts
+    JOIN events e ON e.id = events_fts.id
+    WHERE events_fts MATCH ?1 AND events_fts.user_id = ?2";
+
+// Turn free text into an FTS5 query: every word must match, each as a prefix.
+// Words are quoted so FTS5 operators and punctuation in the input are taken literally.
+pub fn build_match_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+// Runs one of the queries above on the rows of `kind`, leaving out those in the trash
+fn search_table(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    kind: Owned,
+    match_query: &str,
+    user_id: &str,
+    limit: i64,
+) -> Result<Vec<SearchHit>> {
+    let fts_table = match kind {
+        Owned::Area => "areas_fts",
+        Owned::Project => "projects_fts",
+        Owned::Resource => "resources_fts",
+        Owned::Event => "events_fts",
+    };
+    let mut stmt = conn.prepare(&format!(
+        "{} AND NOT {} ORDER BY score LIMIT ?3",
+        sql,
+        service::trash::trashed(kind, &format!("{}.id", fts_table))
+    ))?;
+    let hits = stmt
+        .query_map(params![match_query, user_id, limit], |row| {
+            Ok(SearchHit {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                snippet: row.get(2)?,
+                parent_id: row.get(3)?,
+                start_time: row.get(4)?,
+                score: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(hits)
+}
+
+pub fn search_all(
+    conn: &rusqlite::Connection,
+    user_id: &str,
+    query: &str,
+    limit: i64,
+) -> Result<SearchResults> {
+    let Some(match_query) = build_match_query(query) else {
+        return Ok(SearchResults {
+            query: query.to_string(),
+            resources: Vec::new(),
+            projects: Vec::new(),
+            areas: Vec::new(),
+            events: Vec::new(),
+        });
+    };
+
+    Ok(SearchResults {
+        query: query.to_string(),
+        resources: search_table(
+            conn,
+            RESOURCES_QUERY,
+            Owned::Resource,
+            &match_query,
+            user_id,
+            limit,
+        )?,
+        projects: search_table(
+            conn,
+            PROJECTS_QUERY,
+            Owned::Project,
+            &match_query,
+            user_id,
+            limit,
+        )?,
+        areas: search_table(conn, AREAS_QUERY, Owned::Area, &match_query, user_id, limit)?,
+        events: localize_event_hits(
+            conn,
+            user_id,
+            search_table(
+                conn,
+                EVENTS_QUERY,
+                Owned::Event,
+                &match_query,
+                user_id,
+                limit,
+            )?,
+        )?,
+    })
+}
+
+// All-day events store floating times; place them on their dates in the user's zone
+fn localize_event_hits(
+    conn: &rusqlite::Connection,
+    user_id: &str,
+    mut hits: Vec<SearchHit>,
+) -> Result<Vec<SearchHit>> {
+    let viewer = service::auth::time_zone(conn, user_id)?;
+    let mut stmt = conn.prepare("SELECT time_zone IS NULL FROM events WHERE id = ?1")?;
+    for hit in &mut hits {
+        let floating: bool = stmt.query_row(params![hit.id], |row| row.get(0))?;
+        if floating {
+            hit.start_time = hit
+                .start_time
+                .map(|start| time_zone::from_floating(viewer, start));
+        }
+    }
+    Ok(hits)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub fn search(
+    token: String,
+    query: String,
+    limit: Option<i64>,
+    state: tauri::State<AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let results = search_all(&conn, &user_id, &query, limit)?;
+
+    Ok(serde_json::to_string(&results)?)
+}
@@ -0,0 +1,193 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, params};
+
+use super::{Owned, areas, ensure_owner, projects, workflows};
+use crate::archive::ArchiveListing;
+use crate::areas::AreaInfo;
+use crate::error::RecallError;
+use crate::projects::ProjectInfo;
+
+// Longest wait a user can choose before Done projects are archived
+const MAX_AUTO_ARCHIVE_DAYS: i64 = 3650;
+
+// SQL condition that holds when the project in `column` is archived, itself or through its
+// area. Default listings of resources and events leave out rows it matches.
+pub(crate) fn hidden_project(column: &str) -> String {
+    format!(
+        "EXISTS(SELECT 1 FROM projects ap JOIN areas aa ON aa.id = ap.area_id
+         WHERE ap.id = {} AND (ap.archived_at IS NOT NULL OR aa.archived_at IS NOT NULL))",
+        column
+    )
+}
+
+// The same for an area in `column`, for resources kept directly in an area
+pub(crate) fn hidden_area(column: &str) -> String {
+    format!(
+        "EXISTS(SELECT 1 FROM areas aa WHERE aa.id = {} AND aa.archived_at IS NOT NULL)",
+        column
+    )
+}
+
+// Archiving again keeps the original archived_at
+pub fn archive_area(conn: &Connection, user_id: &str, id: &str) -> Result<AreaInfo, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Area,
+        id,
+        user_id,
+        "You don't have permission to archive this area",
+    )?;
+
+    conn.execute(
+        "UPDATE areas SET archived_at = COALESCE(archived_at, ?1) WHERE id = ?2",
+        params![Utc::now().timestamp(), id],
+    )?;
+
+    areas::get(conn, user_id, id)
+}
+
+// Projects archived on their own stay archived
+pub fn unarchive_area(conn: &Connection, user_id: &str, id: &str) -> Result<AreaInfo, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Area,
+        id,
+        user_id,
+        "You don't have permission to unarchive this area",
+    )?;
+
+    conn.execute(
+        "UPDATE areas SET archived_at = NULL WHERE id = ?1",
+        params![id],
+    )?;
+
+    areas::get(conn, user_id, id)
+}
+
+pub fn archive_project(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+) -> Result<ProjectInfo, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Project,
+        id,
+        user_id,
+        "You don't have permission to archive this project",
+    )?;
+
+    conn.execute(
+        "UPDATE projects SET archived_at = COALESCE(archived_at, ?1) WHERE id = ?2",
+        params![Utc::now().timestamp(), id],
+    )?;
+
+    projects::get(conn, user_id, id)
+}
+
+// A project in an archived area would stay hidden, so its area has to come back first
+pub fn unarchive_project(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+) -> Result<ProjectInfo, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Project,
+        id,
+        user_id,
+        "You don't have permission to unarchive this project",
+    )?;
+    let area_archived: bool = conn.query_row(
+        "SELECT a.archived_at IS NOT NULL FROM projects p JOIN areas a ON a.id = p.area_id
+         WHERE p.id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    if area_archived {
+        return Err(RecallError::validation(
+            "This project's area is archived. Unarchive the area first",
+        ));
+    }
+
+    conn.execute(
+        "UPDATE projects SET archived_at = NULL WHERE id = ?1",
+        params![id],
+    )?;
+
+    projects::get(conn, user_id, id)
+}
+
+// Archived areas, and projects archived themselves or through their area, most recent first
+pub fn list(conn: &Connection, user_id: &str) -> Result<ArchiveListing, RecallError> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM areas WHERE user_id = ?1 AND archived_at IS NOT NULL AND deleted_at IS NULL
+         ORDER BY archived_at DESC",
+    )?;
+    let areas = stmt
+        .query_map(params![user_id], |row| row.get::<_, String>(0))?
+        .map(|id| areas::get(conn, user_id, &id?))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT p.id FROM projects p JOIN areas a ON a.id = p.area_id
+         WHERE p.user_id = ?1 AND (p.archived_at IS NOT NULL OR a.archived_at IS NOT NULL)
+         AND p.deleted_at IS NULL AND a.deleted_at IS NULL
+         ORDER BY COALESCE(p.archived_at, a.archived_at) DESC, p.created_at DESC",
+    )?;
+    let projects = stmt
+        .query_map(params![user_id], |row| row.get::<_, String>(0))?
+        .map(|id| projects::get(conn, user_id, &id?))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ArchiveListing {
+        areas,
+        projects,
+        auto_archive_days: auto_archive_days(conn, user_id)?,
+    })
+}
+
+pub fn auto_archive_days(conn: &Connection, user_id: &str) -> Result<Option<i64>, RecallError> {
+    conn.query_row(
+        "SELECT auto_archive_days FROM users WHERE id = ?1",
+        params![user_id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| RecallError::not_found("User"))
+}
+
+// None turns automatic archiving off
+pub fn set_auto_archive_days(
+    conn: &Connection,
+    user_id: &str,
+    days: Option<i64>,
+) -> Result<Option<i64>, RecallError> {
+    if days.is_some_and(|days| !(1..=MAX_AUTO_ARCHIVE_DAYS).contains(&days)) {
+        return Err(RecallError::validation(format!(
+            "Projects can be archived after 1 to {} days",
+            MAX_AUTO_ARCHIVE_DAYS
+        )));
+    }
+    // A preference rather than a profile edit, so updated_at is left alone
+    conn.execute(
+        "UPDATE users SET auto_archive_days = ?1 WHERE id = ?2",
+        params![days, user_id],
+    )?;
+    Ok(days)
+}
+
+// Archive every project that has been finished for at least its owner's auto_archive_days,
+// returning how many were archived
+pub fn auto_archive(conn: &Connection, now: i64) -> Result<usize, RecallError> {
+    Ok(conn.execute(
+        &format!(
+            "UPDATE projects SET archived_at = ?1
+             WHERE {} AND archived_at IS NULL
+             AND completed_at <= ?1 - 86400 *
+                 (SELECT auto_archive_days FROM users WHERE users.id = projects.user_id)",
+            workflows::done("projects.status", "projects.user_id", "projects.area_id")
+        ),
+        params![now],
+    )?)
+}
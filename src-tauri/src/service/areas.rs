@@ -0,0 +1,125 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use super::{Owned, ensure_owner, trash};
+use crate::areas::{Area, AreaInfo, CreateAreaRequest, UpdateAreaRequest};
+use crate::error::RecallError;
+use crate::try_insert_thing;
+
+const SELECT_AREA: &str =
+    "SELECT id, name, image_url, created_at, updated_at, archived_at FROM areas";
+
+fn area_from_row(row: &Row) -> rusqlite::Result<AreaInfo> {
+    Ok(AreaInfo {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        image_url: row.get(2)?,
+        archived_at: row.get(5)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+fn validate_name(name: &str) -> Result<(), RecallError> {
+    if name.trim().is_empty() {
+        return Err(RecallError::validation("Area name cannot be empty"));
+    }
+    Ok(())
+}
+
+pub fn create(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateAreaRequest,
+) -> Result<AreaInfo, RecallError> {
+    validate_name(&request.name)?;
+
+    let now = Utc::now().timestamp();
+    let mut area = Area {
+        id: None,
+        user_id: user_id.to_string(),
+        name: request.name.trim().to_string(),
+        image_url: request.image_url,
+        archived_at: None,
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let area_id = try_insert_thing(&mut area, conn)?;
+    println!("inserted Area id: {:?}", area_id);
+
+    Ok(AreaInfo {
+        id: area_id,
+        name: area.name,
+        image_url: area.image_url,
+        archived_at: None,
+        created_at: area.created_at,
+        updated_at: area.updated_at,
+    })
+}
+
+// Areas that are neither archived nor in the trash
+pub fn list(conn: &Connection, user_id: &str) -> Result<Vec<AreaInfo>, RecallError> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE user_id = ?1 AND archived_at IS NULL AND deleted_at IS NULL
+         ORDER BY created_at DESC",
+        SELECT_AREA
+    ))?;
+    let areas = stmt
+        .query_map(params![user_id], area_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(areas)
+}
+
+pub fn get(conn: &Connection, user_id: &str, id: &str) -> Result<AreaInfo, RecallError> {
+    conn.query_row(
+        &format!(
+            "{} WHERE id = ?1 AND user_id = ?2 AND deleted_at IS NULL",
+            SELECT_AREA
+        ),
+        params![id, user_id],
+        area_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| RecallError::not_found("Area"))
+}
+
+pub fn update(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    request: UpdateAreaRequest,
+) -> Result<AreaInfo, RecallError> {
+    validate_name(&request.name)?;
+    ensure_owner(
+        conn,
+        Owned::Area,
+        id,
+        user_id,
+        "You don't have permission to update this area",
+    )?;
+
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "UPDATE areas SET name = ?1, image_url = ?2, updated_at = ?3 WHERE id = ?4",
+        params![request.name.trim(), request.image_url, now, id],
+    )?;
+
+    get(conn, user_id, id)
+}
+
+// Moves the area to the trash, hiding its projects and the resources of both with it.
+// Events of those projects stay, shown without a project until it is restored. Purging
+// the area deletes the rest through ON DELETE CASCADE and unlinks the events.
+pub fn delete(conn: &Connection, user_id: &str, id: &str) -> Result<(), RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Area,
+        id,
+        user_id,
+        "You don't have permission to delete this area",
+    )?;
+
+    trash::move_to_trash(conn, Owned::Area, id)
+}
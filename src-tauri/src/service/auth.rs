@@ -0,0 +1,237 @@
+use bcrypt::{DEFAULT_COST, hash, verify};
+use chrono::Utc;
+use chrono_tz::Tz;
+use rand::Rng;
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::auth::{
+    ChangePasswordRequest, Session, SigninRequest, SignupRequest, UpdateUserRequest, User, UserInfo,
+};
+use crate::error::RecallError;
+use crate::{time_zone, try_insert_thing};
+
+// How long a session token stays valid
+pub const SESSION_LIFETIME_SECS: i64 = 30 * 24 * 60 * 60; // 30 days
+
+// A freshly opened session and the user it belongs to
+#[derive(Debug)]
+pub struct SignedIn {
+    pub token: String,
+    pub user: UserInfo,
+}
+
+fn generate_token() -> String {
+    const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let mut rng = rand::rng();
+    (0..64)
+        .map(|_| {
+            let idx = rng.random_range(0..CHARSET.len());
+            CHARSET[idx] as char
+        })
+        .collect()
+}
+
+fn hash_password(password: &str) -> Result<String, RecallError> {
+    hash(password, DEFAULT_COST)
+        .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e).into())
+}
+
+// Start a new session for the user and return its token
+pub fn create_session(conn: &Connection, user_id: &str) -> Result<String, RecallError> {
+    let now = Utc::now().timestamp();
+    let token = generate_token();
+    let mut session = Session {
+        id: None,
+        user_id: user_id.to_string(),
+        token: token.clone(),
+        expires_at: now + SESSION_LIFETIME_SECS,
+        created_at: now,
+    };
+
+    let session_id = try_insert_thing(&mut session, conn)?;
+    println!("inserted Session id: {:?}", session_id);
+
+    Ok(token)
+}
+
+pub fn signup(conn: &Connection, request: SignupRequest) -> Result<SignedIn, RecallError> {
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT id FROM users WHERE email = ?1",
+            params![request.email],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if existing.is_some() {
+        return Err(RecallError::conflict("User with this email already exists"));
+    }
+
+    let now = Utc::now().timestamp();
+    let mut user = User {
+        id: None,
+        email: request.email.clone(),
+        name: request.name.clone(),
+        password_hash: hash_password(&request.password)?,
+        time_zone: None,
+        auto_archive_days: None,
+        trash_retention_days: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let user_id = try_insert_thing(&mut user, conn)?;
+    println!("inserted User id: {:?}", user_id);
+
+    let token = create_session(conn, &user_id)?;
+
+    Ok(SignedIn {
+        token,
+        user: UserInfo {
+            id: user_id,
+            email: request.email,
+            name: request.name,
+        },
+    })
+}
+
+pub fn signin(conn: &Connection, request: SigninRequest) -> Result<SignedIn, RecallError> {
+    let user: Option<(String, String, String, String)> = conn
+        .query_row(
+            "SELECT id, email, name, password_hash FROM users WHERE email = ?1",
+            params![request.email],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?;
+
+    // Same answer for an unknown email and a wrong password
+    let Some((user_id, email, name, password_hash)) = user else {
+        return Err(RecallError::unauthenticated("Invalid email or password"));
+    };
+    if !verify(&request.password, &password_hash).unwrap_or(false) {
+        return Err(RecallError::unauthenticated("Invalid email or password"));
+    }
+
+    let token = create_session(conn, &user_id)?;
+
+    Ok(SignedIn {
+        token,
+        user: UserInfo {
+            id: user_id,
+            email,
+            name,
+        },
+    })
+}
+
+// The user behind a live session, or None if the token is unknown or expired
+pub fn user_for_token(conn: &Connection, token: &str) -> Result<Option<UserInfo>, RecallError> {
+    let now = Utc::now().timestamp();
+    let user = conn
+        .query_row(
+            "SELECT s.user_id, u.email, u.name FROM sessions s
+             INNER JOIN users u ON s.user_id = u.id
+             WHERE s.token = ?1 AND s.expires_at > ?2",
+            params![token, now],
+            |row| {
+                Ok(UserInfo {
+                    id: row.get(0)?,
+                    email: row.get(1)?,
+                    name: row.get(2)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(user)
+}
+
+pub fn update_user(
+    conn: &Connection,
+    user_id: &str,
+    request: UpdateUserRequest,
+) -> Result<UserInfo, RecallError> {
+    // Basic format check only
+    if !request.email.contains('@') || !request.email.contains('.') {
+        return Err(RecallError::validation("Invalid email format"));
+    }
+
+    let taken: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM users WHERE email = ?1 AND id != ?2)",
+        params![request.email, user_id],
+        |row| row.get(0),
+    )?;
+    if taken {
+        return Err(RecallError::conflict(
+            "Email is already taken by another user",
+        ));
+    }
+
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "UPDATE users SET name = ?1, email = ?2, updated_at = ?3 WHERE id = ?4",
+        params![request.name, request.email, now, user_id],
+    )?;
+
+    Ok(UserInfo {
+        id: user_id.to_string(),
+        email: request.email,
+        name: request.name,
+    })
+}
+
+pub fn change_password(
+    conn: &Connection,
+    user_id: &str,
+    request: ChangePasswordRequest,
+) -> Result<(), RecallError> {
+    let password_hash: String = conn
+        .query_row(
+            "SELECT password_hash FROM users WHERE id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| RecallError::not_found("User"))?;
+
+    if !verify(&request.current_password, &password_hash).unwrap_or(false) {
+        return Err(RecallError::forbidden("Current password is incorrect"));
+    }
+
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "UPDATE users SET password_hash = ?1, updated_at = ?2 WHERE id = ?3",
+        params![hash_password(&request.new_password)?, now, user_id],
+    )?;
+
+    Ok(())
+}
+
+// The zone the user's events are shown in
+pub fn time_zone(conn: &Connection, user_id: &str) -> Result<Tz, RecallError> {
+    let name: Option<String> = conn
+        .query_row(
+            "SELECT time_zone FROM users WHERE id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| RecallError::not_found("User"))?;
+    // A zone dropped from the tz database falls back like an unset one
+    Ok(name
+        .and_then(|name| time_zone::parse(&name).ok())
+        .unwrap_or_else(time_zone::system_zone))
+}
+
+pub fn set_time_zone(conn: &Connection, user_id: &str, name: &str) -> Result<Tz, RecallError> {
+    let tz = time_zone::parse(name)?;
+    // Not a profile edit, so updated_at is left alone
+    conn.execute(
+        "UPDATE users SET time_zone = ?1 WHERE id = ?2",
+        params![tz.name(), user_id],
+    )?;
+    Ok(tz)
+}
+
+pub fn delete_session(conn: &Connection, token: &str) -> Result<(), RecallError> {
+    conn.execute("DELETE FROM sessions WHERE token = ?1", params![token])?;
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use chrono::{Duration, NaiveTime};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+
+use super::{auth, events};
+use crate::availability::{FreeBusy, FreeBusyRequest, TimeSlot, WorkingHours};
+use crate::error::RecallError;
+use crate::events::{EventInfo, EventQuery};
+use crate::time_zone;
+
+// Longest range a free/busy query may cover
+const MAX_RANGE_DAYS: i64 = 366;
+
+// The time an event takes up. Events without an end take up their start second, so they
+// still clash with anything spanning it.
+fn span(start: i64, end: Option<i64>) -> TimeSlot {
+    TimeSlot {
+        start,
+        end: end.unwrap_or(start).max(start + 1),
+    }
+}
+
+// All-day events take up their days from midnight to midnight in the user's zone
+fn all_day_span(event: &EventInfo, viewer: Tz) -> TimeSlot {
+    let first = time_zone::day(viewer, event.start_time);
+    let last = time_zone::day(viewer, event.end_time.unwrap_or(event.start_time)).max(first);
+    TimeSlot {
+        start: time_zone::instant(viewer, first.and_time(NaiveTime::MIN)),
+        end: time_zone::instant(viewer, (last + Duration::days(1)).and_time(NaiveTime::MIN)),
+    }
+}
+
+fn overlaps(a: &TimeSlot, b: &TimeSlot) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+fn in_window(
+    conn: &Connection,
+    user_id: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<EventInfo>, RecallError> {
+    events::query(
+        conn,
+        user_id,
+        &EventQuery {
+            start_date: Some(from),
+            end_date: Some(to),
+            ..EventQuery::default()
+        },
+    )
+}
+
+// Timed events, or occurrences, overlapping a proposed slot. Touching end to start is not
+// a conflict. `exclude_id` leaves out an event being rescheduled, with all its occurrences.
+pub fn conflicts(
+    conn: &Connection,
+    user_id: &str,
+    start_time: i64,
+    end_time: Option<i64>,
+    exclude_id: Option<&str>,
+) -> Result<Vec<EventInfo>, RecallError> {
+    if end_time.is_some_and(|end| end <= start_time) {
+        return Err(RecallError::validation("End time must be after start time"));
+    }
+    let slot = span(start_time, end_time);
+    let conflicts = in_window(conn, user_id, slot.start, slot.end)?
+        .into_iter()
+        .filter(|event| !event.all_day && Some(event.id.as_str()) != exclude_id)
+        .filter(|event| overlaps(&span(event.start_time, event.end_time), &slot))
+        .collect();
+    Ok(conflicts)
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, RecallError> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| RecallError::validation(format!("Invalid time of day: {}", value)))
+}
+
+// The parts of [from, to) inside working hours, day by day in the user's zone
+fn working_windows(
+    hours: Option<&WorkingHours>,
+    from: i64,
+    to: i64,
+    viewer: Tz,
+) -> Result<Vec<TimeSlot>, RecallError> {
+    let Some(hours) = hours else {
+        return Ok(vec![TimeSlot {
+            start: from,
+            end: to,
+        }]);
+    };
+    let (open, close) = (parse_time(&hours.start)?, parse_time(&hours.end)?);
+    if close <= open {
+        return Err(RecallError::validation(
+            "Working hours must end after they start",
+        ));
+    }
+
+    let mut windows = Vec::new();
+    let mut day = time_zone::day(viewer, from);
+    while day <= time_zone::day(viewer, to) {
+        let window = TimeSlot {
+            start: time_zone::instant(viewer, day.and_time(open)).max(from),
+            end: time_zone::instant(viewer, day.and_time(close)).min(to),
+        };
+        if window.start < window.end {
+            windows.push(window);
+        }
+        day += Duration::days(1);
+    }
+    Ok(windows)
+}
+
+// Busy time in [start_date, end_date), merged, and the free stretches at least
+// `duration_minutes` long within working hours
+pub fn free_busy(
+    conn: &Connection,
+    user_id: &str,
+    request: &FreeBusyRequest,
+) -> Result<FreeBusy, RecallError> {
+    let (from, to) = (request.start_date, request.end_date);
+    if to <= from {
+        return Err(RecallError::validation("End date must be after start date"));
+    }
+    if to - from > MAX_RANGE_DAYS * 24 * 3600 {
+        return Err(RecallError::validation(format!(
+            "Free/busy ranges are limited to {} days",
+            MAX_RANGE_DAYS
+        )));
+    }
+    if request.duration_minutes < 1 {
+        return Err(RecallError::validation(
+            "Duration must be at least one minute",
+        ));
+    }
+    let viewer = auth::time_zone(conn, user_id)?;
+
+    let mut taken: Vec<TimeSlot> = in_window(conn, user_id, from, to)?
+        .iter()
+        .filter(|event| !(event.all_day && request.ignore_all_day))
+        .map(|event| {
+            if event.all_day {
+                all_day_span(event, viewer)
+            } else {
+                span(event.start_time, event.end_time)
+            }
+        })
+        .collect();
+    taken.sort_by_key(|slot| slot.start);
+
+    let mut busy: Vec<TimeSlot> = Vec::new();
+    for slot in taken {
+        let slot = TimeSlot {
+            start: slot.start.max(from),
+            end: slot.end.min(to),
+        };
+        match busy.last_mut() {
+            Some(last) if slot.start <= last.end => last.end = last.end.max(slot.end),
+            _ => busy.push(slot),
+        }
+    }
+
+    let duration = request.duration_minutes * 60;
+    let mut free = Vec::new();
+    for window in working_windows(request.working_hours.as_ref(), from, to, viewer)? {
+        let mut cursor = window.start;
+        for slot in busy.iter().filter(|slot| overlaps(slot, &window)) {
+            if slot.start - cursor >= duration {
+                free.push(TimeSlot {
+                    start: cursor,
+                    end: slot.start,
+                });
+            }
+            cursor = cursor.max(slot.end);
+        }
+        if window.end - cursor >= duration {
+            free.push(TimeSlot {
+                start: cursor,
+                end: window.end,
+            });
+        }
+    }
+
+    Ok(FreeBusy { busy, free })
+}
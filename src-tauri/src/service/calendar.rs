@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+
+use chrono::Duration;
+use chrono_tz::Tz;
+use rusqlite::{Connection, OptionalExtension, params};
+
+use super::{Owned, auth, ensure_owner, events};
+use crate::error::RecallError;
+use crate::events::{
+    CalendarImportSummary, CreateEventRequest, EventInfo, OccurrenceRequest, UpdateEventRequest,
+};
+use crate::ical::{self, IcsEvent, IcsTime};
+use crate::recurrence::RecurrenceRule;
+use crate::time_zone;
+
+// Title for imported events that have no SUMMARY
+const UNTITLED: &str = "Untitled event";
+
+// UID for events that were not imported, stable across exports
+fn default_uid(event_id: &str) -> String {
+    format!("{}@recall", event_id)
+}
+
+// Imported UIDs, by event id
+fn ical_uids(conn: &Connection, user_id: &str) -> Result<HashMap<String, String>, RecallError> {
+    let mut stmt = conn
+        .prepare("SELECT id, ical_uid FROM events WHERE user_id = ?1 AND ical_uid IS NOT NULL")?;
+    let uids = stmt
+        .query_map(params![user_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(uids)
+}
+
+// The event a UID refers to: one imported with it, or one of ours exported under it
+fn find_by_uid(conn: &Connection, user_id: &str, uid: &str) -> Result<Option<String>, RecallError> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM events
+             WHERE user_id = ?1 AND (ical_uid = ?2 OR (ical_uid IS NULL AND id || '@recall' = ?2))",
+            params![user_id, uid],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+// All-day events are written as their dates in the user's zone
+fn ics_time(timestamp: i64, all_day: bool, viewer: Tz) -> IcsTime {
+    if all_day {
+        IcsTime::Date(time_zone::day(viewer, timestamp))
+    } else {
+        IcsTime::Utc(timestamp)
+    }
+}
+
+fn to_vevent(event: &EventInfo, uid: &str, rule: Option<&RecurrenceRule>, viewer: Tz) -> IcsEvent {
+    let mut vevent = IcsEvent::new(uid, ics_time(event.start_time, event.all_day, viewer));
+    // DTEND is exclusive, so an all-day event ends the day after its last day
+    vevent.end = if event.all_day {
+        let last_day = time_zone::day(viewer, event.end_time.unwrap_or(event.start_time));
+        Some(IcsTime::Date(last_day + Duration::days(1)))
+    } else {
+        event.end_time.map(IcsTime::Utc)
+    };
+    vevent.summary = Some(event.title.clone());
+    vevent.description = event.description.clone();
+    vevent.location = event.location.clone();
+    vevent.categories = event.project_name.iter().cloned().collect();
+    vevent.rrule = rule.map(|r| r.to_ical(event.all_day, events::schedule_zone(event, viewer)));
+    vevent.exdates = event
+        .exception_dates
+        .iter()
+        .map(|&date| ics_time(date, event.all_day, viewer))
+        .collect();
+    vevent.last_modified = Some(event.updated_at);
+    vevent
+}
+
+// Whether an event or any occurrence of a series overlaps the optional bounds
+fn in_range(
+    event: &EventInfo,
+    rule: Option<&RecurrenceRule>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    viewer: Tz,
+) -> bool {
+    let duration = event.end_time.map_or(0, |end| end - event.start_time);
+    match (rule, start_date) {
+        (Some(rule), Some(from)) => !rule
+            .occurrences_between_in(
+                events::schedule_zone(event, viewer),
+                event.start_time,
+                from - duration,
+                end_date.unwrap_or(i64::MAX),
+                1,
+            )
+            .is_empty(),
+        _ => {
+            start_date.is_none_or(|from| event.start_time + duration >= from)
+                && end_date.is_none_or(|to| event.start_time <= to)
+        }
+    }
+}
+
+// A VCALENDAR of the user's events, filtered like events::list. Recurring events are
+// written once with their RRULE and EXDATEs, plus a RECURRENCE-ID VEVENT per edited occurrence.
+pub fn export(
+    conn: &Connection,
+    user_id: &str,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    project_id: Option<&str>,
+) -> Result<String, RecallError> {
+    let viewer = auth::time_zone(conn, user_id)?;
+    let uids = ical_uids(conn, user_id)?;
+    let mut vevents = Vec::new();
+
+    for event in events::list(conn, user_id, None, None, project_id)? {
+        let rule = events::stored_rule(&event)?;
+        if !in_range(&event, rule.as_ref(), start_date, end_date, viewer) {
+            continue;
+        }
+        let uid = uids
+            .get(&event.id)
+            .cloned()
+            .unwrap_or_else(|| default_uid(&event.id));
+        vevents.push(to_vevent(&event, &uid, rule.as_ref(), viewer));
+
+        if rule.is_some() {
+            for edit in events::edited_occurrences(conn, &event, viewer)? {
+                let mut vevent = to_vevent(&edit, &uid, None, viewer);
+                vevent.exdates.clear();
+                vevent.recurrence_id = edit
+                    .occurrence_start
+                    .map(|start| ics_time(start, event.all_day, viewer));
+                vevents.push(vevent);
+            }
+        }
+    }
+
+    Ok(ical::write_calendar(&vevents))
+}
+
+// Start, end and all_day as the frontend sends them: all-day events run from midnight
+// of their first day to the last second of their last day, in the user's zone
+fn event_times(vevent: &IcsEvent, viewer: Tz) -> (i64, Option<i64>, bool) {
+    let start = vevent.start.timestamp(viewer);
+    let all_day = vevent.start.is_date();
+    let end = vevent.end.map(|end| {
+        if all_day {
+            end.timestamp(viewer) - 1
+        } else {
+            end.timestamp(viewer)
+        }
+    });
+    (start, end.filter(|&end| end > start), all_day)
+}
+
+fn title(vevent: &IcsEvent) -> String {
+    vevent
+        .summary
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(UNTITLED)
+        .to_string()
+}
+
+// Create or update the event for one VEVENT without a RECURRENCE-ID, returning its id
+// and whether it already existed
+fn import_event(
+    conn: &Connection,
+    user_id: &str,
+    vevent: &IcsEvent,
+    project_id: Option<&str>,
+    viewer: Tz,
+) -> Result<(String, bool), RecallError> {
+    let (start_time, end_time, all_day) = event_times(vevent, viewer);
+    let exception_dates: Vec<i64> = vevent.exdates.iter().map(|t| t.timestamp(viewer)).collect();
+    // Events with a TZID keep it, so their recurrences follow that zone's DST
+    let time_zone = vevent.start.zone().map(|tz| tz.name().to_string());
+
+    if let Some(id) = find_by_uid(conn, user_id, &vevent.uid)? {
+        // Importing an event again takes it back out of the trash. The caller's savepoint
+        // undoes this if the update below fails.
+        conn.execute(
+            "UPDATE events SET deleted_at = NULL WHERE id = ?1",
+            params![id],
+        )?;
+        // Re-imports without a project keep whatever project the event was moved to
+        let project_id = match project_id {
+            Some(project_id) => Some(project_id.to_string()),
+            None => events::get(conn, user_id, &id)?.project_id,
+        };
+        let request = UpdateEventRequest {
+            project_id,
+            title: title(vevent),
+            description: vevent.description.clone(),
+            start_time,
+            end_time,
+            location: vevent.location.clone(),
+            all_day,
+            time_zone,
+            recurrence_rule: vevent.rrule.clone(),
+            exception_dates: Some(exception_dates),
+            reminders: None,
+        };
+        events::overwrite(conn, user_id, &id, request)?;
+        return Ok((id, true));
+    }
+
+    let request = CreateEventRequest {
+        project_id: project_id.map(str::to_string),
+        title: title(vevent),
+        description: vevent.description.clone(),
+        start_time,
+        end_time,
+        location: vevent.location.clone(),
+        all_day,
+        time_zone,
+        recurrence_rule: vevent.rrule.clone(),
+        exception_dates,
+        reminders: Vec::new(),
+    };
+    let event = events::insert(conn, user_id, request)?;
+    conn.execute(
+        "UPDATE events SET ical_uid = ?1 WHERE id = ?2",
+        params![vevent.uid, event.id],
+    )?;
+    Ok((event.id, false))
+}
+
+// Apply a RECURRENCE-ID VEVENT to its series
+fn import_occurrence(
+    conn: &Connection,
+    user_id: &str,
+    series_id: &str,
+    vevent: &IcsEvent,
+    occurrence_start: i64,
+    viewer: Tz,
+) -> Result<(), RecallError> {
+    if vevent.cancelled {
+        return events::drop_occurrence(conn, user_id, series_id, occurrence_start);
+    }
+    let (start_time, end_time, all_day) = event_times(vevent, viewer);
+    let request = OccurrenceRequest {
+        title: title(vevent),
+        description: vevent.description.clone(),
+        start_time,
+        end_time,
+        location: vevent.location.clone(),
+        all_day,
+        time_zone: vevent.start.zone().map(|tz| tz.name().to_string()),
+    };
+    events::update_occurrence(conn, user_id, series_id, occurrence_start, request)?;
+    Ok(())
+}
+
+// Events the calendar cannot express here are skipped rather than failing the import
+fn skippable(err: &RecallError) -> bool {
+    matches!(err, RecallError::Validation(_) | RecallError::NotFound(_))
+}
+
+// Import a VCALENDAR, optionally into one project. Events are matched to earlier imports
+// (and to our own exports) by UID, so importing the same calendar again updates in place.
+pub fn import(
+    conn: &Connection,
+    user_id: &str,
+    text: &str,
+    project_id: Option<&str>,
+) -> Result<CalendarImportSummary, RecallError> {
+    if let Some(project_id) = project_id {
+        ensure_owner(
+            conn,
+            Owned::Project,
+            project_id,
+            user_id,
+            "You don't have permission to create events for this project",
+        )?;
+    }
+
+    let viewer = auth::time_zone(conn, user_id)?;
+    let vevents = ical::parse_calendar(text)?;
+    let mut summary = CalendarImportSummary::default();
+    // A file imports whole or not at all, and each event in a savepoint so a skipped one
+    // leaves nothing behind
+    let mut tx = conn.unchecked_transaction()?;
+    let mut series_ids: HashMap<&str, String> = HashMap::new();
+
+    // Series first, so their edited occurrences have something to attach to
+    let (occurrences, masters): (Vec<&IcsEvent>, Vec<&IcsEvent>) =
+        vevents.iter().partition(|v| v.recurrence_id.is_some());
+
+    for vevent in masters {
+        if vevent.cancelled {
+            summary.skipped += 1;
+            continue;
+        }
+        let savepoint = tx.savepoint()?;
+        match import_event(&savepoint, user_id, vevent, project_id, viewer) {
+            Ok((id, existed)) => {
+                savepoint.commit()?;
+                if existed {
+                    summary.updated += 1;
+                } else {
+                    summary.created += 1;
+                }
+                series_ids.insert(&vevent.uid, id);
+            }
+            Err(err) if skippable(&err) => summary.skipped += 1,
+            Err(err) => return Err(err),
+        }
+    }
+
+    for vevent in occurrences {
+        let series_id = match series_ids.get(vevent.uid.as_str()) {
+            Some(id) => Some(id.clone()),
+            None => find_by_uid(&tx, user_id, &vevent.uid)?,
+        };
+        let (Some(series_id), Some(recurrence_id)) = (series_id, vevent.recurrence_id) else {
+            summary.skipped += 1;
+            continue;
+        };
+        let savepoint = tx.savepoint()?;
+        match import_occurrence(
+            &savepoint,
+            user_id,
+            &series_id,
+            vevent,
+            recurrence_id.timestamp(viewer),
+            viewer,
+        ) {
+            Ok(()) => {
+                savepoint.commit()?;
+                summary.updated += 1;
+            }
+            Err(err) if skippable(&err) => summary.skipped += 1,
+            Err(err) => return Err(err),
+        }
+    }
+    tx.commit()?;
+
+    Ok(summary)
+}
@@ -0,0 +1,878 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use chrono_tz::Tz;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OptionalExtension, Row, params, params_from_iter};
+
+use super::{Owned, archive, auth, ensure_owner, reminders, tags, trash, trimmed};
+use crate::error::RecallError;
+use crate::events::{
+    CreateEventRequest, Event, EventInfo, EventQuery, OccurrenceRequest, UpdateEventRequest,
+};
+use crate::recurrence::{RecurrenceRule, Until};
+use crate::search::build_match_query;
+use crate::{time_zone, try_insert_thing};
+
+// Most occurrences a single series expands to in one listing
+const MAX_OCCURRENCES: usize = 1_000;
+
+// Floating series compare UNTIL, an instant, against wall-clock readings, which can be
+// up to this far ahead of UTC
+const MAX_UTC_OFFSET: i64 = 14 * 3600;
+
+// Events of a project in the trash show without one; the link comes back when it is restored
+const SELECT_EVENT: &str = "SELECT e.id, p.id as project_id, e.title, e.description, e.start_time, e.end_time, e.location, e.all_day, e.created_at, e.updated_at, p.title as project_name, e.recurrence_rule, e.time_zone
+     FROM events e
+     LEFT JOIN projects p ON e.project_id = p.id AND p.deleted_at IS NULL
+         AND NOT EXISTS(SELECT 1 FROM areas pa WHERE pa.id = p.area_id AND pa.deleted_at IS NOT NULL)";
+
+fn event_from_row(row: &Row) -> rusqlite::Result<EventInfo> {
+    Ok(EventInfo {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        project_name: row.get(10)?,
+        title: row.get(2)?,
+        description: row.get(3)?,
+        start_time: row.get(4)?,
+        end_time: row.get(5)?,
+        location: row.get(6)?,
+        all_day: row.get::<_, i64>(7)? == 1,
+        time_zone: row.get(12)?,
+        day: String::new(),
+        recurrence_rule: row.get(11)?,
+        exception_dates: Vec::new(),
+        reminders: Vec::new(),
+        occurrence_start: None,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+// An edited occurrence, keyed by the start it had in the series
+#[derive(Debug)]
+struct OccurrenceOverride {
+    occurrence_start: i64,
+    title: String,
+    description: Option<String>,
+    start_time: i64,
+    end_time: Option<i64>,
+    location: Option<String>,
+    all_day: bool,
+    time_zone: Option<String>,
+}
+
+// Times as stored: instants in `time_zone`, or floating wall-clock readings when it is None
+struct StoredTimes {
+    start_time: i64,
+    end_time: Option<i64>,
+    time_zone: Option<String>,
+}
+
+// All-day times are kept as the dates they fall on in `tz`, whatever zone they are viewed from
+fn stored_times(all_day: bool, start_time: i64, end_time: Option<i64>, tz: Tz) -> StoredTimes {
+    if all_day {
+        StoredTimes {
+            start_time: time_zone::to_floating(tz, start_time),
+            end_time: end_time.map(|end| time_zone::to_floating(tz, end)),
+            time_zone: None,
+        }
+    } else {
+        StoredTimes {
+            start_time,
+            end_time,
+            time_zone: Some(tz.name().to_string()),
+        }
+    }
+}
+
+// A stored time of a floating or zoned row as an instant in the viewer's zone
+fn load(floating: bool, viewer: Tz, stored: i64) -> i64 {
+    if floating {
+        time_zone::from_floating(viewer, stored)
+    } else {
+        stored
+    }
+}
+
+fn store(floating: bool, tz: Tz, instant: i64) -> i64 {
+    if floating {
+        time_zone::to_floating(tz, instant)
+    } else {
+        instant
+    }
+}
+
+// The zone named in a request, or the user's
+fn request_zone(name: Option<&str>, viewer: Tz) -> Result<Tz, RecallError> {
+    match name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => time_zone::parse(name),
+        None => Ok(viewer),
+    }
+}
+
+// The zone a series' occurrences keep their wall-clock time in. Floating series follow the viewer.
+pub(crate) fn schedule_zone(event: &EventInfo, viewer: Tz) -> Tz {
+    event
+        .time_zone
+        .as_deref()
+        .and_then(|name| time_zone::parse(name).ok())
+        .unwrap_or(viewer)
+}
+
+// Latest possible occurrence start as stored, for window queries
+pub(crate) fn recurrence_end(
+    rule: &RecurrenceRule,
+    start_time: i64,
+    zone: Option<&str>,
+) -> Option<i64> {
+    match zone.and_then(|name| time_zone::parse(name).ok()) {
+        Some(tz) => rule.last_occurrence_in(tz, start_time),
+        // Floating series expand over wall-clock readings as if they were UTC
+        None => match rule.until {
+            Some(Until::Instant(until)) if rule.count.is_none() => Some(until + MAX_UTC_OFFSET),
+            _ => rule.last_occurrence(start_time),
+        },
+    }
+}
+
+fn validate_fields(title: &str, start_time: i64, end_time: Option<i64>) -> Result<(), RecallError> {
+    if title.trim().is_empty() {
+        return Err(RecallError::validation("Event title cannot be empty"));
+    }
+    if end_time.is_some_and(|end| end <= start_time) {
+        return Err(RecallError::validation("End time must be after start time"));
+    }
+    Ok(())
+}
+
+// A blank rule means the event does not repeat
+fn parse_rule(rule: Option<String>) -> Result<Option<RecurrenceRule>, RecallError> {
+    trimmed(rule)
+        .map(|rule| RecurrenceRule::parse(&rule))
+        .transpose()
+}
+
+pub(crate) fn stored_rule(event: &EventInfo) -> Result<Option<RecurrenceRule>, RecallError> {
+    event
+        .recurrence_rule
+        .as_deref()
+        .map(RecurrenceRule::parse)
+        .transpose()
+}
+
+// Stored exception dates; floating series store wall-clock readings
+fn exception_dates(conn: &Connection, event_id: &str) -> Result<Vec<i64>, RecallError> {
+    let mut stmt = conn.prepare(
+        "SELECT occurrence_start FROM event_exceptions WHERE event_id = ?1 ORDER BY occurrence_start",
+    )?;
+    let dates = stmt
+        .query_map(params![event_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(dates)
+}
+
+fn set_exception_dates(
+    conn: &Connection,
+    event_id: &str,
+    dates: &[i64],
+) -> Result<(), RecallError> {
+    conn.execute(
+        "DELETE FROM event_exceptions WHERE event_id = ?1",
+        params![event_id],
+    )?;
+    for date in dates {
+        conn.execute(
+            "INSERT OR IGNORE INTO event_exceptions (event_id, occurrence_start) VALUES (?1, ?2)",
+            params![event_id, date],
+        )?;
+    }
+    Ok(())
+}
+
+// A series' edited occurrences, with times as instants in the viewer's zone
+fn overrides(
+    conn: &Connection,
+    series: &EventInfo,
+    viewer: Tz,
+) -> Result<Vec<OccurrenceOverride>, RecallError> {
+    let series_floating = series.time_zone.is_none();
+    let mut stmt = conn.prepare(
+        "SELECT occurrence_start, title, description, start_time, end_time, location, all_day, time_zone
+         FROM event_overrides WHERE event_id = ?1",
+    )?;
+    let overrides = stmt
+        .query_map(params![series.id], |row| {
+            let time_zone: Option<String> = row.get(7)?;
+            let floating = time_zone.is_none();
+            Ok(OccurrenceOverride {
+                occurrence_start: load(series_floating, viewer, row.get(0)?),
+                title: row.get(1)?,
+                description: row.get(2)?,
+                start_time: load(floating, viewer, row.get(3)?),
+                end_time: row
+                    .get::<_, Option<i64>>(4)?
+                    .map(|end| load(floating, viewer, end)),
+                location: row.get(5)?,
+                all_day: row.get::<_, i64>(6)? == 1,
+                time_zone,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(overrides)
+}
+
+// Turn an event as stored into one as seen from the viewer's zone
+fn localize(conn: &Connection, mut event: EventInfo, viewer: Tz) -> Result<EventInfo, RecallError> {
+    let floating = event.time_zone.is_none();
+    if event.recurrence_rule.is_some() {
+        event.exception_dates = exception_dates(conn, &event.id)?
+            .into_iter()
+            .map(|date| load(floating, viewer, date))
+            .collect();
+    }
+    event.reminders = reminders::for_event(conn, &event.id)?;
+    event.start_time = load(floating, viewer, event.start_time);
+    event.end_time = event.end_time.map(|end| load(floating, viewer, end));
+    event.day = time_zone::day(viewer, event.start_time).to_string();
+    Ok(event)
+}
+
+// The series' details moved to one of its occurrence starts
+fn occurrence_of(series: &EventInfo, occurrence_start: i64, viewer: Tz) -> EventInfo {
+    let mut occurrence = series.clone();
+    occurrence.start_time = occurrence_start;
+    occurrence.end_time = series
+        .end_time
+        .map(|end| occurrence_start + (end - series.start_time));
+    occurrence.occurrence_start = Some(occurrence_start);
+    occurrence.day = time_zone::day(viewer, occurrence_start).to_string();
+    occurrence
+}
+
+fn overridden(series: &EventInfo, edit: &OccurrenceOverride, viewer: Tz) -> EventInfo {
+    let mut occurrence = series.clone();
+    occurrence.title = edit.title.clone();
+    occurrence.description = edit.description.clone();
+    occurrence.start_time = edit.start_time;
+    occurrence.end_time = edit.end_time;
+    occurrence.location = edit.location.clone();
+    occurrence.all_day = edit.all_day;
+    occurrence.time_zone = edit.time_zone.clone();
+    occurrence.occurrence_start = Some(edit.occurrence_start);
+    occurrence.day = time_zone::day(viewer, edit.start_time).to_string();
+    occurrence
+}
+
+// Every edited occurrence of a series, wherever it was moved to
+pub(crate) fn edited_occurrences(
+    conn: &Connection,
+    series: &EventInfo,
+    viewer: Tz,
+) -> Result<Vec<EventInfo>, RecallError> {
+    let mut edits: Vec<EventInfo> = overrides(conn, series, viewer)?
+        .iter()
+        .filter(|edit| !series.exception_dates.contains(&edit.occurrence_start))
+        .map(|edit| overridden(series, edit, viewer))
+        .collect();
+    edits.sort_by_key(|edit| edit.occurrence_start);
+    Ok(edits)
+}
+
+// Occurrences of a series overlapping [from, to], with exceptions and overrides applied
+fn expand(
+    conn: &Connection,
+    series: &EventInfo,
+    rule: &RecurrenceRule,
+    from: i64,
+    to: i64,
+    viewer: Tz,
+    wanted: usize,
+) -> Result<Vec<EventInfo>, RecallError> {
+    let zone = schedule_zone(series, viewer);
+    let cancelled: HashSet<i64> = series.exception_dates.iter().copied().collect();
+    let edits: HashMap<i64, OccurrenceOverride> = overrides(conn, series, viewer)?
+        .into_iter()
+        .map(|edit| (edit.occurrence_start, edit))
+        .collect();
+    // Occurrences starting up to one duration before the window still run into it
+    let earliest = from - series.end_time.map_or(0, |end| end - series.start_time);
+    let overlaps = |edit: &OccurrenceOverride| {
+        edit.start_time <= to && edit.end_time.unwrap_or(edit.start_time) >= from
+    };
+
+    // Past the first `wanted` untouched occurrences nothing scheduled can reach the page
+    let cap = wanted
+        .saturating_add(cancelled.len() + edits.len())
+        .min(MAX_OCCURRENCES);
+    let mut occurrences = Vec::new();
+    let starts = rule.occurrences_between_in(zone, series.start_time, earliest, to, cap);
+    for &start in &starts {
+        if cancelled.contains(&start) {
+            continue;
+        }
+        match edits.get(&start) {
+            // Moved out of the window
+            Some(edit) if !overlaps(edit) => {}
+            Some(edit) => occurrences.push(overridden(series, edit, viewer)),
+            None => occurrences.push(occurrence_of(series, start, viewer)),
+        }
+    }
+
+    // Occurrences moved into the window from elsewhere in the series, or from past the cap
+    for edit in edits.values() {
+        if overlaps(edit)
+            && !starts.contains(&edit.occurrence_start)
+            && !cancelled.contains(&edit.occurrence_start)
+            && rule.is_occurrence_in(zone, series.start_time, edit.occurrence_start)
+        {
+            occurrences.push(overridden(series, edit, viewer));
+        }
+    }
+
+    Ok(occurrences)
+}
+
+// The series a recurring event's occurrence belongs to, after checking it is one
+fn series_occurrence(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    occurrence_start: i64,
+    viewer: Tz,
+) -> Result<EventInfo, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Event,
+        id,
+        user_id,
+        "You don't have permission to update this event",
+    )?;
+    let series = find(conn, user_id, id, viewer)?;
+    let Some(rule) = stored_rule(&series)? else {
+        return Err(RecallError::validation("Event does not repeat"));
+    };
+    if series.exception_dates.contains(&occurrence_start)
+        || !rule.is_occurrence_in(
+            schedule_zone(&series, viewer),
+            series.start_time,
+            occurrence_start,
+        )
+    {
+        return Err(RecallError::not_found("Occurrence"));
+    }
+    Ok(series)
+}
+
+pub fn create(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateEventRequest,
+) -> Result<EventInfo, RecallError> {
+    let tx = conn.unchecked_transaction()?;
+    let event = insert(&tx, user_id, request)?;
+    tx.commit()?;
+    Ok(event)
+}
+
+// create without a transaction of its own, for callers writing more alongside it in theirs
+pub(crate) fn insert(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateEventRequest,
+) -> Result<EventInfo, RecallError> {
+    validate_fields(&request.title, request.start_time, request.end_time)?;
+    reminders::check(&request.reminders)?;
+    let rule = parse_rule(request.recurrence_rule)?;
+    let viewer = auth::time_zone(conn, user_id)?;
+    let tz = request_zone(request.time_zone.as_deref(), viewer)?;
+    if let Some(ref project_id) = request.project_id {
+        ensure_owner(
+            conn,
+            Owned::Project,
+            project_id,
+            user_id,
+            "You don't have permission to create events for this project",
+        )?;
+    }
+
+    let times = stored_times(request.all_day, request.start_time, request.end_time, tz);
+    let exceptions: Vec<i64> = request
+        .exception_dates
+        .iter()
+        .map(|&date| store(request.all_day, tz, date))
+        .collect();
+
+    let now = Utc::now().timestamp();
+    let mut event = Event {
+        id: None,
+        user_id: user_id.to_string(),
+        project_id: request.project_id,
+        title: request.title.trim().to_string(),
+        description: trimmed(request.description),
+        start_time: times.start_time,
+        end_time: times.end_time,
+        location: trimmed(request.location),
+        all_day: if request.all_day { 1 } else { 0 },
+        time_zone: times.time_zone.clone(),
+        recurrence_rule: rule.as_ref().map(|r| r.to_string()),
+        recurrence_end: rule
+            .as_ref()
+            .and_then(|r| recurrence_end(r, times.start_time, times.time_zone.as_deref())),
+        ical_uid: None,
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let event_id = try_insert_thing(&mut event, conn)?;
+    if rule.is_some() {
+        set_exception_dates(conn, &event_id, &exceptions)?;
+    }
+    reminders::set_for_event(conn, &event_id, &request.reminders)?;
+    println!("inserted Event id: {:?}", event_id);
+
+    find(conn, user_id, &event_id, viewer)
+}
+
+// WHERE clauses over `events e` and the values they bind, numbered in the order added
+#[derive(Default)]
+struct Conditions {
+    clauses: Vec<String>,
+    values: Vec<Value>,
+}
+
+impl Conditions {
+    // Bind a value, returning its placeholder
+    fn bind(&mut self, value: impl Into<Value>) -> String {
+        self.values.push(value.into());
+        format!("?{}", self.values.len())
+    }
+
+    fn and(&mut self, clause: impl Into<String>) {
+        self.clauses.push(clause.into());
+    }
+
+    // An instant, or its wall-clock reading for rows whose `zone` column is NULL
+    fn bound(&mut self, zone: &str, viewer: Tz, instant: i64) -> String {
+        let floating = self.bind(time_zone::to_floating(viewer, instant));
+        let zoned = self.bind(instant);
+        format!(
+            "(CASE WHEN {} IS NULL THEN {} ELSE {} END)",
+            zone, floating, zoned
+        )
+    }
+
+    // Clauses for rows of `alias` overlapping [from, to]. Rows without an end last an instant.
+    fn overlapping(
+        &mut self,
+        alias: &str,
+        viewer: Tz,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> Vec<String> {
+        let zone = format!("{}.time_zone", alias);
+        let mut clauses = Vec::new();
+        if let Some(to) = to {
+            // The zone-free bound is the later of the two, so the start_time index can
+            // narrow the scan before the exact comparison
+            let widest = self.bind(to.max(time_zone::to_floating(viewer, to)));
+            let exact = self.bound(&zone, viewer, to);
+            clauses.push(format!("{a}.start_time <= {}", widest, a = alias));
+            clauses.push(format!("{a}.start_time <= {}", exact, a = alias));
+        }
+        if let Some(from) = from {
+            let exact = self.bound(&zone, viewer, from);
+            clauses.push(format!(
+                "COALESCE({a}.end_time, {a}.start_time) >= {}",
+                exact,
+                a = alias
+            ));
+        }
+        clauses
+    }
+
+    fn query(&self, conn: &Connection, viewer: Tz) -> Result<Vec<EventInfo>, RecallError> {
+        let mut stmt = conn.prepare(&format!(
+            "{} WHERE {}",
+            SELECT_EVENT,
+            self.clauses.join(" AND ")
+        ))?;
+        let events = stmt
+            .query_map(params_from_iter(&self.values), event_from_row)?
+            .map(|event| localize(conn, event?, viewer))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(events)
+    }
+
+    // Stored start of the nth matching row in start order, if there are that many
+    fn nth_start(&self, conn: &Connection, n: usize) -> Result<Option<i64>, RecallError> {
+        let start = conn
+            .query_row(
+                &format!(
+                    "SELECT e.start_time FROM events e WHERE {} ORDER BY e.start_time LIMIT 1 OFFSET {}",
+                    self.clauses.join(" AND "),
+                    n - 1
+                ),
+                params_from_iter(&self.values),
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(start)
+    }
+}
+
+// Filters on the event rows themselves, shared by one-off events and series
+fn row_conditions(user_id: &str, query: &EventQuery) -> Conditions {
+    let mut conditions = Conditions::default();
+    let user = conditions.bind(user_id.to_string());
+    conditions.and(format!("e.user_id = {}", user));
+    conditions.and("e.deleted_at IS NULL");
+
+    if !query.project_ids.is_empty() {
+        let ids: Vec<String> = query
+            .project_ids
+            .iter()
+            .map(|id| conditions.bind(id.clone()))
+            .collect();
+        conditions.and(format!("e.project_id IN ({})", ids.join(", ")));
+    } else if !query.include_archived {
+        conditions.and(format!(
+            "(e.project_id IS NULL OR NOT {})",
+            archive::hidden_project("e.project_id")
+        ));
+    }
+    if let Some(tag_ids) = tags::filter(&query.tag_ids) {
+        let tag_ids = conditions.bind(tag_ids);
+        conditions.and(tags::tagged(Owned::Event, "e.id", &tag_ids));
+    }
+    if let Some(text) = query.text.as_deref().and_then(build_match_query) {
+        let text = conditions.bind(text);
+        conditions.and(format!(
+            "e.id IN (SELECT id FROM events_fts WHERE events_fts MATCH {} AND user_id = {})",
+            text, user
+        ));
+    }
+    if query.all_day_only {
+        conditions.and("e.all_day = 1");
+    }
+    conditions
+}
+
+// Events matching a query, ordered by start. With both window bounds, recurring events
+// are expanded into their occurrences overlapping the window; otherwise each series is
+// matched and listed once, as a single row. All-day events are placed in the user's zone.
+pub fn query(
+    conn: &Connection,
+    user_id: &str,
+    query: &EventQuery,
+) -> Result<Vec<EventInfo>, RecallError> {
+    let viewer = auth::time_zone(conn, user_id)?;
+    let window = query.start_date.zip(query.end_date);
+    let offset = query.offset.unwrap_or(0).max(0) as usize;
+    // Rows up to the end of the requested page; only these can land on it
+    let wanted = query
+        .limit
+        .map(|limit| offset.saturating_add(limit.max(0) as usize));
+    if wanted == Some(0) {
+        return Ok(Vec::new());
+    }
+
+    let mut one_off = row_conditions(user_id, query);
+    for clause in one_off.overlapping("e", viewer, query.start_date, query.end_date) {
+        one_off.and(clause);
+    }
+    if window.is_some() {
+        one_off.and("e.recurrence_rule IS NULL");
+    }
+    if let Some(wanted) = wanted {
+        // All-day rows are stored by wall clock, up to MAX_UTC_OFFSET either side of the
+        // instant they sort by, so keep everything that close to the last wanted row
+        if let Some(last) = one_off.nth_start(conn, wanted)? {
+            let cutoff = one_off.bind(last + 2 * MAX_UTC_OFFSET);
+            one_off.and(format!("e.start_time <= {}", cutoff));
+        }
+    }
+    let mut events = one_off.query(conn, viewer)?;
+
+    if let Some((from, to)) = window {
+        // Series that may have an occurrence overlapping the window, or one moved into it.
+        // An occurrence starting before the window can still run into it.
+        let mut series = row_conditions(user_id, query);
+        series.and("e.recurrence_rule IS NOT NULL");
+        let upper = series.bound("e.time_zone", viewer, to);
+        let lower = series.bound("e.time_zone", viewer, from);
+        let moved = series.overlapping("o", viewer, Some(from), Some(to));
+        series.and(format!(
+            "((e.start_time <= {} AND (e.recurrence_end IS NULL
+                 OR e.recurrence_end + COALESCE(e.end_time - e.start_time, 0) >= {}))
+              OR EXISTS (SELECT 1 FROM event_overrides o WHERE o.event_id = e.id AND {}))",
+            upper,
+            lower,
+            moved.join(" AND ")
+        ));
+
+        for series in series.query(conn, viewer)? {
+            if let Some(rule) = stored_rule(&series)? {
+                events.extend(expand(
+                    conn,
+                    &series,
+                    &rule,
+                    from,
+                    to,
+                    viewer,
+                    wanted.unwrap_or(MAX_OCCURRENCES),
+                )?);
+            }
+        }
+        // Edited occurrences may no longer be all-day
+        if query.all_day_only {
+            events.retain(|event| event.all_day);
+        }
+    }
+
+    // All-day times move with the viewer's zone, so order after converting them
+    events.sort_by_key(|event| event.start_time);
+    events.truncate(wanted.unwrap_or(usize::MAX));
+    Ok(events.into_iter().skip(offset).collect())
+}
+
+// Events overlapping [start_date, end_date], either bound optional, optionally for one project
+pub fn list(
+    conn: &Connection,
+    user_id: &str,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    project_id: Option<&str>,
+) -> Result<Vec<EventInfo>, RecallError> {
+    query(
+        conn,
+        user_id,
+        &EventQuery {
+            start_date,
+            end_date,
+            project_ids: project_id.map(str::to_string).into_iter().collect(),
+            ..EventQuery::default()
+        },
+    )
+}
+
+pub fn get(conn: &Connection, user_id: &str, id: &str) -> Result<EventInfo, RecallError> {
+    let viewer = auth::time_zone(conn, user_id)?;
+    find(conn, user_id, id, viewer)
+}
+
+fn find(conn: &Connection, user_id: &str, id: &str, viewer: Tz) -> Result<EventInfo, RecallError> {
+    let event = conn
+        .query_row(
+            &format!(
+                "{} WHERE e.id = ?1 AND e.user_id = ?2 AND e.deleted_at IS NULL",
+                SELECT_EVENT
+            ),
+            params![id, user_id],
+            event_from_row,
+        )
+        .optional()?
+        .ok_or_else(|| RecallError::not_found("Event"))?;
+    localize(conn, event, viewer)
+}
+
+// Changing a series' start, zone or rule discards its cancelled and edited occurrences,
+// since they are keyed by occurrence starts the new schedule may no longer produce
+pub fn update(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    request: UpdateEventRequest,
+) -> Result<EventInfo, RecallError> {
+    let tx = conn.unchecked_transaction()?;
+    let event = overwrite(&tx, user_id, id, request)?;
+    tx.commit()?;
+    Ok(event)
+}
+
+// update without a transaction of its own, for callers writing more alongside it in theirs
+pub(crate) fn overwrite(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    request: UpdateEventRequest,
+) -> Result<EventInfo, RecallError> {
+    validate_fields(&request.title, request.start_time, request.end_time)?;
+    if let Some(ref minutes) = request.reminders {
+        reminders::check(minutes)?;
+    }
+    let rule = parse_rule(request.recurrence_rule)?;
+    let viewer = auth::time_zone(conn, user_id)?;
+    let tz = request_zone(request.time_zone.as_deref(), viewer)?;
+    ensure_owner(
+        conn,
+        Owned::Event,
+        id,
+        user_id,
+        "You don't have permission to update this event",
+    )?;
+    if let Some(ref project_id) = request.project_id {
+        ensure_owner(
+            conn,
+            Owned::Project,
+            project_id,
+            user_id,
+            "You don't have permission to assign events to this project",
+        )?;
+    }
+
+    let times = stored_times(request.all_day, request.start_time, request.end_time, tz);
+    let recurrence_rule = rule.as_ref().map(|r| r.to_string());
+    let previous: (i64, Option<String>, Option<String>) = conn.query_row(
+        "SELECT start_time, time_zone, recurrence_rule FROM events WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    let reschedules = (times.start_time, &times.time_zone, &recurrence_rule)
+        != (previous.0, &previous.1, &previous.2);
+
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "UPDATE events SET project_id = ?1, title = ?2, description = ?3, start_time = ?4, end_time = ?5, location = ?6, all_day = ?7, time_zone = ?8, recurrence_rule = ?9, recurrence_end = ?10, updated_at = ?11 WHERE id = ?12 AND user_id = ?13",
+        params![
+            request.project_id,
+            request.title.trim(),
+            trimmed(request.description),
+            times.start_time,
+            times.end_time,
+            trimmed(request.location),
+            if request.all_day { 1 } else { 0 },
+            times.time_zone,
+            recurrence_rule,
+            rule.as_ref().and_then(|r| recurrence_end(r, times.start_time, times.time_zone.as_deref())),
+            now,
+            id,
+            user_id
+        ],
+    )?;
+    if rule.is_none() || reschedules {
+        conn.execute(
+            "DELETE FROM event_overrides WHERE event_id = ?1",
+            params![id],
+        )?;
+        set_exception_dates(conn, id, &[])?;
+    }
+    if let (Some(_), Some(dates)) = (&rule, &request.exception_dates) {
+        let dates: Vec<i64> = dates
+            .iter()
+            .map(|&date| store(request.all_day, tz, date))
+            .collect();
+        set_exception_dates(conn, id, &dates)?;
+    }
+    if let Some(ref minutes) = request.reminders {
+        reminders::set_for_event(conn, id, minutes)?;
+    }
+
+    find(conn, user_id, id, viewer)
+}
+
+// Give one occurrence of a recurring event its own details
+pub fn update_occurrence(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    occurrence_start: i64,
+    request: OccurrenceRequest,
+) -> Result<EventInfo, RecallError> {
+    validate_fields(&request.title, request.start_time, request.end_time)?;
+    let viewer = auth::time_zone(conn, user_id)?;
+    let tz = request_zone(request.time_zone.as_deref(), viewer)?;
+    let series = series_occurrence(conn, user_id, id, occurrence_start, viewer)?;
+    let key = store(series.time_zone.is_none(), viewer, occurrence_start);
+    let times = stored_times(request.all_day, request.start_time, request.end_time, tz);
+    let floating = times.time_zone.is_none();
+
+    let edit = OccurrenceOverride {
+        occurrence_start,
+        title: request.title.trim().to_string(),
+        description: trimmed(request.description),
+        start_time: load(floating, viewer, times.start_time),
+        end_time: times.end_time.map(|end| load(floating, viewer, end)),
+        location: trimmed(request.location),
+        all_day: request.all_day,
+        time_zone: times.time_zone.clone(),
+    };
+
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "INSERT INTO event_overrides (event_id, occurrence_start, title, description, start_time, end_time, location, all_day, time_zone, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)
+         ON CONFLICT (event_id, occurrence_start) DO UPDATE SET
+             title = excluded.title,
+             description = excluded.description,
+             start_time = excluded.start_time,
+             end_time = excluded.end_time,
+             location = excluded.location,
+             all_day = excluded.all_day,
+             time_zone = excluded.time_zone,
+             updated_at = excluded.updated_at",
+        params![
+            id,
+            key,
+            edit.title,
+            edit.description,
+            times.start_time,
+            times.end_time,
+            edit.location,
+            if edit.all_day { 1 } else { 0 },
+            times.time_zone,
+            now
+        ],
+    )?;
+
+    Ok(overridden(&series, &edit, viewer))
+}
+
+// Drop one occurrence from a recurring event, as an EXDATE
+pub fn cancel_occurrence(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    occurrence_start: i64,
+) -> Result<(), RecallError> {
+    let tx = conn.unchecked_transaction()?;
+    drop_occurrence(&tx, user_id, id, occurrence_start)?;
+    tx.commit()?;
+    Ok(())
+}
+
+// cancel_occurrence without a transaction of its own, for callers writing more in theirs
+pub(crate) fn drop_occurrence(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    occurrence_start: i64,
+) -> Result<(), RecallError> {
+    let viewer = auth::time_zone(conn, user_id)?;
+    let series = series_occurrence(conn, user_id, id, occurrence_start, viewer)?;
+    let key = store(series.time_zone.is_none(), viewer, occurrence_start);
+
+    conn.execute(
+        "INSERT OR IGNORE INTO event_exceptions (event_id, occurrence_start) VALUES (?1, ?2)",
+        params![id, key],
+    )?;
+    conn.execute(
+        "DELETE FROM event_overrides WHERE event_id = ?1 AND occurrence_start = ?2",
+        params![id, key],
+    )?;
+    Ok(())
+}
+
+// Moves the event to the trash, series and all
+pub fn delete(conn: &Connection, user_id: &str, id: &str) -> Result<(), RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Event,
+        id,
+        user_id,
+        "You don't have permission to delete this event",
+    )?;
+
+    trash::move_to_trash(conn, Owned::Event, id)
+}
@@ -0,0 +1,294 @@
+use std::ops::Range;
+
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, params};
+
+use super::{Owned, ensure_owner, resources, revisions, trash};
+use crate::error::RecallError;
+use crate::links::Backlink;
+
+const MAX_TITLE_LENGTH: usize = 200;
+
+// A [[link]] in a run of text. `[[Title]]` names a resource; `[[project:Title]]`,
+// `[[area:Title]]`, `[[event:Title]]` and `[[resource:Title]]` name the kind too.
+struct Link<'a> {
+    range: Range<usize>, // Of the whole link, brackets included
+    prefix: &'a str,     // "project:" and the like as written, or ""
+    kind: Owned,
+    title: &'a str,
+}
+
+fn scan(text: &str) -> Vec<Link<'_>> {
+    let mut links = Vec::new();
+    let mut from = 0;
+    while let Some(start) = text[from..].find("[[").map(|i| from + i) {
+        let Some(end) = text[start + 2..].find("]]").map(|i| start + 2 + i) else {
+            break;
+        };
+        let inner = &text[start + 2..end];
+        if inner.contains(['[', ']', '\n']) {
+            from = start + 1;
+            continue;
+        }
+
+        let (prefix, kind, title) = match inner.split_once(':') {
+            Some((kind, title)) => match Owned::parse(&kind.trim().to_lowercase()) {
+                Ok(owned) => (&inner[..kind.len() + 1], owned, title),
+                Err(_) => ("", Owned::Resource, inner),
+            },
+            None => ("", Owned::Resource, inner),
+        };
+        let title = title.trim();
+        if !title.is_empty() && title.chars().count() <= MAX_TITLE_LENGTH {
+            links.push(Link {
+                range: start..end + 2,
+                prefix,
+                kind,
+                title,
+            });
+        }
+        from = end + 2;
+    }
+    links
+}
+
+// Editor documents are TipTap JSON, plain text otherwise. Links are looked for within each
+// text node, so one with formatting changing halfway through is not seen.
+fn text_nodes<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(text)) = map.get("text") {
+                out.push(text);
+            }
+            if let Some(children) = map.get("content") {
+                text_nodes(children, out);
+            }
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|item| text_nodes(item, out)),
+        _ => {}
+    }
+}
+
+fn text_nodes_mut(value: &mut serde_json::Value, rewrite: &mut impl FnMut(&mut String)) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(text)) = map.get_mut("text") {
+                rewrite(text);
+            }
+            if let Some(children) = map.get_mut("content") {
+                text_nodes_mut(children, rewrite);
+            }
+        }
+        serde_json::Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| text_nodes_mut(item, rewrite)),
+        _ => {}
+    }
+}
+
+fn document(content: &str) -> Option<serde_json::Value> {
+    serde_json::from_str(content)
+        .ok()
+        .filter(|value: &serde_json::Value| value.is_object() || value.is_array())
+}
+
+// Every link in a resource's content, as (kind, title)
+fn parse(content: &str) -> Vec<(Owned, String)> {
+    let mut texts = Vec::new();
+    let value = document(content);
+    match &value {
+        Some(value) => text_nodes(value, &mut texts),
+        None => texts.push(content),
+    }
+    texts
+        .into_iter()
+        .flat_map(scan)
+        .map(|link| (link.kind, link.title.to_string()))
+        .collect()
+}
+
+// `text` with links to the `kind` titled `old` pointed at `new`, or None when it has none
+fn rewrite_text(text: &str, kind: Owned, old: &str, new: &str) -> Option<String> {
+    let mut rewritten = String::with_capacity(text.len());
+    let mut last = 0;
+    for link in scan(text) {
+        if link.kind.name() != kind.name() || !link.title.eq_ignore_ascii_case(old) {
+            continue;
+        }
+        rewritten.push_str(&text[last..link.range.start]);
+        rewritten.push_str(&format!("[[{}{}]]", link.prefix, new));
+        last = link.range.end;
+    }
+    if last == 0 {
+        return None;
+    }
+    rewritten.push_str(&text[last..]);
+    Some(rewritten)
+}
+
+fn rewrite(content: &str, kind: Owned, old: &str, new: &str) -> Option<String> {
+    let Some(mut value) = document(content) else {
+        return rewrite_text(content, kind, old, new);
+    };
+    let mut changed = false;
+    text_nodes_mut(&mut value, &mut |text| {
+        if let Some(rewritten) = rewrite_text(text, kind, old, new) {
+            *text = rewritten;
+            changed = true;
+        }
+    });
+    changed.then(|| value.to_string())
+}
+
+// The user's item of `kind` called `title`, the oldest if several are, skipping the trash
+fn resolve(
+    conn: &Connection,
+    user_id: &str,
+    kind: Owned,
+    title: &str,
+) -> Result<Option<String>, RecallError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT t.id FROM {} t WHERE t.user_id = ?1 AND t.{} = ?2 COLLATE NOCASE AND NOT {}
+                 ORDER BY t.created_at LIMIT 1",
+                kind.table(),
+                kind.title_column(),
+                trash::trashed(kind, "t.id")
+            ),
+            params![user_id, title],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+// Replace the links recorded for a resource with those in its new content
+pub(crate) fn refresh(
+    conn: &Connection,
+    user_id: &str,
+    source_id: &str,
+    content: Option<&str>,
+) -> Result<(), RecallError> {
+    conn.execute(
+        "DELETE FROM resource_links WHERE source_id = ?1",
+        params![source_id],
+    )?;
+
+    let now = Utc::now().timestamp();
+    for (kind, title) in content.map(parse).unwrap_or_default() {
+        let target_id = resolve(conn, user_id, kind, &title)?;
+        // A document mentioning itself is not a backlink
+        if matches!(kind, Owned::Resource) && target_id.as_deref() == Some(source_id) {
+            continue;
+        }
+        conn.execute(
+            "INSERT OR IGNORE INTO resource_links (source_id, target_kind, target_title, target_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![source_id, kind.name(), title, target_id, now],
+        )?;
+    }
+    Ok(())
+}
+
+// Links that lead to the item: those resolved to it, and dangling ones with its title
+const LEADS_TO: &str =
+    "l.target_kind = ?2 AND (l.target_id = ?3 OR (l.target_id IS NULL AND l.target_title = ?4))";
+
+// After an item is renamed from `old` to `new`, point the links to it in the user's
+// documents at the new title. Changed documents get a revision like any other save.
+pub(crate) fn renamed(
+    conn: &Connection,
+    user_id: &str,
+    kind: Owned,
+    id: &str,
+    old: &str,
+    new: &str,
+) -> Result<(), RecallError> {
+    if old == new {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT r.id, r.content FROM resource_links l JOIN resources r ON r.id = l.source_id
+         WHERE r.user_id = ?1 AND r.content IS NOT NULL AND {}",
+        LEADS_TO
+    ))?;
+    let documents = stmt
+        .query_map(params![user_id, kind.name(), id, old], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let now = Utc::now().timestamp();
+    for (source_id, content) in documents {
+        let Some(content) = rewrite(&content, kind, old, new) else {
+            continue;
+        };
+        revisions::record(conn, &source_id, Some(&content), now, true)?;
+        conn.execute(
+            "UPDATE resources SET content = ?1, updated_at = ?2 WHERE id = ?3",
+            params![content, now, source_id],
+        )?;
+    }
+
+    conn.execute(
+        &format!(
+            "UPDATE OR REPLACE resource_links AS l SET target_id = ?3, target_title = ?5
+             WHERE l.source_id IN (SELECT id FROM resources WHERE user_id = ?1) AND {}",
+            LEADS_TO
+        ),
+        params![user_id, kind.name(), id, old, new],
+    )?;
+    Ok(())
+}
+
+// Resources whose documents link to the area, project, resource or event, most recently
+// updated first. Documents in the trash are left out.
+pub fn backlinks(
+    conn: &Connection,
+    user_id: &str,
+    kind: Owned,
+    id: &str,
+) -> Result<Vec<Backlink>, RecallError> {
+    ensure_owner(
+        conn,
+        kind,
+        id,
+        user_id,
+        &format!("You don't have permission to view this {}", kind.name()),
+    )?;
+    let title: String = conn.query_row(
+        &format!(
+            "SELECT {} FROM {} WHERE id = ?1",
+            kind.title_column(),
+            kind.table()
+        ),
+        params![id],
+        |row| row.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT DISTINCT r.id, r.name, r.project_id, p.title, r.content, r.updated_at
+         FROM resource_links l
+         JOIN resources r ON r.id = l.source_id
+         LEFT JOIN projects p ON p.id = r.project_id
+         WHERE r.user_id = ?1 AND r.id != ?3 AND {} AND NOT {}
+         ORDER BY r.updated_at DESC, r.name COLLATE NOCASE",
+        LEADS_TO,
+        trash::trashed(Owned::Resource, "r.id")
+    ))?;
+    let backlinks = stmt
+        .query_map(params![user_id, kind.name(), id, title], |row| {
+            let content: Option<String> = row.get(4)?;
+            Ok(Backlink {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                project_id: row.get(2)?,
+                project_title: row.get(3)?,
+                snippet: content.as_deref().and_then(resources::content_snippet),
+                updated_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(backlinks)
+}
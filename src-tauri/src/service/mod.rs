@@ -0,0 +1,119 @@
+// Business logic behind the Tauri commands. Everything here works on a plain
+// rusqlite::Connection and returns typed values, so it can be called from commands,
+// bin/populate_data.rs and tests alike. Commands only parse input, resolve the user
+// from the session token and serialize what these functions return.
+pub mod archive;
+pub mod areas;
+pub mod auth;
+pub mod availability;
+pub mod calendar;
+pub mod events;
+pub mod links;
+pub mod notes;
+pub mod projects;
+pub mod reminders;
+pub mod resources;
+pub mod revisions;
+pub mod tags;
+pub mod tasks;
+pub mod trash;
+pub mod workflows;
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::error::RecallError;
+
+// Tables whose rows are owned through a user_id column
+#[derive(Debug, Clone, Copy)]
+pub enum Owned {
+    Area,
+    Project,
+    Resource,
+    Event,
+}
+
+impl Owned {
+    // "area", "project", "resource" or "event", as commands and the trash and tags name them
+    pub fn parse(kind: &str) -> Result<Owned, RecallError> {
+        match kind {
+            "area" => Ok(Owned::Area),
+            "project" => Ok(Owned::Project),
+            "resource" => Ok(Owned::Resource),
+            "event" => Ok(Owned::Event),
+            _ => Err(RecallError::validation(format!(
+                "Unknown kind of item: {}",
+                kind
+            ))),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Owned::Area => "area",
+            Owned::Project => "project",
+            Owned::Resource => "resource",
+            Owned::Event => "event",
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Owned::Area => "areas",
+            Owned::Project => "projects",
+            Owned::Resource => "resources",
+            Owned::Event => "events",
+        }
+    }
+
+    // Column holding what the item is called
+    fn title_column(self) -> &'static str {
+        match self {
+            Owned::Area | Owned::Resource => "name",
+            Owned::Project | Owned::Event => "title",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Owned::Area => "Area",
+            Owned::Project => "Project",
+            Owned::Resource => "Resource",
+            Owned::Event => "Event",
+        }
+    }
+}
+
+// NotFound if the row does not exist or is in the trash, Forbidden with `denied` if another
+// user owns it
+pub fn ensure_owner(
+    conn: &Connection,
+    kind: Owned,
+    id: &str,
+    user_id: &str,
+    denied: &str,
+) -> Result<(), RecallError> {
+    let owner: Option<(String, bool)> = conn
+        .query_row(
+            &format!(
+                "SELECT user_id, {} FROM {} WHERE id = ?1",
+                trash::trashed(kind, &format!("{}.id", kind.table())),
+                kind.table()
+            ),
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    match owner {
+        None | Some((_, true)) => Err(RecallError::not_found(kind.label())),
+        Some((owner, _)) if owner != user_id => Err(RecallError::forbidden(denied)),
+        Some(_) => Ok(()),
+    }
+}
+
+// Trim an optional text field, treating blank input as absent
+pub(crate) fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
@@ -0,0 +1,352 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use super::{Owned, ensure_owner, projects, resources, trash, trimmed, workflows};
+use crate::error::RecallError;
+use crate::notes::{CreateNoteRequest, Note, NoteInfo, NoteQuery, UpdateNoteRequest};
+use crate::projects::{CreateProjectRequest, ProjectInfo};
+use crate::resources::{CreateResourceRequest, ResourceInfo};
+use crate::try_insert_thing;
+
+const SELECT_NOTE: &str = "SELECT n.id, n.project_id, p.title, n.area_id, a.name, n.title, n.description, n.content, n.status, n.created_at, n.updated_at
+     FROM notes n
+     LEFT JOIN projects p ON p.id = n.project_id
+     LEFT JOIN areas a ON a.id = COALESCE(n.area_id, p.area_id)";
+
+fn note_from_row(row: &Row) -> rusqlite::Result<NoteInfo> {
+    Ok(NoteInfo {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        project_title: row.get(2)?,
+        area_id: row.get(3)?,
+        area_name: row.get(4)?,
+        title: row.get(5)?,
+        description: row.get(6)?,
+        content: row.get(7)?,
+        status: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+// Notes of a project or area in the trash are hidden with it, and purged along with it
+fn visible() -> String {
+    format!(
+        "NOT {} AND NOT {}",
+        trash::trashed(Owned::Project, "n.project_id"),
+        trash::trashed(Owned::Area, "n.area_id")
+    )
+}
+
+fn validate_status(status: &str) -> Result<(), RecallError> {
+    if !matches!(status, "Inbox" | "Draft" | "Final") {
+        return Err(RecallError::validation(
+            "Invalid status. Must be: Inbox, Draft, or Final",
+        ));
+    }
+    Ok(())
+}
+
+// Check the fields and that the user owns where the note is put
+fn validate_fields(
+    conn: &Connection,
+    user_id: &str,
+    title: &str,
+    status: &str,
+    project_id: Option<&str>,
+    area_id: Option<&str>,
+) -> Result<(), RecallError> {
+    if title.trim().is_empty() {
+        return Err(RecallError::validation("Note title cannot be empty"));
+    }
+    validate_status(status)?;
+    match (project_id, area_id) {
+        (Some(_), Some(_)) => Err(RecallError::validation(
+            "A note belongs to a project or an area, not both",
+        )),
+        (Some(project_id), None) => ensure_owner(
+            conn,
+            Owned::Project,
+            project_id,
+            user_id,
+            "You don't have permission to add notes to this project",
+        ),
+        (None, Some(area_id)) => ensure_owner(
+            conn,
+            Owned::Area,
+            area_id,
+            user_id,
+            "You don't have permission to add notes to this area",
+        ),
+        (None, None) => Ok(()),
+    }
+}
+
+fn ensure_note(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    denied: &str,
+) -> Result<(), RecallError> {
+    let owner: Option<(String, bool)> = conn
+        .query_row(
+            &format!(
+                "SELECT n.user_id, {} FROM notes n WHERE n.id = ?1",
+                visible()
+            ),
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    match owner {
+        None | Some((_, false)) => Err(RecallError::not_found("Note")),
+        Some((owner, _)) if owner != user_id => Err(RecallError::forbidden(denied)),
+        Some(_) => Ok(()),
+    }
+}
+
+pub fn create(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateNoteRequest,
+) -> Result<NoteInfo, RecallError> {
+    let status = request.status.unwrap_or_else(|| "Inbox".to_string());
+    validate_fields(
+        conn,
+        user_id,
+        &request.title,
+        &status,
+        request.project_id.as_deref(),
+        request.area_id.as_deref(),
+    )?;
+
+    let now = Utc::now().timestamp();
+    let mut note = Note {
+        id: None,
+        user_id: user_id.to_string(),
+        project_id: request.project_id,
+        area_id: request.area_id,
+        title: request.title.trim().to_string(),
+        description: trimmed(request.description),
+        content: request.content,
+        status,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let note_id = try_insert_thing(&mut note, conn)?;
+
+    get(conn, user_id, &note_id)
+}
+
+pub fn list(
+    conn: &Connection,
+    user_id: &str,
+    query: &NoteQuery,
+) -> Result<Vec<NoteInfo>, RecallError> {
+    if let Some(status) = &query.status {
+        validate_status(status)?;
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE n.user_id = ?1 AND {}
+         AND (?2 IS NULL OR n.status = ?2)
+         AND (?3 IS NULL OR n.project_id = ?3)
+         AND (?4 IS NULL OR n.area_id = ?4 OR p.area_id = ?4)
+         AND (NOT ?5 OR (n.project_id IS NULL AND n.area_id IS NULL))
+         ORDER BY n.updated_at DESC, n.title COLLATE NOCASE",
+        SELECT_NOTE,
+        visible()
+    ))?;
+    let notes = stmt
+        .query_map(
+            params![
+                user_id,
+                query.status,
+                query.project_id,
+                query.area_id,
+                query.unattached
+            ],
+            note_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(notes)
+}
+
+pub fn get(conn: &Connection, user_id: &str, id: &str) -> Result<NoteInfo, RecallError> {
+    ensure_note(
+        conn,
+        user_id,
+        id,
+        "You don't have permission to view this note",
+    )?;
+
+    Ok(conn.query_row(
+        &format!("{} WHERE n.id = ?1", SELECT_NOTE),
+        params![id],
+        note_from_row,
+    )?)
+}
+
+pub fn update(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    request: UpdateNoteRequest,
+) -> Result<NoteInfo, RecallError> {
+    ensure_note(
+        conn,
+        user_id,
+        id,
+        "You don't have permission to update this note",
+    )?;
+    validate_fields(
+        conn,
+        user_id,
+        &request.title,
+        &request.status,
+        request.project_id.as_deref(),
+        request.area_id.as_deref(),
+    )?;
+
+    conn.execute(
+        "UPDATE notes SET project_id = ?1, area_id = ?2, title = ?3, description = ?4, content = ?5, status = ?6, updated_at = ?7
+         WHERE id = ?8",
+        params![
+            request.project_id,
+            request.area_id,
+            request.title.trim(),
+            trimmed(request.description),
+            request.content,
+            request.status,
+            Utc::now().timestamp(),
+            id
+        ],
+    )?;
+
+    get(conn, user_id, id)
+}
+
+pub fn move_to(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    new_status: &str,
+) -> Result<NoteInfo, RecallError> {
+    validate_status(new_status)?;
+    ensure_note(
+        conn,
+        user_id,
+        id,
+        "You don't have permission to move this note",
+    )?;
+
+    conn.execute(
+        "UPDATE notes SET status = ?1, updated_at = ?2 WHERE id = ?3",
+        params![new_status, Utc::now().timestamp(), id],
+    )?;
+
+    get(conn, user_id, id)
+}
+
+pub fn delete(conn: &Connection, user_id: &str, id: &str) -> Result<(), RecallError> {
+    ensure_note(
+        conn,
+        user_id,
+        id,
+        "You don't have permission to delete this note",
+    )?;
+
+    conn.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+// The note becomes a resource named after it holding its content, in `project_id` or else
+// wherever the note is: its project, its area, or on its own for an inbox note
+pub fn promote_to_resource(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    project_id: Option<&str>,
+) -> Result<ResourceInfo, RecallError> {
+    let note = get(conn, user_id, id)?;
+    let (project_id, area_id) = match project_id {
+        Some(project_id) => (Some(project_id.to_string()), None),
+        None => (note.project_id, note.area_id),
+    };
+
+    // The note is only gone once what it became exists
+    let tx = conn.unchecked_transaction()?;
+    let resource = resources::insert(
+        &tx,
+        user_id,
+        CreateResourceRequest {
+            project_id,
+            area_id,
+            name: note.title,
+            content: note.content,
+            file_data: None,
+            file_type: None,
+            file_size: None,
+        },
+    )?;
+    tx.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+    tx.commit()?;
+
+    Ok(resource)
+}
+
+// The note becomes an Inbox project with its title and description, in `area_id` or else the
+// note's own area or its project's. Its content is kept as a resource of the new project.
+pub fn promote_to_project(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    area_id: Option<&str>,
+) -> Result<ProjectInfo, RecallError> {
+    let note = get(conn, user_id, id)?;
+    let note_area: Option<String> = conn.query_row(
+        "SELECT COALESCE(n.area_id, p.area_id) FROM notes n LEFT JOIN projects p ON p.id = n.project_id
+         WHERE n.id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    let Some(area_id) = area_id.map(str::to_string).or(note_area) else {
+        return Err(RecallError::validation("Choose an area for the project"));
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    let status = workflows::first_status(&tx, user_id, &area_id)?;
+    let project = projects::create(
+        &tx,
+        user_id,
+        CreateProjectRequest {
+            area_id,
+            title: note.title.clone(),
+            description: note.description,
+            status,
+            priority: None,
+            start_date: None,
+            end_date: None,
+        },
+    )?;
+    if note.content.is_some() {
+        resources::insert(
+            &tx,
+            user_id,
+            CreateResourceRequest {
+                project_id: Some(project.id.clone()),
+                area_id: None,
+                name: note.title,
+                content: note.content,
+                file_data: None,
+                file_type: None,
+                file_size: None,
+            },
+        )?;
+    }
+    tx.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
+    tx.commit()?;
+
+    projects::get(conn, user_id, &project.id)
+}
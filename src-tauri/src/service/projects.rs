@@ -0,0 +1,444 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use super::{Owned, ensure_owner, links, tags, trash, trimmed, workflows};
+use crate::error::RecallError;
+use crate::projects::{CreateProjectRequest, Project, ProjectInfo, UpdateProjectRequest};
+use crate::try_insert_thing;
+
+const SELECT_PROJECT: &str = "SELECT p.id, p.area_id, a.name as area_name, p.title, p.description, p.status, p.priority, p.start_date, p.end_date, p.created_at, p.updated_at, p.archived_at,
+     (SELECT COUNT(*) FROM tasks k WHERE k.project_id = p.id), (SELECT COUNT(*) FROM tasks k WHERE k.project_id = p.id AND k.done), p.position
+     FROM projects p
+     LEFT JOIN areas a ON p.area_id = a.id";
+
+// New completed_at for an UPDATE setting the status parameter, in the area `area`, at the now
+// parameter. It is kept while the project stays finished, so editing a finished project does
+// not delay auto-archiving.
+fn completed_at(status: &str, area: &str, now: &str) -> String {
+    format!(
+        "CASE WHEN NOT {} THEN NULL WHEN {} THEN completed_at ELSE {} END",
+        workflows::done(status, "projects.user_id", area),
+        workflows::done("projects.status", "projects.user_id", "projects.area_id"),
+        now
+    )
+}
+
+fn project_from_row(row: &Row) -> rusqlite::Result<ProjectInfo> {
+    let task_count: i64 = row.get(12)?;
+    let done_task_count: i64 = row.get(13)?;
+    Ok(ProjectInfo {
+        id: row.get(0)?,
+        area_id: row.get(1)?,
+        area_name: row.get(2)?,
+        title: row.get(3)?,
+        description: row.get(4)?,
+        status: row.get(5)?,
+        priority: row.get(6)?,
+        start_date: row.get(7)?,
+        end_date: row.get(8)?,
+        archived_at: row.get(11)?,
+        position: row.get(14)?,
+        task_count,
+        done_task_count,
+        progress: (task_count > 0).then(|| done_task_count * 100 / task_count),
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    })
+}
+
+// Spacing between neighbouring cards when a column is numbered afresh
+const POSITION_GAP: f64 = 1024.0;
+// Neighbours closer than this have their column renumbered before a card goes between them
+const MIN_POSITION_GAP: f64 = 1e-6;
+
+// Position of a neighbouring card, which must be in the area_id/status column
+fn neighbour_position(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    area_id: &str,
+    status: &str,
+    neighbour_id: &str,
+) -> Result<f64, RecallError> {
+    conn.query_row(
+        "SELECT position FROM projects
+         WHERE id = ?1 AND user_id = ?2 AND area_id = ?3 AND status = ?4 AND id != ?5",
+        params![neighbour_id, user_id, area_id, status, id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| RecallError::validation("The neighbouring project is not in that column"))
+}
+
+// Number the area_id/status column afresh, keeping its order
+fn renumber_column(conn: &Connection, area_id: &str, status: &str) -> Result<(), RecallError> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM projects WHERE area_id = ?1 AND status = ?2
+         ORDER BY position, created_at DESC, id",
+    )?;
+    let ids = stmt
+        .query_map(params![area_id, status], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (rank, id) in ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE projects SET position = ?1 WHERE id = ?2",
+            params![(rank + 1) as f64 * POSITION_GAP, id],
+        )?;
+    }
+    Ok(())
+}
+
+// Position for project `id` dropped in the area_id/status column between before_id (the
+// card above) and after_id (the card below). With neither it goes to the top.
+fn position_between(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    area_id: &str,
+    status: &str,
+    before_id: Option<&str>,
+    after_id: Option<&str>,
+) -> Result<f64, RecallError> {
+    let neighbours = || -> Result<(Option<f64>, Option<f64>), RecallError> {
+        Ok((
+            before_id
+                .map(|n| neighbour_position(conn, user_id, id, area_id, status, n))
+                .transpose()?,
+            after_id
+                .map(|n| neighbour_position(conn, user_id, id, area_id, status, n))
+                .transpose()?,
+        ))
+    };
+
+    let (mut above, mut below) = neighbours()?;
+    if let (Some(a), Some(b)) = (above, below) {
+        if a >= b {
+            return Err(RecallError::validation(
+                "The neighbouring projects are not in that order",
+            ));
+        }
+        if b - a < MIN_POSITION_GAP {
+            renumber_column(conn, area_id, status)?;
+            (above, below) = neighbours()?;
+        }
+    }
+
+    Ok(match (above, below) {
+        (Some(a), Some(b)) => (a + b) / 2.0,
+        (Some(a), None) => a + POSITION_GAP,
+        (None, Some(b)) => b - POSITION_GAP,
+        (None, None) => top_position(conn, area_id, status)?,
+    })
+}
+
+// Position putting a card above everything else in the area_id/status column
+fn top_position(conn: &Connection, area_id: &str, status: &str) -> Result<f64, RecallError> {
+    let top: Option<f64> = conn.query_row(
+        "SELECT MIN(position) FROM projects WHERE area_id = ?1 AND status = ?2",
+        params![area_id, status],
+        |row| row.get(0),
+    )?;
+    Ok(top.map_or(0.0, |top| top - POSITION_GAP))
+}
+
+// Position putting a card below everything else in the area_id/status column
+pub(crate) fn bottom_position(
+    conn: &Connection,
+    area_id: &str,
+    status: &str,
+) -> Result<f64, RecallError> {
+    let bottom: Option<f64> = conn.query_row(
+        "SELECT MAX(position) FROM projects WHERE area_id = ?1 AND status = ?2",
+        params![area_id, status],
+        |row| row.get(0),
+    )?;
+    Ok(bottom.map_or(0.0, |bottom| bottom + POSITION_GAP))
+}
+
+pub(crate) fn validate_priority(priority: Option<&str>) -> Result<(), RecallError> {
+    if priority.is_some_and(|p| !matches!(p, "High" | "Medium" | "Low")) {
+        return Err(RecallError::validation(
+            "Invalid priority. Must be: High, Medium, or Low",
+        ));
+    }
+    Ok(())
+}
+
+// Statuses are checked against the area's workflow, see workflows::check_status
+fn validate_fields(title: &str, priority: Option<&str>) -> Result<(), RecallError> {
+    if title.trim().is_empty() {
+        return Err(RecallError::validation("Project title cannot be empty"));
+    }
+    validate_priority(priority)
+}
+
+pub fn create(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateProjectRequest,
+) -> Result<ProjectInfo, RecallError> {
+    validate_fields(&request.title, request.priority.as_deref())?;
+    ensure_owner(
+        conn,
+        Owned::Area,
+        &request.area_id,
+        user_id,
+        "You don't have permission to create projects in this area",
+    )?;
+    let area_archived: bool = conn.query_row(
+        "SELECT archived_at IS NOT NULL FROM areas WHERE id = ?1",
+        params![request.area_id],
+        |row| row.get(0),
+    )?;
+    if area_archived {
+        return Err(RecallError::validation(
+            "Cannot create projects in an archived area",
+        ));
+    }
+    let done = workflows::check_status(conn, user_id, &request.area_id, &request.status)?;
+
+    let now = Utc::now().timestamp();
+    let completed_at = done.then_some(now);
+    // New cards go on top of their column
+    let position = top_position(conn, &request.area_id, &request.status)?;
+    let mut project = Project {
+        id: None,
+        user_id: user_id.to_string(),
+        area_id: request.area_id,
+        title: request.title.trim().to_string(),
+        description: trimmed(request.description),
+        status: request.status,
+        priority: request.priority,
+        start_date: request.start_date,
+        end_date: request.end_date,
+        archived_at: None,
+        completed_at,
+        position,
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let project_id = try_insert_thing(&mut project, conn)?;
+    println!("inserted Project id: {:?}", project_id);
+
+    get(conn, user_id, &project_id)
+}
+
+// The user's projects that are not archived, optionally narrowed to one area. Without an
+// area, projects in archived areas are left out too.
+pub fn list(
+    conn: &Connection,
+    user_id: &str,
+    area_id: Option<&str>,
+) -> Result<Vec<ProjectInfo>, RecallError> {
+    list_tagged(conn, user_id, area_id, &[])
+}
+
+// The projects list returns that carry every tag in tag_ids
+pub fn list_tagged(
+    conn: &Connection,
+    user_id: &str,
+    area_id: Option<&str>,
+    tag_ids: &[String],
+) -> Result<Vec<ProjectInfo>, RecallError> {
+    if let Some(area_id) = area_id {
+        // Someone else's area looks the same as a missing one
+        let owned: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM areas WHERE id = ?1 AND user_id = ?2 AND deleted_at IS NULL)",
+            params![area_id, user_id],
+            |row| row.get(0),
+        )?;
+        if !owned {
+            return Err(RecallError::not_found("Area"));
+        }
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE p.user_id = ?1 AND p.archived_at IS NULL AND p.deleted_at IS NULL
+         AND a.deleted_at IS NULL AND (?2 IS NULL AND a.archived_at IS NULL OR p.area_id = ?2)
+         AND (?3 IS NULL OR {})
+         ORDER BY p.position, p.created_at DESC",
+        SELECT_PROJECT,
+        tags::tagged(Owned::Project, "p.id", "?3")
+    ))?;
+    let projects = stmt
+        .query_map(
+            params![user_id, area_id, tags::filter(tag_ids)],
+            project_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(projects)
+}
+
+pub fn get(conn: &Connection, user_id: &str, id: &str) -> Result<ProjectInfo, RecallError> {
+    conn.query_row(
+        &format!(
+            "{} WHERE p.id = ?1 AND p.user_id = ?2 AND p.deleted_at IS NULL AND a.deleted_at IS NULL",
+            SELECT_PROJECT
+        ),
+        params![id, user_id],
+        project_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| RecallError::not_found("Project"))
+}
+
+pub fn update(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    request: UpdateProjectRequest,
+) -> Result<ProjectInfo, RecallError> {
+    validate_fields(&request.title, request.priority.as_deref())?;
+    ensure_owner(
+        conn,
+        Owned::Project,
+        id,
+        user_id,
+        "You don't have permission to update this project",
+    )?;
+    ensure_owner(
+        conn,
+        Owned::Area,
+        &request.area_id,
+        user_id,
+        "You don't have permission to use this area",
+    )?;
+    let (old_title, old_area_id, old_status, old_position): (String, String, String, f64) = conn
+        .query_row(
+            "SELECT title, area_id, status, position FROM projects WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+    // Moves within an area follow its workflow's transitions; to another area only its
+    // statuses apply
+    if old_area_id == request.area_id {
+        workflows::check_move(conn, user_id, &old_area_id, &old_status, &request.status)?;
+    } else {
+        workflows::check_status(conn, user_id, &request.area_id, &request.status)?;
+    }
+
+    let now = Utc::now().timestamp();
+    let tx = conn.unchecked_transaction()?;
+    // A card changing column goes on top of the new one
+    let position = if old_area_id == request.area_id && old_status == request.status {
+        old_position
+    } else {
+        top_position(&tx, &request.area_id, &request.status)?
+    };
+    tx.execute(
+        &format!(
+            "UPDATE projects SET area_id = ?1, title = ?2, description = ?3, status = ?4, priority = ?5, start_date = ?6, end_date = ?7, updated_at = ?8, completed_at = {}, position = ?10 WHERE id = ?9",
+            completed_at("?4", "?1", "?8")
+        ),
+        params![
+            request.area_id,
+            request.title.trim(),
+            trimmed(request.description),
+            request.status,
+            request.priority,
+            request.start_date,
+            request.end_date,
+            now,
+            id,
+            position
+        ],
+    )?;
+    links::renamed(
+        &tx,
+        user_id,
+        Owned::Project,
+        id,
+        &old_title,
+        request.title.trim(),
+    )?;
+    tx.commit()?;
+
+    get(conn, user_id, id)
+}
+
+// Change the status and place the card in its new column together, as when dragging a
+// card between board columns
+pub fn move_to(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    new_status: &str,
+    before_id: Option<&str>,
+    after_id: Option<&str>,
+) -> Result<ProjectInfo, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Project,
+        id,
+        user_id,
+        "You don't have permission to move this project",
+    )?;
+    let (area_id, status): (String, String) = conn.query_row(
+        "SELECT area_id, status FROM projects WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    workflows::check_move(conn, user_id, &area_id, &status, new_status)?;
+
+    let now = Utc::now().timestamp();
+    let tx = conn.unchecked_transaction()?;
+    let position = position_between(&tx, user_id, id, &area_id, new_status, before_id, after_id)?;
+    tx.execute(
+        &format!(
+            "UPDATE projects SET status = ?1, updated_at = ?2, completed_at = {}, position = ?4 WHERE id = ?3",
+            completed_at("?1", "projects.area_id", "?2")
+        ),
+        params![new_status, now, id, position],
+    )?;
+    tx.commit()?;
+
+    get(conn, user_id, id)
+}
+
+// Place the card elsewhere in its own column. Order is not an edit, so updated_at stays.
+pub fn reorder(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    before_id: Option<&str>,
+    after_id: Option<&str>,
+) -> Result<ProjectInfo, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Project,
+        id,
+        user_id,
+        "You don't have permission to move this project",
+    )?;
+
+    let tx = conn.unchecked_transaction()?;
+    let (area_id, status): (String, String) = tx.query_row(
+        "SELECT area_id, status FROM projects WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let position = position_between(&tx, user_id, id, &area_id, &status, before_id, after_id)?;
+    tx.execute(
+        "UPDATE projects SET position = ?1 WHERE id = ?2",
+        params![position, id],
+    )?;
+    tx.commit()?;
+
+    get(conn, user_id, id)
+}
+
+// Moves the project to the trash, hiding its resources with it. Its events stay, shown
+// without a project until it is restored; purging it deletes the resources and unlinks them.
+pub fn delete(conn: &Connection, user_id: &str, id: &str) -> Result<(), RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Project,
+        id,
+        user_id,
+        "You don't have permission to delete this project",
+    )?;
+
+    trash::move_to_trash(conn, Owned::Project, id)
+}
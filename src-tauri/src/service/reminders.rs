@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use super::{Owned, archive, events, trash, workflows};
+use crate::error::RecallError;
+use crate::reminders::{ReminderInfo, ReminderKey};
+
+// Longest reminder offset: four weeks
+const MAX_MINUTES_BEFORE: i64 = 4 * 7 * 24 * 60;
+
+// Projects remind their owner a day before their end date until they are done
+pub const PROJECT_MINUTES_BEFORE: i64 = 24 * 60;
+
+// Reminders missed while the app was closed are still shown if what they are for
+// started no longer ago than this
+const LATE_LIMIT: i64 = 3600;
+
+// Delivery records are kept this long after their occurrence, then pruned
+const RETENTION: i64 = 30 * 24 * 3600;
+
+const EVENT: &str = "event";
+const PROJECT: &str = "project";
+
+// Sorted, deduplicated offsets, each within [0, MAX_MINUTES_BEFORE]
+fn validate(minutes: &[i64]) -> Result<Vec<i64>, RecallError> {
+    if let Some(bad) = minutes
+        .iter()
+        .find(|&&m| !(0..=MAX_MINUTES_BEFORE).contains(&m))
+    {
+        return Err(RecallError::validation(format!(
+            "Reminders must be between 0 and {} minutes before the event, not {}",
+            MAX_MINUTES_BEFORE, bad
+        )));
+    }
+    let mut minutes = minutes.to_vec();
+    minutes.sort_unstable();
+    minutes.dedup();
+    Ok(minutes)
+}
+
+pub(crate) fn for_event(conn: &Connection, event_id: &str) -> Result<Vec<i64>, RecallError> {
+    let mut stmt = conn.prepare(
+        "SELECT minutes_before FROM event_reminders WHERE event_id = ?1 ORDER BY minutes_before",
+    )?;
+    let minutes = stmt
+        .query_map(params![event_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(minutes)
+}
+
+// Replace an event's reminders. Callers check ownership.
+pub(crate) fn set_for_event(
+    conn: &Connection,
+    event_id: &str,
+    minutes: &[i64],
+) -> Result<(), RecallError> {
+    let minutes = validate(minutes)?;
+    conn.execute(
+        "DELETE FROM event_reminders WHERE event_id = ?1",
+        params![event_id],
+    )?;
+    for m in minutes {
+        conn.execute(
+            "INSERT INTO event_reminders (event_id, minutes_before) VALUES (?1, ?2)",
+            params![event_id, m],
+        )?;
+    }
+    Ok(())
+}
+
+// Checked up front so an invalid request changes nothing
+pub(crate) fn check(minutes: &[i64]) -> Result<(), RecallError> {
+    validate(minutes).map(|_| ())
+}
+
+// Users who could have a reminder due
+fn users_with_reminders(conn: &Connection) -> Result<Vec<String>, RecallError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT e.user_id FROM event_reminders r JOIN events e ON e.id = r.event_id
+         UNION
+         SELECT user_id FROM projects WHERE end_date IS NOT NULL AND NOT {}",
+        workflows::done("projects.status", "projects.user_id", "projects.area_id")
+    ))?;
+    let users = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(users)
+}
+
+// A user's reminders due by `due_by` for events starting, and projects ending, from `from`
+fn scheduled(
+    conn: &Connection,
+    user_id: &str,
+    from: i64,
+    due_by: i64,
+) -> Result<Vec<ReminderInfo>, RecallError> {
+    let mut offsets: HashMap<String, Vec<i64>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT r.event_id, r.minutes_before FROM event_reminders r
+         JOIN events e ON e.id = r.event_id WHERE e.user_id = ?1",
+    )?;
+    for row in stmt.query_map(params![user_id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })? {
+        let (event_id, minutes) = row?;
+        offsets.entry(event_id).or_default().push(minutes);
+    }
+
+    let mut reminders = Vec::new();
+    if let Some(longest) = offsets.values().flatten().max() {
+        let to = due_by + longest * 60;
+        for event in events::list(conn, user_id, Some(from), Some(to), None)? {
+            if event.start_time < from {
+                continue;
+            }
+            for &minutes in offsets.get(&event.id).into_iter().flatten() {
+                let due_at = event.start_time - minutes * 60;
+                if due_at > due_by {
+                    continue;
+                }
+                reminders.push(ReminderInfo {
+                    user_id: user_id.to_string(),
+                    kind: EVENT.to_string(),
+                    source_id: event.id.clone(),
+                    occurrence_start: event.start_time,
+                    minutes_before: minutes,
+                    title: event.title.clone(),
+                    due_at,
+                });
+            }
+        }
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, title, end_date FROM projects
+         WHERE user_id = ?1 AND NOT {} AND end_date BETWEEN ?2 AND ?3
+         AND NOT {} AND NOT {}",
+        workflows::done("projects.status", "projects.user_id", "projects.area_id"),
+        archive::hidden_project("projects.id"),
+        trash::trashed(Owned::Project, "projects.id")
+    ))?;
+    let to = due_by + PROJECT_MINUTES_BEFORE * 60;
+    for row in stmt.query_map(params![user_id, from, to], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    })? {
+        let (id, title, end_date) = row?;
+        reminders.push(ReminderInfo {
+            user_id: user_id.to_string(),
+            kind: PROJECT.to_string(),
+            source_id: id,
+            occurrence_start: end_date,
+            minutes_before: PROJECT_MINUTES_BEFORE,
+            title,
+            due_at: end_date - PROJECT_MINUTES_BEFORE * 60,
+        });
+    }
+    Ok(reminders)
+}
+
+fn reminder_from_row(row: &rusqlite::Row) -> rusqlite::Result<ReminderInfo> {
+    Ok(ReminderInfo {
+        user_id: row.get(0)?,
+        kind: row.get(1)?,
+        source_id: row.get(2)?,
+        occurrence_start: row.get(3)?,
+        minutes_before: row.get(4)?,
+        title: row.get(5)?,
+        due_at: row.get(6)?,
+    })
+}
+
+const SELECT_DELIVERY: &str =
+    "SELECT user_id, kind, source_id, occurrence_start, minutes_before, title, due_at
+     FROM reminder_deliveries";
+
+// Reminders that are due at `now` and have not been shown yet, plus snoozed ones whose
+// snooze is over. They are recorded as shown before being returned, so each is returned once.
+pub fn take_due(conn: &Connection, now: i64) -> Result<Vec<ReminderInfo>, RecallError> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "DELETE FROM reminder_deliveries WHERE occurrence_start < ?1",
+        params![now - RETENTION],
+    )?;
+
+    let mut due = Vec::new();
+    for user_id in users_with_reminders(&tx)? {
+        for reminder in scheduled(&tx, &user_id, now - LATE_LIMIT, now)? {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO reminder_deliveries
+                     (user_id, kind, source_id, occurrence_start, minutes_before, title, status, due_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'fired', ?7)",
+                params![
+                    reminder.user_id,
+                    reminder.kind,
+                    reminder.source_id,
+                    reminder.occurrence_start,
+                    reminder.minutes_before,
+                    reminder.title,
+                    reminder.due_at
+                ],
+            )?;
+            if inserted > 0 {
+                due.push(reminder);
+            }
+        }
+    }
+
+    let mut stmt = tx.prepare(&format!(
+        "{} WHERE status = 'snoozed' AND due_at <= ?1",
+        SELECT_DELIVERY
+    ))?;
+    let woken = stmt
+        .query_map(params![now], reminder_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+    tx.execute(
+        "UPDATE reminder_deliveries SET status = 'fired' WHERE status = 'snoozed' AND due_at <= ?1",
+        params![now],
+    )?;
+    due.extend(woken);
+
+    tx.commit()?;
+    due.sort_by_key(|reminder| reminder.due_at);
+    Ok(due)
+}
+
+// When the next reminder after `now` is due, looking no further ahead than `horizon`
+pub fn next_due(conn: &Connection, now: i64, horizon: i64) -> Result<Option<i64>, RecallError> {
+    let mut next: Option<i64> = conn
+        .query_row(
+            "SELECT MIN(due_at) FROM reminder_deliveries WHERE status = 'snoozed'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+
+    for user_id in users_with_reminders(conn)? {
+        for reminder in scheduled(conn, &user_id, now, horizon)? {
+            if reminder.due_at > now {
+                next = Some(next.map_or(reminder.due_at, |n| n.min(reminder.due_at)));
+            }
+        }
+    }
+    Ok(next)
+}
+
+// Reminders that have been shown and are neither snoozed nor dismissed
+pub fn pending(conn: &Connection, user_id: &str) -> Result<Vec<ReminderInfo>, RecallError> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE user_id = ?1 AND status = 'fired' ORDER BY due_at",
+        SELECT_DELIVERY
+    ))?;
+    let reminders = stmt
+        .query_map(params![user_id], reminder_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(reminders)
+}
+
+fn set_status(
+    conn: &Connection,
+    user_id: &str,
+    key: &ReminderKey,
+    status: &str,
+    due_at: Option<i64>,
+) -> Result<(), RecallError> {
+    let updated = conn.execute(
+        "UPDATE reminder_deliveries SET status = ?1, due_at = COALESCE(?2, due_at)
+         WHERE user_id = ?3 AND kind = ?4 AND source_id = ?5
+           AND occurrence_start = ?6 AND minutes_before = ?7",
+        params![
+            status,
+            due_at,
+            user_id,
+            key.kind,
+            key.source_id,
+            key.occurrence_start,
+            key.minutes_before
+        ],
+    )?;
+    // Other users' reminders look the same as missing ones
+    if updated == 0 {
+        return Err(RecallError::not_found("Reminder"));
+    }
+    Ok(())
+}
+
+// Show a reminder again `minutes` from `now`
+pub fn snooze(
+    conn: &Connection,
+    user_id: &str,
+    key: &ReminderKey,
+    minutes: i64,
+    now: i64,
+) -> Result<(), RecallError> {
+    if !(1..=MAX_MINUTES_BEFORE).contains(&minutes) {
+        return Err(RecallError::validation(format!(
+            "Reminders can be snoozed for 1 to {} minutes",
+            MAX_MINUTES_BEFORE
+        )));
+    }
+    set_status(conn, user_id, key, "snoozed", Some(now + minutes * 60))
+}
+
+pub fn dismiss(conn: &Connection, user_id: &str, key: &ReminderKey) -> Result<(), RecallError> {
+    set_status(conn, user_id, key, "dismissed", None)
+}
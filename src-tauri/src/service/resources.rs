@@ -0,0 +1,629 @@
+use std::path::Path;
+
+use base64::Engine;
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use super::{Owned, archive, links, revisions, tags, trash};
+use crate::blobs::{self, MAX_BLOB_SIZE};
+use crate::error::RecallError;
+use crate::resources::{
+    CreateResourceRequest, ImportResourceRequest, Resource, ResourceInfo, ResourceSummary,
+    ResourceSummaryPage, UpdateResourceRequest,
+};
+use crate::try_insert_thing;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+const SNIPPET_LENGTH: usize = 160;
+
+// File bodies are left out; get() adds the body for the one resource it returns
+const SELECT_RESOURCE: &str = "SELECT r.id, r.project_id, r.name, r.content, r.file_type, r.file_size, r.created_at, r.updated_at, p.title as project_name, r.blob_hash, r.area_id, a.name as area_name
+     FROM resources r
+     LEFT JOIN projects p ON r.project_id = p.id
+     LEFT JOIN areas a ON a.id = COALESCE(r.area_id, p.area_id)";
+
+fn resource_from_row(row: &Row) -> rusqlite::Result<ResourceInfo> {
+    Ok(ResourceInfo {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        project_name: row.get(8)?,
+        area_id: row.get(10)?,
+        area_name: row.get(11)?,
+        name: row.get(2)?,
+        content: row.get(3)?,
+        file_data: None,
+        file_type: row.get(4)?,
+        file_size: row.get(5)?,
+        blob_hash: row.get(9)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+// The stored file behind a resource
+#[derive(Debug)]
+pub struct ResourceFile {
+    pub blob_hash: Option<String>,
+    pub name: String,
+    pub file_type: Option<String>,
+}
+
+// Decode a base64 upload, returning None for an empty request field
+fn decode_file_data(file_data: Option<&str>) -> Result<Option<Vec<u8>>, RecallError> {
+    file_data
+        .map(|data| base64::engine::general_purpose::STANDARD.decode(data))
+        .transpose()
+        .map_err(|_| RecallError::validation("File data is not valid base64"))
+}
+
+fn check_file_size(size: Option<i64>) -> Result<(), RecallError> {
+    if size.is_some_and(|size| size > MAX_BLOB_SIZE) {
+        return Err(RecallError::validation(format!(
+            "File size exceeds maximum of {}MB",
+            MAX_BLOB_SIZE / (1024 * 1024)
+        )));
+    }
+    Ok(())
+}
+
+// Decode an upload and enforce the size limit, on both the declared and the real size
+fn upload_bytes(
+    file_data: Option<&str>,
+    declared_size: Option<i64>,
+) -> Result<Option<Vec<u8>>, RecallError> {
+    check_file_size(declared_size)?;
+    let bytes = decode_file_data(file_data)?;
+    check_file_size(bytes.as_ref().map(|b| b.len() as i64))?;
+    Ok(bytes)
+}
+
+// NotFound unless the user owns the project or area and it is not in the trash
+fn ensure_parent(
+    conn: &Connection,
+    user_id: &str,
+    kind: Owned,
+    id: &str,
+) -> Result<(), RecallError> {
+    let exists: bool = conn.query_row(
+        &format!(
+            "SELECT EXISTS(SELECT 1 FROM {table} WHERE id = ?1 AND user_id = ?2 AND NOT {})",
+            trash::trashed(kind, &format!("{}.id", kind.table())),
+            table = kind.table()
+        ),
+        params![id, user_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(RecallError::not_found(kind.label()));
+    }
+    Ok(())
+}
+
+// A resource belongs to a project, an area, or neither; never both
+fn ensure_place(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+    area_id: Option<&str>,
+) -> Result<(), RecallError> {
+    match (project_id, area_id) {
+        (Some(_), Some(_)) => Err(RecallError::validation(
+            "A resource belongs to a project or an area, not both",
+        )),
+        (Some(project_id), None) => ensure_parent(conn, user_id, Owned::Project, project_id),
+        (None, Some(area_id)) => ensure_parent(conn, user_id, Owned::Area, area_id),
+        (None, None) => Ok(()),
+    }
+}
+
+// Map a client sort key onto a column; anything unknown falls back to created_at
+fn sort_column(sort_by: Option<&str>) -> &'static str {
+    match sort_by {
+        Some("name") => "r.name COLLATE NOCASE",
+        Some("updated_at") => "r.updated_at",
+        Some("file_size") => "r.file_size",
+        Some("file_type") => "r.file_type",
+        _ => "r.created_at",
+    }
+}
+
+fn sort_direction(sort_order: Option<&str>) -> &'static str {
+    match sort_order {
+        Some(order) if order.eq_ignore_ascii_case("asc") => "ASC",
+        _ => "DESC",
+    }
+}
+
+// Editor documents are stored as TipTap JSON; collect their text nodes in order
+fn collect_text(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::String(text)) = map.get("text") {
+                if !out.is_empty() && !out.ends_with(' ') {
+                    out.push(' ');
+                }
+                out.push_str(text);
+            }
+            if let Some(children) = map.get("content") {
+                collect_text(children, out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_text(item, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Short plain-text preview of a resource's content
+pub(crate) fn content_snippet(content: &str) -> Option<String> {
+    let text = match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => {
+            let mut text = String::new();
+            collect_text(&value, &mut text);
+            text
+        }
+        _ => content.to_string(),
+    };
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut snippet: String = text.chars().take(SNIPPET_LENGTH).collect();
+    if snippet.len() < text.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+pub fn create(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateResourceRequest,
+) -> Result<ResourceInfo, RecallError> {
+    // Store the blob and the row referencing it together so garbage collection never sees
+    // the blob unreferenced
+    let tx = conn.unchecked_transaction()?;
+    let resource = insert(&tx, user_id, request)?;
+    tx.commit()?;
+    Ok(resource)
+}
+
+// create without a transaction of its own, for callers writing more alongside it in theirs
+pub(crate) fn insert(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateResourceRequest,
+) -> Result<ResourceInfo, RecallError> {
+    let file_bytes = upload_bytes(request.file_data.as_deref(), request.file_size)?;
+    ensure_place(
+        conn,
+        user_id,
+        request.project_id.as_deref(),
+        request.area_id.as_deref(),
+    )?;
+
+    let blob_hash = match &file_bytes {
+        Some(bytes) => Some(blobs::put_blob(conn, bytes)?),
+        None => None,
+    };
+
+    let now = Utc::now().timestamp();
+    let mut resource = Resource {
+        id: None,
+        user_id: user_id.to_string(),
+        project_id: request.project_id,
+        area_id: request.area_id,
+        name: request.name,
+        content: request.content,
+        file_type: request.file_type,
+        file_size: file_bytes
+            .as_ref()
+            .map(|bytes| bytes.len() as i64)
+            .or(request.file_size),
+        blob_hash,
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let resource_id = try_insert_thing(&mut resource, conn)?;
+    links::refresh(conn, user_id, &resource_id, resource.content.as_deref())?;
+
+    Ok(ResourceInfo {
+        id: resource_id,
+        project_id: resource.project_id,
+        project_name: None,
+        area_id: resource.area_id,
+        area_name: None,
+        name: resource.name,
+        content: resource.content,
+        file_data: None,
+        file_type: resource.file_type,
+        file_size: resource.file_size,
+        blob_hash: resource.blob_hash,
+        created_at: resource.created_at,
+        updated_at: resource.updated_at,
+    })
+}
+
+// List condition for one project's resources (?2), one area's including those of its
+// projects (?3), or without either, all not archived. Trashed resources are never listed.
+fn listed(project_id: Option<&str>, area_id: Option<&str>) -> String {
+    let project = match project_id {
+        Some(_) => "r.project_id = ?2",
+        None => "?2 IS NULL",
+    };
+    let area = match area_id {
+        Some(_) => {
+            "(r.area_id = ?3 OR r.project_id IN (SELECT id FROM projects WHERE area_id = ?3))"
+        }
+        None => "?3 IS NULL",
+    };
+    let archived = match (project_id, area_id) {
+        (None, None) => format!(
+            " AND NOT {} AND NOT {}",
+            archive::hidden_project("r.project_id"),
+            archive::hidden_area("r.area_id")
+        ),
+        _ => String::new(),
+    };
+    format!(
+        "{} AND {}{} AND NOT {}",
+        project,
+        area,
+        archived,
+        trash::trashed(Owned::Resource, "r.id")
+    )
+}
+
+// Resources without file bodies, for one project or every one not archived
+pub fn list(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+) -> Result<Vec<ResourceInfo>, RecallError> {
+    list_tagged(conn, user_id, project_id, None, &[])
+}
+
+// The resources in a project and/or an area that carry every tag in tag_ids
+pub fn list_tagged(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+    area_id: Option<&str>,
+    tag_ids: &[String],
+) -> Result<Vec<ResourceInfo>, RecallError> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE r.user_id = ?1 AND {} AND (?4 IS NULL OR {}) ORDER BY r.created_at DESC",
+        SELECT_RESOURCE,
+        listed(project_id, area_id),
+        tags::tagged(Owned::Resource, "r.id", "?4")
+    ))?;
+    let resources = stmt
+        .query_map(
+            params![user_id, project_id, area_id, tags::filter(tag_ids)],
+            resource_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(resources)
+}
+
+// One page of metadata-only summaries, filtered like list_tagged
+#[allow(clippy::too_many_arguments)]
+pub fn list_summaries(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+    area_id: Option<&str>,
+    tag_ids: &[String],
+    sort_by: Option<&str>,
+    sort_order: Option<&str>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<ResourceSummaryPage, RecallError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let offset = offset.unwrap_or(0).max(0);
+
+    let tag_ids = tags::filter(tag_ids);
+    let condition = format!(
+        "r.user_id = ?1 AND {} AND (?4 IS NULL OR {})",
+        listed(project_id, area_id),
+        tags::tagged(Owned::Resource, "r.id", "?4")
+    );
+
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM resources r WHERE {}", condition),
+        params![user_id, project_id, area_id, tag_ids],
+        |row| row.get(0),
+    )?;
+
+    // Only whitelisted column names are interpolated; values stay bound parameters
+    let query = format!(
+        "SELECT r.id, r.project_id, p.title as project_name, r.name, r.content, r.file_type, r.file_size, r.blob_hash, r.created_at, r.updated_at, r.area_id, a.name as area_name
+         FROM resources r
+         LEFT JOIN projects p ON r.project_id = p.id
+         LEFT JOIN areas a ON a.id = COALESCE(r.area_id, p.area_id)
+         WHERE {}
+         ORDER BY {} {}, r.id
+         LIMIT ?5 OFFSET ?6",
+        condition,
+        sort_column(sort_by),
+        sort_direction(sort_order)
+    );
+    let mut stmt = conn.prepare(&query)?;
+
+    let resources = stmt
+        .query_map(
+            params![user_id, project_id, area_id, tag_ids, limit, offset],
+            |row| {
+                let content: Option<String> = row.get(4)?;
+                let blob_hash: Option<String> = row.get(7)?;
+                Ok(ResourceSummary {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    project_name: row.get(2)?,
+                    area_id: row.get(10)?,
+                    area_name: row.get(11)?,
+                    name: row.get(3)?,
+                    snippet: content.as_deref().and_then(content_snippet),
+                    has_file: blob_hash.is_some(),
+                    file_type: row.get(5)?,
+                    file_size: row.get(6)?,
+                    blob_hash,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ResourceSummaryPage {
+        resources,
+        total,
+        limit,
+        offset,
+    })
+}
+
+// One resource with its file body, base64 encoded
+pub fn get(conn: &Connection, user_id: &str, id: &str) -> Result<ResourceInfo, RecallError> {
+    let mut resource = conn
+        .query_row(
+            &format!(
+                "{} WHERE r.id = ?1 AND r.user_id = ?2 AND NOT {}",
+                SELECT_RESOURCE,
+                trash::trashed(Owned::Resource, "r.id")
+            ),
+            params![id, user_id],
+            resource_from_row,
+        )
+        .optional()?
+        .ok_or_else(|| RecallError::not_found("Resource"))?;
+
+    if let Some(hash) = &resource.blob_hash {
+        resource.file_data = blobs::get_blob(conn, hash)?
+            .map(|data| base64::engine::general_purpose::STANDARD.encode(data));
+    }
+    Ok(resource)
+}
+
+// Saves the name, and the content when it is given. The stored file is replaced when
+// file_data is given, dropped with remove_file, and otherwise kept as it is.
+pub fn update(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    request: UpdateResourceRequest,
+) -> Result<ResourceInfo, RecallError> {
+    if request.file_data.is_some() && request.remove_file {
+        return Err(RecallError::validation(
+            "Upload a new file or remove the current one, not both",
+        ));
+    }
+    let file_bytes = upload_bytes(request.file_data.as_deref(), request.file_size)?;
+
+    let old_name: String = conn
+        .query_row(
+            &format!(
+                "SELECT name FROM resources WHERE id = ?1 AND user_id = ?2 AND NOT {}",
+                trash::trashed(Owned::Resource, "resources.id")
+            ),
+            params![id, user_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| RecallError::not_found("Resource"))?;
+
+    let tx = conn.unchecked_transaction()?;
+    let now = Utc::now().timestamp();
+    if let Some(content) = request.content.as_deref() {
+        revisions::record(&tx, id, Some(content), now, true)?;
+    }
+    tx.execute(
+        "UPDATE resources SET name = ?1, content = COALESCE(?2, content), updated_at = ?3 WHERE id = ?4 AND user_id = ?5",
+        params![request.name, request.content, now, id, user_id],
+    )?;
+
+    let file_changed = file_bytes.is_some() || request.remove_file;
+    if file_changed {
+        let blob_hash = match &file_bytes {
+            Some(bytes) => Some(blobs::put_blob(&tx, bytes)?),
+            None => None,
+        };
+        let file_type = file_bytes.as_ref().and(request.file_type);
+        tx.execute(
+            "UPDATE resources SET blob_hash = ?1, file_type = ?2, file_size = ?3 WHERE id = ?4 AND user_id = ?5",
+            params![
+                blob_hash,
+                file_type,
+                file_bytes.as_ref().map(|bytes| bytes.len() as i64),
+                id,
+                user_id
+            ],
+        )?;
+    }
+    if let Some(content) = request.content.as_deref() {
+        links::refresh(&tx, user_id, id, Some(content))?;
+    }
+    links::renamed(&tx, user_id, Owned::Resource, id, &old_name, &request.name)?;
+    tx.commit()?;
+
+    // The previous file may no longer be referenced
+    if file_changed {
+        blobs::collect_garbage(conn)?;
+    }
+
+    get(conn, user_id, id)
+}
+
+// Reparent the resource onto a project, an area, or neither
+pub fn move_to(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    project_id: Option<&str>,
+    area_id: Option<&str>,
+) -> Result<ResourceInfo, RecallError> {
+    ensure_place(conn, user_id, project_id, area_id)?;
+
+    let rows_affected = conn.execute(
+        &format!(
+            "UPDATE resources SET project_id = ?1, area_id = ?2, updated_at = ?3
+             WHERE id = ?4 AND user_id = ?5 AND NOT {}",
+            trash::trashed(Owned::Resource, "resources.id")
+        ),
+        params![project_id, area_id, Utc::now().timestamp(), id, user_id],
+    )?;
+    if rows_affected == 0 {
+        return Err(RecallError::not_found("Resource"));
+    }
+
+    get(conn, user_id, id)
+}
+
+// Moves the resource to the trash; its file is kept until the trash is purged
+pub fn delete(conn: &Connection, user_id: &str, id: &str) -> Result<(), RecallError> {
+    let rows_affected = conn.execute(
+        &format!(
+            "UPDATE resources SET deleted_at = ?1 WHERE id = ?2 AND user_id = ?3 AND NOT {}",
+            trash::trashed(Owned::Resource, "resources.id")
+        ),
+        params![Utc::now().timestamp(), id, user_id],
+    )?;
+    if rows_affected == 0 {
+        return Err(RecallError::not_found("Resource"));
+    }
+    Ok(())
+}
+
+// Look up the blob behind a resource the user owns, along with its name and type
+pub fn file(conn: &Connection, user_id: &str, id: &str) -> Result<ResourceFile, RecallError> {
+    conn.query_row(
+        &format!(
+            "SELECT blob_hash, name, file_type FROM resources WHERE id = ?1 AND user_id = ?2 AND NOT {}",
+            trash::trashed(Owned::Resource, "resources.id")
+        ),
+        params![id, user_id],
+        |row| {
+            Ok(ResourceFile {
+                blob_hash: row.get(0)?,
+                name: row.get(1)?,
+                file_type: row.get(2)?,
+            })
+        },
+    )
+    .optional()?
+    .ok_or_else(|| RecallError::not_found("Resource"))
+}
+
+// Stream a resource's file to path. `on_progress` receives (bytes written, total bytes).
+pub fn export_to_path(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    path: &Path,
+    on_progress: impl FnMut(u64, u64),
+) -> Result<u64, RecallError> {
+    let blob_hash = file(conn, user_id, id)?
+        .blob_hash
+        .ok_or_else(|| RecallError::NotFound("No file data available".to_string()))?;
+
+    Ok(blobs::export_blob_to_path(
+        conn,
+        &blob_hash,
+        path,
+        on_progress,
+    )?)
+}
+
+// Create a resource from a file on disk, streaming it into the blob store.
+// `on_progress` receives (bytes read, total bytes).
+pub fn import_from_path(
+    conn: &Connection,
+    user_id: &str,
+    request: ImportResourceRequest,
+    on_progress: impl FnMut(u64, u64),
+) -> Result<ResourceInfo, RecallError> {
+    ensure_place(
+        conn,
+        user_id,
+        request.project_id.as_deref(),
+        request.area_id.as_deref(),
+    )?;
+
+    let source = Path::new(&request.path);
+    let name = request
+        .name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .or_else(|| source.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "Imported file".to_string());
+    let file_type = request.file_type.or_else(|| {
+        source
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+    });
+
+    let tx = conn.unchecked_transaction()?;
+    let (blob_hash, file_size) = blobs::import_blob_from_path(&tx, source, on_progress)?;
+
+    let now = Utc::now().timestamp();
+    let mut resource = Resource {
+        id: None,
+        user_id: user_id.to_string(),
+        project_id: request.project_id,
+        area_id: request.area_id,
+        name,
+        content: None,
+        file_type,
+        file_size: Some(file_size),
+        blob_hash: Some(blob_hash),
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let resource_id = try_insert_thing(&mut resource, &tx)?;
+    tx.commit()?;
+
+    Ok(ResourceInfo {
+        id: resource_id,
+        project_id: resource.project_id,
+        project_name: None,
+        area_id: resource.area_id,
+        area_name: None,
+        name: resource.name,
+        content: None,
+        file_data: None,
+        file_type: resource.file_type,
+        file_size: resource.file_size,
+        blob_hash: resource.blob_hash,
+        created_at: resource.created_at,
+        updated_at: resource.updated_at,
+    })
+}
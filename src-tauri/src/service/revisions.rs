@@ -0,0 +1,231 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use similar::{ChangeTag, TextDiff};
+
+use super::{Owned, ensure_owner, links, resources};
+use crate::error::RecallError;
+use crate::resources::ResourceInfo;
+use crate::revisions::{DiffLine, Revision, RevisionDiff, RevisionSummary};
+
+// A save this soon after the last revision is folded into it, so typing in the editor
+// does not leave a revision per autosave
+const COALESCE_SECONDS: i64 = 10 * 60;
+
+// Oldest revisions are dropped past either limit; the newest is always kept
+const MAX_REVISIONS: i64 = 50;
+const MAX_REVISION_BYTES: i64 = 8 * 1024 * 1024;
+
+const SELECT_REVISION: &str = "SELECT id, resource_id, name, content, content_size, saved_at, created_at FROM resource_revisions";
+
+fn revision_from_row(row: &Row) -> rusqlite::Result<Revision> {
+    Ok(Revision {
+        id: row.get(0)?,
+        resource_id: row.get(1)?,
+        name: row.get(2)?,
+        content: row.get(3)?,
+        content_size: row.get(4)?,
+        saved_at: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+// Keep the resource's current content as a revision before it is replaced by `content`.
+// Nothing is kept when the content is unchanged, or, unless `coalesce` is false, when the
+// last revision is recent enough to stand for this save too.
+pub(crate) fn record(
+    conn: &Connection,
+    resource_id: &str,
+    content: Option<&str>,
+    now: i64,
+    coalesce: bool,
+) -> Result<(), RecallError> {
+    let (name, current, saved_at): (String, Option<String>, i64) = conn.query_row(
+        "SELECT name, content, updated_at FROM resources WHERE id = ?1",
+        params![resource_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    let Some(current) = current.filter(|current| Some(current.as_str()) != content) else {
+        return Ok(());
+    };
+
+    if coalesce {
+        let last: Option<i64> = conn.query_row(
+            "SELECT MAX(created_at) FROM resource_revisions WHERE resource_id = ?1",
+            params![resource_id],
+            |row| row.get(0),
+        )?;
+        if last.is_some_and(|last| now - last < COALESCE_SECONDS) {
+            return Ok(());
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO resource_revisions (resource_id, name, content, content_size, saved_at, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![resource_id, name, current, current.len() as i64, saved_at, now],
+    )?;
+    conn.execute(
+        "DELETE FROM resource_revisions WHERE id IN (
+             SELECT id FROM (
+                 SELECT id, ROW_NUMBER() OVER newest AS n, SUM(content_size) OVER newest AS bytes
+                 FROM resource_revisions WHERE resource_id = ?1
+                 WINDOW newest AS (ORDER BY id DESC)
+             )
+             WHERE n > 1 AND (n > ?2 OR bytes > ?3)
+         )",
+        params![resource_id, MAX_REVISIONS, MAX_REVISION_BYTES],
+    )?;
+    Ok(())
+}
+
+// Newest first, without their content
+pub fn list(
+    conn: &Connection,
+    user_id: &str,
+    resource_id: &str,
+) -> Result<Vec<RevisionSummary>, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Resource,
+        resource_id,
+        user_id,
+        "You don't have permission to view this resource",
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE resource_id = ?1 ORDER BY id DESC",
+        SELECT_REVISION
+    ))?;
+    let revisions = stmt
+        .query_map(params![resource_id], revision_from_row)?
+        .map(|revision| {
+            revision.map(|revision| RevisionSummary {
+                id: revision.id,
+                resource_id: revision.resource_id,
+                name: revision.name,
+                snippet: resources::content_snippet(&revision.content),
+                content_size: revision.content_size,
+                saved_at: revision.saved_at,
+                created_at: revision.created_at,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(revisions)
+}
+
+pub fn get(
+    conn: &Connection,
+    user_id: &str,
+    resource_id: &str,
+    id: i64,
+) -> Result<Revision, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Resource,
+        resource_id,
+        user_id,
+        "You don't have permission to view this resource",
+    )?;
+
+    conn.query_row(
+        &format!("{} WHERE id = ?1 AND resource_id = ?2", SELECT_REVISION),
+        params![id, resource_id],
+        revision_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| RecallError::not_found("Revision"))
+}
+
+// Text of a document for comparing, one line per paragraph, heading or list item
+fn document_lines(content: &str) -> String {
+    fn walk(value: &serde_json::Value, out: &mut String) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(serde_json::Value::String(text)) = map.get("text") {
+                    out.push_str(text);
+                }
+                if map.get("type").and_then(|t| t.as_str()) == Some("hardBreak") {
+                    out.push('\n');
+                }
+                if let Some(children) = map.get("content") {
+                    walk(children, out);
+                    if !out.is_empty() && !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    walk(item, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => {
+            let mut text = String::new();
+            walk(&value, &mut text);
+            text
+        }
+        _ => content.to_string(),
+    }
+}
+
+// Line changes from revision `from` to revision `to`, or to the current content when
+// `to` is None
+pub fn diff(
+    conn: &Connection,
+    user_id: &str,
+    resource_id: &str,
+    from: i64,
+    to: Option<i64>,
+) -> Result<RevisionDiff, RecallError> {
+    let old = get(conn, user_id, resource_id, from)?.content;
+    let new = match to {
+        Some(to) => get(conn, user_id, resource_id, to)?.content,
+        None => resources::get(conn, user_id, resource_id)?
+            .content
+            .unwrap_or_default(),
+    };
+
+    let (old, new) = (document_lines(&old), document_lines(&new));
+    let changes = TextDiff::from_lines(&old, &new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            change: match change.tag() {
+                ChangeTag::Equal => "equal",
+                ChangeTag::Insert => "insert",
+                ChangeTag::Delete => "delete",
+            }
+            .to_string(),
+            text: change.value().trim_end_matches('\n').to_string(),
+        })
+        .collect();
+
+    Ok(RevisionDiff { from, to, changes })
+}
+
+// Make a revision's content current again. What it replaces is kept as a revision of its
+// own, so restoring can be undone.
+pub fn restore(
+    conn: &Connection,
+    user_id: &str,
+    resource_id: &str,
+    id: i64,
+) -> Result<ResourceInfo, RecallError> {
+    let revision = get(conn, user_id, resource_id, id)?;
+
+    let now = Utc::now().timestamp();
+    let tx = conn.unchecked_transaction()?;
+    record(&tx, resource_id, Some(&revision.content), now, false)?;
+    tx.execute(
+        "UPDATE resources SET content = ?1, updated_at = ?2 WHERE id = ?3",
+        params![revision.content, now, resource_id],
+    )?;
+    links::refresh(&tx, user_id, resource_id, Some(&revision.content))?;
+    tx.commit()?;
+
+    resources::get(conn, user_id, resource_id)
+}
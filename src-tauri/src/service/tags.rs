@@ -0,0 +1,370 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use super::{Owned, ensure_owner, trash, trimmed};
+use crate::error::RecallError;
+use crate::tags::{CreateTagRequest, Tag, TagInfo, TaggedItem, UpdateTagRequest};
+use crate::try_insert_thing;
+
+const MAX_NAME_LENGTH: usize = 50;
+
+const KINDS: [Owned; 4] = [Owned::Area, Owned::Project, Owned::Resource, Owned::Event];
+
+// SQL condition that holds when the assignment aliased `alias` is for an item not in the trash
+fn live(alias: &str) -> String {
+    let trashed: Vec<String> = KINDS
+        .iter()
+        .map(|&kind| {
+            format!(
+                "{}.kind = '{}' AND {}",
+                alias,
+                kind.name(),
+                trash::trashed(kind, &format!("{}.item_id", alias))
+            )
+        })
+        .collect();
+    format!("NOT ({})", trashed.join(" OR "))
+}
+
+fn select_tag() -> String {
+    format!(
+        "SELECT t.id, t.name, t.color, t.created_at, t.updated_at,
+            (SELECT COUNT(*) FROM tag_assignments tg WHERE tg.tag_id = t.id AND {}) AS item_count
+         FROM tags t",
+        live("tg")
+    )
+}
+
+fn tag_from_row(row: &Row) -> rusqlite::Result<TagInfo> {
+    Ok(TagInfo {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        color: row.get(2)?,
+        item_count: row.get(5)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+// SQL condition that holds when the `kind` row whose id is `column` carries every tag in
+// the JSON array of ids bound to `param`. `param` must not be NULL; see filter.
+pub(crate) fn tagged(kind: Owned, column: &str, param: &str) -> String {
+    format!(
+        "{} IN (SELECT tf.item_id FROM tag_assignments tf
+         WHERE tf.kind = '{}' AND tf.tag_id IN (SELECT value FROM json_each({}))
+         GROUP BY tf.item_id HAVING COUNT(*) = json_array_length({}))",
+        column,
+        kind.name(),
+        param,
+        param
+    )
+}
+
+// Tag ids to bind for tagged, or None when there are none to filter on
+pub(crate) fn filter(tag_ids: &[String]) -> Option<String> {
+    let mut ids: Vec<&String> = tag_ids.iter().collect();
+    ids.sort();
+    ids.dedup();
+    (!ids.is_empty()).then(|| serde_json::json!(ids).to_string())
+}
+
+fn valid_name(name: &str) -> Result<String, RecallError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(RecallError::validation("Tag name is required"));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(RecallError::validation(format!(
+            "Tag names can be at most {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+    Ok(name.to_string())
+}
+
+// "#rrggbb", stored lowercase
+fn valid_color(color: Option<String>) -> Result<Option<String>, RecallError> {
+    let Some(color) = trimmed(color) else {
+        return Ok(None);
+    };
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(RecallError::validation(format!(
+            "Invalid color: {}. Use the form #rrggbb",
+            color
+        )));
+    }
+    Ok(Some(color.to_lowercase()))
+}
+
+// NotFound if the tag does not exist, Forbidden if another user's
+fn ensure_tag(conn: &Connection, user_id: &str, id: &str) -> Result<(), RecallError> {
+    let owner: Option<String> = conn
+        .query_row(
+            "SELECT user_id FROM tags WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match owner {
+        None => Err(RecallError::not_found("Tag")),
+        Some(owner) if owner != user_id => Err(RecallError::forbidden(
+            "You don't have permission to change this tag",
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// The user's tag with this name, whatever its case
+fn find_by_name(
+    conn: &Connection,
+    user_id: &str,
+    name: &str,
+) -> Result<Option<String>, RecallError> {
+    Ok(conn
+        .query_row(
+            "SELECT id FROM tags WHERE user_id = ?1 AND name = ?2 COLLATE NOCASE",
+            params![user_id, name],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn insert(
+    conn: &Connection,
+    user_id: &str,
+    name: String,
+    color: Option<String>,
+) -> Result<String, RecallError> {
+    let now = Utc::now().timestamp();
+    let mut tag = Tag {
+        id: None,
+        user_id: user_id.to_string(),
+        name,
+        color,
+        created_at: now,
+        updated_at: now,
+    };
+    try_insert_thing(&mut tag, conn)
+}
+
+// Alphabetical, with how many items not in the trash carry each
+pub fn list(conn: &Connection, user_id: &str) -> Result<Vec<TagInfo>, RecallError> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE t.user_id = ?1 ORDER BY t.name COLLATE NOCASE",
+        select_tag()
+    ))?;
+    let tags = stmt
+        .query_map(params![user_id], tag_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tags)
+}
+
+pub fn get(conn: &Connection, user_id: &str, id: &str) -> Result<TagInfo, RecallError> {
+    conn.query_row(
+        &format!("{} WHERE t.id = ?1 AND t.user_id = ?2", select_tag()),
+        params![id, user_id],
+        tag_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| RecallError::not_found("Tag"))
+}
+
+pub fn create(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateTagRequest,
+) -> Result<TagInfo, RecallError> {
+    let name = valid_name(&request.name)?;
+    let color = valid_color(request.color)?;
+    if find_by_name(conn, user_id, &name)?.is_some() {
+        return Err(RecallError::validation(format!(
+            "A tag named \"{}\" already exists",
+            name
+        )));
+    }
+
+    let id = insert(conn, user_id, name, color)?;
+    get(conn, user_id, &id)
+}
+
+// Rename or recolor. Renaming onto another tag's name is refused; merge them instead.
+pub fn update(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    request: UpdateTagRequest,
+) -> Result<TagInfo, RecallError> {
+    ensure_tag(conn, user_id, id)?;
+    let name = valid_name(&request.name)?;
+    let color = valid_color(request.color)?;
+    if find_by_name(conn, user_id, &name)?.is_some_and(|other| other != id) {
+        return Err(RecallError::validation(format!(
+            "A tag named \"{}\" already exists. Merge the two tags instead",
+            name
+        )));
+    }
+
+    conn.execute(
+        "UPDATE tags SET name = ?1, color = ?2, updated_at = ?3 WHERE id = ?4",
+        params![name, color, Utc::now().timestamp(), id],
+    )?;
+    get(conn, user_id, id)
+}
+
+// Move everything tagged `source_id` to `target_id`, then delete `source_id`
+pub fn merge(
+    conn: &Connection,
+    user_id: &str,
+    source_id: &str,
+    target_id: &str,
+) -> Result<TagInfo, RecallError> {
+    ensure_tag(conn, user_id, source_id)?;
+    ensure_tag(conn, user_id, target_id)?;
+    if source_id == target_id {
+        return Err(RecallError::validation(
+            "A tag cannot be merged into itself",
+        ));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT OR IGNORE INTO tag_assignments (tag_id, kind, item_id, created_at)
+         SELECT ?1, kind, item_id, created_at FROM tag_assignments WHERE tag_id = ?2",
+        params![target_id, source_id],
+    )?;
+    tx.execute("DELETE FROM tags WHERE id = ?1", params![source_id])?;
+    tx.execute(
+        "UPDATE tags SET updated_at = ?1 WHERE id = ?2",
+        params![Utc::now().timestamp(), target_id],
+    )?;
+    tx.commit()?;
+
+    get(conn, user_id, target_id)
+}
+
+// Removes the tag from everything carrying it
+pub fn delete(conn: &Connection, user_id: &str, id: &str) -> Result<(), RecallError> {
+    ensure_tag(conn, user_id, id)?;
+    conn.execute("DELETE FROM tags WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+pub fn item_tags(
+    conn: &Connection,
+    user_id: &str,
+    kind: Owned,
+    item_id: &str,
+) -> Result<Vec<TagInfo>, RecallError> {
+    ensure_owner(
+        conn,
+        kind,
+        item_id,
+        user_id,
+        &format!("You don't have permission to view this {}", kind.name()),
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "{} JOIN tag_assignments a ON a.tag_id = t.id
+         WHERE a.kind = ?1 AND a.item_id = ?2 ORDER BY t.name COLLATE NOCASE",
+        select_tag()
+    ))?;
+    let tags = stmt
+        .query_map(params![kind.name(), item_id], tag_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tags)
+}
+
+// Replace an item's tags with those named, creating tags that do not exist yet
+pub fn set_item_tags(
+    conn: &Connection,
+    user_id: &str,
+    kind: Owned,
+    item_id: &str,
+    names: &[String],
+) -> Result<Vec<TagInfo>, RecallError> {
+    ensure_owner(
+        conn,
+        kind,
+        item_id,
+        user_id,
+        &format!("You don't have permission to tag this {}", kind.name()),
+    )?;
+
+    let tx = conn.unchecked_transaction()?;
+    let mut seen = HashSet::new();
+    let mut tag_ids = Vec::new();
+    for name in names {
+        let name = valid_name(name)?;
+        if !seen.insert(name.to_lowercase()) {
+            continue;
+        }
+        let id = match find_by_name(&tx, user_id, &name)? {
+            Some(id) => id,
+            None => insert(&tx, user_id, name, None)?,
+        };
+        tag_ids.push(id);
+    }
+
+    tx.execute(
+        "DELETE FROM tag_assignments WHERE kind = ?1 AND item_id = ?2
+         AND tag_id NOT IN (SELECT value FROM json_each(?3))",
+        params![kind.name(), item_id, serde_json::json!(tag_ids).to_string()],
+    )?;
+    let now = Utc::now().timestamp();
+    for tag_id in &tag_ids {
+        tx.execute(
+            "INSERT OR IGNORE INTO tag_assignments (tag_id, kind, item_id, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![tag_id, kind.name(), item_id, now],
+        )?;
+    }
+    tx.commit()?;
+
+    item_tags(conn, user_id, kind, item_id)
+}
+
+// Everything carrying the tag that is not in the trash, grouped by kind and then by title
+pub fn items(
+    conn: &Connection,
+    user_id: &str,
+    tag_id: &str,
+) -> Result<Vec<TaggedItem>, RecallError> {
+    ensure_tag(conn, user_id, tag_id)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT 'area', a.id, a.name, NULL FROM tag_assignments tg
+         JOIN areas a ON a.id = tg.item_id
+         WHERE tg.tag_id = ?1 AND tg.kind = 'area' AND a.deleted_at IS NULL
+         UNION ALL
+         SELECT 'project', p.id, p.title, a.name FROM tag_assignments tg
+         JOIN projects p ON p.id = tg.item_id JOIN areas a ON a.id = p.area_id
+         WHERE tg.tag_id = ?1 AND tg.kind = 'project'
+         AND p.deleted_at IS NULL AND a.deleted_at IS NULL
+         UNION ALL
+         SELECT 'resource', r.id, r.name, COALESCE(p.title, a.name) FROM tag_assignments tg
+         JOIN resources r ON r.id = tg.item_id LEFT JOIN projects p ON p.id = r.project_id
+         LEFT JOIN areas a ON a.id = COALESCE(r.area_id, p.area_id)
+         WHERE tg.tag_id = ?1 AND tg.kind = 'resource'
+         AND r.deleted_at IS NULL AND p.deleted_at IS NULL AND a.deleted_at IS NULL
+         UNION ALL
+         SELECT 'event', e.id, e.title, p.title FROM tag_assignments tg
+         JOIN events e ON e.id = tg.item_id
+         LEFT JOIN projects p ON p.id = e.project_id AND p.deleted_at IS NULL
+         WHERE tg.tag_id = ?1 AND tg.kind = 'event' AND e.deleted_at IS NULL
+         ORDER BY 1, 3 COLLATE NOCASE",
+    )?;
+    let items = stmt
+        .query_map(params![tag_id], |row| {
+            Ok(TaggedItem {
+                kind: row.get(0)?,
+                id: row.get(1)?,
+                title: row.get(2)?,
+                parent_title: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
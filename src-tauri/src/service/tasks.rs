@@ -0,0 +1,315 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use super::{Owned, ensure_owner, projects, trash, trimmed};
+use crate::error::RecallError;
+use crate::tasks::{CreateTaskRequest, Task, TaskInfo, UpdateTaskRequest};
+use crate::try_insert_thing;
+
+const SELECT_TASK: &str = "SELECT k.id, k.project_id, k.parent_id, k.title, k.notes, k.done, k.due_date, k.priority, k.position, k.completed_at, k.created_at, k.updated_at
+     FROM tasks k";
+
+fn task_from_row(row: &Row) -> rusqlite::Result<TaskInfo> {
+    Ok(TaskInfo {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        parent_id: row.get(2)?,
+        title: row.get(3)?,
+        notes: row.get(4)?,
+        done: row.get::<_, i64>(5)? != 0,
+        due_date: row.get(6)?,
+        priority: row.get(7)?,
+        position: row.get(8)?,
+        completed_at: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
+fn validate_fields(title: &str, priority: Option<&str>) -> Result<(), RecallError> {
+    if title.trim().is_empty() {
+        return Err(RecallError::validation("Task title cannot be empty"));
+    }
+    projects::validate_priority(priority)
+}
+
+// The project of a task the user owns. Tasks of a project in the trash are gone with it.
+fn ensure_task(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    denied: &str,
+) -> Result<String, RecallError> {
+    let task: Option<(String, String, bool)> = conn
+        .query_row(
+            &format!(
+                "SELECT k.user_id, k.project_id, {} FROM tasks k WHERE k.id = ?1",
+                trash::trashed(Owned::Project, "k.project_id")
+            ),
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    match task {
+        None | Some((_, _, true)) => Err(RecallError::not_found("Task")),
+        Some((owner, _, _)) if owner != user_id => Err(RecallError::forbidden(denied)),
+        Some((_, project_id, _)) => Ok(project_id),
+    }
+}
+
+// A parent must be a task of the same project
+fn ensure_parent(
+    conn: &Connection,
+    user_id: &str,
+    project_id: &str,
+    parent_id: &str,
+) -> Result<(), RecallError> {
+    let parent_project = ensure_task(
+        conn,
+        user_id,
+        parent_id,
+        "You don't have permission to use this task",
+    )?;
+    if parent_project != project_id {
+        return Err(RecallError::validation(
+            "A subtask must be in the same project as its parent",
+        ));
+    }
+    Ok(())
+}
+
+// Position after the last task sharing the parent
+fn next_position(
+    conn: &Connection,
+    project_id: &str,
+    parent_id: Option<&str>,
+) -> Result<i64, RecallError> {
+    Ok(conn.query_row(
+        "SELECT COALESCE(MAX(position) + 1, 0) FROM tasks WHERE project_id = ?1 AND parent_id IS ?2",
+        params![project_id, parent_id],
+        |row| row.get(0),
+    )?)
+}
+
+pub fn create(
+    conn: &Connection,
+    user_id: &str,
+    request: CreateTaskRequest,
+) -> Result<TaskInfo, RecallError> {
+    validate_fields(&request.title, request.priority.as_deref())?;
+    ensure_owner(
+        conn,
+        Owned::Project,
+        &request.project_id,
+        user_id,
+        "You don't have permission to add tasks to this project",
+    )?;
+    if let Some(parent_id) = &request.parent_id {
+        ensure_parent(conn, user_id, &request.project_id, parent_id)?;
+    }
+
+    let now = Utc::now().timestamp();
+    let position = next_position(conn, &request.project_id, request.parent_id.as_deref())?;
+    let mut task = Task {
+        id: None,
+        user_id: user_id.to_string(),
+        project_id: request.project_id,
+        parent_id: request.parent_id,
+        title: request.title.trim().to_string(),
+        notes: trimmed(request.notes),
+        done: 0,
+        due_date: request.due_date,
+        priority: request.priority,
+        position,
+        completed_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let task_id = try_insert_thing(&mut task, conn)?;
+
+    get(conn, user_id, &task_id)
+}
+
+pub fn list(
+    conn: &Connection,
+    user_id: &str,
+    project_id: &str,
+) -> Result<Vec<TaskInfo>, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Project,
+        project_id,
+        user_id,
+        "You don't have permission to view this project",
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE k.project_id = ?1 ORDER BY k.position, k.created_at",
+        SELECT_TASK
+    ))?;
+    let tasks = stmt
+        .query_map(params![project_id], task_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tasks)
+}
+
+pub fn get(conn: &Connection, user_id: &str, id: &str) -> Result<TaskInfo, RecallError> {
+    ensure_task(
+        conn,
+        user_id,
+        id,
+        "You don't have permission to view this task",
+    )?;
+
+    Ok(conn.query_row(
+        &format!("{} WHERE k.id = ?1", SELECT_TASK),
+        params![id],
+        task_from_row,
+    )?)
+}
+
+pub fn update(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    request: UpdateTaskRequest,
+) -> Result<TaskInfo, RecallError> {
+    validate_fields(&request.title, request.priority.as_deref())?;
+    let project_id = ensure_task(
+        conn,
+        user_id,
+        id,
+        "You don't have permission to update this task",
+    )?;
+
+    let parent_id: Option<String> = conn.query_row(
+        "SELECT parent_id FROM tasks WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    let mut position = None;
+    if request.parent_id != parent_id {
+        if let Some(new_parent) = &request.parent_id {
+            ensure_parent(conn, user_id, &project_id, new_parent)?;
+            // The new parent must not be the task itself or one of its subtasks
+            let cycle: bool = conn.query_row(
+                "WITH RECURSIVE below(id) AS (
+                     SELECT ?1 UNION SELECT t.id FROM tasks t JOIN below ON t.parent_id = below.id
+                 )
+                 SELECT EXISTS(SELECT 1 FROM below WHERE id = ?2)",
+                params![id, new_parent],
+                |row| row.get(0),
+            )?;
+            if cycle {
+                return Err(RecallError::validation(
+                    "A task cannot be moved under itself or one of its subtasks",
+                ));
+            }
+        }
+        position = Some(next_position(
+            conn,
+            &project_id,
+            request.parent_id.as_deref(),
+        )?);
+    }
+
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "UPDATE tasks SET parent_id = ?1, title = ?2, notes = ?3, done = ?4, due_date = ?5, priority = ?6,
+         position = COALESCE(?7, position),
+         completed_at = CASE WHEN NOT ?4 THEN NULL WHEN done THEN completed_at ELSE ?8 END,
+         updated_at = ?8
+         WHERE id = ?9",
+        params![
+            request.parent_id,
+            request.title.trim(),
+            trimmed(request.notes),
+            request.done,
+            request.due_date,
+            request.priority,
+            position,
+            now,
+            id
+        ],
+    )?;
+
+    get(conn, user_id, id)
+}
+
+pub fn set_done(
+    conn: &Connection,
+    user_id: &str,
+    id: &str,
+    done: bool,
+) -> Result<TaskInfo, RecallError> {
+    ensure_task(
+        conn,
+        user_id,
+        id,
+        "You don't have permission to update this task",
+    )?;
+
+    let now = Utc::now().timestamp();
+    conn.execute(
+        "UPDATE tasks SET done = ?1, updated_at = ?2,
+         completed_at = CASE WHEN NOT ?1 THEN NULL WHEN done THEN completed_at ELSE ?2 END
+         WHERE id = ?3",
+        params![done, now, id],
+    )?;
+
+    get(conn, user_id, id)
+}
+
+// `ids` must be every task directly under the parent, each once
+pub fn reorder(
+    conn: &Connection,
+    user_id: &str,
+    project_id: &str,
+    parent_id: Option<&str>,
+    ids: &[String],
+) -> Result<(), RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Project,
+        project_id,
+        user_id,
+        "You don't have permission to change this project",
+    )?;
+
+    let mut siblings: Vec<String> = conn
+        .prepare("SELECT id FROM tasks WHERE project_id = ?1 AND parent_id IS ?2")?
+        .query_map(params![project_id, parent_id], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let mut given = ids.to_vec();
+    siblings.sort();
+    given.sort();
+    if siblings != given {
+        return Err(RecallError::validation(
+            "List every task under the parent exactly once",
+        ));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for (position, id) in ids.iter().enumerate() {
+        tx.execute(
+            "UPDATE tasks SET position = ?1 WHERE id = ?2",
+            params![position as i64, id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+// Subtasks go with their parent
+pub fn delete(conn: &Connection, user_id: &str, id: &str) -> Result<(), RecallError> {
+    ensure_task(
+        conn,
+        user_id,
+        id,
+        "You don't have permission to delete this task",
+    )?;
+
+    conn.execute("DELETE FROM tasks WHERE id = ?1", params![id])?;
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, ToSql, params};
+
+use super::Owned;
+use crate::blobs;
+use crate::error::RecallError;
+use crate::trash::{TrashListing, TrashedItem};
+
+// How long deleted items are kept when the user has not chosen
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+const MAX_RETENTION_DAYS: i64 = 3650;
+
+// Resources and events first; purging a project or area cascades to whatever is left under it
+const PURGE_ORDER: [Owned; 4] = [Owned::Resource, Owned::Event, Owned::Project, Owned::Area];
+
+// SQL condition that holds when the `kind` row whose id is `column` is in the trash, deleted
+// itself or with the area or project it belongs to. `column` must be qualified with its table.
+pub(crate) fn trashed(kind: Owned, column: &str) -> String {
+    match kind {
+        Owned::Area => format!(
+            "EXISTS(SELECT 1 FROM areas ta WHERE ta.id = {} AND ta.deleted_at IS NOT NULL)",
+            column
+        ),
+        Owned::Project => format!(
+            "EXISTS(SELECT 1 FROM projects tp JOIN areas ta ON ta.id = tp.area_id
+             WHERE tp.id = {} AND (tp.deleted_at IS NOT NULL OR ta.deleted_at IS NOT NULL))",
+            column
+        ),
+        Owned::Resource => format!(
+            "EXISTS(SELECT 1 FROM resources tr LEFT JOIN projects tp ON tp.id = tr.project_id
+             LEFT JOIN areas ta ON ta.id = COALESCE(tr.area_id, tp.area_id) WHERE tr.id = {}
+             AND (tr.deleted_at IS NOT NULL OR tp.deleted_at IS NOT NULL OR ta.deleted_at IS NOT NULL))",
+            column
+        ),
+        Owned::Event => format!(
+            "EXISTS(SELECT 1 FROM events te WHERE te.id = {} AND te.deleted_at IS NOT NULL)",
+            column
+        ),
+    }
+}
+
+// Stamp a row the caller has checked the user owns. Reminders already shown for it are
+// dropped; restoring brings back any still to come.
+pub(crate) fn move_to_trash(conn: &Connection, kind: Owned, id: &str) -> Result<(), RecallError> {
+    conn.execute(
+        &format!("UPDATE {} SET deleted_at = ?1 WHERE id = ?2", kind.table()),
+        params![Utc::now().timestamp(), id],
+    )?;
+    if matches!(kind, Owned::Event | Owned::Project) {
+        conn.execute(
+            "DELETE FROM reminder_deliveries WHERE kind = ?1 AND source_id = ?2",
+            params![kind.name(), id],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn retention_days(conn: &Connection, user_id: &str) -> Result<i64, RecallError> {
+    let days: Option<i64> = conn
+        .query_row(
+            "SELECT trash_retention_days FROM users WHERE id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| RecallError::not_found("User"))?;
+    Ok(days.unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+// None goes back to the default
+pub fn set_retention_days(
+    conn: &Connection,
+    user_id: &str,
+    days: Option<i64>,
+) -> Result<i64, RecallError> {
+    if days.is_some_and(|days| !(1..=MAX_RETENTION_DAYS).contains(&days)) {
+        return Err(RecallError::validation(format!(
+            "Deleted items can be kept for 1 to {} days",
+            MAX_RETENTION_DAYS
+        )));
+    }
+    // A preference rather than a profile edit, so updated_at is left alone
+    conn.execute(
+        "UPDATE users SET trash_retention_days = ?1 WHERE id = ?2",
+        params![days, user_id],
+    )?;
+    Ok(days.unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+// Everything the user deleted, most recent first. Items under a deleted area or project
+// are not listed on their own: they come back with it.
+pub fn list(conn: &Connection, user_id: &str) -> Result<TrashListing, RecallError> {
+    let retention_days = retention_days(conn, user_id)?;
+    let mut stmt = conn.prepare(
+        "SELECT 'area', id, name, NULL, deleted_at FROM areas
+         WHERE user_id = ?1 AND deleted_at IS NOT NULL
+         UNION ALL
+         SELECT 'project', p.id, p.title, a.name, p.deleted_at FROM projects p
+         JOIN areas a ON a.id = p.area_id
+         WHERE p.user_id = ?1 AND p.deleted_at IS NOT NULL AND a.deleted_at IS NULL
+         UNION ALL
+         SELECT 'resource', r.id, r.name, COALESCE(p.title, a.name), r.deleted_at FROM resources r
+         LEFT JOIN projects p ON p.id = r.project_id
+         LEFT JOIN areas a ON a.id = COALESCE(r.area_id, p.area_id)
+         WHERE r.user_id = ?1 AND r.deleted_at IS NOT NULL
+         AND p.deleted_at IS NULL AND a.deleted_at IS NULL
+         UNION ALL
+         SELECT 'event', id, title, NULL, deleted_at FROM events
+         WHERE user_id = ?1 AND deleted_at IS NOT NULL
+         ORDER BY 5 DESC",
+    )?;
+    let items = stmt
+        .query_map(params![user_id], |row| {
+            let deleted_at: i64 = row.get(4)?;
+            Ok(TrashedItem {
+                kind: row.get(0)?,
+                id: row.get(1)?,
+                title: row.get(2)?,
+                parent_title: row.get(3)?,
+                deleted_at,
+                purge_at: deleted_at + retention_days * 24 * 3600,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TrashListing {
+        items,
+        retention_days,
+    })
+}
+
+// Take an item out of the trash. What was deleted with it comes back too, and events
+// show their project again once it is restored.
+pub fn restore(conn: &Connection, user_id: &str, kind: Owned, id: &str) -> Result<(), RecallError> {
+    let in_trash: bool = conn
+        .query_row(
+            &format!(
+                "SELECT deleted_at IS NOT NULL FROM {} WHERE id = ?1 AND user_id = ?2",
+                kind.table()
+            ),
+            params![id, user_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| RecallError::not_found(kind.label()))?;
+    if !in_trash {
+        return Err(RecallError::validation(format!(
+            "{} is not in the trash",
+            kind.label()
+        )));
+    }
+
+    let parent = match kind {
+        Owned::Project => Some((
+            Owned::Area,
+            conn.query_row(
+                "SELECT area_id FROM projects WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )?,
+        )),
+        // A resource may sit in a project, an area, or on its own
+        Owned::Resource => {
+            let (project_id, area_id): (Option<String>, Option<String>) = conn.query_row(
+                "SELECT project_id, area_id FROM resources WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            project_id
+                .map(|id| (Owned::Project, id))
+                .or(area_id.map(|id| (Owned::Area, id)))
+        }
+        Owned::Area | Owned::Event => None,
+    };
+    if let Some((parent, parent_id)) = parent {
+        let parent_trashed: bool = conn.query_row(
+            &format!("SELECT {}", trashed(parent, "?1")),
+            params![parent_id],
+            |row| row.get(0),
+        )?;
+        if parent_trashed {
+            return Err(RecallError::validation(format!(
+                "Its {} is in the trash. Restore that first",
+                parent.label().to_lowercase()
+            )));
+        }
+    }
+
+    conn.execute(
+        &format!(
+            "UPDATE {} SET deleted_at = NULL WHERE id = ?1",
+            kind.table()
+        ),
+        params![id],
+    )?;
+    Ok(())
+}
+
+// Permanently delete the trashed rows of each table matching `condition(table)`, returning
+// how many went. Deleting a project or area cascades to its resources; events are kept
+// and unlinked.
+fn purge_where(
+    conn: &Connection,
+    condition: impl Fn(&str) -> String,
+    value: &dyn ToSql,
+) -> Result<usize, RecallError> {
+    let tx = conn.unchecked_transaction()?;
+    let mut purged = 0;
+    for kind in PURGE_ORDER {
+        purged += tx.execute(
+            &format!(
+                "DELETE FROM {} WHERE deleted_at IS NOT NULL AND {}",
+                kind.table(),
+                condition(kind.table())
+            ),
+            params![value],
+        )?;
+    }
+    tx.commit()?;
+    if purged > 0 {
+        blobs::collect_garbage(conn)?;
+    }
+    Ok(purged)
+}
+
+// Everything in the user's trash, now
+pub fn empty(conn: &Connection, user_id: &str) -> Result<usize, RecallError> {
+    purge_where(conn, |_| "user_id = ?1".to_string(), &user_id)
+}
+
+// Everything deleted longer ago than its owner's retention window
+pub fn purge_expired(conn: &Connection, now: i64) -> Result<usize, RecallError> {
+    purge_where(
+        conn,
+        |table| {
+            format!(
+                "deleted_at <= ?1 - 86400 * COALESCE(
+                    (SELECT trash_retention_days FROM users WHERE users.id = {}.user_id), {})",
+                table, DEFAULT_RETENTION_DAYS
+            )
+        },
+        &now,
+    )
+}
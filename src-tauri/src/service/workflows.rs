@@ -0,0 +1,466 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use rusqlite::{Connection, params};
+
+use super::{Owned, ensure_owner, projects};
+use crate::error::RecallError;
+use crate::try_insert_thing;
+use crate::workflows::{
+    SetWorkflowRequest, Workflow, WorkflowInfo, WorkflowStatus, WorkflowTransition,
+};
+
+const MAX_NAME_LENGTH: usize = 50;
+
+// What users who have not set up their own workflow get
+const BUILT_IN_STATUSES: [(&str, bool); 4] = [
+    ("Inbox", false),
+    ("Planned", false),
+    ("Progress", false),
+    ("Done", true),
+];
+
+// SQL for the id of the workflow governing a project of `user` in `area`: the area's own,
+// else the user's, else NULL for the built-in one. Pass qualified columns, as the
+// subquery has user_id and area_id columns of its own.
+fn governing(user: &str, area: &str) -> String {
+    format!(
+        "(SELECT wg.id FROM workflows wg WHERE wg.user_id = {} AND (wg.area_id = {} OR wg.area_id IS NULL)
+          ORDER BY wg.area_id IS NULL LIMIT 1)",
+        user, area
+    )
+}
+
+// SQL condition that holds when `status` is a finished status for a project of `user` in
+// `area`, with columns qualified as for governing
+pub(crate) fn done(status: &str, user: &str, area: &str) -> String {
+    let workflow = governing(user, area);
+    format!(
+        "COALESCE((SELECT wd.done FROM workflow_statuses wd WHERE wd.workflow_id = {} AND wd.name = {}),
+                  {} IS NULL AND {} = 'Done')",
+        workflow, status, workflow, status
+    )
+}
+
+fn built_in() -> WorkflowInfo {
+    WorkflowInfo {
+        id: None,
+        area_id: None,
+        name: "Default".to_string(),
+        statuses: BUILT_IN_STATUSES
+            .iter()
+            .map(|&(name, done)| WorkflowStatus {
+                name: name.to_string(),
+                done,
+            })
+            .collect(),
+        transitions: Vec::new(),
+    }
+}
+
+fn load(conn: &Connection, id: &str) -> Result<WorkflowInfo, RecallError> {
+    let (area_id, name) = conn.query_row(
+        "SELECT area_id, name FROM workflows WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT name, done FROM workflow_statuses WHERE workflow_id = ?1 ORDER BY position",
+    )?;
+    let statuses = stmt
+        .query_map(params![id], |row| {
+            Ok(WorkflowStatus {
+                name: row.get(0)?,
+                done: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT t.from_status, t.to_status FROM workflow_transitions t
+         JOIN workflow_statuses f ON f.workflow_id = t.workflow_id AND f.name = t.from_status
+         JOIN workflow_statuses s ON s.workflow_id = t.workflow_id AND s.name = t.to_status
+         WHERE t.workflow_id = ?1 ORDER BY f.position, s.position",
+    )?;
+    let transitions = stmt
+        .query_map(params![id], |row| {
+            Ok(WorkflowTransition {
+                from: row.get(0)?,
+                to: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(WorkflowInfo {
+        id: Some(id.to_string()),
+        area_id,
+        name,
+        statuses,
+        transitions,
+    })
+}
+
+// The workflow for projects of the user in area_id, without checking who owns the area
+fn governing_workflow(
+    conn: &Connection,
+    user_id: &str,
+    area_id: Option<&str>,
+) -> Result<WorkflowInfo, RecallError> {
+    let id: Option<String> = conn.query_row(
+        &format!("SELECT {}", governing("?1", "?2")),
+        params![user_id, area_id],
+        |row| row.get(0),
+    )?;
+    match id {
+        Some(id) => load(conn, &id),
+        None => Ok(built_in()),
+    }
+}
+
+// "A", "A or B", "A, B, or C"
+fn choices(workflow: &WorkflowInfo) -> String {
+    let names: Vec<&str> = workflow.statuses.iter().map(|s| s.name.as_str()).collect();
+    match names.as_slice() {
+        [] => String::new(),
+        [one] => one.to_string(),
+        [first, second] => format!("{} or {}", first, second),
+        [rest @ .., last] => format!("{}, or {}", rest.join(", "), last),
+    }
+}
+
+fn find<'a>(workflow: &'a WorkflowInfo, status: &str) -> Result<&'a WorkflowStatus, RecallError> {
+    workflow
+        .statuses
+        .iter()
+        .find(|s| s.name == status)
+        .ok_or_else(|| {
+            RecallError::validation(format!("Invalid status. Must be: {}", choices(workflow)))
+        })
+}
+
+// Check a status for a project in area_id, returning whether it is a finished one
+pub(crate) fn check_status(
+    conn: &Connection,
+    user_id: &str,
+    area_id: &str,
+    status: &str,
+) -> Result<bool, RecallError> {
+    let workflow = governing_workflow(conn, user_id, Some(area_id))?;
+    Ok(find(&workflow, status)?.done)
+}
+
+// Check that a project in area_id may move from one status to another, returning whether
+// the new one is finished
+pub(crate) fn check_move(
+    conn: &Connection,
+    user_id: &str,
+    area_id: &str,
+    from: &str,
+    to: &str,
+) -> Result<bool, RecallError> {
+    let workflow = governing_workflow(conn, user_id, Some(area_id))?;
+    let done = find(&workflow, to)?.done;
+    let allowed = from == to
+        || workflow.transitions.is_empty()
+        || workflow
+            .transitions
+            .iter()
+            .any(|t| t.from == from && t.to == to);
+    if !allowed {
+        return Err(RecallError::validation(format!(
+            "Projects cannot move from {} to {}",
+            from, to
+        )));
+    }
+    Ok(done)
+}
+
+// Where new projects in area_id start when no status is chosen
+pub(crate) fn first_status(
+    conn: &Connection,
+    user_id: &str,
+    area_id: &str,
+) -> Result<String, RecallError> {
+    let workflow = governing_workflow(conn, user_id, Some(area_id))?;
+    Ok(workflow.statuses[0].name.clone())
+}
+
+pub fn get(
+    conn: &Connection,
+    user_id: &str,
+    area_id: Option<&str>,
+) -> Result<WorkflowInfo, RecallError> {
+    if let Some(area_id) = area_id {
+        ensure_owner(
+            conn,
+            Owned::Area,
+            area_id,
+            user_id,
+            "You don't have permission to view this area",
+        )?;
+    }
+    governing_workflow(conn, user_id, area_id)
+}
+
+fn validate(request: &SetWorkflowRequest) -> Result<(), RecallError> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(RecallError::validation("Workflow name cannot be empty"));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(RecallError::validation(format!(
+            "Workflow name cannot be longer than {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+    if request.statuses.is_empty() {
+        return Err(RecallError::validation(
+            "A workflow needs at least one status",
+        ));
+    }
+
+    let mut names = HashSet::new();
+    for status in &request.statuses {
+        let name = status.name.trim();
+        if name.is_empty() {
+            return Err(RecallError::validation("Status names cannot be empty"));
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(RecallError::validation(format!(
+                "Status names cannot be longer than {} characters",
+                MAX_NAME_LENGTH
+            )));
+        }
+        if !names.insert(name) {
+            return Err(RecallError::validation(format!(
+                "{} is in the workflow twice",
+                name
+            )));
+        }
+    }
+    for transition in &request.transitions {
+        for status in [&transition.from, &transition.to] {
+            if !names.contains(status.trim()) {
+                return Err(RecallError::validation(format!(
+                    "{} is not a status of the workflow",
+                    status
+                )));
+            }
+        }
+        if transition.from.trim() == transition.to.trim() {
+            return Err(RecallError::validation(
+                "A transition must go to another status",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Condition on projects p for those the workflow for area ?2 governs, or the user's own
+// workflow with ?2 NULL: every project in an area without a workflow of its own
+const SCOPE: &str = "p.user_id = ?1 AND (p.area_id = ?2 OR ?2 IS NULL AND p.area_id NOT IN
+     (SELECT area_id FROM workflows WHERE user_id = ?1 AND area_id IS NOT NULL))";
+
+// Put each project in scope in the status `target` gives for its current one, keeping
+// completed_at in step with whether the new status is finished. Projects changing column
+// go below the cards already there, in their old order. Trashed and archived projects
+// move too, so they fit when they come back.
+fn refile(
+    conn: &Connection,
+    user_id: &str,
+    area_id: Option<&str>,
+    target: impl Fn(&str) -> Result<(String, bool), RecallError>,
+) -> Result<(), RecallError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT p.id, p.area_id, p.status, p.completed_at FROM projects p WHERE {}
+         ORDER BY p.status, p.position, p.created_at DESC, p.id",
+        SCOPE
+    ))?;
+    let moving = stmt
+        .query_map(params![user_id, area_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let now = Utc::now().timestamp();
+    for (id, project_area, old_status, completed_at) in moving {
+        let (status, done) = target(&old_status)?;
+        let completed_at = if done {
+            completed_at.or(Some(now))
+        } else {
+            None
+        };
+        if status == old_status {
+            conn.execute(
+                "UPDATE projects SET completed_at = ?1 WHERE id = ?2",
+                params![completed_at, id],
+            )?;
+        } else {
+            let position = projects::bottom_position(conn, &project_area, &status)?;
+            conn.execute(
+                "UPDATE projects SET status = ?1, completed_at = ?2, position = ?3 WHERE id = ?4",
+                params![status, completed_at, position, id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// Replace the workflow of an area, or the user's own, moving projects along: a status kept
+// by name keeps its projects and `from` says where those of the others go
+pub fn set(
+    conn: &Connection,
+    user_id: &str,
+    request: SetWorkflowRequest,
+) -> Result<WorkflowInfo, RecallError> {
+    validate(&request)?;
+    let area_id = request.area_id.as_deref();
+    if let Some(area_id) = area_id {
+        ensure_owner(
+            conn,
+            Owned::Area,
+            area_id,
+            user_id,
+            "You don't have permission to change this area's workflow",
+        )?;
+    }
+
+    let current = governing_workflow(conn, user_id, area_id)?;
+    let statuses: Vec<WorkflowStatus> = request
+        .statuses
+        .iter()
+        .map(|s| WorkflowStatus {
+            name: s.name.trim().to_string(),
+            done: s.done,
+        })
+        .collect();
+
+    // Where the projects of each current status go
+    let mut moves: HashMap<String, String> = HashMap::new();
+    for (status, new) in request.statuses.iter().zip(&statuses) {
+        for old in &status.from {
+            if !current.statuses.iter().any(|s| &s.name == old) {
+                return Err(RecallError::validation(format!(
+                    "{} is not a status of the current workflow",
+                    old
+                )));
+            }
+            if moves.insert(old.clone(), new.name.clone()).is_some() {
+                return Err(RecallError::validation(format!(
+                    "Projects in {} can only move to one status",
+                    old
+                )));
+            }
+        }
+    }
+    for old in &current.statuses {
+        if statuses.iter().any(|s| s.name == old.name) {
+            moves
+                .entry(old.name.clone())
+                .or_insert_with(|| old.name.clone());
+        }
+    }
+
+    let now = Utc::now().timestamp();
+    let tx = conn.unchecked_transaction()?;
+    let id = match current.id {
+        // Editing the workflow in place; an area still on the user's gets its own
+        Some(id) if current.area_id.as_deref() == area_id => {
+            tx.execute(
+                "UPDATE workflows SET name = ?1, updated_at = ?2 WHERE id = ?3",
+                params![request.name.trim(), now, id],
+            )?;
+            tx.execute(
+                "DELETE FROM workflow_transitions WHERE workflow_id = ?1",
+                params![id],
+            )?;
+            tx.execute(
+                "DELETE FROM workflow_statuses WHERE workflow_id = ?1",
+                params![id],
+            )?;
+            id
+        }
+        _ => {
+            let mut workflow = Workflow {
+                id: None,
+                user_id: user_id.to_string(),
+                area_id: request.area_id.clone(),
+                name: request.name.trim().to_string(),
+                created_at: now,
+                updated_at: now,
+            };
+            try_insert_thing(&mut workflow, &tx)?
+        }
+    };
+
+    for (position, status) in statuses.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO workflow_statuses (workflow_id, name, position, done) VALUES (?1, ?2, ?3, ?4)",
+            params![id, status.name, position as i64, status.done],
+        )?;
+    }
+    for transition in &request.transitions {
+        tx.execute(
+            "INSERT OR IGNORE INTO workflow_transitions (workflow_id, from_status, to_status)
+             VALUES (?1, ?2, ?3)",
+            params![id, transition.from.trim(), transition.to.trim()],
+        )?;
+    }
+
+    refile(&tx, user_id, area_id, |old| {
+        let new = moves.get(old).map(String::as_str).unwrap_or(old);
+        match statuses.iter().find(|s| s.name == new) {
+            Some(status) => Ok((status.name.clone(), status.done)),
+            None => Err(RecallError::validation(format!(
+                "Projects in {} need a status to move to",
+                old
+            ))),
+        }
+    })?;
+    tx.commit()?;
+
+    load(conn, &id)
+}
+
+// Drop an area's own workflow, returning the user's that now applies there. Its projects
+// must already be in statuses that workflow has.
+pub fn delete(
+    conn: &Connection,
+    user_id: &str,
+    area_id: &str,
+) -> Result<WorkflowInfo, RecallError> {
+    ensure_owner(
+        conn,
+        Owned::Area,
+        area_id,
+        user_id,
+        "You don't have permission to change this area's workflow",
+    )?;
+    let current = governing_workflow(conn, user_id, Some(area_id))?;
+    let Some(id) = current.id.filter(|_| current.area_id.is_some()) else {
+        return Err(RecallError::not_found("Workflow"));
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM workflows WHERE id = ?1", params![id])?;
+    let fallback = governing_workflow(&tx, user_id, Some(area_id))?;
+    refile(&tx, user_id, Some(area_id), |status| {
+        find(&fallback, status)
+            .map(|s| (s.name.clone(), s.done))
+            .map_err(|_| {
+                RecallError::validation(format!(
+                    "{} has no {} status. Move the projects in it first",
+                    fallback.name, status
+                ))
+            })
+    })?;
+    tx.commit()?;
+
+    Ok(fallback)
+}
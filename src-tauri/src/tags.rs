@@ -0,0 +1,192 @@
+use exemplar::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, parse_request, respond, respond_with_message};
+use crate::service::{self, Owned};
+use crate::{AppState, HasId, get_user_id_from_token};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Model)]
+#[table("tags")]
+#[check("../sql/tags.sql")]
+pub struct Tag {
+    pub id: Option<String>,
+    pub user_id: String,
+    pub name: String,
+    pub color: Option<String>, // Hex color such as #f59e0b
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl HasId for Tag {
+    fn id_mut(&mut self) -> &mut Option<String> {
+        &mut self.id
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTagRequest {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTagRequest {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TagInfo {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub item_count: i64, // Items carrying it that are not in the trash
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+// Something carrying a tag
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaggedItem {
+    pub kind: String, // "area", "project", "resource" or "event"
+    pub id: String,
+    pub title: String,
+    pub parent_title: Option<String>, // Area of a project, project or area of a resource, project of an event
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagResponse {
+    pub tag: Option<TagInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagsResponse {
+    pub tags: Vec<TagInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaggedItemsResponse {
+    pub tag: TagInfo,
+    pub items: Vec<TaggedItem>,
+}
+
+#[tauri::command]
+pub fn get_tags(token: String, state: tauri::State<'_, AppState>) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let tags = service::tags::list(&conn, &user_id)?;
+
+    respond(TagsResponse { tags })
+}
+
+#[tauri::command]
+pub fn create_tag(
+    token: String,
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: CreateTagRequest = parse_request(&json)?;
+
+    let tag = service::tags::create(&conn, &user_id, request)?;
+
+    respond(TagResponse { tag: Some(tag) })
+}
+
+// Rename or recolor a tag
+#[tauri::command]
+pub fn update_tag(
+    token: String,
+    id: String,
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: UpdateTagRequest = parse_request(&json)?;
+
+    let tag = service::tags::update(&conn, &user_id, &id, request)?;
+
+    respond(TagResponse { tag: Some(tag) })
+}
+
+// Retag everything carrying source_id with target_id and delete source_id
+#[tauri::command(rename_all = "snake_case")]
+pub fn merge_tags(
+    token: String,
+    source_id: String,
+    target_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let tag = service::tags::merge(&conn, &user_id, &source_id, &target_id)?;
+
+    respond_with_message("Tags merged", TagResponse { tag: Some(tag) })
+}
+
+#[tauri::command]
+pub fn delete_tag(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    service::tags::delete(&conn, &user_id, &id)?;
+
+    respond_with_message("Tag deleted successfully", TagResponse { tag: None })
+}
+
+#[tauri::command]
+pub fn get_item_tags(
+    token: String,
+    kind: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let kind = Owned::parse(&kind)?;
+
+    let tags = service::tags::item_tags(&conn, &user_id, kind, &id)?;
+
+    respond(TagsResponse { tags })
+}
+
+// Replace the tags on an area, project, resource or event; unknown names become new tags
+#[tauri::command]
+pub fn set_item_tags(
+    token: String,
+    kind: String,
+    id: String,
+    names: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let kind = Owned::parse(&kind)?;
+
+    let tags = service::tags::set_item_tags(&conn, &user_id, kind, &id, &names)?;
+
+    respond(TagsResponse { tags })
+}
+
+#[tauri::command]
+pub fn get_tagged_items(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let items = service::tags::items(&conn, &user_id, &id)?;
+    let tag = service::tags::get(&conn, &user_id, &id)?;
+
+    respond(TaggedItemsResponse { tag, items })
+}
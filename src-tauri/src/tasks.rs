@@ -0,0 +1,187 @@
+use exemplar::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, parse_request, respond, respond_with_message};
+use crate::service;
+use crate::{AppState, HasId, get_user_id_from_token};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Model)]
+#[table("tasks")]
+#[check("../sql/tasks.sql")]
+pub struct Task {
+    pub id: Option<String>,
+    pub user_id: String,
+    pub project_id: String,
+    pub parent_id: Option<String>,
+    pub title: String,
+    pub notes: Option<String>,
+    pub done: i64, // 0 = false, 1 = true
+    pub due_date: Option<i64>,
+    pub priority: Option<String>,
+    pub position: i64,
+    pub completed_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl HasId for Task {
+    fn id_mut(&mut self) -> &mut Option<String> {
+        &mut self.id
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTaskRequest {
+    pub project_id: String,
+    pub parent_id: Option<String>,
+    pub title: String,
+    pub notes: Option<String>,
+    pub due_date: Option<i64>,
+    pub priority: Option<String>,
+}
+
+// Moving a task under another parent puts it last among its new siblings
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTaskRequest {
+    pub parent_id: Option<String>,
+    pub title: String,
+    pub notes: Option<String>,
+    pub done: bool,
+    pub due_date: Option<i64>,
+    pub priority: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaskInfo {
+    pub id: String,
+    pub project_id: String,
+    pub parent_id: Option<String>,
+    pub title: String,
+    pub notes: Option<String>,
+    pub done: bool,
+    pub due_date: Option<i64>,
+    pub priority: Option<String>,
+    pub position: i64,
+    pub completed_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskResponse {
+    pub task: Option<TaskInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TasksResponse {
+    pub tasks: Vec<TaskInfo>,
+}
+
+#[tauri::command]
+pub fn create_task(
+    token: String,
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: CreateTaskRequest = parse_request(&json)?;
+
+    let task = service::tasks::create(&conn, &user_id, request)?;
+
+    respond(TaskResponse { task: Some(task) })
+}
+
+// Every task in a project, subtasks included, in order among their siblings
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_tasks(
+    token: String,
+    project_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let tasks = service::tasks::list(&conn, &user_id, &project_id)?;
+
+    respond(TasksResponse { tasks })
+}
+
+#[tauri::command]
+pub fn get_task_by_id(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let task = service::tasks::get(&conn, &user_id, &id)?;
+
+    respond(TaskResponse { task: Some(task) })
+}
+
+#[tauri::command]
+pub fn update_task(
+    token: String,
+    id: String,
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: UpdateTaskRequest = parse_request(&json)?;
+
+    let task = service::tasks::update(&conn, &user_id, &id, request)?;
+
+    respond(TaskResponse { task: Some(task) })
+}
+
+// Tick or untick a task, as from its checkbox
+#[tauri::command]
+pub fn set_task_done(
+    token: String,
+    id: String,
+    done: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let task = service::tasks::set_done(&conn, &user_id, &id, done)?;
+
+    respond(TaskResponse { task: Some(task) })
+}
+
+// Put the tasks directly under parent_id, or under the project itself, in the order of ids
+#[tauri::command(rename_all = "snake_case")]
+pub fn reorder_tasks(
+    token: String,
+    project_id: String,
+    parent_id: Option<String>,
+    ids: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    service::tasks::reorder(&conn, &user_id, &project_id, parent_id.as_deref(), &ids)?;
+    let tasks = service::tasks::list(&conn, &user_id, &project_id)?;
+
+    respond(TasksResponse { tasks })
+}
+
+// Deletes the task along with its subtasks
+#[tauri::command]
+pub fn delete_task(
+    token: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    service::tasks::delete(&conn, &user_id, &id)?;
+
+    respond_with_message("Task deleted successfully", TaskResponse { task: None })
+}
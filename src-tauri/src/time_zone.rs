@@ -0,0 +1,57 @@
+// Conversions between instants (Unix seconds), wall-clock times in an IANA zone, and
+// floating times. Floating times have no zone: they are stored as their wall-clock
+// reading encoded as if it were UTC, and mean that reading in whichever zone they are
+// viewed from. All-day events are floating, so they stay on their date when the user travels.
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+
+use crate::error::RecallError;
+
+// The machine's zone, for users who have not chosen one
+pub fn system_zone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+pub fn parse(name: &str) -> Result<Tz, RecallError> {
+    name.trim()
+        .parse()
+        .map_err(|_| RecallError::validation(format!("Unknown time zone: {}", name)))
+}
+
+// The instant a wall-clock time in `tz` refers to. Times repeated when clocks go back
+// take the earlier instant; times skipped when they go forward land after the gap.
+pub fn instant(tz: Tz, wall: NaiveDateTime) -> i64 {
+    match tz.from_local_datetime(&wall).earliest() {
+        Some(t) => t.timestamp(),
+        None => {
+            let offset = tz.offset_from_utc_datetime(&wall).fix();
+            (wall - offset).and_utc().timestamp()
+        }
+    }
+}
+
+pub fn wall_clock(tz: Tz, instant: i64) -> NaiveDateTime {
+    DateTime::from_timestamp(instant, 0)
+        .unwrap_or_default()
+        .with_timezone(&tz)
+        .naive_local()
+}
+
+// The calendar date an instant falls on in `tz`
+pub fn day(tz: Tz, instant: i64) -> NaiveDate {
+    wall_clock(tz, instant).date()
+}
+
+pub fn to_floating(tz: Tz, instant: i64) -> i64 {
+    wall_clock(tz, instant).and_utc().timestamp()
+}
+
+pub fn from_floating(tz: Tz, floating: i64) -> i64 {
+    let wall = DateTime::from_timestamp(floating, 0)
+        .unwrap_or_default()
+        .naive_utc();
+    instant(tz, wall)
+}
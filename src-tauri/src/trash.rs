@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, respond, respond_with_message};
+use crate::service;
+use crate::{AppState, get_user_id_from_token};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashedItem {
+    pub kind: String, // "area", "project", "resource" or "event"
+    pub id: String,
+    pub title: String,
+    pub parent_title: Option<String>, // Area of a project, project or area of a resource
+    pub deleted_at: i64,
+    pub purge_at: i64, // When it will be deleted for good
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrashListing {
+    pub items: Vec<TrashedItem>,
+    pub retention_days: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetentionResponse {
+    pub retention_days: i64,
+}
+
+#[tauri::command]
+pub fn get_trash(token: String, state: tauri::State<'_, AppState>) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let trash = service::trash::list(&conn, &user_id)?;
+
+    respond(trash)
+}
+
+#[tauri::command]
+pub fn restore_item(
+    token: String,
+    kind: String,
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let kind = service::Owned::parse(&kind)?;
+
+    service::trash::restore(&conn, &user_id, kind, &id)?;
+    state.scheduler.wake();
+
+    respond_with_message("Restored from the trash", ())
+}
+
+#[tauri::command]
+pub fn empty_trash(
+    token: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    service::trash::empty(&conn, &user_id)?;
+
+    respond_with_message("Trash emptied", ())
+}
+
+// Keep deleted items for `days` days before the background scheduler purges them;
+// null restores the default
+#[tauri::command]
+pub fn set_trash_retention(
+    token: String,
+    days: Option<i64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let retention_days = service::trash::set_retention_days(&conn, &user_id, days)?;
+
+    respond(RetentionResponse { retention_days })
+}
@@ -0,0 +1,131 @@
+use exemplar::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{RecallError, parse_request, respond, respond_with_message};
+use crate::service;
+use crate::{AppState, HasId, get_user_id_from_token};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Model)]
+#[table("workflows")]
+#[check("../sql/workflows.sql")]
+pub struct Workflow {
+    pub id: Option<String>,
+    pub user_id: String,
+    pub area_id: Option<String>, // None for the user's own workflow
+    pub name: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl HasId for Workflow {
+    fn id_mut(&mut self) -> &mut Option<String> {
+        &mut self.id
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowStatus {
+    pub name: String,
+    pub done: bool, // Projects here are finished
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowTransition {
+    pub from: String,
+    pub to: String,
+}
+
+// Replaces the whole workflow of an area, or the user's own without area_id
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetWorkflowRequest {
+    pub area_id: Option<String>,
+    pub name: String,
+    pub statuses: Vec<WorkflowStatusRequest>, // In board order
+    // Without any, projects may move between every pair of statuses
+    #[serde(default)]
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowStatusRequest {
+    pub name: String,
+    #[serde(default)]
+    pub done: bool,
+    // Statuses of the current workflow whose projects move into this one, to rename or
+    // merge them. A status kept under the same name keeps its projects.
+    #[serde(default)]
+    pub from: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowInfo {
+    pub id: Option<String>,      // None for the built-in workflow
+    pub area_id: Option<String>, // Set when it is an area's own
+    pub name: String,
+    pub statuses: Vec<WorkflowStatus>,
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowResponse {
+    pub workflow: Option<WorkflowInfo>,
+}
+
+// The workflow projects in area_id follow, or without it the user's own
+#[tauri::command(rename_all = "snake_case")]
+pub fn get_workflow(
+    token: String,
+    area_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let workflow = service::workflows::get(&conn, &user_id, area_id.as_deref())?;
+
+    respond(WorkflowResponse {
+        workflow: Some(workflow),
+    })
+}
+
+#[tauri::command]
+pub fn set_workflow(
+    token: String,
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+    let request: SetWorkflowRequest = parse_request(&json)?;
+
+    let workflow = service::workflows::set(&conn, &user_id, request)?;
+    state.scheduler.wake();
+
+    respond_with_message(
+        "Workflow saved successfully",
+        WorkflowResponse {
+            workflow: Some(workflow),
+        },
+    )
+}
+
+// Drop an area's own workflow so its projects follow the user's again
+#[tauri::command(rename_all = "snake_case")]
+pub fn delete_workflow(
+    token: String,
+    area_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, RecallError> {
+    let conn = state.pool.get()?;
+    let user_id = get_user_id_from_token(&token, &conn)?;
+
+    let workflow = service::workflows::delete(&conn, &user_id, &area_id)?;
+    state.scheduler.wake();
+
+    respond_with_message(
+        "Workflow deleted successfully",
+        WorkflowResponse {
+            workflow: Some(workflow),
+        },
+    )
+}
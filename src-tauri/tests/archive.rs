@@ -0,0 +1,178 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::events::EventQuery;
+use recall_lib::projects::CreateProjectRequest;
+use recall_lib::service;
+
+use common::*;
+
+const DAY: i64 = 24 * 3600;
+
+fn names<T>(items: &[T], name: impl Fn(&T) -> &str) -> Vec<String> {
+    let mut names: Vec<String> = items.iter().map(|item| name(item).to_string()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn archived_areas_and_projects_leave_the_default_listings() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let home = area(&conn, id, "Home");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let hiring = project(&conn, id, &work.id, "Hiring");
+    let garden = project(&conn, id, &home.id, "Garden");
+    resource(&conn, id, &launch.id, "Launch plan", None);
+    resource(&conn, id, &garden.id, "Seeds", None);
+    event(&conn, id, Some(&hiring.id), "Interview", 1_700_000_000);
+    event(&conn, id, Some(&garden.id), "Plant roses", 1_700_000_000);
+    event(&conn, id, None, "Dentist", 1_700_000_000);
+
+    let archived = service::archive::archive_area(&conn, id, &work.id).unwrap();
+    assert!(archived.archived_at.is_some());
+    service::archive::archive_project(&conn, id, &garden.id).unwrap();
+
+    let areas = service::areas::list(&conn, id).unwrap();
+    assert_eq!(names(&areas, |a| &a.name), ["Home"]);
+    assert!(service::projects::list(&conn, id, None).unwrap().is_empty());
+    assert!(
+        service::resources::list(&conn, id, None)
+            .unwrap()
+            .is_empty()
+    );
+    let summaries =
+        service::resources::list_summaries(&conn, id, None, None, &[], None, None, None, None)
+            .unwrap();
+    assert_eq!(summaries.total, 0);
+    let events = service::events::list(&conn, id, None, None, None).unwrap();
+    assert_eq!(names(&events, |e| &e.title), ["Dentist"]);
+
+    // Asking for an archived parent by id still shows what is in it
+    let work_projects = service::projects::list(&conn, id, Some(&work.id)).unwrap();
+    assert_eq!(names(&work_projects, |p| &p.title), ["Hiring", "Launch"]);
+    assert_eq!(
+        service::resources::list(&conn, id, Some(&launch.id))
+            .unwrap()
+            .len(),
+        1
+    );
+    let everything = EventQuery {
+        include_archived: true,
+        ..EventQuery::default()
+    };
+    assert_eq!(
+        service::events::query(&conn, id, &everything)
+            .unwrap()
+            .len(),
+        3
+    );
+
+    let archive = service::archive::list(&conn, id).unwrap();
+    assert_eq!(names(&archive.areas, |a| &a.name), ["Work"]);
+    assert_eq!(
+        names(&archive.projects, |p| &p.title),
+        ["Garden", "Hiring", "Launch"]
+    );
+
+    // Unarchiving the area brings back its projects, but not one archived on its own
+    service::archive::unarchive_area(&conn, id, &work.id).unwrap();
+    service::archive::archive_project(&conn, id, &launch.id).unwrap();
+    let projects = service::projects::list(&conn, id, None).unwrap();
+    assert_eq!(names(&projects, |p| &p.title), ["Hiring"]);
+    service::archive::unarchive_project(&conn, id, &garden.id).unwrap();
+    let events = service::events::list(&conn, id, None, None, None).unwrap();
+    assert_eq!(
+        names(&events, |e| &e.title),
+        ["Dentist", "Interview", "Plant roses"]
+    );
+}
+
+#[test]
+fn archive_changes_are_checked() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let work = area(&conn, &alice.user.id, "Work");
+    let launch = project(&conn, &alice.user.id, &work.id, "Launch");
+
+    let err = service::archive::archive_area(&conn, &bob.user.id, &work.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    let err = service::archive::archive_project(&conn, &bob.user.id, "missing").unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+
+    service::archive::archive_area(&conn, &alice.user.id, &work.id).unwrap();
+    let err = service::archive::unarchive_project(&conn, &alice.user.id, &launch.id).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    let err = service::projects::create(
+        &conn,
+        &alice.user.id,
+        CreateProjectRequest {
+            area_id: work.id.clone(),
+            title: "Hiring".to_string(),
+            description: None,
+            status: "Inbox".to_string(),
+            priority: None,
+            start_date: None,
+            end_date: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+}
+
+#[test]
+fn done_projects_are_archived_after_the_chosen_days() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let work = area(&conn, &alice.user.id, "Work");
+    let launch = project(&conn, &alice.user.id, &work.id, "Launch");
+    let hiring = project(&conn, &alice.user.id, &work.id, "Hiring");
+    let bobs = area(&conn, &bob.user.id, "Bob's");
+    let bobs_project = project(&conn, &bob.user.id, &bobs.id, "Bob's project");
+
+    for (user_id, project_id) in [
+        (&alice.user.id, &launch.id),
+        (&alice.user.id, &hiring.id),
+        (&bob.user.id, &bobs_project.id),
+    ] {
+        service::projects::move_to(&conn, user_id, project_id, "Done", None, None).unwrap();
+    }
+    // Reopened, so no longer counted as finished
+    service::projects::move_to(&conn, &alice.user.id, &hiring.id, "Progress", None, None).unwrap();
+
+    let err = service::archive::set_auto_archive_days(&conn, &alice.user.id, Some(0)).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    service::archive::set_auto_archive_days(&conn, &alice.user.id, Some(7)).unwrap();
+
+    let done_at: i64 = conn
+        .query_row(
+            "SELECT completed_at FROM projects WHERE id = ?1",
+            [&launch.id],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(
+        service::archive::auto_archive(&conn, done_at + 6 * DAY).unwrap(),
+        0
+    );
+    // Bob never turned it on
+    assert_eq!(
+        service::archive::auto_archive(&conn, done_at + 7 * DAY).unwrap(),
+        1
+    );
+
+    let archive = service::archive::list(&conn, &alice.user.id).unwrap();
+    assert_eq!(names(&archive.projects, |p| &p.title), ["Launch"]);
+    assert_eq!(archive.auto_archive_days, Some(7));
+    assert!(
+        service::archive::list(&conn, &bob.user.id)
+            .unwrap()
+            .projects
+            .is_empty()
+    );
+}
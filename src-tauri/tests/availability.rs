@@ -0,0 +1,282 @@
+mod common;
+
+use chrono::NaiveDateTime;
+use recall_lib::availability::{FreeBusyRequest, TimeSlot, WorkingHours};
+use recall_lib::error::RecallError;
+use recall_lib::events::{CreateEventRequest, EventInfo};
+use recall_lib::service;
+
+use common::*;
+
+fn at(date: &str) -> i64 {
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M")
+        .unwrap()
+        .and_utc()
+        .timestamp()
+}
+
+fn slot(start: &str, end: &str) -> TimeSlot {
+    TimeSlot {
+        start: at(start),
+        end: at(end),
+    }
+}
+
+fn create(
+    conn: &rusqlite::Connection,
+    user_id: &str,
+    title: &str,
+    start: &str,
+    end: Option<&str>,
+    all_day: bool,
+    rule: Option<&str>,
+) -> EventInfo {
+    service::events::create(
+        conn,
+        user_id,
+        CreateEventRequest {
+            project_id: None,
+            title: title.to_string(),
+            description: None,
+            start_time: at(start),
+            end_time: end.map(at),
+            location: None,
+            all_day,
+            time_zone: None,
+            recurrence_rule: rule.map(str::to_string),
+            exception_dates: Vec::new(),
+            reminders: Vec::new(),
+        },
+    )
+    .unwrap()
+}
+
+fn request(from: &str, to: &str, minutes: i64, hours: Option<(&str, &str)>) -> FreeBusyRequest {
+    FreeBusyRequest {
+        start_date: at(from),
+        end_date: at(to),
+        duration_minutes: minutes,
+        working_hours: hours.map(|(start, end)| WorkingHours {
+            start: start.to_string(),
+            end: end.to_string(),
+        }),
+        ignore_all_day: false,
+    }
+}
+
+#[test]
+fn overlapping_timed_events_are_reported_as_conflicts() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+
+    create(
+        &conn,
+        &alice.user.id,
+        "Standup",
+        "2025-03-10 09:00",
+        Some("2025-03-10 09:30"),
+        false,
+        Some("FREQ=DAILY;COUNT=5"),
+    );
+    let lunch = create(
+        &conn,
+        &alice.user.id,
+        "Lunch",
+        "2025-03-11 12:00",
+        Some("2025-03-11 13:00"),
+        false,
+        None,
+    );
+    create(
+        &conn,
+        &alice.user.id,
+        "Holiday",
+        "2025-03-11 00:00",
+        None,
+        true,
+        None,
+    );
+    create(
+        &conn,
+        &bob.user.id,
+        "Bob's meeting",
+        "2025-03-11 09:00",
+        Some("2025-03-11 10:00"),
+        false,
+        None,
+    );
+
+    let conflicts = |start: &str, end: &str, exclude: Option<&str>| {
+        service::availability::conflicts(&conn, &alice.user.id, at(start), Some(at(end)), exclude)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.title, e.start_time))
+            .collect::<Vec<_>>()
+    };
+
+    // Only the occurrence on the 11th, not the all-day event or Bob's meeting
+    assert_eq!(
+        conflicts("2025-03-11 09:15", "2025-03-11 10:00", None),
+        vec![("Standup".to_string(), at("2025-03-11 09:00"))]
+    );
+    assert_eq!(
+        conflicts("2025-03-11 11:00", "2025-03-11 14:00", None),
+        vec![("Lunch".to_string(), at("2025-03-11 12:00"))]
+    );
+    // Back to back is fine
+    assert!(conflicts("2025-03-11 13:00", "2025-03-11 14:00", None).is_empty());
+    // Moving Lunch does not clash with itself
+    assert!(conflicts("2025-03-11 12:30", "2025-03-11 13:30", Some(&lunch.id)).is_empty());
+
+    // A point in time clashes with whatever spans it
+    let point =
+        service::availability::conflicts(&conn, &alice.user.id, at("2025-03-12 09:10"), None, None)
+            .unwrap();
+    assert_eq!(point.len(), 1);
+
+    let err = service::availability::conflicts(
+        &conn,
+        &alice.user.id,
+        at("2025-03-11 10:00"),
+        Some(at("2025-03-11 09:00")),
+        None,
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+}
+
+#[test]
+fn free_slots_skip_busy_time_and_all_day_events() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    create(
+        &conn,
+        &alice.user.id,
+        "Review",
+        "2025-03-10 10:00",
+        Some("2025-03-10 11:30"),
+        false,
+        None,
+    );
+    create(
+        &conn,
+        &alice.user.id,
+        "Planning",
+        "2025-03-10 11:00",
+        Some("2025-03-10 12:00"),
+        false,
+        None,
+    );
+    create(
+        &conn,
+        &alice.user.id,
+        "Coffee",
+        "2025-03-10 16:30",
+        Some("2025-03-10 16:45"),
+        false,
+        None,
+    );
+    create(
+        &conn,
+        &alice.user.id,
+        "Holiday",
+        "2025-03-11 00:00",
+        None,
+        true,
+        None,
+    );
+
+    let free_busy = service::availability::free_busy(
+        &conn,
+        &alice.user.id,
+        &request(
+            "2025-03-10 00:00",
+            "2025-03-13 00:00",
+            60,
+            Some(("09:00", "17:00")),
+        ),
+    )
+    .unwrap();
+
+    assert_eq!(
+        free_busy.busy,
+        vec![
+            slot("2025-03-10 10:00", "2025-03-10 12:00"),
+            slot("2025-03-10 16:30", "2025-03-10 16:45"),
+            slot("2025-03-11 00:00", "2025-03-12 00:00"),
+        ]
+    );
+    // The half hour before Coffee and the quarter hour after are too short
+    assert_eq!(
+        free_busy.free,
+        vec![
+            slot("2025-03-10 09:00", "2025-03-10 10:00"),
+            slot("2025-03-10 12:00", "2025-03-10 16:30"),
+            slot("2025-03-12 09:00", "2025-03-12 17:00"),
+        ]
+    );
+
+    // All-day events can be left out, and without working hours the whole range is open
+    let mut anytime = request("2025-03-11 06:00", "2025-03-11 18:00", 30, None);
+    anytime.ignore_all_day = true;
+    let free_busy = service::availability::free_busy(&conn, &alice.user.id, &anytime).unwrap();
+    assert!(free_busy.busy.is_empty());
+    assert_eq!(
+        free_busy.free,
+        vec![slot("2025-03-11 06:00", "2025-03-11 18:00")]
+    );
+}
+
+#[test]
+fn working_hours_follow_the_users_zone() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    service::auth::set_time_zone(&conn, &alice.user.id, "America/New_York").unwrap();
+
+    let free_busy = service::availability::free_busy(
+        &conn,
+        &alice.user.id,
+        &request(
+            "2025-03-12 00:00",
+            "2025-03-13 00:00",
+            60,
+            Some(("09:00", "17:00")),
+        ),
+    )
+    .unwrap();
+
+    // 9 to 5 in New York, which is four hours behind UTC after the March change
+    assert_eq!(
+        free_busy.free,
+        vec![slot("2025-03-12 13:00", "2025-03-12 21:00")]
+    );
+}
+
+#[test]
+fn free_busy_requests_are_validated() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    for bad in [
+        request("2025-03-12 00:00", "2025-03-11 00:00", 60, None),
+        request("2025-03-11 00:00", "2025-03-12 00:00", 0, None),
+        request("2024-01-01 00:00", "2025-06-01 00:00", 60, None),
+        request(
+            "2025-03-11 00:00",
+            "2025-03-12 00:00",
+            60,
+            Some(("17:00", "09:00")),
+        ),
+        request(
+            "2025-03-11 00:00",
+            "2025-03-12 00:00",
+            60,
+            Some(("9am", "5pm")),
+        ),
+    ] {
+        let err = service::availability::free_busy(&conn, &alice.user.id, &bad).unwrap_err();
+        assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    }
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use recall_lib::blobs;
+use recall_lib::service;
+
+use common::*;
+
+#[test]
+fn deleting_an_area_removes_its_projects_and_resources_but_keeps_events() {
+    // Pooled connections get foreign keys from the pool's init, as in the app
+    let db = TestDb::new();
+    let conn = db.conn();
+    let alice = user(&conn, "Alice");
+    let area = area(&conn, &alice.user.id, "Work");
+    let other_area = common::area(&conn, &alice.user.id, "Home");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+    let kept = common::project(&conn, &alice.user.id, &other_area.id, "Garden");
+    let file = resource(&conn, &alice.user.id, &project.id, "Plan", Some(b"plan"));
+    resource(&conn, &alice.user.id, &kept.id, "Seeds", None);
+    let event = event(&conn, &alice.user.id, Some(&project.id), "Kickoff", 1_000);
+
+    service::areas::delete(&conn, &alice.user.id, &area.id).unwrap();
+    service::trash::empty(&conn, &alice.user.id).unwrap();
+
+    let projects = service::projects::list(&conn, &alice.user.id, None).unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].id, kept.id);
+    assert_eq!(count(&conn, "resources"), 1);
+
+    // The event survives without its project
+    let event = service::events::get(&conn, &alice.user.id, &event.id).unwrap();
+    assert_eq!(event.project_id, None);
+
+    // The deleted resource's file went with it
+    let blob = blobs::get_blob(&conn, file.blob_hash.as_deref().unwrap()).unwrap();
+    assert!(blob.is_none());
+}
+
+#[test]
+fn deleting_a_project_unlinks_its_events() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let area = area(&conn, &alice.user.id, "Work");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+    resource(&conn, &alice.user.id, &project.id, "Plan", None);
+    let event = event(&conn, &alice.user.id, Some(&project.id), "Kickoff", 1_000);
+
+    service::projects::delete(&conn, &alice.user.id, &project.id).unwrap();
+    service::trash::empty(&conn, &alice.user.id).unwrap();
+
+    assert_eq!(count(&conn, "projects"), 0);
+    assert_eq!(count(&conn, "resources"), 0);
+    let event = service::events::get(&conn, &alice.user.id, &event.id).unwrap();
+    assert_eq!(event.project_id, None);
+}
+
+#[test]
+fn shared_blobs_outlive_one_of_their_resources() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let area = area(&conn, &alice.user.id, "Work");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+    let first = resource(&conn, &alice.user.id, &project.id, "Copy 1", Some(b"same"));
+    let second = resource(&conn, &alice.user.id, &project.id, "Copy 2", Some(b"same"));
+    assert_eq!(first.blob_hash, second.blob_hash);
+    assert_eq!(count(&conn, "blobs"), 1);
+
+    service::resources::delete(&conn, &alice.user.id, &first.id).unwrap();
+    service::trash::empty(&conn, &alice.user.id).unwrap();
+    assert_eq!(count(&conn, "blobs"), 1);
+
+    service::resources::delete(&conn, &alice.user.id, &second.id).unwrap();
+    service::trash::empty(&conn, &alice.user.id).unwrap();
+    assert_eq!(count(&conn, "blobs"), 0);
+}
+
+#[test]
+fn deleting_a_user_removes_everything_they_own() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let area = area(&conn, &alice.user.id, "Work");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+    resource(&conn, &alice.user.id, &project.id, "Plan", None);
+    event(&conn, &alice.user.id, Some(&project.id), "Kickoff", 1_000);
+    common::area(&conn, &bob.user.id, "Bob's");
+
+    conn.execute("DELETE FROM users WHERE id = ?1", [&alice.user.id])
+        .unwrap();
+
+    assert_eq!(count(&conn, "sessions"), 1);
+    assert_eq!(count(&conn, "areas"), 1);
+    assert_eq!(count(&conn, "projects"), 0);
+    assert_eq!(count(&conn, "resources"), 0);
+    assert_eq!(count(&conn, "events"), 0);
+}
@@ -0,0 +1,206 @@
+// Shared setup for the integration tests: databases with the real schema and fixtures
+// created through the service layer, the same way the commands create them.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use recall_lib::areas::{AreaInfo, CreateAreaRequest};
+use recall_lib::auth::SignupRequest;
+use recall_lib::events::{CreateEventRequest, EventInfo};
+use recall_lib::projects::{CreateProjectRequest, ProjectInfo};
+use recall_lib::resources::{CreateResourceRequest, ResourceInfo};
+use recall_lib::service::auth::SignedIn;
+use recall_lib::{db, migrations, service};
+use rusqlite::Connection;
+
+// A migrated database in memory, for tests that only need one connection
+pub fn memory_db() -> Connection {
+    let mut conn = Connection::open_in_memory().expect("open in-memory database");
+    db::configure_connection(&mut conn).expect("configure connection");
+    migrations::run_migrations(&mut conn).expect("run migrations");
+    conn
+}
+
+// A database at an older schema `version`, built from the migration SQL alone, for
+// seeding rows in the shape an earlier release wrote them before upgrading
+pub fn db_at_version(version: i64) -> Connection {
+    let mut conn = Connection::open_in_memory().expect("open in-memory database");
+    db::configure_connection(&mut conn).expect("configure connection");
+    for migration in migrations::MIGRATIONS
+        .iter()
+        .filter(|m| m.version <= version)
+    {
+        conn.execute_batch(migration.sql)
+            .expect("apply migration sql");
+    }
+    conn.pragma_update(None, "user_version", version)
+        .expect("set schema version");
+    conn
+}
+
+static NEXT_DB: AtomicUsize = AtomicUsize::new(0);
+
+// A migrated database file behind a pool configured like the app's. The file is removed on drop.
+pub struct TestDb {
+    pub pool: Pool<SqliteConnectionManager>,
+    path: PathBuf,
+}
+
+impl TestDb {
+    pub fn new() -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "recall-test-{}-{}.db",
+            std::process::id(),
+            NEXT_DB.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut conn = Connection::open(&path).expect("open test database");
+        db::configure_connection(&mut conn).expect("configure connection");
+        migrations::run_migrations(&mut conn).expect("run migrations");
+        drop(conn);
+
+        let manager = SqliteConnectionManager::file(&path).with_init(db::configure_connection);
+        let pool = Pool::builder()
+            .max_size(4)
+            .build(manager)
+            .expect("build pool");
+        TestDb { pool, path }
+    }
+
+    pub fn conn(&self) -> PooledConnection<SqliteConnectionManager> {
+        self.pool.get().expect("pooled connection")
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut path = self.path.clone().into_os_string();
+            path.push(suffix);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+pub const PASSWORD: &str = "correct horse battery staple";
+
+// Sign up a user named `name` with a session, as the signup command does
+pub fn user(conn: &Connection, name: &str) -> SignedIn {
+    let signed_in = service::auth::signup(
+        conn,
+        SignupRequest {
+            email: format!("{}@example.com", name.to_lowercase()),
+            name: name.to_string(),
+            password: PASSWORD.to_string(),
+        },
+    )
+    .expect("sign up user");
+    // Pin the display zone so results don't depend on the machine running the tests
+    service::auth::set_time_zone(conn, &signed_in.user.id, "UTC").expect("set time zone");
+    signed_in
+}
+
+pub fn area(conn: &Connection, user_id: &str, name: &str) -> AreaInfo {
+    service::areas::create(
+        conn,
+        user_id,
+        CreateAreaRequest {
+            name: name.to_string(),
+            image_url: None,
+        },
+    )
+    .expect("create area")
+}
+
+pub fn project(conn: &Connection, user_id: &str, area_id: &str, title: &str) -> ProjectInfo {
+    service::projects::create(
+        conn,
+        user_id,
+        CreateProjectRequest {
+            area_id: area_id.to_string(),
+            title: title.to_string(),
+            description: None,
+            status: "Inbox".to_string(),
+            priority: None,
+            start_date: None,
+            end_date: None,
+        },
+    )
+    .expect("create project")
+}
+
+// A resource holding `file` as its attachment, or a plain text resource when None
+pub fn resource(
+    conn: &Connection,
+    user_id: &str,
+    project_id: &str,
+    name: &str,
+    file: Option<&[u8]>,
+) -> ResourceInfo {
+    use base64::Engine;
+
+    service::resources::create(
+        conn,
+        user_id,
+        CreateResourceRequest {
+            project_id: Some(project_id.to_string()),
+            area_id: None,
+            name: name.to_string(),
+            content: file.is_none().then(|| format!("Notes for {}", name)),
+            file_data: file.map(|f| base64::engine::general_purpose::STANDARD.encode(f)),
+            file_type: file.map(|_| "text/plain".to_string()),
+            file_size: file.map(|f| f.len() as i64),
+        },
+    )
+    .expect("create resource")
+}
+
+pub fn event(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+    title: &str,
+    start_time: i64,
+) -> EventInfo {
+    recurring_event(conn, user_id, project_id, title, start_time, None)
+}
+
+// An hour-long event repeating by `rule`, or a one-off event when None
+pub fn recurring_event(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+    title: &str,
+    start_time: i64,
+    rule: Option<&str>,
+) -> EventInfo {
+    service::events::create(
+        conn,
+        user_id,
+        CreateEventRequest {
+            project_id: project_id.map(str::to_string),
+            title: title.to_string(),
+            description: None,
+            start_time,
+            end_time: Some(start_time + 3600),
+            location: None,
+            all_day: false,
+            time_zone: None,
+            recurrence_rule: rule.map(str::to_string),
+            exception_dates: Vec::new(),
+            reminders: Vec::new(),
+        },
+    )
+    .expect("create event")
+}
+
+pub fn count(conn: &Connection, table: &str) -> i64 {
+    conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+        row.get(0)
+    })
+    .expect("count rows")
+}
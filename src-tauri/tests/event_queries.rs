@@ -0,0 +1,375 @@
+mod common;
+
+use chrono::NaiveDateTime;
+use recall_lib::events::{CreateEventRequest, EventInfo, EventQuery, OccurrenceRequest};
+use recall_lib::service;
+
+use common::*;
+
+fn at(date: &str) -> i64 {
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M")
+        .unwrap()
+        .and_utc()
+        .timestamp()
+}
+
+fn create(
+    conn: &rusqlite::Connection,
+    user_id: &str,
+    title: &str,
+    start: &str,
+    end: Option<&str>,
+    rule: Option<&str>,
+) -> EventInfo {
+    service::events::create(
+        conn,
+        user_id,
+        CreateEventRequest {
+            project_id: None,
+            title: title.to_string(),
+            description: None,
+            start_time: at(start),
+            end_time: end.map(at),
+            location: None,
+            all_day: false,
+            time_zone: None,
+            recurrence_rule: rule.map(str::to_string),
+            exception_dates: Vec::new(),
+            reminders: Vec::new(),
+        },
+    )
+    .unwrap()
+}
+
+fn titles(events: &[EventInfo]) -> Vec<&str> {
+    events.iter().map(|e| e.title.as_str()).collect()
+}
+
+#[test]
+fn events_overlapping_the_window_are_listed() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    create(
+        &conn,
+        &alice.user.id,
+        "Conference",
+        "2025-03-09 09:00",
+        Some("2025-03-11 17:00"),
+        None,
+    );
+    create(
+        &conn,
+        &alice.user.id,
+        "Dinner",
+        "2025-03-09 19:00",
+        Some("2025-03-09 22:00"),
+        None,
+    );
+    create(
+        &conn,
+        &alice.user.id,
+        "Reminder",
+        "2025-03-10 00:00",
+        None,
+        None,
+    );
+    create(
+        &conn,
+        &alice.user.id,
+        "Flight",
+        "2025-03-10 23:00",
+        Some("2025-03-11 06:00"),
+        None,
+    );
+
+    let today = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-03-10 00:00")),
+        Some(at("2025-03-10 23:59")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(titles(&today), ["Conference", "Reminder", "Flight"]);
+
+    // With only a lower bound, anything still running counts
+    let from_today = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-03-10 00:00")),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(titles(&from_today), ["Conference", "Reminder", "Flight"]);
+}
+
+#[test]
+fn occurrences_running_into_the_window_are_listed() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    // Three days every week, Friday to Sunday
+    let retreat = create(
+        &conn,
+        &alice.user.id,
+        "Retreat",
+        "2025-03-07 18:00",
+        Some("2025-03-09 16:00"),
+        Some("FREQ=WEEKLY;COUNT=3"),
+    );
+
+    let sunday = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-03-16 00:00")),
+        Some(at("2025-03-16 23:59")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(sunday.len(), 1);
+    assert_eq!(sunday[0].start_time, at("2025-03-14 18:00"));
+    assert_eq!(sunday[0].end_time, Some(at("2025-03-16 16:00")));
+
+    // The last occurrence ends on the 23rd, so the 24th is empty
+    let monday = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-03-24 00:00")),
+        Some(at("2025-03-24 23:59")),
+        None,
+    )
+    .unwrap();
+    assert!(monday.is_empty());
+
+    // An occurrence moved to overlap a window is found through its override
+    service::events::update_occurrence(
+        &conn,
+        &alice.user.id,
+        &retreat.id,
+        at("2025-03-21 18:00"),
+        recall_lib::events::OccurrenceRequest {
+            title: "Long retreat".to_string(),
+            description: None,
+            start_time: at("2025-03-21 18:00"),
+            end_time: Some(at("2025-03-25 12:00")),
+            location: None,
+            all_day: false,
+            time_zone: None,
+        },
+    )
+    .unwrap();
+    let monday = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-03-24 00:00")),
+        Some(at("2025-03-24 23:59")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(titles(&monday), ["Long retreat"]);
+}
+
+#[test]
+fn filters_combine() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let work = area(&conn, &alice.user.id, "Work");
+    let launch = project(&conn, &alice.user.id, &work.id, "Launch");
+    let hiring = project(&conn, &alice.user.id, &work.id, "Hiring");
+    let garden = project(&conn, &alice.user.id, &work.id, "Garden");
+
+    event(
+        &conn,
+        &alice.user.id,
+        Some(&launch.id),
+        "Launch review",
+        at("2025-03-10 09:00"),
+    );
+    event(
+        &conn,
+        &alice.user.id,
+        Some(&hiring.id),
+        "Interview",
+        at("2025-03-10 11:00"),
+    );
+    event(
+        &conn,
+        &alice.user.id,
+        Some(&garden.id),
+        "Plant roses",
+        at("2025-03-10 15:00"),
+    );
+    service::events::create(
+        &conn,
+        &alice.user.id,
+        CreateEventRequest {
+            project_id: Some(launch.id.clone()),
+            title: "Launch day".to_string(),
+            description: Some("Everyone in the office".to_string()),
+            start_time: at("2025-03-12 00:00"),
+            end_time: None,
+            location: None,
+            all_day: true,
+            time_zone: None,
+            recurrence_rule: None,
+            exception_dates: Vec::new(),
+            reminders: Vec::new(),
+        },
+    )
+    .unwrap();
+
+    let projects = EventQuery {
+        project_ids: vec![launch.id.clone(), hiring.id.clone()],
+        ..EventQuery::default()
+    };
+    let work_events = service::events::query(&conn, &alice.user.id, &projects).unwrap();
+    assert_eq!(
+        titles(&work_events),
+        ["Launch review", "Interview", "Launch day"]
+    );
+
+    let text = EventQuery {
+        text: Some("launch".to_string()),
+        ..projects.clone()
+    };
+    let launches = service::events::query(&conn, &alice.user.id, &text).unwrap();
+    assert_eq!(titles(&launches), ["Launch review", "Launch day"]);
+
+    let all_day = EventQuery {
+        all_day_only: true,
+        ..text.clone()
+    };
+    let launch_days = service::events::query(&conn, &alice.user.id, &all_day).unwrap();
+    assert_eq!(titles(&launch_days), ["Launch day"]);
+
+    let page = EventQuery {
+        start_date: Some(at("2025-03-01 00:00")),
+        end_date: Some(at("2025-03-31 00:00")),
+        limit: Some(2),
+        offset: Some(1),
+        ..EventQuery::default()
+    };
+    let second_page = service::events::query(&conn, &alice.user.id, &page).unwrap();
+    assert_eq!(titles(&second_page), ["Interview", "Plant roses"]);
+
+    // Other users' events never match, whatever the filters
+    let bob = user(&conn, "Bob");
+    let theirs = service::events::query(&conn, &bob.user.id, &projects).unwrap();
+    assert!(theirs.is_empty());
+}
+
+#[test]
+fn window_queries_use_the_range_index() {
+    let conn = memory_db();
+    let plan: Vec<String> = conn
+        .prepare(
+            "EXPLAIN QUERY PLAN SELECT e.start_time FROM events e
+             WHERE e.user_id = ?1 AND e.start_time <= ?2
+             ORDER BY e.start_time LIMIT 1 OFFSET 10",
+        )
+        .unwrap()
+        .query_map(rusqlite::params!["u", 0], |row| row.get(3))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert!(
+        plan.iter()
+            .any(|step| step.contains("idx_events_user_start")),
+        "{plan:?}"
+    );
+    // Pages come straight off the index rather than sorting every match
+    assert!(
+        !plan.iter().any(|step| step.contains("TEMP B-TREE")),
+        "{plan:?}"
+    );
+}
+
+#[test]
+fn pages_match_the_unpaged_order() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    // All-day events sort by the viewer's midnight, nine hours before their stored reading
+    service::auth::set_time_zone(&conn, &alice.user.id, "Asia/Tokyo").unwrap();
+
+    for day in 1..=6 {
+        create(
+            &conn,
+            &alice.user.id,
+            &format!("Call {day}"),
+            &format!("2025-05-0{day} 20:00"),
+            None,
+            None,
+        );
+    }
+    service::events::create(
+        &conn,
+        &alice.user.id,
+        CreateEventRequest {
+            project_id: None,
+            title: "Holiday".to_string(),
+            description: None,
+            start_time: at("2025-05-03 00:00"),
+            end_time: None,
+            location: None,
+            all_day: true,
+            time_zone: None,
+            recurrence_rule: None,
+            exception_dates: Vec::new(),
+            reminders: Vec::new(),
+        },
+    )
+    .unwrap();
+    let standup = create(
+        &conn,
+        &alice.user.id,
+        "Standup",
+        "2025-05-01 09:00",
+        Some("2025-05-01 09:15"),
+        Some("FREQ=DAILY"),
+    );
+    // Moved from past any page's share of the series to the front of the window
+    service::events::update_occurrence(
+        &conn,
+        &alice.user.id,
+        &standup.id,
+        at("2025-05-20 09:00"),
+        OccurrenceRequest {
+            title: "Kickoff".to_string(),
+            description: None,
+            start_time: at("2025-05-01 06:00"),
+            end_time: None,
+            location: None,
+            all_day: false,
+            time_zone: None,
+        },
+    )
+    .unwrap();
+
+    let may = EventQuery {
+        start_date: Some(at("2025-05-01 00:00")),
+        end_date: Some(at("2025-05-31 23:59")),
+        ..EventQuery::default()
+    };
+    let all = service::events::query(&conn, &alice.user.id, &may).unwrap();
+    assert_eq!(all.len(), 6 + 1 + 31 - 1 + 1);
+    assert_eq!(all[0].title, "Kickoff");
+
+    let mut paged = Vec::new();
+    for offset in (0..all.len()).step_by(4) {
+        let page = EventQuery {
+            limit: Some(4),
+            offset: Some(offset as i64),
+            ..may.clone()
+        };
+        let page = service::events::query(&conn, &alice.user.id, &page).unwrap();
+        assert!(page.len() <= 4);
+        paged.extend(page);
+    }
+    assert_eq!(
+        paged.iter().map(|e| e.start_time).collect::<Vec<_>>(),
+        all.iter().map(|e| e.start_time).collect::<Vec<_>>()
+    );
+    assert_eq!(titles(&paged), titles(&all));
+}
@@ -0,0 +1,394 @@
+mod common;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
+use recall_lib::events::{CalendarImportSummary, CreateEventRequest, OccurrenceRequest};
+use recall_lib::ical::{self, IcsEvent, IcsTime};
+use recall_lib::service;
+
+use common::*;
+
+fn at(date: &str) -> i64 {
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M")
+        .unwrap()
+        .and_utc()
+        .timestamp()
+}
+
+const OUTLOOK_EXPORT: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example//Calendar//EN\r
+BEGIN:VTIMEZONE\r
+TZID:Europe/Paris\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:planning-1@example.com\r
+DTSTAMP:20250101T000000Z\r
+DTSTART;TZID=Europe/Paris:20250113T100000\r
+DURATION:PT1H30M\r
+RRULE:FREQ=WEEKLY;COUNT=4\r
+EXDATE;TZID=Europe/Paris:20250120T100000\r
+SUMMARY:Planning\\, weekly\r
+DESCRIPTION:Bring the roadmap\\nand the budget\r
+LOCATION:Room 4\\; second floor\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+DESCRIPTION:Reminder\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:planning-1@example.com\r
+RECURRENCE-ID;TZID=Europe/Paris:20250127T100000\r
+DTSTART;TZID=Europe/Paris:20250127T140000\r
+DTEND;TZID=Europe/Paris:20250127T150000\r
+SUMMARY:Planning (moved)\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:holiday-2@example.com\r
+DTSTART;VALUE=DATE:20250501\r
+DTEND;VALUE=DATE:20250503\r
+SUMMARY:Long weekend with a summary that is far too long to fit on a single\r
+  line\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+#[test]
+fn written_lines_are_escaped_and_folded() {
+    let mut event = IcsEvent::new("a@recall", IcsTime::Utc(at("2025-01-01 09:00")));
+    event.summary = Some("Lunch; then coffee, maybe".to_string());
+    event.description = Some(format!("Line one\nLine two \\ {}", "é".repeat(80)));
+    let text = ical::write_calendar(&[event.clone()]);
+
+    assert!(text.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(text.ends_with("END:VCALENDAR\r\n"));
+    assert!(text.contains("SUMMARY:Lunch\\; then coffee\\, maybe\r\n"));
+    assert!(text.contains("DTSTART:20250101T090000Z\r\n"));
+    for line in text.split("\r\n") {
+        assert!(line.len() <= 75, "{line:?} is {} octets", line.len());
+    }
+
+    let parsed = ical::parse_calendar(&text).unwrap();
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[0].summary, event.summary);
+    assert_eq!(parsed[0].description, event.description);
+    assert_eq!(parsed[0].start, event.start);
+}
+
+#[test]
+fn parses_zones_durations_and_nested_components() {
+    let events = ical::parse_calendar(OUTLOOK_EXPORT).unwrap();
+    assert_eq!(events.len(), 3);
+
+    let planning = &events[0];
+    assert_eq!(planning.summary.as_deref(), Some("Planning, weekly"));
+    assert_eq!(
+        planning.description.as_deref(),
+        Some("Bring the roadmap\nand the budget")
+    );
+    assert_eq!(planning.location.as_deref(), Some("Room 4; second floor"));
+    // 10:00 in Paris is 09:00 UTC in winter
+    assert_eq!(planning.start.timestamp(Tz::UTC), at("2025-01-13 09:00"));
+    assert_eq!(
+        planning.end.map(|end| end.timestamp(Tz::UTC)),
+        Some(at("2025-01-13 10:30"))
+    );
+    assert_eq!(planning.exdates.len(), 1);
+
+    let moved = &events[1];
+    assert_eq!(
+        moved.recurrence_id.map(|t| t.timestamp(Tz::UTC)),
+        Some(at("2025-01-27 09:00"))
+    );
+
+    let holiday = &events[2];
+    assert_eq!(
+        holiday.summary.as_deref(),
+        Some("Long weekend with a summary that is far too long to fit on a single line")
+    );
+    assert_eq!(
+        holiday.start,
+        IcsTime::Date(NaiveDate::from_ymd_opt(2025, 5, 1).unwrap())
+    );
+
+    let err = ical::parse_calendar("BEGIN:VCARD\r\nEND:VCARD\r\n").unwrap_err();
+    assert!(matches!(err, recall_lib::error::RecallError::Validation(_)));
+}
+
+#[test]
+fn importing_twice_updates_instead_of_duplicating() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    let summary = service::calendar::import(&conn, &alice.user.id, OUTLOOK_EXPORT, None).unwrap();
+    assert_eq!(
+        summary,
+        CalendarImportSummary {
+            created: 2,
+            updated: 1,
+            skipped: 0,
+        }
+    );
+
+    let january = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-01-01 00:00")),
+        Some(at("2025-01-31 23:59")),
+        None,
+    )
+    .unwrap();
+    let starts: Vec<i64> = january.iter().map(|e| e.start_time).collect();
+    // The 20th is excluded and the 27th moved to the afternoon
+    assert_eq!(starts, vec![at("2025-01-13 09:00"), at("2025-01-27 13:00")]);
+    assert_eq!(january[1].title, "Planning (moved)");
+
+    let holiday = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-05-01 00:00")),
+        Some(at("2025-05-31 00:00")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(holiday.len(), 1);
+    assert!(holiday[0].all_day);
+
+    let renamed = OUTLOOK_EXPORT.replace("SUMMARY:Planning\\, weekly", "SUMMARY:Planning");
+    let summary = service::calendar::import(&conn, &alice.user.id, &renamed, None).unwrap();
+    assert_eq!(summary.created, 0);
+    assert_eq!(summary.updated, 3);
+    assert_eq!(count(&conn, "events"), 2);
+    let events = service::events::list(&conn, &alice.user.id, None, None, None).unwrap();
+    assert!(events.iter().any(|e| e.title == "Planning"));
+
+    // UIDs are per user: someone else importing the same file gets their own copies
+    let bob = user(&conn, "Bob");
+    let summary = service::calendar::import(&conn, &bob.user.id, OUTLOOK_EXPORT, None).unwrap();
+    assert_eq!(summary.created, 2);
+    assert_eq!(count(&conn, "events"), 4);
+}
+
+#[test]
+fn export_round_trips_through_import() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let area = area(&conn, &alice.user.id, "Work");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+
+    event(
+        &conn,
+        &alice.user.id,
+        Some(&project.id),
+        "Kickoff, part 1",
+        at("2025-03-03 09:00"),
+    );
+    let series = service::events::create(
+        &conn,
+        &alice.user.id,
+        CreateEventRequest {
+            project_id: None,
+            title: "Standup".to_string(),
+            description: Some("Daily; short".to_string()),
+            start_time: at("2025-03-03 08:30"),
+            end_time: Some(at("2025-03-03 08:45")),
+            location: None,
+            all_day: false,
+            time_zone: None,
+            recurrence_rule: Some("FREQ=DAILY;COUNT=5".to_string()),
+            exception_dates: vec![at("2025-03-04 08:30")],
+            reminders: Vec::new(),
+        },
+    )
+    .unwrap();
+    service::events::update_occurrence(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        at("2025-03-05 08:30"),
+        OccurrenceRequest {
+            title: "Standup (late)".to_string(),
+            description: None,
+            start_time: at("2025-03-05 10:00"),
+            end_time: Some(at("2025-03-05 10:15")),
+            location: None,
+            all_day: false,
+            time_zone: None,
+        },
+    )
+    .unwrap();
+    event(&conn, &alice.user.id, None, "Later", at("2025-06-01 09:00"));
+
+    let text = service::calendar::export(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-03-01 00:00")),
+        Some(at("2025-03-31 00:00")),
+        None,
+    )
+    .unwrap();
+    assert!(!text.contains("SUMMARY:Later"));
+    assert!(text.contains("RRULE:FREQ=DAILY;COUNT=5\r\n"));
+    assert!(text.contains("EXDATE:20250304T083000Z\r\n"));
+    assert!(text.contains("RECURRENCE-ID:20250305T083000Z\r\n"));
+    assert!(text.contains("CATEGORIES:Launch\r\n"));
+    assert!(text.contains(&format!("UID:{}@recall\r\n", series.id)));
+
+    let by_project =
+        service::calendar::export(&conn, &alice.user.id, None, None, Some(&project.id)).unwrap();
+    assert_eq!(by_project.matches("BEGIN:VEVENT").count(), 1);
+
+    // Importing our own export back matches events by their exported UIDs
+    let summary = service::calendar::import(&conn, &alice.user.id, &text, None).unwrap();
+    assert_eq!(summary.created, 0);
+    assert_eq!(count(&conn, "events"), 3);
+
+    let bob = user(&conn, "Bob");
+    service::calendar::import(&conn, &bob.user.id, &text, None).unwrap();
+    let window = (Some(at("2025-03-01 00:00")), Some(at("2025-03-31 00:00")));
+    let original = service::events::list(&conn, &alice.user.id, window.0, window.1, None).unwrap();
+    let copied = service::events::list(&conn, &bob.user.id, window.0, window.1, None).unwrap();
+    let shape = |events: &[recall_lib::events::EventInfo]| {
+        events
+            .iter()
+            .map(|e| {
+                (
+                    e.title.clone(),
+                    e.description.clone(),
+                    e.start_time,
+                    e.end_time,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(shape(&original), shape(&copied));
+    assert_eq!(copied.len(), 5);
+}
+
+#[test]
+fn unsupported_events_are_skipped() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let text = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:hourly@example.com\r
+DTSTART:20250101T090000Z\r
+RRULE:FREQ=HOURLY\r
+SUMMARY:Too often\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:orphan@example.com\r
+RECURRENCE-ID:20250101T090000Z\r
+DTSTART:20250101T100000Z\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:untitled@example.com\r
+DTSTART:20250102T090000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+    let summary = service::calendar::import(&conn, &alice.user.id, text, None).unwrap();
+    assert_eq!(
+        summary,
+        CalendarImportSummary {
+            created: 1,
+            updated: 0,
+            skipped: 2,
+        }
+    );
+    let events = service::events::list(&conn, &alice.user.id, None, None, None).unwrap();
+    assert_eq!(events[0].title, "Untitled event");
+}
+
+#[test]
+fn out_of_range_durations_skip_the_event() {
+    let text = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:forever@example.com\r
+DTSTART:20250101T090000Z\r
+DURATION:P99999999999999W\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:ages@example.com\r
+DTSTART;VALUE=DATE:20250101\r
+DURATION:P9999999999D\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:lunch@example.com\r
+DTSTART:20250101T120000Z\r
+DURATION:PT1H\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+    let events = ical::parse_calendar(text).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].uid, "lunch@example.com");
+}
+
+#[test]
+fn categories_with_commas_round_trip() {
+    let mut event = IcsEvent::new("a@recall", IcsTime::Utc(at("2025-01-01 09:00")));
+    event.categories = vec!["Launch, phase 1".to_string(), "Q3".to_string()];
+    let text = ical::write_calendar(&[event.clone()]);
+    assert!(text.contains("CATEGORIES:Launch\\, phase 1,Q3\r\n"));
+
+    let parsed = ical::parse_calendar(&text).unwrap();
+    assert_eq!(parsed[0].categories, event.categories);
+}
+
+#[test]
+fn a_failed_import_leaves_nothing_behind() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    // The holiday is stored after the planning series, then fails
+    conn.execute_batch(
+        "CREATE TRIGGER no_long_weekends BEFORE INSERT ON events WHEN NEW.all_day = 1
+         BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+    )
+    .unwrap();
+    service::calendar::import(&conn, &alice.user.id, OUTLOOK_EXPORT, None).unwrap_err();
+    assert_eq!(count(&conn, "events"), 0);
+    assert_eq!(count(&conn, "event_exceptions"), 0);
+}
+
+#[test]
+fn a_skipped_reimport_leaves_the_event_in_the_trash() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let text = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:review@example.com\r
+DTSTART:20250101T090000Z\r
+SUMMARY:Review\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+    service::calendar::import(&conn, &alice.user.id, text, None).unwrap();
+    let review = service::events::list(&conn, &alice.user.id, None, None, None).unwrap();
+    service::events::delete(&conn, &alice.user.id, &review[0].id).unwrap();
+
+    // An hourly rule cannot be stored, so the update is skipped
+    let hourly = text.replace(
+        "SUMMARY:Review\r\n",
+        "SUMMARY:Review\r\nRRULE:FREQ=HOURLY\r\n",
+    );
+    let summary = service::calendar::import(&conn, &alice.user.id, &hourly, None).unwrap();
+    assert_eq!(summary.skipped, 1);
+    assert!(
+        service::events::list(&conn, &alice.user.id, None, None, None)
+            .unwrap()
+            .is_empty()
+    );
+
+    // A clean re-import restores it
+    let summary = service::calendar::import(&conn, &alice.user.id, text, None).unwrap();
+    assert_eq!(summary.updated, 1);
+    assert_eq!(
+        service::events::list(&conn, &alice.user.id, None, None, None)
+            .unwrap()
+            .len(),
+        1
+    );
+}
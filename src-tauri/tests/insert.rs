@@ -0,0 +1,83 @@
+mod common;
+
+use recall_lib::areas::Area;
+use recall_lib::error::RecallError;
+use recall_lib::try_insert_thing;
+
+use common::*;
+
+fn area_with_id(user_id: &str, id: Option<&str>, name: &str) -> Area {
+    Area {
+        id: id.map(str::to_string),
+        user_id: user_id.to_string(),
+        name: name.to_string(),
+        image_url: None,
+        archived_at: None,
+        deleted_at: None,
+        created_at: 0,
+        updated_at: 0,
+    }
+}
+
+#[test]
+fn missing_or_short_ids_are_generated() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    let mut area = area_with_id(&alice.user.id, None, "Work");
+    let id = try_insert_thing(&mut area, &conn).unwrap();
+    assert_eq!(id.len(), 8);
+    assert_eq!(area.id.as_deref(), Some(id.as_str()));
+
+    let mut area = area_with_id(&alice.user.id, Some("ABC"), "Home");
+    let id = try_insert_thing(&mut area, &conn).unwrap();
+    assert_ne!(id, "ABC");
+    assert_eq!(id.len(), 8);
+}
+
+#[test]
+fn id_collision_is_retried_with_a_fresh_id() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    let mut first = area_with_id(&alice.user.id, Some("TAKEN123"), "Work");
+    assert_eq!(try_insert_thing(&mut first, &conn).unwrap(), "TAKEN123");
+
+    let mut second = area_with_id(&alice.user.id, Some("TAKEN123"), "Home");
+    let id = try_insert_thing(&mut second, &conn).unwrap();
+    assert_ne!(id, "TAKEN123");
+    assert_eq!(count(&conn, "areas"), 2);
+}
+
+#[test]
+fn duplicate_values_are_a_conflict_naming_the_field() {
+    let conn = memory_db();
+    user(&conn, "Alice");
+
+    // The email stays taken whatever id is drawn, so it is not retried
+    let mut duplicate = recall_lib::auth::User {
+        id: None,
+        email: "alice@example.com".to_string(),
+        name: "Alice again".to_string(),
+        password_hash: "x".to_string(),
+        time_zone: None,
+        auto_archive_days: None,
+        trash_retention_days: None,
+        created_at: 0,
+        updated_at: 0,
+    };
+    let err = try_insert_thing(&mut duplicate, &conn).unwrap_err();
+    assert!(matches!(err, RecallError::Conflict(_)), "{err}");
+    assert_eq!(err.to_string(), "User with this email already exists");
+    assert_eq!(count(&conn, "users"), 1);
+}
+
+#[test]
+fn other_insert_errors_are_not_retried() {
+    let conn = memory_db();
+
+    // No such user, so the foreign key rejects the row outright
+    let mut orphan = area_with_id("NOBODY00", None, "Work");
+    let err = try_insert_thing(&mut orphan, &conn).unwrap_err();
+    assert!(matches!(err, RecallError::Storage(_)), "{err}");
+}
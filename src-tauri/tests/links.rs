@@ -0,0 +1,207 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::projects::UpdateProjectRequest;
+use recall_lib::resources::UpdateResourceRequest;
+use recall_lib::service;
+use recall_lib::service::Owned;
+use rusqlite::Connection;
+
+use common::*;
+
+fn save(conn: &Connection, user_id: &str, id: &str, name: &str, content: &str) {
+    service::resources::update(
+        conn,
+        user_id,
+        id,
+        UpdateResourceRequest {
+            name: name.to_string(),
+            content: Some(content.to_string()),
+            file_data: None,
+            file_type: None,
+            file_size: None,
+            remove_file: false,
+        },
+    )
+    .unwrap();
+}
+
+// Names of the linking resources, alphabetically
+fn backlinks(conn: &Connection, user_id: &str, kind: Owned, id: &str) -> Vec<String> {
+    let mut names: Vec<_> = service::links::backlinks(conn, user_id, kind, id)
+        .unwrap()
+        .into_iter()
+        .map(|backlink| backlink.name)
+        .collect();
+    names.sort();
+    names
+}
+
+// A TipTap document with one paragraph per line
+fn document(lines: &[&str]) -> String {
+    let paragraphs: Vec<_> = lines
+        .iter()
+        .map(|line| {
+            serde_json::json!({
+                "type": "paragraph",
+                "content": [{ "type": "text", "text": line }],
+            })
+        })
+        .collect();
+    serde_json::json!({ "type": "doc", "content": paragraphs }).to_string()
+}
+
+#[test]
+fn links_of_every_kind_show_up_as_backlinks() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+    let budget = resource(&conn, id, &launch.id, "Budget", None);
+    let review = event(&conn, id, None, "Review", 1_700_000_000);
+
+    save(
+        &conn,
+        id,
+        &plan.id,
+        "Plan",
+        &document(&[
+            "See [[budget]] and [[Plan]] for [[Project: Launch]]",
+            "Discussed at [[event:Review]] in [[area:Work]], not in [[Later]]",
+        ]),
+    );
+    save(
+        &conn,
+        id,
+        &budget.id,
+        "Budget",
+        "Plain text linking [[project:launch]]",
+    );
+
+    assert_eq!(backlinks(&conn, id, Owned::Resource, &budget.id), ["Plan"]);
+    // A document linking to itself is not its own backlink
+    assert!(backlinks(&conn, id, Owned::Resource, &plan.id).is_empty());
+    assert_eq!(
+        backlinks(&conn, id, Owned::Project, &launch.id),
+        ["Budget", "Plan"]
+    );
+    assert_eq!(backlinks(&conn, id, Owned::Event, &review.id), ["Plan"]);
+    assert_eq!(backlinks(&conn, id, Owned::Area, &work.id), ["Plan"]);
+
+    // A link to something that does not exist yet counts once it does
+    let later = resource(&conn, id, &launch.id, "Later", None);
+    assert_eq!(backlinks(&conn, id, Owned::Resource, &later.id), ["Plan"]);
+
+    // Removing a link drops the backlink, and documents in the trash are left out
+    save(&conn, id, &budget.id, "Budget", "No links");
+    assert_eq!(backlinks(&conn, id, Owned::Project, &launch.id), ["Plan"]);
+    service::resources::delete(&conn, id, &plan.id).unwrap();
+    assert!(backlinks(&conn, id, Owned::Project, &launch.id).is_empty());
+}
+
+#[test]
+fn renaming_a_target_rewrites_the_links_to_it() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+    let budget = resource(&conn, id, &launch.id, "Budget", None);
+    let notes = resource(&conn, id, &launch.id, "Notes", None);
+
+    save(
+        &conn,
+        id,
+        &plan.id,
+        "Plan",
+        &document(&[
+            "Costs are in [[Budget]] for [[project:Launch]]",
+            "Unrelated [[Notes]]",
+        ]),
+    );
+    save(
+        &conn,
+        id,
+        &notes.id,
+        "Notes",
+        "Also [[budget]], but not Budget",
+    );
+
+    save(&conn, id, &budget.id, "Costs", "Notes for Budget");
+    let plan_now = service::resources::get(&conn, id, &plan.id).unwrap();
+    assert_eq!(
+        plan_now.content.unwrap(),
+        document(&[
+            "Costs are in [[Costs]] for [[project:Launch]]",
+            "Unrelated [[Notes]]"
+        ])
+    );
+    let notes_now = service::resources::get(&conn, id, &notes.id).unwrap();
+    assert_eq!(notes_now.content.unwrap(), "Also [[Costs]], but not Budget");
+    assert_eq!(
+        backlinks(&conn, id, Owned::Resource, &budget.id),
+        ["Notes", "Plan"]
+    );
+
+    service::projects::update(
+        &conn,
+        id,
+        &launch.id,
+        UpdateProjectRequest {
+            area_id: work.id.clone(),
+            title: " Relaunch ".to_string(),
+            description: None,
+            status: "Inbox".to_string(),
+            priority: None,
+            start_date: None,
+            end_date: None,
+        },
+    )
+    .unwrap();
+    let plan_now = service::resources::get(&conn, id, &plan.id).unwrap();
+    assert_eq!(
+        plan_now.content.unwrap(),
+        document(&[
+            "Costs are in [[Costs]] for [[project:Relaunch]]",
+            "Unrelated [[Notes]]"
+        ])
+    );
+    assert_eq!(backlinks(&conn, id, Owned::Project, &launch.id), ["Plan"]);
+
+    // Purging a target leaves the links in place but pointing nowhere
+    service::resources::delete(&conn, id, &budget.id).unwrap();
+    service::trash::empty(&conn, id).unwrap();
+    let replacement = resource(&conn, id, &launch.id, "Costs", None);
+    assert_eq!(
+        backlinks(&conn, id, Owned::Resource, &replacement.id),
+        ["Notes", "Plan"]
+    );
+}
+
+#[test]
+fn backlinks_are_private() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+
+    let bobs_area = area(&conn, &bob.user.id, "Bob's");
+    let bobs_project = project(&conn, &bob.user.id, &bobs_area.id, "Launch");
+    let bobs = resource(&conn, &bob.user.id, &bobs_project.id, "Mine", None);
+    save(&conn, &bob.user.id, &bobs.id, "Mine", "[[project:Launch]]");
+
+    // Bob's link is to his own project of the same name
+    assert!(backlinks(&conn, id, Owned::Project, &launch.id).is_empty());
+    assert_eq!(
+        backlinks(&conn, &bob.user.id, Owned::Project, &bobs_project.id),
+        ["Mine"]
+    );
+    let err =
+        service::links::backlinks(&conn, &bob.user.id, Owned::Project, &launch.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+}
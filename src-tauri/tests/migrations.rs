@@ -0,0 +1,200 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use recall_lib::migrations;
+use rusqlite::Connection;
+
+use common::*;
+
+#[test]
+fn file_data_moves_into_the_blob_store() {
+    let mut conn = db_at_version(2);
+    conn.execute_batch(
+        "INSERT INTO users (id, email, name, password_hash, created_at, updated_at)
+             VALUES ('u1', 'old@example.com', 'Old', 'x', 0, 0);
+         INSERT INTO areas (id, user_id, name, created_at, updated_at)
+             VALUES ('a1', 'u1', 'Work', 0, 0);
+         INSERT INTO projects (id, user_id, area_id, title, created_at, updated_at)
+             VALUES ('p1', 'u1', 'a1', 'Launch', 0, 0);
+         -- 'aGVsbG8=' is base64 for 'hello'; the last payload was never base64
+         INSERT INTO resources (id, user_id, project_id, name, file_data, file_size, created_at, updated_at)
+             VALUES ('r1', 'u1', 'p1', 'One', 'aGVsbG8=', 99, 0, 0),
+                    ('r2', 'u1', 'p1', 'Two', 'aGVsbG8=', 99, 0, 0),
+                    ('r3', 'u1', 'p1', 'Three', 'not base64!', NULL, 0, 0);",
+    )
+    .unwrap();
+
+    migrations::run_migrations(&mut conn).unwrap();
+    assert_eq!(
+        migrations::current_version(&conn).unwrap(),
+        migrations::latest_version()
+    );
+
+    let blob = |id: &str| -> (Vec<u8>, i64, i64) {
+        conn.query_row(
+            "SELECT b.data, r.file_size, b.ref_count FROM resources r
+             JOIN blobs b ON b.hash = r.blob_hash WHERE r.id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap()
+    };
+    assert_eq!(blob("r1"), (b"hello".to_vec(), 5, 2));
+    assert_eq!(blob("r2"), (b"hello".to_vec(), 5, 2));
+    assert_eq!(blob("r3"), (b"not base64!".to_vec(), 11, 1));
+    assert_eq!(count(&conn, "blobs"), 2);
+}
+
+#[test]
+fn links_in_existing_documents_are_indexed() {
+    let mut conn = db_at_version(15);
+    conn.execute_batch(
+        r#"INSERT INTO users (id, email, name, password_hash, created_at, updated_at)
+               VALUES ('u1', 'old@example.com', 'Old', 'x', 0, 0);
+           INSERT INTO areas (id, user_id, name, created_at, updated_at)
+               VALUES ('a1', 'u1', 'Work', 0, 0);
+           INSERT INTO projects (id, user_id, area_id, title, created_at, updated_at, deleted_at)
+               VALUES ('p1', 'u1', 'a1', 'Launch', 0, 0, NULL),
+                      ('p2', 'u1', 'a1', 'Dropped', 0, 0, 5);
+           INSERT INTO resources (id, user_id, project_id, name, content, created_at, updated_at)
+               VALUES ('r1', 'u1', 'p1', 'Plan',
+                       '{"type":"doc","content":[{"type":"paragraph","content":[{"type":"text","text":"See [[Budget]], [[project:Launch]] and [[Plan]]"}]}]}',
+                       1, 1),
+                      ('r2', 'u1', 'p1', 'Budget', 'Back to [[area:Work]], not [[Leftovers]]', 2, 2),
+                      ('r3', 'u1', 'p2', 'Leftovers', NULL, 3, 3);"#,
+    )
+    .unwrap();
+
+    migrations::run_migrations(&mut conn).unwrap();
+    assert_eq!(
+        migrations::current_version(&conn).unwrap(),
+        migrations::latest_version()
+    );
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT source_id, target_kind, target_title, target_id FROM resource_links
+             ORDER BY source_id, target_kind, target_title",
+        )
+        .unwrap();
+    let links = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let link = |source: &str, kind: &str, title: &str, target: Option<&str>| {
+        (
+            source.to_string(),
+            kind.to_string(),
+            title.to_string(),
+            target.map(str::to_string),
+        )
+    };
+    // The self-link is dropped and the resource under the trashed project stays unresolved
+    assert_eq!(
+        links,
+        vec![
+            link("r1", "project", "Launch", Some("p1")),
+            link("r1", "resource", "Budget", Some("r2")),
+            link("r2", "area", "Work", Some("a1")),
+            link("r2", "resource", "Leftovers", None),
+        ]
+    );
+}
+
+// Tables with their columns, and indexes and triggers with what they are on, skipping
+// SQLite's own objects and the shadow tables behind full-text indexes. Columns are sorted
+// since ALTER TABLE appends where the sql/ files keep related ones together.
+fn schema(conn: &Connection) -> BTreeMap<String, Vec<String>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT type, name, tbl_name FROM sqlite_master
+             WHERE name NOT LIKE 'sqlite_%' AND name NOT GLOB '*_fts_*'",
+        )
+        .unwrap();
+    let objects: Vec<(String, String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let mut schema = BTreeMap::new();
+    for (kind, name, table) in objects {
+        let details = match kind.as_str() {
+            "table" => {
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1) ORDER BY name",
+                    )
+                    .unwrap();
+                stmt.query_map([&name], |row| {
+                    Ok(format!(
+                        "{} {} notnull={} default={:?} pk={}",
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, i64>(4)?,
+                    ))
+                })
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+            }
+            "index" => {
+                let mut stmt = conn
+                    .prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")
+                    .unwrap();
+                let columns: Vec<Option<String>> = stmt
+                    .query_map([&name], |row| row.get(0))
+                    .unwrap()
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+                vec![format!("on {table} {columns:?}")]
+            }
+            _ => vec![format!("on {table}")],
+        };
+        schema.insert(format!("{kind} {name}"), details);
+    }
+    schema
+}
+
+#[test]
+fn sql_files_match_the_migrated_schema() {
+    let migrated = memory_db();
+
+    let mut mirrored = Connection::open_in_memory().unwrap();
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("sql");
+    let mut pending: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
+        .collect();
+    pending.sort();
+    // A file may index or trigger on a table another one creates, so apply what can be
+    // until nothing more can, each file all or nothing
+    while !pending.is_empty() {
+        let mut failed = Vec::new();
+        for file in &pending {
+            let tx = mirrored.transaction().unwrap();
+            match tx.execute_batch(&std::fs::read_to_string(file).unwrap()) {
+                Ok(()) => tx.commit().unwrap(),
+                Err(e) => failed.push((file.clone(), e)),
+            }
+        }
+        if failed.len() == pending.len() {
+            panic!("cannot apply {failed:?}");
+        }
+        pending = failed.into_iter().map(|(file, _)| file).collect();
+    }
+
+    assert_eq!(schema(&mirrored), schema(&migrated));
+}
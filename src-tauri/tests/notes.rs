@@ -0,0 +1,222 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::notes::{CreateNoteRequest, NoteInfo, NoteQuery, UpdateNoteRequest};
+use recall_lib::service;
+use rusqlite::Connection;
+
+use common::*;
+
+fn note(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+    area_id: Option<&str>,
+    title: &str,
+) -> NoteInfo {
+    service::notes::create(
+        conn,
+        user_id,
+        CreateNoteRequest {
+            project_id: project_id.map(str::to_string),
+            area_id: area_id.map(str::to_string),
+            title: title.to_string(),
+            description: Some(format!("About {}", title)),
+            content: Some(format!("Notes on {}", title)),
+            status: None,
+        },
+    )
+    .expect("create note")
+}
+
+fn titles(conn: &Connection, user_id: &str, query: NoteQuery) -> Vec<String> {
+    let mut titles: Vec<_> = service::notes::list(conn, user_id, &query)
+        .unwrap()
+        .into_iter()
+        .map(|note| note.title)
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn notes_live_in_the_inbox_an_area_or_a_project() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let home = area(&conn, id, "Home");
+    let launch = project(&conn, id, &work.id, "Launch");
+
+    let idea = note(&conn, id, None, None, "Idea");
+    assert_eq!(idea.status, "Inbox");
+    note(&conn, id, None, Some(&work.id), "Team");
+    let pitch = note(&conn, id, Some(&launch.id), None, "Pitch");
+    assert_eq!(pitch.area_name.as_deref(), Some("Work"));
+    note(&conn, id, None, Some(&home.id), "Groceries");
+
+    let unattached = NoteQuery {
+        unattached: true,
+        ..NoteQuery::default()
+    };
+    assert_eq!(titles(&conn, id, unattached), ["Idea"]);
+    let in_work = NoteQuery {
+        area_id: Some(work.id.clone()),
+        ..NoteQuery::default()
+    };
+    assert_eq!(titles(&conn, id, in_work.clone()), ["Pitch", "Team"]);
+    let in_launch = NoteQuery {
+        project_id: Some(launch.id.clone()),
+        ..NoteQuery::default()
+    };
+    assert_eq!(titles(&conn, id, in_launch), ["Pitch"]);
+
+    // The workflow moves notes on, and filters follow
+    let drafted = service::notes::move_to(&conn, id, &idea.id, "Draft").unwrap();
+    assert_eq!(drafted.status, "Draft");
+    let err = service::notes::move_to(&conn, id, &idea.id, "Done").unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    let drafts = NoteQuery {
+        status: Some("Draft".to_string()),
+        ..NoteQuery::default()
+    };
+    assert_eq!(titles(&conn, id, drafts), ["Idea"]);
+
+    // Filing the note; one place at most
+    let filed = service::notes::update(
+        &conn,
+        id,
+        &idea.id,
+        UpdateNoteRequest {
+            project_id: None,
+            area_id: Some(work.id.clone()),
+            title: " Big idea ".to_string(),
+            description: None,
+            content: drafted.content.clone(),
+            status: "Final".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(filed.title, "Big idea");
+    assert_eq!(filed.status, "Final");
+    assert_eq!(titles(&conn, id, in_work), ["Big idea", "Pitch", "Team"]);
+    let err = service::notes::create(
+        &conn,
+        id,
+        CreateNoteRequest {
+            project_id: Some(launch.id.clone()),
+            area_id: Some(work.id.clone()),
+            title: "Both".to_string(),
+            description: None,
+            content: None,
+            status: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    // Notes of a trashed area are hidden with it and purged with it
+    service::areas::delete(&conn, id, &work.id).unwrap();
+    assert_eq!(titles(&conn, id, NoteQuery::default()), ["Groceries"]);
+    let err = service::notes::get(&conn, id, &pitch.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    service::trash::empty(&conn, id).unwrap();
+    assert_eq!(count(&conn, "notes"), 1);
+}
+
+#[test]
+fn notes_can_be_promoted_to_resources_and_projects() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+
+    let pitch = note(&conn, id, Some(&launch.id), None, "Pitch");
+    let resource = service::notes::promote_to_resource(&conn, id, &pitch.id, None).unwrap();
+    assert_eq!(resource.name, "Pitch");
+    assert_eq!(resource.project_id.as_deref(), Some(launch.id.as_str()));
+    assert_eq!(resource.content.as_deref(), Some("Notes on Pitch"));
+    let err = service::notes::get(&conn, id, &pitch.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+
+    // An area's note stays in the area, an inbox note becomes a resource of its own
+    let team = note(&conn, id, None, Some(&work.id), "Team");
+    let resource = service::notes::promote_to_resource(&conn, id, &team.id, None).unwrap();
+    assert_eq!(resource.project_id, None);
+    assert_eq!(resource.area_id.as_deref(), Some(work.id.as_str()));
+    let scratch = note(&conn, id, None, None, "Scratch");
+    let resource = service::notes::promote_to_resource(&conn, id, &scratch.id, None).unwrap();
+    assert_eq!((resource.project_id, resource.area_id), (None, None));
+
+    // An inbox note needs to be told which area a project goes in
+    let idea = note(&conn, id, None, None, "Idea");
+    let err = service::notes::promote_to_project(&conn, id, &idea.id, None).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    let project = service::notes::promote_to_project(&conn, id, &idea.id, Some(&work.id)).unwrap();
+    assert_eq!(project.title, "Idea");
+    assert_eq!(project.status, "Inbox");
+    assert_eq!(project.description.as_deref(), Some("About Idea"));
+    let resources = service::resources::list(&conn, id, Some(&project.id)).unwrap();
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0].content.as_deref(), Some("Notes on Idea"));
+    assert_eq!(count(&conn, "notes"), 0);
+}
+
+#[test]
+fn notes_are_private() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let idea = note(&conn, id, None, None, "Idea");
+
+    assert!(
+        service::notes::list(&conn, &bob.user.id, &NoteQuery::default())
+            .unwrap()
+            .is_empty()
+    );
+    let err = service::notes::get(&conn, &bob.user.id, &idea.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    let err = service::notes::delete(&conn, &bob.user.id, &idea.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    // Nor can Bob file his notes in Alice's area
+    let bobs = note(&conn, &bob.user.id, None, None, "Mine");
+    let err = service::notes::promote_to_project(&conn, &bob.user.id, &bobs.id, Some(&work.id))
+        .unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+}
+
+#[test]
+fn failed_promotions_write_nothing() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let idea = note(&conn, id, None, Some(&work.id), "Idea");
+
+    // The project is created before the resource holding the note's content fails
+    conn.execute_batch(
+        "CREATE TRIGGER no_resources BEFORE INSERT ON resources
+         BEGIN SELECT RAISE(ABORT, 'no resources'); END;",
+    )
+    .unwrap();
+    service::notes::promote_to_project(&conn, id, &idea.id, None).unwrap_err();
+    assert_eq!(count(&conn, "projects"), 0);
+    assert_eq!(count(&conn, "notes"), 1);
+
+    // The resource is created before deleting the note fails
+    conn.execute_batch(
+        "DROP TRIGGER no_resources;
+         CREATE TRIGGER keep_notes BEFORE DELETE ON notes
+         BEGIN SELECT RAISE(ABORT, 'keep notes'); END;",
+    )
+    .unwrap();
+    service::notes::promote_to_resource(&conn, id, &idea.id, None).unwrap_err();
+    service::notes::promote_to_project(&conn, id, &idea.id, None).unwrap_err();
+    assert_eq!(count(&conn, "resources"), 0);
+    assert_eq!(count(&conn, "projects"), 0);
+    assert_eq!(count(&conn, "notes"), 1);
+}
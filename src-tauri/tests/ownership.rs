@@ -0,0 +1,191 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::events::UpdateEventRequest;
+use recall_lib::projects::UpdateProjectRequest;
+use recall_lib::service;
+
+use common::*;
+
+fn update_project_request(area_id: &str) -> UpdateProjectRequest {
+    UpdateProjectRequest {
+        area_id: area_id.to_string(),
+        title: "Renamed".to_string(),
+        description: None,
+        status: "Planned".to_string(),
+        priority: None,
+        start_date: None,
+        end_date: None,
+    }
+}
+
+fn update_event_request(project_id: Option<&str>) -> UpdateEventRequest {
+    UpdateEventRequest {
+        project_id: project_id.map(str::to_string),
+        title: "Moved".to_string(),
+        description: None,
+        start_time: 2_000,
+        end_time: None,
+        location: None,
+        all_day: false,
+        time_zone: None,
+        recurrence_rule: None,
+        exception_dates: None,
+        reminders: None,
+    }
+}
+
+#[test]
+fn other_users_project_cannot_be_read_or_changed() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let mallory = user(&conn, "Mallory");
+    let area = area(&conn, &alice.user.id, "Work");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+    let mallory_area = common::area(&conn, &mallory.user.id, "Mine");
+
+    let err = service::projects::get(&conn, &mallory.user.id, &project.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    assert!(
+        service::projects::list(&conn, &mallory.user.id, None)
+            .unwrap()
+            .is_empty()
+    );
+    let err = service::projects::list(&conn, &mallory.user.id, Some(&area.id)).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+
+    let err = service::projects::update(
+        &conn,
+        &mallory.user.id,
+        &project.id,
+        update_project_request(&mallory_area.id),
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    let err = service::projects::move_to(&conn, &mallory.user.id, &project.id, "Done", None, None)
+        .unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    let err = service::projects::delete(&conn, &mallory.user.id, &project.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    // Nothing changed for the owner
+    let unchanged = service::projects::get(&conn, &alice.user.id, &project.id).unwrap();
+    assert_eq!(unchanged.title, "Launch");
+    assert_eq!(unchanged.status, "Inbox");
+}
+
+#[test]
+fn projects_cannot_be_placed_in_another_users_area() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let mallory = user(&conn, "Mallory");
+    let alice_area = area(&conn, &alice.user.id, "Work");
+    let mallory_area = area(&conn, &mallory.user.id, "Mine");
+    let mallory_project = project(&conn, &mallory.user.id, &mallory_area.id, "Plot");
+
+    let err = service::projects::update(
+        &conn,
+        &mallory.user.id,
+        &mallory_project.id,
+        update_project_request(&alice_area.id),
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    let projects = service::projects::list(&conn, &alice.user.id, Some(&alice_area.id)).unwrap();
+    assert!(projects.is_empty());
+}
+
+#[test]
+fn other_users_event_cannot_be_read_or_changed() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let mallory = user(&conn, "Mallory");
+    let area = area(&conn, &alice.user.id, "Work");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+    let event = event(&conn, &alice.user.id, Some(&project.id), "Kickoff", 1_000);
+
+    let err = service::events::get(&conn, &mallory.user.id, &event.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    assert!(
+        service::events::list(&conn, &mallory.user.id, None, None, None)
+            .unwrap()
+            .is_empty()
+    );
+
+    let err = service::events::update(
+        &conn,
+        &mallory.user.id,
+        &event.id,
+        update_event_request(None),
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    let err = service::events::delete(&conn, &mallory.user.id, &event.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    let unchanged = service::events::get(&conn, &alice.user.id, &event.id).unwrap();
+    assert_eq!(unchanged.title, "Kickoff");
+}
+
+#[test]
+fn events_cannot_be_attached_to_another_users_project() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let mallory = user(&conn, "Mallory");
+    let area = area(&conn, &alice.user.id, "Work");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+    let mallory_event = event(&conn, &mallory.user.id, None, "Lurk", 1_000);
+
+    let err = service::events::update(
+        &conn,
+        &mallory.user.id,
+        &mallory_event.id,
+        update_event_request(Some(&project.id)),
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    let err = service::events::create(
+        &conn,
+        &mallory.user.id,
+        recall_lib::events::CreateEventRequest {
+            project_id: Some(project.id.clone()),
+            title: "Lurk again".to_string(),
+            description: None,
+            start_time: 1_000,
+            end_time: None,
+            location: None,
+            all_day: false,
+            time_zone: None,
+            recurrence_rule: None,
+            exception_dates: Vec::new(),
+            reminders: Vec::new(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    let events =
+        service::events::list(&conn, &alice.user.id, None, None, Some(&project.id)).unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn other_users_resource_is_invisible() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let mallory = user(&conn, "Mallory");
+    let area = area(&conn, &alice.user.id, "Work");
+    let project = project(&conn, &alice.user.id, &area.id, "Launch");
+    let resource = resource(&conn, &alice.user.id, &project.id, "Plan", Some(b"secret"));
+
+    let err = service::resources::get(&conn, &mallory.user.id, &resource.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    let err = service::resources::delete(&conn, &mallory.user.id, &resource.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    assert_eq!(count(&conn, "resources"), 1);
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::projects::UpdateProjectRequest;
+use recall_lib::service;
+use rusqlite::Connection;
+
+use common::*;
+
+// Titles of one board column, in board order
+fn column(conn: &Connection, user_id: &str, area_id: &str, status: &str) -> Vec<String> {
+    service::projects::list(conn, user_id, Some(area_id))
+        .unwrap()
+        .into_iter()
+        .filter(|project| project.status == status)
+        .map(|project| project.title)
+        .collect()
+}
+
+#[test]
+fn cards_keep_their_place_in_a_column() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let hiring = project(&conn, id, &work.id, "Hiring");
+    let budget = project(&conn, id, &work.id, "Budget");
+
+    // New cards go on top
+    assert_eq!(
+        column(&conn, id, &work.id, "Inbox"),
+        ["Budget", "Hiring", "Launch"]
+    );
+
+    let moved = service::projects::reorder(&conn, id, &budget.id, Some(&launch.id), None).unwrap();
+    assert_eq!(moved.updated_at, budget.updated_at);
+    assert_eq!(
+        column(&conn, id, &work.id, "Inbox"),
+        ["Hiring", "Launch", "Budget"]
+    );
+    service::projects::reorder(&conn, id, &hiring.id, Some(&launch.id), Some(&budget.id)).unwrap();
+    assert_eq!(
+        column(&conn, id, &work.id, "Inbox"),
+        ["Launch", "Hiring", "Budget"]
+    );
+
+    // Moving changes the column and the place in it at once
+    service::projects::move_to(&conn, id, &launch.id, "Progress", None, None).unwrap();
+    service::projects::move_to(&conn, id, &budget.id, "Progress", Some(&launch.id), None).unwrap();
+    let moved =
+        service::projects::move_to(&conn, id, &hiring.id, "Progress", None, Some(&launch.id))
+            .unwrap();
+    assert_eq!(moved.status, "Progress");
+    assert_eq!(
+        column(&conn, id, &work.id, "Progress"),
+        ["Hiring", "Launch", "Budget"]
+    );
+    assert!(column(&conn, id, &work.id, "Inbox").is_empty());
+
+    // Editing a card into another column puts it on top there
+    let planned = project(&conn, id, &work.id, "Planned");
+    service::projects::move_to(&conn, id, &planned.id, "Planned", None, None).unwrap();
+    service::projects::update(
+        &conn,
+        id,
+        &budget.id,
+        UpdateProjectRequest {
+            area_id: work.id.clone(),
+            title: "Budget".to_string(),
+            description: None,
+            status: "Planned".to_string(),
+            priority: None,
+            start_date: None,
+            end_date: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        column(&conn, id, &work.id, "Planned"),
+        ["Budget", "Planned"]
+    );
+}
+
+#[test]
+fn neighbours_must_be_in_the_column_and_in_order() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let hiring = project(&conn, id, &work.id, "Hiring");
+    let budget = project(&conn, id, &work.id, "Budget");
+    service::projects::move_to(&conn, id, &launch.id, "Done", None, None).unwrap();
+
+    let err =
+        service::projects::reorder(&conn, id, &hiring.id, Some(&launch.id), None).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    let err =
+        service::projects::reorder(&conn, id, &hiring.id, Some(&hiring.id), None).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    // Budget is above Hiring, so nothing fits below Hiring and above Budget
+    let other = project(&conn, id, &work.id, "Other");
+    let err = service::projects::reorder(&conn, id, &other.id, Some(&hiring.id), Some(&budget.id))
+        .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    // A failed move leaves the card where it was
+    let err = service::projects::move_to(&conn, id, &hiring.id, "Done", Some(&budget.id), None)
+        .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    assert_eq!(
+        service::projects::get(&conn, id, &hiring.id)
+            .unwrap()
+            .status,
+        "Inbox"
+    );
+
+    let bobs_area = area(&conn, &bob.user.id, "Bob's");
+    let bobs = project(&conn, &bob.user.id, &bobs_area.id, "Bob's");
+    let err = service::projects::reorder(&conn, &bob.user.id, &hiring.id, None, None).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    let err = service::projects::reorder(&conn, &bob.user.id, &bobs.id, Some(&hiring.id), None)
+        .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+}
+
+#[test]
+fn a_crowded_gap_is_renumbered() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let last = project(&conn, id, &work.id, "Last");
+    let first = project(&conn, id, &work.id, "First");
+
+    // Halving the same gap over and over runs out of room long before 100 cards
+    let mut expected = vec!["First".to_string()];
+    let mut above = first.id.clone();
+    for i in 0..100 {
+        let title = format!("Card {:03}", i);
+        let card = project(&conn, id, &work.id, &title);
+        service::projects::reorder(&conn, id, &card.id, Some(&above), Some(&last.id)).unwrap();
+        above = card.id;
+        expected.push(title);
+    }
+    expected.push("Last".to_string());
+
+    assert_eq!(column(&conn, id, &work.id, "Inbox"), expected);
+}
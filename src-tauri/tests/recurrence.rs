@@ -0,0 +1,444 @@
+mod common;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use recall_lib::error::RecallError;
+use recall_lib::events::{OccurrenceRequest, UpdateEventRequest};
+use recall_lib::recurrence::RecurrenceRule;
+use recall_lib::service;
+
+use common::*;
+
+fn at(date: &str) -> i64 {
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M")
+        .unwrap()
+        .and_utc()
+        .timestamp()
+}
+
+fn dates(rule: &str, start: &str, until: &str) -> Vec<NaiveDate> {
+    let rule = RecurrenceRule::parse(rule).unwrap();
+    rule.occurrences_between(at(start), at(start), at(until), 100)
+        .into_iter()
+        .map(|t| chrono::DateTime::from_timestamp(t, 0).unwrap().date_naive())
+        .collect()
+}
+
+fn day(date: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn weekly_byday_with_interval() {
+    // Every other week on Monday and Wednesday, starting Monday 2025-01-06
+    let found = dates(
+        "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE",
+        "2025-01-06 09:00",
+        "2025-02-01 00:00",
+    );
+    assert_eq!(
+        found,
+        vec![
+            day("2025-01-06"),
+            day("2025-01-08"),
+            day("2025-01-20"),
+            day("2025-01-22"),
+        ]
+    );
+}
+
+#[test]
+fn count_includes_the_first_occurrence() {
+    let found = dates(
+        "RRULE:FREQ=DAILY;COUNT=3",
+        "2025-03-30 10:00",
+        "2026-01-01 00:00",
+    );
+    assert_eq!(
+        found,
+        vec![day("2025-03-30"), day("2025-03-31"), day("2025-04-01")]
+    );
+}
+
+#[test]
+fn until_is_inclusive() {
+    let found = dates(
+        "FREQ=DAILY;UNTIL=20250103",
+        "2025-01-01 10:00",
+        "2026-01-01 00:00",
+    );
+    assert_eq!(found.len(), 3);
+}
+
+#[test]
+fn until_dates_are_days_in_the_series_zone() {
+    let rule = RecurrenceRule::parse("FREQ=DAILY;UNTIL=20250103").unwrap();
+    let local_days = |zone: &str, start: &str| -> Vec<NaiveDate> {
+        let tz: chrono_tz::Tz = zone.parse().unwrap();
+        let start = NaiveDateTime::parse_from_str(start, "%Y-%m-%d %H:%M").unwrap();
+        let start = recall_lib::time_zone::instant(tz, start);
+        rule.occurrences_between_in(tz, start, start, at("2026-01-01 00:00"), 100)
+            .into_iter()
+            .map(|t| recall_lib::time_zone::day(tz, t))
+            .collect()
+    };
+
+    // Evenings in New York are already the next day in UTC, mornings in Tokyo still the day before
+    let expected = vec![day("2025-01-01"), day("2025-01-02"), day("2025-01-03")];
+    assert_eq!(local_days("America/New_York", "2025-01-01 20:00"), expected);
+    assert_eq!(local_days("Asia/Tokyo", "2025-01-01 08:00"), expected);
+    assert_eq!(rule.to_string(), "FREQ=DAILY;UNTIL=20250103");
+}
+
+#[test]
+fn monthly_rules_skip_missing_days() {
+    // The 31st only exists in some months
+    let found = dates("FREQ=MONTHLY", "2025-01-31 08:00", "2025-06-01 00:00");
+    assert_eq!(
+        found,
+        vec![day("2025-01-31"), day("2025-03-31"), day("2025-05-31")]
+    );
+
+    // Last Friday of the month
+    let found = dates(
+        "FREQ=MONTHLY;BYDAY=-1FR",
+        "2025-01-31 08:00",
+        "2025-04-01 00:00",
+    );
+    assert_eq!(
+        found,
+        vec![day("2025-01-31"), day("2025-02-28"), day("2025-03-28")]
+    );
+
+    // Last day of the month
+    let found = dates(
+        "FREQ=MONTHLY;BYMONTHDAY=-1",
+        "2025-01-31 08:00",
+        "2025-04-01 00:00",
+    );
+    assert_eq!(
+        found,
+        vec![day("2025-01-31"), day("2025-02-28"), day("2025-03-31")]
+    );
+}
+
+#[test]
+fn yearly_by_month_and_weekday() {
+    // US Thanksgiving: fourth Thursday of November
+    let found = dates(
+        "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH",
+        "2024-11-28 12:00",
+        "2027-01-01 00:00",
+    );
+    assert_eq!(
+        found,
+        vec![day("2024-11-28"), day("2025-11-27"), day("2026-11-26")]
+    );
+}
+
+#[test]
+fn yearly_month_days_repeat_every_month() {
+    // Without BYMONTH, BYMONTHDAY picks that day of each month
+    let found = dates(
+        "FREQ=YEARLY;BYMONTHDAY=15",
+        "2025-01-15 09:00",
+        "2025-04-30 00:00",
+    );
+    assert_eq!(
+        found,
+        vec![
+            day("2025-01-15"),
+            day("2025-02-15"),
+            day("2025-03-15"),
+            day("2025-04-15"),
+        ]
+    );
+}
+
+#[test]
+fn impossible_rules_end_instead_of_spinning() {
+    let found = dates(
+        "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30",
+        "2025-01-01 00:00",
+        "2100-01-01 00:00",
+    );
+    assert_eq!(found, vec![day("2025-01-01")]);
+}
+
+#[test]
+fn invalid_rules_are_rejected() {
+    for rule in [
+        "",
+        "FREQ=HOURLY",
+        "FREQ=DAILY;COUNT=2;UNTIL=20250101",
+        "FREQ=WEEKLY;BYDAY=2MO",
+        "FREQ=MONTHLY;BYSETPOS=1",
+        "FREQ=DAILY;INTERVAL=0",
+        "FREQ=MONTHLY;BYMONTHDAY=32",
+    ] {
+        let err = RecurrenceRule::parse(rule).unwrap_err();
+        assert!(matches!(err, RecallError::Validation(_)), "{rule}: {err}");
+    }
+}
+
+#[test]
+fn rules_are_stored_normalized() {
+    let rule =
+        RecurrenceRule::parse("rrule:freq=weekly;byday=mo,fr;until=20250301T000000Z").unwrap();
+    assert_eq!(
+        rule.to_string(),
+        "FREQ=WEEKLY;UNTIL=20250301T000000Z;BYDAY=MO,FR"
+    );
+}
+
+#[test]
+fn listing_a_window_expands_series() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let standup = recurring_event(
+        &conn,
+        &alice.user.id,
+        None,
+        "Standup",
+        at("2025-01-06 09:00"),
+        Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"),
+    );
+    event(
+        &conn,
+        &alice.user.id,
+        None,
+        "Dentist",
+        at("2025-01-15 14:00"),
+    );
+
+    let events = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-01-13 00:00")),
+        Some(at("2025-01-19 23:59")),
+        None,
+    )
+    .unwrap();
+    let standups: Vec<_> = events.iter().filter(|e| e.id == standup.id).collect();
+    assert_eq!(standups.len(), 5);
+    assert_eq!(standups[0].start_time, at("2025-01-13 09:00"));
+    assert_eq!(standups[0].end_time, Some(at("2025-01-13 10:00")));
+    assert_eq!(standups[0].occurrence_start, Some(at("2025-01-13 09:00")));
+    assert_eq!(events.len(), 6);
+    assert!(
+        events
+            .windows(2)
+            .all(|w| w[0].start_time <= w[1].start_time)
+    );
+
+    // Without a full window each series is listed once
+    let events = service::events::list(&conn, &alice.user.id, None, None, None).unwrap();
+    assert_eq!(events.len(), 2);
+}
+
+#[test]
+fn cancelled_and_edited_occurrences() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let series = recurring_event(
+        &conn,
+        &alice.user.id,
+        None,
+        "Gym",
+        at("2025-01-01 18:00"),
+        Some("FREQ=DAILY;COUNT=10"),
+    );
+    let window = (Some(at("2025-01-01 00:00")), Some(at("2025-01-31 00:00")));
+
+    service::events::cancel_occurrence(&conn, &alice.user.id, &series.id, at("2025-01-02 18:00"))
+        .unwrap();
+    let moved = service::events::update_occurrence(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        at("2025-01-03 18:00"),
+        OccurrenceRequest {
+            title: "Gym (late)".to_string(),
+            description: None,
+            start_time: at("2025-01-03 20:00"),
+            end_time: Some(at("2025-01-03 21:00")),
+            location: None,
+            all_day: false,
+            time_zone: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(moved.occurrence_start, Some(at("2025-01-03 18:00")));
+
+    let events = service::events::list(&conn, &alice.user.id, window.0, window.1, None).unwrap();
+    assert_eq!(events.len(), 9);
+    assert!(
+        !events
+            .iter()
+            .any(|e| e.start_time == at("2025-01-02 18:00"))
+    );
+    let late = events
+        .iter()
+        .find(|e| e.occurrence_start == Some(at("2025-01-03 18:00")))
+        .unwrap();
+    assert_eq!(late.title, "Gym (late)");
+    assert_eq!(late.start_time, at("2025-01-03 20:00"));
+
+    // Only real occurrences can be edited
+    let err = service::events::cancel_occurrence(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        at("2025-01-02 18:00"),
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    let err = service::events::cancel_occurrence(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        at("2025-01-20 18:00"),
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+
+    let series = service::events::get(&conn, &alice.user.id, &series.id).unwrap();
+    assert_eq!(series.exception_dates, vec![at("2025-01-02 18:00")]);
+}
+
+#[test]
+fn occurrence_moved_into_a_window_is_listed() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let series = recurring_event(
+        &conn,
+        &alice.user.id,
+        None,
+        "Review",
+        at("2025-01-06 10:00"),
+        Some("FREQ=WEEKLY;COUNT=2"),
+    );
+    service::events::update_occurrence(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        at("2025-01-13 10:00"),
+        OccurrenceRequest {
+            title: "Review".to_string(),
+            description: None,
+            start_time: at("2025-03-03 10:00"),
+            end_time: None,
+            location: None,
+            all_day: false,
+            time_zone: None,
+        },
+    )
+    .unwrap();
+
+    let march = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-03-01 00:00")),
+        Some(at("2025-03-31 00:00")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(march.len(), 1);
+    assert_eq!(march[0].start_time, at("2025-03-03 10:00"));
+
+    let january = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-01-01 00:00")),
+        Some(at("2025-01-31 00:00")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(january.len(), 1);
+}
+
+#[test]
+fn rescheduling_a_series_drops_occurrence_changes() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let series = recurring_event(
+        &conn,
+        &alice.user.id,
+        None,
+        "Sync",
+        at("2025-01-01 09:00"),
+        Some("FREQ=DAILY"),
+    );
+    service::events::cancel_occurrence(&conn, &alice.user.id, &series.id, at("2025-01-02 09:00"))
+        .unwrap();
+
+    let request = |rule: Option<&str>, start: i64| UpdateEventRequest {
+        project_id: None,
+        title: "Sync".to_string(),
+        description: None,
+        start_time: start,
+        end_time: None,
+        location: None,
+        all_day: false,
+        time_zone: None,
+        recurrence_rule: rule.map(str::to_string),
+        exception_dates: None,
+        reminders: None,
+    };
+
+    // Same schedule: exceptions survive a title-only edit
+    let updated = service::events::update(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        request(Some("FREQ=DAILY"), at("2025-01-01 09:00")),
+    )
+    .unwrap();
+    assert_eq!(updated.exception_dates.len(), 1);
+
+    let updated = service::events::update(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        request(Some("FREQ=WEEKLY"), at("2025-01-01 09:00")),
+    )
+    .unwrap();
+    assert!(updated.exception_dates.is_empty());
+    assert_eq!(updated.recurrence_rule.as_deref(), Some("FREQ=WEEKLY"));
+
+    let updated = service::events::update(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        request(None, at("2025-01-01 09:00")),
+    )
+    .unwrap();
+    assert_eq!(updated.recurrence_rule, None);
+}
+
+#[test]
+fn bounded_series_outside_the_window_are_skipped() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    recurring_event(
+        &conn,
+        &alice.user.id,
+        None,
+        "Course",
+        at("2025-01-01 09:00"),
+        Some("FREQ=DAILY;COUNT=5"),
+    );
+    let events = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at("2025-02-01 00:00")),
+        Some(at("2025-02-28 00:00")),
+        None,
+    )
+    .unwrap();
+    assert!(events.is_empty());
+
+    let recurrence_end: Option<i64> = conn
+        .query_row("SELECT recurrence_end FROM events", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(recurrence_end, Some(at("2025-01-05 09:00")));
+}
@@ -0,0 +1,234 @@
+mod common;
+
+use chrono::NaiveDateTime;
+use recall_lib::error::RecallError;
+use recall_lib::events::{CreateEventRequest, EventInfo, UpdateEventRequest};
+use recall_lib::reminders::{ReminderInfo, ReminderKey};
+use recall_lib::service;
+
+use common::*;
+
+fn at(date: &str) -> i64 {
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M")
+        .unwrap()
+        .and_utc()
+        .timestamp()
+}
+
+fn reminded_event(
+    conn: &rusqlite::Connection,
+    user_id: &str,
+    title: &str,
+    start: &str,
+    rule: Option<&str>,
+    reminders: Vec<i64>,
+) -> EventInfo {
+    service::events::create(
+        conn,
+        user_id,
+        CreateEventRequest {
+            project_id: None,
+            title: title.to_string(),
+            description: None,
+            start_time: at(start),
+            end_time: None,
+            location: None,
+            all_day: false,
+            time_zone: None,
+            recurrence_rule: rule.map(str::to_string),
+            exception_dates: Vec::new(),
+            reminders,
+        },
+    )
+    .unwrap()
+}
+
+fn key(reminder: &ReminderInfo) -> ReminderKey {
+    ReminderKey {
+        kind: reminder.kind.clone(),
+        source_id: reminder.source_id.clone(),
+        occurrence_start: reminder.occurrence_start,
+        minutes_before: reminder.minutes_before,
+    }
+}
+
+#[test]
+fn reminders_are_stored_with_the_event() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    let event = reminded_event(
+        &conn,
+        &alice.user.id,
+        "Dentist",
+        "2025-03-10 09:00",
+        None,
+        vec![60, 10, 60],
+    );
+    assert_eq!(event.reminders, vec![10, 60]);
+
+    let updated = service::events::update(
+        &conn,
+        &alice.user.id,
+        &event.id,
+        UpdateEventRequest {
+            project_id: None,
+            title: "Dentist".to_string(),
+            description: None,
+            start_time: event.start_time,
+            end_time: None,
+            location: None,
+            all_day: false,
+            time_zone: None,
+            recurrence_rule: None,
+            exception_dates: None,
+            reminders: Some(vec![-5]),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(updated, RecallError::Validation(_)), "{updated}");
+    assert_eq!(count(&conn, "event_reminders"), 2);
+
+    service::events::delete(&conn, &alice.user.id, &event.id).unwrap();
+    service::trash::empty(&conn, &alice.user.id).unwrap();
+    assert_eq!(count(&conn, "event_reminders"), 0);
+}
+
+#[test]
+fn due_reminders_are_delivered_once() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    reminded_event(
+        &conn,
+        &alice.user.id,
+        "Dentist",
+        "2025-03-10 09:00",
+        None,
+        vec![15],
+    );
+
+    let now = at("2025-03-10 08:30");
+    assert!(service::reminders::take_due(&conn, now).unwrap().is_empty());
+    assert_eq!(
+        service::reminders::next_due(&conn, now, now + 3600).unwrap(),
+        Some(at("2025-03-10 08:45"))
+    );
+
+    let due = service::reminders::take_due(&conn, at("2025-03-10 08:50")).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].title, "Dentist");
+    assert_eq!(due[0].due_at, at("2025-03-10 08:45"));
+
+    // Recorded as delivered, so a later check or a restart does not repeat it
+    assert!(
+        service::reminders::take_due(&conn, at("2025-03-10 08:55"))
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        service::reminders::pending(&conn, &alice.user.id).unwrap(),
+        due
+    );
+}
+
+#[test]
+fn each_occurrence_is_reminded_and_stale_ones_are_skipped() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    reminded_event(
+        &conn,
+        &alice.user.id,
+        "Standup",
+        "2025-03-10 09:00",
+        Some("FREQ=DAILY;COUNT=3"),
+        vec![5],
+    );
+
+    // The app was closed on the 10th: that occurrence is long past and not shown late
+    let due = service::reminders::take_due(&conn, at("2025-03-11 08:56")).unwrap();
+    let starts: Vec<i64> = due.iter().map(|r| r.occurrence_start).collect();
+    assert_eq!(starts, vec![at("2025-03-11 09:00")]);
+
+    // Started half an hour ago: still worth showing
+    let due = service::reminders::take_due(&conn, at("2025-03-12 09:30")).unwrap();
+    let starts: Vec<i64> = due.iter().map(|r| r.occurrence_start).collect();
+    assert_eq!(starts, vec![at("2025-03-12 09:00")]);
+}
+
+#[test]
+fn snoozed_reminders_come_back_and_dismissed_ones_do_not() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    reminded_event(
+        &conn,
+        &alice.user.id,
+        "Call",
+        "2025-03-10 09:00",
+        None,
+        vec![0],
+    );
+
+    let now = at("2025-03-10 09:00");
+    let due = service::reminders::take_due(&conn, now).unwrap();
+    assert_eq!(due.len(), 1);
+
+    let err = service::reminders::snooze(&conn, &bob.user.id, &key(&due[0]), 5, now).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+
+    service::reminders::snooze(&conn, &alice.user.id, &key(&due[0]), 5, now).unwrap();
+    assert!(
+        service::reminders::pending(&conn, &alice.user.id)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        service::reminders::next_due(&conn, now, now + 3600).unwrap(),
+        Some(at("2025-03-10 09:05"))
+    );
+    assert!(
+        service::reminders::take_due(&conn, at("2025-03-10 09:04"))
+            .unwrap()
+            .is_empty()
+    );
+    let again = service::reminders::take_due(&conn, at("2025-03-10 09:05")).unwrap();
+    assert_eq!(again.len(), 1);
+    assert_eq!(again[0].due_at, at("2025-03-10 09:05"));
+
+    service::reminders::dismiss(&conn, &alice.user.id, &key(&again[0])).unwrap();
+    assert!(
+        service::reminders::pending(&conn, &alice.user.id)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        service::reminders::take_due(&conn, at("2025-03-10 09:30"))
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn projects_are_reminded_before_their_end_date_until_done() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let work = area(&conn, &alice.user.id, "Work");
+    let launch = project(&conn, &alice.user.id, &work.id, "Launch");
+    let hiring = project(&conn, &alice.user.id, &work.id, "Hiring");
+    for (project, status) in [(&launch, "Progress"), (&hiring, "Done")] {
+        conn.execute(
+            "UPDATE projects SET end_date = ?1, status = ?2 WHERE id = ?3",
+            rusqlite::params![at("2025-04-01 00:00"), status, project.id],
+        )
+        .unwrap();
+    }
+
+    let due = service::reminders::take_due(&conn, at("2025-03-31 00:00")).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].kind, "project");
+    assert_eq!(due[0].title, "Launch");
+    assert_eq!(
+        due[0].minutes_before,
+        service::reminders::PROJECT_MINUTES_BEFORE
+    );
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use base64::Engine;
+use recall_lib::error::RecallError;
+use recall_lib::resources::UpdateResourceRequest;
+use recall_lib::service::{self, Owned};
+
+use common::*;
+
+fn encode(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+fn rename(name: &str) -> UpdateResourceRequest {
+    UpdateResourceRequest {
+        name: name.to_string(),
+        content: None,
+        file_data: None,
+        file_type: None,
+        file_size: None,
+        remove_file: false,
+    }
+}
+
+#[test]
+fn listings_leave_file_bodies_to_the_single_fetch() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = &alice.user.id;
+    let area = area(&conn, id, "Work");
+    let project = project(&conn, id, &area.id, "Launch");
+    let file = resource(&conn, id, &project.id, "Plan", Some(b"plan"));
+    // Nor is the uploaded body echoed back
+    assert_eq!(file.file_data, None);
+
+    let listed = service::resources::list(&conn, id, Some(&project.id)).unwrap();
+    assert_eq!(listed[0].file_data, None);
+    assert_eq!(listed[0].blob_hash, file.blob_hash);
+
+    let fetched = service::resources::get(&conn, id, &file.id).unwrap();
+    assert_eq!(fetched.file_data, Some(encode(b"plan")));
+}
+
+#[test]
+fn saving_without_file_data_keeps_the_file() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = &alice.user.id;
+    let area = area(&conn, id, "Work");
+    let project = project(&conn, id, &area.id, "Launch");
+    let file = resource(&conn, id, &project.id, "Plan", Some(b"plan"));
+
+    let request = rename("Final plan");
+    let saved = service::resources::update(&conn, id, &file.id, request).unwrap();
+    assert_eq!(saved.name, "Final plan");
+    assert_eq!(saved.blob_hash, file.blob_hash);
+    assert_eq!(saved.file_type.as_deref(), Some("text/plain"));
+    assert_eq!(saved.file_size, Some(4));
+    assert_eq!(saved.file_data, Some(encode(b"plan")));
+    assert_eq!(count(&conn, "blobs"), 1);
+}
+
+#[test]
+fn files_are_replaced_or_removed_on_request() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = &alice.user.id;
+    let area = area(&conn, id, "Work");
+    let project = project(&conn, id, &area.id, "Launch");
+    let file = resource(&conn, id, &project.id, "Plan", Some(b"plan"));
+
+    let mut request = rename("Plan");
+    request.file_data = Some(encode(b"new plan"));
+    request.file_type = Some("text/markdown".to_string());
+    let replaced = service::resources::update(&conn, id, &file.id, request).unwrap();
+    assert_ne!(replaced.blob_hash, file.blob_hash);
+    assert_eq!(replaced.file_size, Some(8));
+    // The old body is no longer referenced and is collected
+    assert_eq!(count(&conn, "blobs"), 1);
+
+    let mut request = rename("Plan");
+    request.remove_file = true;
+    let removed = service::resources::update(&conn, id, &file.id, request).unwrap();
+    assert_eq!(removed.blob_hash, None);
+    assert_eq!(removed.file_data, None);
+    assert_eq!(removed.file_type, None);
+    assert_eq!(removed.file_size, None);
+    assert_eq!(count(&conn, "blobs"), 0);
+}
+
+#[test]
+fn uploading_and_removing_at_once_is_rejected() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = &alice.user.id;
+    let area = area(&conn, id, "Work");
+    let project = project(&conn, id, &area.id, "Launch");
+    let file = resource(&conn, id, &project.id, "Plan", Some(b"plan"));
+
+    let mut request = rename("Plan");
+    request.file_data = Some(encode(b"new plan"));
+    request.remove_file = true;
+    let err = service::resources::update(&conn, id, &file.id, request).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    let kept = service::resources::get(&conn, id, &file.id).unwrap();
+    assert_eq!(kept.blob_hash, file.blob_hash);
+}
+
+#[test]
+fn saving_without_content_keeps_the_document() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = &alice.user.id;
+    let area = area(&conn, id, "Work");
+    let project = project(&conn, id, &area.id, "Launch");
+    let notes = resource(&conn, id, &project.id, "Notes", None);
+    let budget = resource(&conn, id, &project.id, "Budget", None);
+
+    let mut request = rename("Notes");
+    request.content = Some("See [[Budget]]".to_string());
+    service::resources::update(&conn, id, &notes.id, request).unwrap();
+
+    let renamed =
+        service::resources::update(&conn, id, &notes.id, rename("Meeting notes")).unwrap();
+    assert_eq!(renamed.name, "Meeting notes");
+    assert_eq!(renamed.content.as_deref(), Some("See [[Budget]]"));
+    // Its links are kept with it
+    let backlinks = service::links::backlinks(&conn, id, Owned::Resource, &budget.id).unwrap();
+    assert_eq!(backlinks.len(), 1);
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::resources::{CreateResourceRequest, ResourceInfo};
+use recall_lib::service;
+use recall_lib::service::Owned;
+use rusqlite::Connection;
+
+use common::*;
+
+fn placed(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+    area_id: Option<&str>,
+    name: &str,
+) -> Result<ResourceInfo, RecallError> {
+    service::resources::create(
+        conn,
+        user_id,
+        CreateResourceRequest {
+            project_id: project_id.map(str::to_string),
+            area_id: area_id.map(str::to_string),
+            name: name.to_string(),
+            content: Some(format!("Notes for {}", name)),
+            file_data: None,
+            file_type: None,
+            file_size: None,
+        },
+    )
+}
+
+fn names(
+    conn: &Connection,
+    user_id: &str,
+    project_id: Option<&str>,
+    area_id: Option<&str>,
+) -> Vec<String> {
+    let mut names: Vec<_> =
+        service::resources::list_tagged(conn, user_id, project_id, area_id, &[])
+            .unwrap()
+            .into_iter()
+            .map(|resource| resource.name)
+            .collect();
+    names.sort();
+
+    // The paged summaries the frontend lists cover the same resources
+    let page = service::resources::list_summaries(
+        conn,
+        user_id,
+        project_id,
+        area_id,
+        &[],
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let mut summaries: Vec<_> = page
+        .resources
+        .into_iter()
+        .map(|resource| resource.name)
+        .collect();
+    summaries.sort();
+    assert_eq!(summaries, names);
+    assert_eq!(page.total, names.len() as i64);
+    names
+}
+
+#[test]
+fn resources_belong_to_a_project_an_area_or_nothing() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let home = area(&conn, id, "Home");
+    let launch = project(&conn, id, &work.id, "Launch");
+
+    resource(&conn, id, &launch.id, "Plan", None);
+    let handbook = placed(&conn, id, None, Some(&work.id), "Handbook").unwrap();
+    let loose = placed(&conn, id, None, None, "Loose").unwrap();
+    assert_eq!(loose.project_id, None);
+    assert_eq!(loose.area_id, None);
+
+    let fetched = service::resources::get(&conn, id, &handbook.id).unwrap();
+    assert_eq!(fetched.area_name.as_deref(), Some("Work"));
+    let plan = &service::resources::list(&conn, id, Some(&launch.id)).unwrap()[0];
+    assert_eq!(plan.area_name.as_deref(), Some("Work"));
+
+    // An area covers its own resources and those of its projects
+    assert_eq!(names(&conn, id, None, Some(&work.id)), ["Handbook", "Plan"]);
+    assert_eq!(names(&conn, id, None, None), ["Handbook", "Loose", "Plan"]);
+    assert!(names(&conn, id, None, Some(&home.id)).is_empty());
+
+    let err = placed(&conn, id, Some(&launch.id), Some(&work.id), "Both").unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    // Moving reparents, and back out to nowhere
+    let moved = service::resources::move_to(&conn, id, &loose.id, None, Some(&home.id)).unwrap();
+    assert_eq!(moved.area_name.as_deref(), Some("Home"));
+    assert_eq!(names(&conn, id, None, Some(&home.id)), ["Loose"]);
+    let moved =
+        service::resources::move_to(&conn, id, &handbook.id, Some(&launch.id), None).unwrap();
+    assert_eq!(moved.project_id.as_deref(), Some(launch.id.as_str()));
+    assert_eq!(
+        names(&conn, id, Some(&launch.id), None),
+        ["Handbook", "Plan"]
+    );
+    let moved = service::resources::move_to(&conn, id, &handbook.id, None, None).unwrap();
+    assert_eq!((moved.project_id, moved.area_id), (None, None));
+    assert_eq!(names(&conn, id, None, Some(&work.id)), ["Plan"]);
+}
+
+#[test]
+fn area_resources_follow_their_area_into_the_archive_and_trash() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let handbook = placed(&conn, id, None, Some(&work.id), "Handbook").unwrap();
+    placed(&conn, id, None, None, "Loose").unwrap();
+
+    service::archive::archive_area(&conn, id, &work.id).unwrap();
+    assert_eq!(names(&conn, id, None, None), ["Loose"]);
+    assert_eq!(names(&conn, id, None, Some(&work.id)), ["Handbook"]);
+    service::archive::unarchive_area(&conn, id, &work.id).unwrap();
+
+    service::resources::delete(&conn, id, &handbook.id).unwrap();
+    let trash = service::trash::list(&conn, id).unwrap();
+    assert_eq!(trash.items[0].parent_title.as_deref(), Some("Work"));
+
+    // The resource cannot come back while its area is in the trash
+    service::areas::delete(&conn, id, &work.id).unwrap();
+    let err = service::trash::restore(&conn, id, Owned::Resource, &handbook.id).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    service::trash::restore(&conn, id, Owned::Area, &work.id).unwrap();
+    service::trash::restore(&conn, id, Owned::Resource, &handbook.id).unwrap();
+    assert_eq!(names(&conn, id, None, None), ["Handbook", "Loose"]);
+
+    // Purging the area takes its resources with it
+    service::areas::delete(&conn, id, &work.id).unwrap();
+    assert_eq!(names(&conn, id, None, None), ["Loose"]);
+    service::trash::empty(&conn, id).unwrap();
+    assert_eq!(count(&conn, "resources"), 1);
+}
+
+#[test]
+fn resources_only_go_where_the_user_owns() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let mine = placed(&conn, &bob.user.id, None, None, "Mine").unwrap();
+
+    let err = placed(&conn, &bob.user.id, None, Some(&work.id), "Sneaky").unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    let err = service::resources::move_to(&conn, &bob.user.id, &mine.id, None, Some(&work.id))
+        .unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    let err = service::resources::move_to(&conn, id, &mine.id, None, Some(&work.id)).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+}
+
+#[test]
+fn the_database_rejects_a_resource_in_both_a_project_and_an_area() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+
+    let err = conn
+        .execute(
+            "UPDATE resources SET area_id = ?1 WHERE id = ?2",
+            rusqlite::params![work.id, plan.id],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("CHECK constraint failed"), "{err}");
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::resources::UpdateResourceRequest;
+use recall_lib::service;
+use rusqlite::Connection;
+
+use common::*;
+
+fn save(conn: &Connection, user_id: &str, id: &str, content: &str) {
+    service::resources::update(
+        conn,
+        user_id,
+        id,
+        UpdateResourceRequest {
+            name: "Plan".to_string(),
+            content: Some(content.to_string()),
+            file_data: None,
+            file_type: None,
+            file_size: None,
+            remove_file: false,
+        },
+    )
+    .unwrap();
+}
+
+// Pretend every revision so far was taken an hour earlier
+fn age_revisions(conn: &Connection) {
+    conn.execute(
+        "UPDATE resource_revisions SET created_at = created_at - 3600",
+        [],
+    )
+    .unwrap();
+}
+
+fn paragraphs(lines: &[&str]) -> String {
+    let content: Vec<_> = lines
+        .iter()
+        .map(|line| {
+            serde_json::json!({
+                "type": "paragraph",
+                "content": [{ "type": "text", "text": line }],
+            })
+        })
+        .collect();
+    serde_json::json!({ "type": "doc", "content": content }).to_string()
+}
+
+#[test]
+fn saves_keep_what_they_replace() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+
+    save(&conn, id, &plan.id, "First draft");
+    // Saved again right away: folded into the revision just taken
+    save(&conn, id, &plan.id, "Second draft");
+    // Unchanged content is not a revision
+    age_revisions(&conn);
+    save(&conn, id, &plan.id, "Second draft");
+    save(&conn, id, &plan.id, "Third draft");
+
+    let revisions = service::revisions::list(&conn, id, &plan.id).unwrap();
+    let snippets: Vec<_> = revisions.iter().map(|r| r.snippet.as_deref()).collect();
+    assert_eq!(snippets, [Some("Second draft"), Some("Notes for Plan")]);
+    assert_eq!(revisions[0].content_size, "Second draft".len() as i64);
+
+    let revision = service::revisions::get(&conn, id, &plan.id, revisions[1].id).unwrap();
+    assert_eq!(revision.content, "Notes for Plan");
+}
+
+#[test]
+fn revisions_can_be_compared_and_restored() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+
+    save(&conn, id, &plan.id, &paragraphs(&["Goals", "Ship in May"]));
+    age_revisions(&conn);
+    save(
+        &conn,
+        id,
+        &plan.id,
+        &paragraphs(&["Goals", "Ship in June", "Hire"]),
+    );
+    let revisions = service::revisions::list(&conn, id, &plan.id).unwrap();
+    let older = revisions[0].id;
+
+    let diff = service::revisions::diff(&conn, id, &plan.id, older, None).unwrap();
+    let changes: Vec<_> = diff
+        .changes
+        .iter()
+        .map(|line| (line.change.as_str(), line.text.as_str()))
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            ("equal", "Goals"),
+            ("delete", "Ship in May"),
+            ("insert", "Ship in June"),
+            ("insert", "Hire"),
+        ]
+    );
+
+    let restored = service::revisions::restore(&conn, id, &plan.id, older).unwrap();
+    assert_eq!(
+        restored.content,
+        Some(paragraphs(&["Goals", "Ship in May"]))
+    );
+    // The content it replaced is kept, even though the last revision is recent
+    let revisions = service::revisions::list(&conn, id, &plan.id).unwrap();
+    assert_eq!(revisions.len(), 3);
+    assert_eq!(
+        revisions[0].snippet.as_deref(),
+        Some("Goals Ship in June Hire")
+    );
+}
+
+#[test]
+fn old_revisions_are_dropped() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+
+    for draft in 1..=60 {
+        save(&conn, id, &plan.id, &format!("Draft {}", draft));
+        age_revisions(&conn);
+    }
+
+    let revisions = service::revisions::list(&conn, id, &plan.id).unwrap();
+    assert_eq!(revisions.len(), 50);
+    assert_eq!(revisions[0].snippet.as_deref(), Some("Draft 59"));
+    assert_eq!(revisions[49].snippet.as_deref(), Some("Draft 10"));
+
+    // They go with the resource once it is purged
+    service::resources::delete(&conn, id, &plan.id).unwrap();
+    service::trash::empty(&conn, id).unwrap();
+    assert_eq!(count(&conn, "resource_revisions"), 0);
+}
+
+#[test]
+fn revisions_are_private() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+    let other = resource(&conn, id, &launch.id, "Other", None);
+    save(&conn, id, &plan.id, "Changed");
+    let revision = service::revisions::list(&conn, id, &plan.id).unwrap()[0].id;
+
+    let err = service::revisions::list(&conn, &bob.user.id, &plan.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    let err = service::revisions::restore(&conn, &bob.user.id, &plan.id, revision).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    // A revision is only found through its own resource
+    let err = service::revisions::get(&conn, id, &other.id, revision).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+}
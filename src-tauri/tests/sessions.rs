@@ -0,0 +1,121 @@
+mod common;
+
+use chrono::Utc;
+use recall_lib::auth::SigninRequest;
+use recall_lib::error::RecallError;
+use recall_lib::get_user_id_from_token;
+use recall_lib::service;
+
+use common::*;
+
+fn expire(conn: &rusqlite::Connection, token: &str, expires_at: i64) {
+    conn.execute(
+        "UPDATE sessions SET expires_at = ?1 WHERE token = ?2",
+        rusqlite::params![expires_at, token],
+    )
+    .unwrap();
+}
+
+#[test]
+fn live_token_resolves_to_its_user() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    user(&conn, "Bob");
+
+    assert_eq!(
+        get_user_id_from_token(&alice.token, &conn).unwrap(),
+        alice.user.id
+    );
+    let user = service::auth::user_for_token(&conn, &alice.token)
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.email, "alice@example.com");
+}
+
+#[test]
+fn unknown_token_is_rejected() {
+    let conn = memory_db();
+    user(&conn, "Alice");
+
+    let err = get_user_id_from_token("not-a-token", &conn).unwrap_err();
+    assert!(matches!(err, RecallError::Unauthenticated(_)), "{err}");
+    assert!(
+        service::auth::user_for_token(&conn, "not-a-token")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn expired_token_is_rejected() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let now = Utc::now().timestamp();
+
+    expire(&conn, &alice.token, now - 1);
+    let err = get_user_id_from_token(&alice.token, &conn).unwrap_err();
+    assert!(matches!(err, RecallError::Unauthenticated(_)), "{err}");
+    assert!(
+        service::auth::user_for_token(&conn, &alice.token)
+            .unwrap()
+            .is_none()
+    );
+
+    // A session is valid strictly before its expiry time
+    expire(&conn, &alice.token, now + 60);
+    assert!(get_user_id_from_token(&alice.token, &conn).is_ok());
+}
+
+#[test]
+fn new_sessions_last_the_configured_lifetime() {
+    let conn = memory_db();
+    let before = Utc::now().timestamp();
+    let alice = user(&conn, "Alice");
+
+    let expires_at: i64 = conn
+        .query_row(
+            "SELECT expires_at FROM sessions WHERE token = ?1",
+            [&alice.token],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert!(expires_at >= before + service::auth::SESSION_LIFETIME_SECS);
+}
+
+#[test]
+fn signed_out_token_is_rejected() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    service::auth::delete_session(&conn, &alice.token).unwrap();
+    let err = get_user_id_from_token(&alice.token, &conn).unwrap_err();
+    assert!(matches!(err, RecallError::Unauthenticated(_)), "{err}");
+}
+
+#[test]
+fn signin_opens_a_second_session() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    let err = service::auth::signin(
+        &conn,
+        SigninRequest {
+            email: "alice@example.com".to_string(),
+            password: "wrong".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Unauthenticated(_)), "{err}");
+
+    let again = service::auth::signin(
+        &conn,
+        SigninRequest {
+            email: "alice@example.com".to_string(),
+            password: PASSWORD.to_string(),
+        },
+    )
+    .unwrap();
+    assert_ne!(again.token, alice.token);
+    assert_eq!(again.user.id, alice.user.id);
+    assert!(get_user_id_from_token(&alice.token, &conn).is_ok());
+}
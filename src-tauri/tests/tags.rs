@@ -0,0 +1,246 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::events::EventQuery;
+use recall_lib::service;
+use recall_lib::service::Owned;
+use recall_lib::tags::{CreateTagRequest, UpdateTagRequest};
+
+use common::*;
+
+fn names(tags: &[recall_lib::tags::TagInfo]) -> Vec<&str> {
+    tags.iter().map(|tag| tag.name.as_str()).collect()
+}
+
+fn tag(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn items_of_every_kind_can_be_tagged() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let home = area(&conn, id, "Home");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let garden = project(&conn, id, &home.id, "Garden");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+    let review = event(&conn, id, None, "Review", 1_700_000_000);
+
+    let tags = service::tags::set_item_tags(
+        &conn,
+        id,
+        Owned::Project,
+        &launch.id,
+        &tag(&["urgent", "Q3", "URGENT", " q3 "]),
+    )
+    .unwrap();
+    assert_eq!(names(&tags), ["Q3", "urgent"]);
+    service::tags::set_item_tags(&conn, id, Owned::Project, &garden.id, &tag(&["Urgent"])).unwrap();
+    service::tags::set_item_tags(&conn, id, Owned::Resource, &plan.id, &tag(&["urgent"])).unwrap();
+    service::tags::set_item_tags(&conn, id, Owned::Event, &review.id, &tag(&["Q3"])).unwrap();
+    service::tags::set_item_tags(&conn, id, Owned::Area, &home.id, &tag(&["urgent"])).unwrap();
+
+    let all = service::tags::list(&conn, id).unwrap();
+    assert_eq!(names(&all), ["Q3", "urgent"]);
+    assert_eq!(all[1].item_count, 4);
+    let urgent = &all[1].id;
+
+    let items = service::tags::items(&conn, id, urgent).unwrap();
+    let listed: Vec<_> = items
+        .iter()
+        .map(|item| (item.kind.as_str(), item.title.as_str()))
+        .collect();
+    assert_eq!(
+        listed,
+        [
+            ("area", "Home"),
+            ("project", "Garden"),
+            ("project", "Launch"),
+            ("resource", "Plan"),
+        ]
+    );
+
+    // Replacing the tags drops those left out
+    let tags =
+        service::tags::set_item_tags(&conn, id, Owned::Project, &launch.id, &tag(&["Q3"])).unwrap();
+    assert_eq!(names(&tags), ["Q3"]);
+    assert_eq!(service::tags::get(&conn, id, urgent).unwrap().item_count, 3);
+
+    // Trashed items are not counted or listed, and purging them drops their tags
+    service::projects::delete(&conn, id, &garden.id).unwrap();
+    assert_eq!(service::tags::items(&conn, id, urgent).unwrap().len(), 2);
+    assert_eq!(service::tags::get(&conn, id, urgent).unwrap().item_count, 2);
+    service::trash::empty(&conn, id).unwrap();
+    assert_eq!(count(&conn, "tag_assignments"), 4);
+}
+
+#[test]
+fn listings_filter_on_every_tag_given() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let hiring = project(&conn, id, &work.id, "Hiring");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+    resource(&conn, id, &launch.id, "Budget", None);
+    let review = event(&conn, id, Some(&launch.id), "Review", 1_700_000_000);
+    event(&conn, id, Some(&launch.id), "Standup", 1_700_000_000);
+
+    let both = tag(&["urgent", "Q3"]);
+    service::tags::set_item_tags(&conn, id, Owned::Project, &launch.id, &both).unwrap();
+    service::tags::set_item_tags(&conn, id, Owned::Project, &hiring.id, &tag(&["urgent"])).unwrap();
+    service::tags::set_item_tags(&conn, id, Owned::Resource, &plan.id, &tag(&["Q3"])).unwrap();
+    service::tags::set_item_tags(&conn, id, Owned::Event, &review.id, &both).unwrap();
+    let all = service::tags::list(&conn, id).unwrap();
+    let (q3, urgent) = (all[0].id.clone(), all[1].id.clone());
+
+    let projects =
+        service::projects::list_tagged(&conn, id, None, &[urgent.clone(), q3.clone()]).unwrap();
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].title, "Launch");
+    // Repeating a tag does not change the result
+    let projects =
+        service::projects::list_tagged(&conn, id, None, &[urgent.clone(), urgent.clone()]).unwrap();
+    assert_eq!(projects.len(), 2);
+
+    let resources = service::resources::list_tagged(
+        &conn,
+        id,
+        Some(&launch.id),
+        None,
+        std::slice::from_ref(&q3),
+    )
+    .unwrap();
+    assert_eq!(resources.len(), 1);
+    assert_eq!(resources[0].name, "Plan");
+    let page = service::resources::list_summaries(
+        &conn,
+        id,
+        None,
+        Some(&work.id),
+        std::slice::from_ref(&q3),
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.resources[0].name, "Plan");
+
+    let events = service::events::query(
+        &conn,
+        id,
+        &EventQuery {
+            tag_ids: vec![urgent.clone()],
+            ..EventQuery::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].title, "Review");
+}
+
+#[test]
+fn tags_can_be_renamed_recolored_and_merged() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let hiring = project(&conn, id, &work.id, "Hiring");
+
+    let todo = service::tags::create(
+        &conn,
+        id,
+        CreateTagRequest {
+            name: "todo".to_string(),
+            color: Some("#F59E0B".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(todo.color.as_deref(), Some("#f59e0b"));
+    let err = service::tags::create(
+        &conn,
+        id,
+        CreateTagRequest {
+            name: "TODO".to_string(),
+            color: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    service::tags::set_item_tags(
+        &conn,
+        id,
+        Owned::Project,
+        &launch.id,
+        &tag(&["todo", "later"]),
+    )
+    .unwrap();
+    service::tags::set_item_tags(&conn, id, Owned::Project, &hiring.id, &tag(&["later"])).unwrap();
+    let later = service::tags::list(&conn, id).unwrap()[0].id.clone();
+
+    // Renaming onto an existing name asks for a merge
+    let rename = |name: &str, color: Option<&str>| UpdateTagRequest {
+        name: name.to_string(),
+        color: color.map(str::to_string),
+    };
+    let err = service::tags::update(&conn, id, &later, rename("Todo", None)).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    let err =
+        service::tags::update(&conn, id, &later, rename("Later", Some("orange"))).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    let renamed = service::tags::update(&conn, id, &later, rename("Someday", None)).unwrap();
+    assert_eq!(renamed.name, "Someday");
+
+    let merged = service::tags::merge(&conn, id, &later, &todo.id).unwrap();
+    assert_eq!(merged.item_count, 2);
+    assert_eq!(names(&service::tags::list(&conn, id).unwrap()), ["todo"]);
+    let err = service::tags::get(&conn, id, &later).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    let err = service::tags::merge(&conn, id, &todo.id, &todo.id).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    service::tags::delete(&conn, id, &todo.id).unwrap();
+    assert!(
+        service::tags::item_tags(&conn, id, Owned::Project, &launch.id)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn tags_are_private() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let tags =
+        service::tags::set_item_tags(&conn, id, Owned::Area, &work.id, &tag(&["mine"])).unwrap();
+    let bobs = service::tags::set_item_tags(
+        &conn,
+        &bob.user.id,
+        Owned::Area,
+        &area(&conn, &bob.user.id, "Bob's").id,
+        &tag(&["mine"]),
+    )
+    .unwrap();
+    // Same name, separate tags
+    assert_ne!(tags[0].id, bobs[0].id);
+
+    let err =
+        service::tags::set_item_tags(&conn, &bob.user.id, Owned::Area, &work.id, &tag(&["x"]))
+            .unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    let err = service::tags::items(&conn, &bob.user.id, &tags[0].id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    let err = service::tags::merge(&conn, &bob.user.id, &bobs[0].id, &tags[0].id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    assert!(Owned::parse("task").is_err());
+}
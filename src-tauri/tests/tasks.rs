@@ -0,0 +1,239 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::service;
+use recall_lib::tasks::{CreateTaskRequest, TaskInfo, UpdateTaskRequest};
+use rusqlite::Connection;
+
+use common::*;
+
+fn task(
+    conn: &Connection,
+    user_id: &str,
+    project_id: &str,
+    parent: Option<&str>,
+    title: &str,
+) -> TaskInfo {
+    service::tasks::create(
+        conn,
+        user_id,
+        CreateTaskRequest {
+            project_id: project_id.to_string(),
+            parent_id: parent.map(str::to_string),
+            title: title.to_string(),
+            notes: None,
+            due_date: None,
+            priority: None,
+        },
+    )
+    .expect("create task")
+}
+
+fn edit(task: &TaskInfo) -> UpdateTaskRequest {
+    UpdateTaskRequest {
+        parent_id: task.parent_id.clone(),
+        title: task.title.clone(),
+        notes: task.notes.clone(),
+        done: task.done,
+        due_date: task.due_date,
+        priority: task.priority.clone(),
+    }
+}
+
+fn titles(conn: &Connection, user_id: &str, project_id: &str, parent: Option<&str>) -> Vec<String> {
+    service::tasks::list(conn, user_id, project_id)
+        .unwrap()
+        .into_iter()
+        .filter(|task| task.parent_id.as_deref() == parent)
+        .map(|task| task.title)
+        .collect()
+}
+
+#[test]
+fn tasks_are_ordered_and_nested() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let hiring = project(&conn, id, &work.id, "Hiring");
+
+    let draft = task(&conn, id, &launch.id, None, "Draft");
+    let review = task(&conn, id, &launch.id, None, "Review");
+    let ship = task(&conn, id, &launch.id, None, "Ship");
+    let outline = task(&conn, id, &launch.id, Some(&draft.id), "Outline");
+    let write = task(&conn, id, &launch.id, Some(&draft.id), "Write");
+    assert_eq!(
+        titles(&conn, id, &launch.id, None),
+        ["Draft", "Review", "Ship"]
+    );
+    assert_eq!(
+        titles(&conn, id, &launch.id, Some(&draft.id)),
+        ["Outline", "Write"]
+    );
+
+    service::tasks::reorder(
+        &conn,
+        id,
+        &launch.id,
+        None,
+        &[ship.id.clone(), draft.id.clone(), review.id.clone()],
+    )
+    .unwrap();
+    assert_eq!(
+        titles(&conn, id, &launch.id, None),
+        ["Ship", "Draft", "Review"]
+    );
+    // Leaving a sibling out, or naming a subtask, is refused
+    let err = service::tasks::reorder(
+        &conn,
+        id,
+        &launch.id,
+        None,
+        &[ship.id.clone(), draft.id.clone()],
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    let err = service::tasks::reorder(
+        &conn,
+        id,
+        &launch.id,
+        None,
+        &[ship.id.clone(), draft.id.clone(), outline.id.clone()],
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    // Moving under another parent puts the task last there
+    let moved = service::tasks::update(
+        &conn,
+        id,
+        &review.id,
+        UpdateTaskRequest {
+            parent_id: Some(draft.id.clone()),
+            ..edit(&review)
+        },
+    )
+    .unwrap();
+    assert_eq!(moved.parent_id.as_deref(), Some(draft.id.as_str()));
+    assert_eq!(
+        titles(&conn, id, &launch.id, Some(&draft.id)),
+        ["Outline", "Write", "Review"]
+    );
+
+    // No cycles, and no parents from another project
+    let err = service::tasks::update(
+        &conn,
+        id,
+        &draft.id,
+        UpdateTaskRequest {
+            parent_id: Some(write.id.clone()),
+            ..edit(&draft)
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    let elsewhere = task(&conn, id, &hiring.id, None, "Post the job");
+    let err = service::tasks::create(
+        &conn,
+        id,
+        CreateTaskRequest {
+            project_id: launch.id.clone(),
+            parent_id: Some(elsewhere.id.clone()),
+            title: "Misplaced".to_string(),
+            notes: None,
+            due_date: None,
+            priority: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    // Deleting a task takes its subtasks with it
+    service::tasks::delete(&conn, id, &draft.id).unwrap();
+    assert_eq!(titles(&conn, id, &launch.id, None), ["Ship"]);
+    assert_eq!(
+        service::tasks::list(&conn, id, &launch.id).unwrap().len(),
+        1
+    );
+}
+
+#[test]
+fn project_progress_follows_its_tasks() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    assert_eq!(launch.task_count, 0);
+    assert_eq!(launch.progress, None);
+
+    let draft = task(&conn, id, &launch.id, None, "Draft");
+    task(&conn, id, &launch.id, Some(&draft.id), "Outline");
+    task(&conn, id, &launch.id, None, "Ship");
+
+    let done = service::tasks::set_done(&conn, id, &draft.id, true).unwrap();
+    assert!(done.done);
+    let completed_at = done.completed_at.expect("completed");
+    let project = service::projects::get(&conn, id, &launch.id).unwrap();
+    assert_eq!((project.task_count, project.done_task_count), (3, 1));
+    assert_eq!(project.progress, Some(33));
+
+    // Saving a done task again keeps when it was done; undoing clears it
+    let edited = service::tasks::update(
+        &conn,
+        id,
+        &draft.id,
+        UpdateTaskRequest {
+            title: " Draft it ".to_string(),
+            notes: Some("  ".to_string()),
+            ..edit(&done)
+        },
+    )
+    .unwrap();
+    assert_eq!(edited.title, "Draft it");
+    assert_eq!(edited.notes, None);
+    assert_eq!(edited.completed_at, Some(completed_at));
+    let undone = service::tasks::set_done(&conn, id, &draft.id, false).unwrap();
+    assert_eq!(undone.completed_at, None);
+
+    let err = service::tasks::update(
+        &conn,
+        id,
+        &draft.id,
+        UpdateTaskRequest {
+            priority: Some("Urgent".to_string()),
+            ..edit(&undone)
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    let projects = service::projects::list(&conn, id, Some(&work.id)).unwrap();
+    assert_eq!(projects[0].progress, Some(0));
+}
+
+#[test]
+fn tasks_are_private_and_go_with_their_project() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let draft = task(&conn, id, &launch.id, None, "Draft");
+
+    let err = service::tasks::get(&conn, &bob.user.id, &draft.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    let err = service::tasks::list(&conn, &bob.user.id, &launch.id).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+    let err = service::tasks::set_done(&conn, &bob.user.id, &draft.id, true).unwrap_err();
+    assert!(matches!(err, RecallError::Forbidden(_)), "{err}");
+
+    // Hidden while the project is in the trash, deleted when it is purged
+    service::projects::delete(&conn, id, &launch.id).unwrap();
+    let err = service::tasks::get(&conn, id, &draft.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    service::trash::empty(&conn, id).unwrap();
+    assert_eq!(count(&conn, "tasks"), 0);
+}
@@ -0,0 +1,260 @@
+mod common;
+
+use chrono::NaiveDateTime;
+use chrono_tz::Tz;
+use recall_lib::error::RecallError;
+use recall_lib::events::CreateEventRequest;
+use recall_lib::recurrence::RecurrenceRule;
+use recall_lib::service;
+use recall_lib::time_zone;
+
+use common::*;
+
+fn at(tz: Tz, date: &str) -> i64 {
+    time_zone::instant(
+        tz,
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap(),
+    )
+}
+
+fn request(title: &str, start: i64, all_day: bool, rule: Option<&str>) -> CreateEventRequest {
+    CreateEventRequest {
+        project_id: None,
+        title: title.to_string(),
+        description: None,
+        start_time: start,
+        end_time: None,
+        location: None,
+        all_day,
+        time_zone: None,
+        recurrence_rule: rule.map(str::to_string),
+        exception_dates: Vec::new(),
+        reminders: Vec::new(),
+    }
+}
+
+#[test]
+fn all_day_events_keep_their_date_when_travelling() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    service::auth::set_time_zone(&conn, &alice.user.id, "America/New_York").unwrap();
+
+    let birthday = service::events::create(
+        &conn,
+        &alice.user.id,
+        request(
+            "Birthday",
+            at(Tz::America__New_York, "2025-03-10 00:00"),
+            true,
+            None,
+        ),
+    )
+    .unwrap();
+    assert_eq!(birthday.day, "2025-03-10");
+    assert_eq!(birthday.time_zone, None);
+
+    // Fourteen hours ahead, the event is still on the 10th, from local midnight
+    service::auth::set_time_zone(&conn, &alice.user.id, "Asia/Tokyo").unwrap();
+    let birthday = service::events::get(&conn, &alice.user.id, &birthday.id).unwrap();
+    assert_eq!(birthday.day, "2025-03-10");
+    assert_eq!(birthday.start_time, at(Tz::Asia__Tokyo, "2025-03-10 00:00"));
+
+    let tokyo_day = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at(Tz::Asia__Tokyo, "2025-03-10 00:00")),
+        Some(at(Tz::Asia__Tokyo, "2025-03-10 23:59")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(tokyo_day.len(), 1);
+}
+
+#[test]
+fn timed_events_keep_their_instant_and_zone() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    service::auth::set_time_zone(&conn, &alice.user.id, "Europe/Paris").unwrap();
+
+    let call = service::events::create(
+        &conn,
+        &alice.user.id,
+        request(
+            "Call",
+            at(Tz::Europe__Paris, "2025-06-01 00:30"),
+            false,
+            None,
+        ),
+    )
+    .unwrap();
+    assert_eq!(call.time_zone.as_deref(), Some("Europe/Paris"));
+    assert_eq!(call.day, "2025-06-01");
+
+    // Seen from New York the same instant falls on the previous evening
+    service::auth::set_time_zone(&conn, &alice.user.id, "America/New_York").unwrap();
+    let call = service::events::get(&conn, &alice.user.id, &call.id).unwrap();
+    assert_eq!(call.start_time, at(Tz::Europe__Paris, "2025-06-01 00:30"));
+    assert_eq!(call.day, "2025-05-31");
+}
+
+#[test]
+fn recurring_events_keep_their_wall_clock_time_across_dst() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    service::auth::set_time_zone(&conn, &alice.user.id, "Europe/Paris").unwrap();
+    let paris = Tz::Europe__Paris;
+
+    // Clocks in Paris go forward on 2025-03-30
+    let series = service::events::create(
+        &conn,
+        &alice.user.id,
+        request(
+            "Team sync",
+            at(paris, "2025-03-24 09:00"),
+            false,
+            Some("FREQ=WEEKLY;COUNT=3"),
+        ),
+    )
+    .unwrap();
+    let events = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at(paris, "2025-03-01 00:00")),
+        Some(at(paris, "2025-04-30 00:00")),
+        None,
+    )
+    .unwrap();
+    let starts: Vec<i64> = events.iter().map(|e| e.start_time).collect();
+    assert_eq!(
+        starts,
+        vec![
+            at(paris, "2025-03-24 09:00"),
+            at(paris, "2025-03-31 09:00"),
+            at(paris, "2025-04-07 09:00"),
+        ]
+    );
+    // 08:00 UTC before the change, 07:00 UTC after it
+    assert_eq!(starts[1] - starts[0], 7 * 24 * 3600 - 3600);
+
+    // Occurrences are addressed by those same instants
+    service::events::cancel_occurrence(
+        &conn,
+        &alice.user.id,
+        &series.id,
+        at(paris, "2025-03-31 09:00"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn zone_given_with_the_event_wins_over_the_users() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let new_york = Tz::America__New_York;
+
+    let mut standup = request(
+        "NY standup",
+        at(new_york, "2025-03-07 09:00"),
+        false,
+        Some("FREQ=DAILY;COUNT=4"),
+    );
+    standup.time_zone = Some("America/New_York".to_string());
+    service::events::create(&conn, &alice.user.id, standup).unwrap();
+
+    // Alice views in UTC; the US changes clocks on 2025-03-09
+    let events = service::events::list(
+        &conn,
+        &alice.user.id,
+        Some(at(new_york, "2025-03-07 00:00")),
+        Some(at(new_york, "2025-03-11 00:00")),
+        None,
+    )
+    .unwrap();
+    let days: Vec<&str> = events.iter().map(|e| e.day.as_str()).collect();
+    assert_eq!(
+        days,
+        ["2025-03-07", "2025-03-08", "2025-03-09", "2025-03-10"]
+    );
+    assert!(events.iter().all(|e| {
+        time_zone::wall_clock(new_york, e.start_time)
+            .format("%H:%M")
+            .to_string()
+            == "09:00"
+    }));
+}
+
+#[test]
+fn unknown_zones_are_rejected() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+
+    let err = service::auth::set_time_zone(&conn, &alice.user.id, "Mars/Olympus_Mons").unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    let mut bad = request("Trip", 1_000, false, None);
+    bad.time_zone = Some("Nowhere".to_string());
+    let err = service::events::create(&conn, &alice.user.id, bad).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    assert_eq!(count(&conn, "events"), 0);
+}
+
+#[test]
+fn migration_pins_existing_events_to_the_machine_zone() {
+    let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+    recall_lib::db::configure_connection(&mut conn).unwrap();
+    for migration in recall_lib::migrations::MIGRATIONS
+        .iter()
+        .filter(|m| m.version < 9)
+    {
+        conn.execute_batch(migration.sql).unwrap();
+    }
+    conn.pragma_update(None, "user_version", 8).unwrap();
+
+    let zone = time_zone::system_zone();
+    let midnight = at(zone, "2025-03-10 00:00");
+    // Series ends were the last occurrence expanded in UTC
+    let series_start = at(zone, "2025-10-15 09:00");
+    let utc_end = series_start + 29 * 86_400;
+    conn.execute_batch(&format!(
+        "INSERT INTO users (id, email, name, password_hash, created_at, updated_at)
+             VALUES ('u1', 'old@example.com', 'Old', 'x', 0, 0);
+         INSERT INTO events (id, user_id, title, start_time, all_day, created_at, updated_at)
+             VALUES ('timed', 'u1', 'Timed', 1000, 0, 0, 0),
+                    ('allday', 'u1', 'All day', {midnight}, 1, 0, 0);
+         INSERT INTO events (id, user_id, title, start_time, all_day, recurrence_rule, recurrence_end, created_at, updated_at)
+             VALUES ('series', 'u1', 'Standup', {series_start}, 0, 'FREQ=DAILY;COUNT=30', {utc_end}, 0, 0);"
+    ))
+    .unwrap();
+
+    recall_lib::migrations::run_migrations(&mut conn).unwrap();
+
+    let row = |id: &str| -> (i64, Option<String>) {
+        conn.query_row(
+            "SELECT start_time, time_zone FROM events WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+    };
+    assert_eq!(row("timed"), (1000, Some(zone.name().to_string())));
+    let floating_midnight = chrono::NaiveDate::from_ymd_opt(2025, 3, 10)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+        .timestamp();
+    assert_eq!(row("allday"), (floating_midnight, None));
+
+    // Expanded in its zone, the last standup is an hour later wherever clocks go back in
+    // between; the stored end must still cover it
+    let rule = RecurrenceRule::parse("FREQ=DAILY;COUNT=30").unwrap();
+    let last = rule.last_occurrence_in(zone, series_start).unwrap();
+    let recurrence_end: i64 = conn
+        .query_row(
+            "SELECT recurrence_end FROM events WHERE id = 'series'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert!(recurrence_end >= last, "{recurrence_end} < {last}");
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use recall_lib::blobs;
+use recall_lib::error::RecallError;
+use recall_lib::search::search_all;
+use recall_lib::service;
+use recall_lib::service::Owned;
+
+use common::*;
+
+const DAY: i64 = 24 * 3600;
+
+#[test]
+fn deleted_items_are_hidden_until_restored() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Launch plan", Some(b"plan"));
+    let kickoff = event(&conn, id, Some(&launch.id), "Kickoff", 1_700_000_000);
+
+    service::areas::delete(&conn, id, &work.id).unwrap();
+
+    assert!(service::areas::list(&conn, id).unwrap().is_empty());
+    assert!(service::projects::list(&conn, id, None).unwrap().is_empty());
+    assert!(
+        service::resources::list(&conn, id, None)
+            .unwrap()
+            .is_empty()
+    );
+    let err = service::resources::get(&conn, id, &plan.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    let results = search_all(&conn, id, "launch", 10).unwrap();
+    assert!(results.projects.is_empty() && results.resources.is_empty());
+
+    // The event stays, without its project for now
+    let event = service::events::get(&conn, id, &kickoff.id).unwrap();
+    assert_eq!(event.project_id, None);
+
+    // Nothing was deleted for good
+    assert_eq!(count(&conn, "resources"), 1);
+    assert!(
+        blobs::get_blob(&conn, plan.blob_hash.as_deref().unwrap())
+            .unwrap()
+            .is_some()
+    );
+
+    // Only the area is listed; what was in it comes back with it
+    let trash = service::trash::list(&conn, id).unwrap();
+    assert_eq!(trash.items.len(), 1);
+    assert_eq!(trash.items[0].kind, "area");
+    assert_eq!(
+        trash.items[0].purge_at,
+        trash.items[0].deleted_at + 30 * DAY
+    );
+
+    service::trash::restore(&conn, id, Owned::Area, &work.id).unwrap();
+
+    assert_eq!(service::projects::list(&conn, id, None).unwrap().len(), 1);
+    assert_eq!(
+        service::resources::get(&conn, id, &plan.id).unwrap().name,
+        "Launch plan"
+    );
+    let event = service::events::get(&conn, id, &kickoff.id).unwrap();
+    assert_eq!(event.project_id.as_deref(), Some(launch.id.as_str()));
+    assert!(service::trash::list(&conn, id).unwrap().items.is_empty());
+}
+
+#[test]
+fn items_in_the_trash_cannot_be_changed() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let kickoff = event(&conn, id, None, "Kickoff", 1_700_000_000);
+
+    service::projects::delete(&conn, id, &launch.id).unwrap();
+    service::events::delete(&conn, id, &kickoff.id).unwrap();
+
+    let err = service::projects::delete(&conn, id, &launch.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    let err = service::archive::archive_project(&conn, id, &launch.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    let err = service::events::get(&conn, id, &kickoff.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+    assert!(
+        service::events::list(&conn, id, None, None, None)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn restoring_needs_the_parent_out_of_the_trash() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", None);
+
+    service::resources::delete(&conn, id, &plan.id).unwrap();
+    service::projects::delete(&conn, id, &launch.id).unwrap();
+
+    // Only the project is listed while both are in the trash
+    let trash = service::trash::list(&conn, id).unwrap();
+    assert_eq!(trash.items.len(), 1);
+    assert_eq!(trash.items[0].parent_title.as_deref(), Some("Work"));
+
+    let err = service::trash::restore(&conn, id, Owned::Resource, &plan.id).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    let err = service::trash::restore(&conn, &bob.user.id, Owned::Project, &launch.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+
+    service::trash::restore(&conn, id, Owned::Project, &launch.id).unwrap();
+    // The resource was deleted on its own, so it stays in the trash
+    assert!(
+        service::resources::list(&conn, id, Some(&launch.id))
+            .unwrap()
+            .is_empty()
+    );
+    service::trash::restore(&conn, id, Owned::Resource, &plan.id).unwrap();
+    assert_eq!(
+        service::resources::list(&conn, id, Some(&launch.id))
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let err = service::trash::restore(&conn, id, Owned::Resource, &plan.id).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+}
+
+#[test]
+fn expired_items_are_purged_for_good() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let plan = resource(&conn, id, &launch.id, "Plan", Some(b"plan"));
+    let kickoff = event(&conn, id, Some(&launch.id), "Kickoff", 1_700_000_000);
+    let bobs = area(&conn, &bob.user.id, "Bob's");
+
+    service::areas::delete(&conn, id, &work.id).unwrap();
+    service::areas::delete(&conn, &bob.user.id, &bobs.id).unwrap();
+    assert_eq!(
+        service::trash::set_retention_days(&conn, id, Some(7)).unwrap(),
+        7
+    );
+    let now = chrono::Utc::now().timestamp();
+
+    assert_eq!(
+        service::trash::purge_expired(&conn, now + 6 * DAY).unwrap(),
+        0
+    );
+    // Alice keeps items for a week, Bob for the default 30 days
+    assert_eq!(
+        service::trash::purge_expired(&conn, now + 8 * DAY).unwrap(),
+        1
+    );
+    assert_eq!(count(&conn, "areas"), 1);
+    assert_eq!(count(&conn, "projects"), 0);
+    assert_eq!(count(&conn, "resources"), 0);
+    assert!(
+        blobs::get_blob(&conn, plan.blob_hash.as_deref().unwrap())
+            .unwrap()
+            .is_none()
+    );
+    let event = service::events::get(&conn, id, &kickoff.id).unwrap();
+    assert_eq!(event.project_id, None);
+
+    assert_eq!(
+        service::trash::purge_expired(&conn, now + 31 * DAY).unwrap(),
+        1
+    );
+    assert_eq!(count(&conn, "areas"), 0);
+}
+
+#[test]
+fn retention_is_bounded() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+
+    for days in [0, -1, 3651] {
+        let err = service::trash::set_retention_days(&conn, id, Some(days)).unwrap_err();
+        assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    }
+    service::trash::set_retention_days(&conn, id, Some(90)).unwrap();
+    assert_eq!(service::trash::retention_days(&conn, id).unwrap(), 90);
+    service::trash::set_retention_days(&conn, id, None).unwrap();
+    assert_eq!(service::trash::retention_days(&conn, id).unwrap(), 30);
+}
@@ -0,0 +1,460 @@
+mod common;
+
+use recall_lib::error::RecallError;
+use recall_lib::projects::CreateProjectRequest;
+use recall_lib::service;
+use recall_lib::workflows::{SetWorkflowRequest, WorkflowStatusRequest, WorkflowTransition};
+use rusqlite::Connection;
+
+use common::*;
+
+const DAY: i64 = 24 * 3600;
+
+// A status taking the projects of `from`
+fn status(name: &str, done: bool, from: &[&str]) -> WorkflowStatusRequest {
+    WorkflowStatusRequest {
+        name: name.to_string(),
+        done,
+        from: from.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+fn transition(from: &str, to: &str) -> WorkflowTransition {
+    WorkflowTransition {
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+fn workflow(
+    area_id: Option<&str>,
+    statuses: Vec<WorkflowStatusRequest>,
+    transitions: Vec<WorkflowTransition>,
+) -> SetWorkflowRequest {
+    SetWorkflowRequest {
+        area_id: area_id.map(str::to_string),
+        name: "Writing".to_string(),
+        statuses,
+        transitions,
+    }
+}
+
+fn names(conn: &Connection, user_id: &str, area_id: Option<&str>) -> Vec<String> {
+    service::workflows::get(conn, user_id, area_id)
+        .unwrap()
+        .statuses
+        .into_iter()
+        .map(|s| s.name)
+        .collect()
+}
+
+fn status_of(conn: &Connection, user_id: &str, id: &str) -> String {
+    service::projects::get(conn, user_id, id).unwrap().status
+}
+
+fn completed_at(conn: &Connection, id: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT completed_at FROM projects WHERE id = ?1",
+        [id],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn projects_follow_the_built_in_workflow_until_one_is_set() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+
+    let built_in = service::workflows::get(&conn, id, None).unwrap();
+    assert_eq!(built_in.id, None);
+    assert_eq!(
+        names(&conn, id, None),
+        ["Inbox", "Planned", "Progress", "Done"]
+    );
+
+    let err =
+        service::projects::move_to(&conn, id, &launch.id, "Drafting", None, None).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid status. Must be: Inbox, Planned, Progress, or Done"
+    );
+
+    // Renaming and merging statuses moves their projects along
+    let hiring = project(&conn, id, &work.id, "Hiring");
+    service::projects::move_to(&conn, id, &hiring.id, "Progress", None, None).unwrap();
+    let set = service::workflows::set(
+        &conn,
+        id,
+        workflow(
+            None,
+            vec![
+                status("Ideas", false, &["Inbox", "Planned"]),
+                status("Drafting", false, &["Progress"]),
+                status("Done", true, &[]),
+            ],
+            Vec::new(),
+        ),
+    )
+    .unwrap();
+    assert!(set.id.is_some());
+    assert_eq!(
+        names(&conn, id, Some(&work.id)),
+        ["Ideas", "Drafting", "Done"]
+    );
+    assert_eq!(status_of(&conn, id, &launch.id), "Ideas");
+    assert_eq!(status_of(&conn, id, &hiring.id), "Drafting");
+
+    let err = service::projects::move_to(&conn, id, &launch.id, "Inbox", None, None).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    service::projects::move_to(&conn, id, &launch.id, "Drafting", None, None).unwrap();
+
+    // A status dropped without saying where its projects go is refused
+    let err = service::workflows::set(
+        &conn,
+        id,
+        workflow(None, vec![status("Ideas", false, &[])], Vec::new()),
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    assert_eq!(status_of(&conn, id, &launch.id), "Drafting");
+
+    // Promoted notes start in the first status
+    let note = service::notes::create(
+        &conn,
+        id,
+        recall_lib::notes::CreateNoteRequest {
+            project_id: None,
+            area_id: Some(work.id.clone()),
+            title: "Idea".to_string(),
+            description: None,
+            content: None,
+            status: None,
+        },
+    )
+    .unwrap();
+    let promoted = service::notes::promote_to_project(&conn, id, &note.id, None).unwrap();
+    assert_eq!(promoted.status, "Ideas");
+}
+
+#[test]
+fn transitions_limit_where_projects_can_move() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+
+    service::workflows::set(
+        &conn,
+        id,
+        workflow(
+            None,
+            vec![
+                status("Inbox", false, &[]),
+                status("Review", false, &["Planned", "Progress"]),
+                status("Shipped", true, &["Done"]),
+            ],
+            vec![
+                transition("Inbox", "Review"),
+                transition("Review", "Inbox"),
+                transition("Review", "Shipped"),
+            ],
+        ),
+    )
+    .unwrap();
+
+    let err = service::projects::move_to(&conn, id, &launch.id, "Shipped", None, None).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Projects cannot move from Inbox to Shipped"
+    );
+    service::projects::move_to(&conn, id, &launch.id, "Review", None, None).unwrap();
+    let shipped = service::projects::move_to(&conn, id, &launch.id, "Shipped", None, None).unwrap();
+    assert!(completed_at(&conn, &shipped.id).is_some());
+
+    // Creating can use any status, as nothing moves
+    let created = service::projects::create(
+        &conn,
+        id,
+        CreateProjectRequest {
+            area_id: work.id.clone(),
+            title: "Old".to_string(),
+            description: None,
+            status: "Shipped".to_string(),
+            priority: None,
+            start_date: None,
+            end_date: None,
+        },
+    )
+    .unwrap();
+    assert!(completed_at(&conn, &created.id).is_some());
+
+    let err = service::workflows::set(
+        &conn,
+        id,
+        workflow(
+            None,
+            vec![status("Inbox", false, &[])],
+            vec![transition("Inbox", "Nowhere")],
+        ),
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+}
+
+#[test]
+fn an_area_can_have_its_own_workflow() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let home = area(&conn, id, "Home");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let garden = project(&conn, id, &home.id, "Garden");
+
+    service::workflows::set(
+        &conn,
+        id,
+        workflow(
+            Some(&home.id),
+            vec![
+                status("Someday", false, &["Inbox", "Planned", "Progress"]),
+                status("Done", true, &[]),
+            ],
+            Vec::new(),
+        ),
+    )
+    .unwrap();
+    assert_eq!(names(&conn, id, Some(&home.id)), ["Someday", "Done"]);
+    assert_eq!(names(&conn, id, Some(&work.id)).len(), 4);
+    assert_eq!(status_of(&conn, id, &garden.id), "Someday");
+    assert_eq!(status_of(&conn, id, &launch.id), "Inbox");
+
+    // Changing the user's workflow leaves the area's alone
+    service::workflows::set(
+        &conn,
+        id,
+        workflow(
+            None,
+            vec![
+                status("Todo", false, &["Inbox", "Planned", "Progress"]),
+                status("Done", true, &[]),
+            ],
+            Vec::new(),
+        ),
+    )
+    .unwrap();
+    assert_eq!(status_of(&conn, id, &launch.id), "Todo");
+    assert_eq!(status_of(&conn, id, &garden.id), "Someday");
+
+    // Moving a project to another area needs a status there
+    let err = service::projects::update(
+        &conn,
+        id,
+        &launch.id,
+        recall_lib::projects::UpdateProjectRequest {
+            area_id: home.id.clone(),
+            title: "Launch".to_string(),
+            description: None,
+            status: "Todo".to_string(),
+            priority: None,
+            start_date: None,
+            end_date: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+
+    // Dropping the area's workflow needs its projects to fit the user's
+    let err = service::workflows::delete(&conn, id, &home.id).unwrap_err();
+    assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    service::projects::move_to(&conn, id, &garden.id, "Done", None, None).unwrap();
+    let fallback = service::workflows::delete(&conn, id, &home.id).unwrap();
+    assert_eq!(fallback.area_id, None);
+    assert_eq!(names(&conn, id, Some(&home.id)), ["Todo", "Done"]);
+    let err = service::workflows::delete(&conn, id, &home.id).unwrap_err();
+    assert!(matches!(err, RecallError::NotFound(_)), "{err}");
+}
+
+#[test]
+fn finished_statuses_drive_completion_and_auto_archive() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let launch = project(&conn, id, &work.id, "Launch");
+    let hiring = project(&conn, id, &work.id, "Hiring");
+    service::archive::set_auto_archive_days(&conn, id, Some(7)).unwrap();
+
+    // Done stays a status but no longer finishes projects
+    service::workflows::set(
+        &conn,
+        id,
+        workflow(
+            None,
+            vec![
+                status("Inbox", false, &["Planned", "Progress"]),
+                status("Done", false, &[]),
+                status("Released", true, &[]),
+            ],
+            Vec::new(),
+        ),
+    )
+    .unwrap();
+    let done = service::projects::move_to(&conn, id, &hiring.id, "Done", None, None).unwrap();
+    assert_eq!(completed_at(&conn, &done.id), None);
+    let released =
+        service::projects::move_to(&conn, id, &launch.id, "Released", None, None).unwrap();
+    let released_at = completed_at(&conn, &released.id).unwrap();
+
+    assert_eq!(
+        service::archive::auto_archive(&conn, released_at + 7 * DAY).unwrap(),
+        1
+    );
+    let launch = service::projects::get(&conn, id, &launch.id).unwrap();
+    assert!(launch.archived_at.is_some());
+    let hiring = service::projects::get(&conn, id, &hiring.id).unwrap();
+    assert_eq!(hiring.archived_at, None);
+
+    // Marking a status finished completes the projects already in it
+    service::workflows::set(
+        &conn,
+        id,
+        workflow(
+            None,
+            vec![
+                status("Inbox", false, &[]),
+                status("Done", true, &["Released"]),
+            ],
+            Vec::new(),
+        ),
+    )
+    .unwrap();
+    let hiring = service::projects::get(&conn, id, &hiring.id).unwrap();
+    assert!(completed_at(&conn, &hiring.id).is_some());
+    let launch = service::projects::get(&conn, id, &launch.id).unwrap();
+    assert_eq!(
+        (launch.status.as_str(), completed_at(&conn, &launch.id)),
+        ("Done", Some(released_at))
+    );
+}
+
+#[test]
+fn workflows_are_checked_and_private() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let bob = user(&conn, "Bob");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+
+    for request in [
+        workflow(None, Vec::new(), Vec::new()),
+        workflow(
+            None,
+            vec![status("Inbox", false, &[]), status(" Inbox ", false, &[])],
+            Vec::new(),
+        ),
+        workflow(None, vec![status("  ", false, &[])], Vec::new()),
+        workflow(
+            None,
+            vec![
+                status("Inbox", false, &["Nowhere"]),
+                status("Done", true, &[]),
+            ],
+            Vec::new(),
+        ),
+    ] {
+        let err = service::workflows::set(&conn, id, request).unwrap_err();
+        assert!(matches!(err, RecallError::Validation(_)), "{err}");
+    }
+    assert_eq!(names(&conn, id, None).len(), 4);
+
+    let err = service::workflows::get(&conn, &bob.user.id, Some(&work.id)).unwrap_err();
+    assert!(
+        matches!(err, RecallError::NotFound(_) | RecallError::Forbidden(_)),
+        "{err}"
+    );
+    let err = service::workflows::set(
+        &conn,
+        &bob.user.id,
+        workflow(Some(&work.id), vec![status("Mine", false, &[])], Vec::new()),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, RecallError::NotFound(_) | RecallError::Forbidden(_)),
+        "{err}"
+    );
+
+    // Bob's own workflow does not touch Alice's projects
+    service::workflows::set(
+        &conn,
+        &bob.user.id,
+        workflow(
+            None,
+            vec![status(
+                "Mine",
+                false,
+                &["Inbox", "Planned", "Progress", "Done"],
+            )],
+            Vec::new(),
+        ),
+    )
+    .unwrap();
+    assert_eq!(names(&conn, id, Some(&work.id)).len(), 4);
+}
+
+#[test]
+fn merged_statuses_go_below_the_column_they_join() {
+    let conn = memory_db();
+    let alice = user(&conn, "Alice");
+    let id = alice.user.id.as_str();
+    let work = area(&conn, id, "Work");
+    let ids: Vec<String> = ["Launch", "Hiring", "Budget", "Offsite"]
+        .iter()
+        .map(|name| project(&conn, id, &work.id, name).id)
+        .collect();
+    service::projects::move_to(&conn, id, &ids[2], "Planned", None, None).unwrap();
+    service::projects::move_to(&conn, id, &ids[3], "Planned", None, None).unwrap();
+    // Interleaved with, and colliding with, the Inbox positions
+    for (project_id, position) in ids.iter().zip([1024.0, 3072.0, 2048.0, 1024.0]) {
+        conn.execute(
+            "UPDATE projects SET position = ?1 WHERE id = ?2",
+            rusqlite::params![position, project_id],
+        )
+        .unwrap();
+    }
+
+    service::workflows::set(
+        &conn,
+        id,
+        workflow(
+            None,
+            vec![
+                status("Ideas", false, &["Inbox", "Planned"]),
+                status("Drafting", false, &["Progress"]),
+                status("Done", true, &[]),
+            ],
+            Vec::new(),
+        ),
+    )
+    .unwrap();
+
+    let mut stmt = conn
+        .prepare("SELECT title, position FROM projects WHERE status = 'Ideas' ORDER BY position")
+        .unwrap();
+    let column: Vec<(String, f64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let titles: Vec<&str> = column.iter().map(|(title, _)| title.as_str()).collect();
+    assert_eq!(titles, ["Launch", "Hiring", "Offsite", "Budget"]);
+    assert!(
+        column.windows(2).all(|pair| pair[0].1 < pair[1].1),
+        "{column:?}"
+    );
+}
@@ -25,6 +25,7 @@ import {
     AlertDialogHeader,
     AlertDialogTitle,
 } from "@/components/ui/alert-dialog";
+import { errorMessage } from "@/lib/errors";
 
 // Helper to safely invoke Tauri commands
 async function tauriInvoke<T = any>(cmd: string, args?: any): Promise<T> {
@@ -147,7 +148,7 @@ export default function AccountPage() {
             }
         } catch (err) {
             console.error("Update user error:", err);
-            setError(err instanceof Error ? err.message : "Failed to update account");
+            setError(errorMessage(err, "Failed to update account"));
         } finally {
             setSaving(false);
         }
@@ -209,7 +210,7 @@ export default function AccountPage() {
             }
         } catch (err) {
             console.error("Password change error:", err);
-            setPasswordError(err instanceof Error ? err.message : "Failed to change password");
+            setPasswordError(errorMessage(err, "Failed to change password"));
         } finally {
             setChangingPassword(false);
         }
@@ -1,3 +1,8 @@
+"use client";
+
+import * as React from "react";
+import { format } from "date-fns";
+import { ArchiveRestore, Folder, FolderKanban, Loader2 } from "lucide-react";
 import {
   Breadcrumb,
   BreadcrumbItem,
@@ -6,20 +11,191 @@ import {
   BreadcrumbPage,
   BreadcrumbSeparator,
 } from "@/components/ui/breadcrumb";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { errorMessage } from "@/lib/errors";
+
+async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  return await invoke<T>(cmd, args);
+}
+
+// Matches ArchiveListing in src-tauri/src/archive.rs
+interface ArchivedArea {
+  id: string;
+  name: string;
+  archived_at: number | null;
+}
+
+interface ArchivedProject {
+  id: string;
+  area_id: string;
+  area_name?: string | null;
+  title: string;
+  status: string;
+  // Null when the project is only hidden because its area is archived
+  archived_at: number | null;
+}
+
+const AUTO_ARCHIVE_OPTIONS = [
+  { value: "", label: "Never" },
+  { value: "7", label: "After 1 week" },
+  { value: "30", label: "After 30 days" },
+  { value: "90", label: "After 90 days" },
+];
+
 export default function Archive() {
+  const [areas, setAreas] = React.useState<ArchivedArea[]>([]);
+  const [projects, setProjects] = React.useState<ArchivedProject[]>([]);
+  const [autoArchiveDays, setAutoArchiveDays] = React.useState("");
+  const [loading, setLoading] = React.useState(true);
+  const [error, setError] = React.useState<string | null>(null);
+
+  const fetchArchive = React.useCallback(async () => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    try {
+      const response = JSON.parse(await tauriInvoke<string>("get_archive", { token }));
+      if (response.success) {
+        setAreas(response.areas || []);
+        setProjects(response.projects || []);
+        setAutoArchiveDays(response.auto_archive_days ? String(response.auto_archive_days) : "");
+      }
+    } catch (err) {
+      console.error("Error fetching archive:", err);
+      setError(errorMessage(err, "Failed to load the archive"));
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  React.useEffect(() => {
+    fetchArchive();
+  }, [fetchArchive]);
+
+  const restore = async (command: "unarchive_area" | "unarchive_project", id: string) => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    setError(null);
+    try {
+      await tauriInvoke<string>(command, { token, id });
+      await fetchArchive();
+    } catch (err) {
+      setError(errorMessage(err, "Failed to restore"));
+    }
+  };
+
+  const changeAutoArchive = async (value: string) => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    setError(null);
+    try {
+      await tauriInvoke<string>("set_auto_archive", {
+        token,
+        days: value === "" ? null : Number(value),
+      });
+      setAutoArchiveDays(value);
+    } catch (err) {
+      setError(errorMessage(err, "Failed to save the setting"));
+    }
+  };
+
+  const archivedOn = (archivedAt: number | null) =>
+    archivedAt ? `Archived ${format(new Date(archivedAt * 1000), "d MMM yyyy")}` : "Area archived";
+
   return (
-    <Breadcrumb>
-      <BreadcrumbList>
-        <BreadcrumbItem>
-          <BreadcrumbLink href="/dashboard">Dashboard</BreadcrumbLink>
-        </BreadcrumbItem>
-        <BreadcrumbSeparator />
-        <BreadcrumbItem>
-          <BreadcrumbPage>
-            <BreadcrumbLink href="/dashboard/archive">Archive</BreadcrumbLink>
-          </BreadcrumbPage>
-        </BreadcrumbItem>
-      </BreadcrumbList>
-    </Breadcrumb>
+    <div className="flex flex-col gap-6">
+      <Breadcrumb>
+        <BreadcrumbList>
+          <BreadcrumbItem>
+            <BreadcrumbLink href="/dashboard">Dashboard</BreadcrumbLink>
+          </BreadcrumbItem>
+          <BreadcrumbSeparator />
+          <BreadcrumbItem>
+            <BreadcrumbPage>
+              <BreadcrumbLink href="/dashboard/archive">Archive</BreadcrumbLink>
+            </BreadcrumbPage>
+          </BreadcrumbItem>
+        </BreadcrumbList>
+      </Breadcrumb>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>Automatic archiving</CardTitle>
+          <CardDescription>Archive projects once they have been done for a while.</CardDescription>
+        </CardHeader>
+        <CardContent>
+          <select
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+            value={autoArchiveDays}
+            onChange={(e) => changeAutoArchive(e.target.value)}
+          >
+            {/* Keep a value set elsewhere selectable */}
+            {!AUTO_ARCHIVE_OPTIONS.some((o) => o.value === autoArchiveDays) && (
+              <option value={autoArchiveDays}>After {autoArchiveDays} days</option>
+            )}
+            {AUTO_ARCHIVE_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+        </CardContent>
+      </Card>
+
+      {error && (
+        <div className="text-sm text-destructive bg-destructive/10 p-2 rounded">{error}</div>
+      )}
+
+      {loading ? (
+        <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+      ) : areas.length === 0 && projects.length === 0 ? (
+        <p className="text-sm text-muted-foreground">Nothing is archived.</p>
+      ) : (
+        <div className="grid gap-6 md:grid-cols-2">
+          <section className="flex flex-col gap-2">
+            <h2 className="font-semibold">Areas</h2>
+            {areas.map((area) => (
+              <Card key={area.id} className="flex-row items-center gap-3 p-4">
+                <Folder className="h-5 w-5 text-primary" />
+                <div className="min-w-0 flex-1">
+                  <div className="truncate font-medium">{area.name}</div>
+                  <div className="text-sm text-muted-foreground">{archivedOn(area.archived_at)}</div>
+                </div>
+                <Button size="sm" variant="outline" onClick={() => restore("unarchive_area", area.id)}>
+                  <ArchiveRestore className="mr-2 h-4 w-4" />
+                  Restore
+                </Button>
+              </Card>
+            ))}
+          </section>
+          <section className="flex flex-col gap-2">
+            <h2 className="font-semibold">Projects</h2>
+            {projects.map((project) => (
+              <Card key={project.id} className="flex-row items-center gap-3 p-4">
+                <FolderKanban className="h-5 w-5 text-primary" />
+                <div className="min-w-0 flex-1">
+                  <div className="truncate font-medium">{project.title}</div>
+                  <div className="text-sm text-muted-foreground">
+                    {project.area_name ? `${project.area_name} · ` : ""}
+                    {archivedOn(project.archived_at)}
+                  </div>
+                </div>
+                {project.archived_at && (
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={() => restore("unarchive_project", project.id)}
+                  >
+                    <ArchiveRestore className="mr-2 h-4 w-4" />
+                    Restore
+                  </Button>
+                )}
+              </Card>
+            ))}
+          </section>
+        </div>
+      )}
+    </div>
   );
 }
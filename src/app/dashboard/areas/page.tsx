@@ -13,6 +13,7 @@ import {
   BreadcrumbSeparator,
 } from "@/components/ui/breadcrumb";
 import { Loader2 } from "lucide-react";
+import { errorMessage } from "@/lib/errors";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   try {
@@ -57,8 +58,7 @@ export default function Areas() {
       setAreas(areasData);
     } catch (err: unknown) {
       console.error("Error fetching areas:", err);
-      const errorMessage = err instanceof Error ? err.message : "Failed to fetch areas";
-      setError(errorMessage);
+      setError(errorMessage(err, "Failed to fetch areas"));
     } finally {
       setLoading(false);
     }
@@ -80,8 +80,7 @@ export default function Areas() {
       setSelectedArea(areaData);
     } catch (err: unknown) {
       console.error("Error fetching area:", err);
-      const errorMessage = err instanceof Error ? err.message : "Failed to fetch area";
-      setError(errorMessage);
+      setError(errorMessage(err, "Failed to fetch area"));
     }
   }, []);
 
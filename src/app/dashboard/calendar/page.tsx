@@ -37,6 +37,9 @@ interface Event {
   end_time?: number | null;
   location?: string | null;
   all_day: boolean;
+  day: string;
+  recurrence_rule?: string | null;
+  occurrence_start?: number | null;
   created_at: number;
   updated_at: number;
 }
@@ -105,9 +108,13 @@ export default function CalendarPage() {
   }, [fetchEvents, fetchProjects]);
 
   const getEventsForDate = (date: Date): Event[] => {
+    // Multi-day events show on every day they cover
+    const day = format(date, "yyyy-MM-dd");
     return events.filter((event) => {
-      const eventDate = new Date(event.start_time * 1000);
-      return isSameDay(eventDate, date);
+      const lastDay = event.end_time
+        ? format(new Date(event.end_time * 1000), "yyyy-MM-dd")
+        : event.day;
+      return event.day <= day && day <= lastDay;
     });
   };
 
@@ -230,7 +237,7 @@ export default function CalendarPage() {
                     <div className="space-y-1">
                       {dayEvents.slice(0, 2).map((event) => (
                         <div
-                          key={event.id}
+                          key={`${event.id}-${event.occurrence_start ?? ""}`}
                           className="text-xs bg-blue-500/20 text-blue-700 dark:text-blue-300 px-1 rounded truncate"
                           title={event.title}
                         >
@@ -277,7 +284,7 @@ export default function CalendarPage() {
                     <div className="space-y-2">
                       {selectedDateEvents.map((event) => (
                         <div
-                          key={event.id}
+                          key={`${event.id}-${event.occurrence_start ?? ""}`}
                           className="p-2 border rounded-md bg-blue-50 dark:bg-blue-950"
                         >
                           <div className="font-medium text-sm">{event.title}</div>
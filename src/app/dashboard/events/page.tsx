@@ -2,7 +2,7 @@
 
 import * as React from "react";
 import { useState, useCallback, useEffect } from "react";
-import { Plus, Calendar as CalendarIcon, MapPin, Trash2, Edit2, Clock } from "lucide-react";
+import { Plus, Calendar as CalendarIcon, MapPin, Trash2, Edit2, Clock, Repeat, Upload, Download } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import {
@@ -26,6 +26,7 @@ import {
 import { NewEventDialog } from "@/components/events/NewEventDialog";
 import { NativeSelect, NativeSelectOption } from "@/components/ui/native-select";
 import { format } from "date-fns";
+import { errorMessage, hasErrorCode } from "@/lib/errors";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   try {
@@ -47,6 +48,11 @@ interface Event {
   end_time?: number | null;
   location?: string | null;
   all_day: boolean;
+  time_zone?: string | null;
+  day: string;
+  recurrence_rule?: string | null;
+  reminders?: number[];
+  occurrence_start?: number | null;
   created_at: number;
   updated_at: number;
 }
@@ -142,9 +148,55 @@ export default function EventsPage() {
     }
   };
 
+  // The user closing the file dialog is not an error
+  const isCancelled = (err: unknown) =>
+    hasErrorCode(err, "VALIDATION") && errorMessage(err, "").includes("cancelled");
+
+  const handleExport = async () => {
+    try {
+      const token = localStorage.getItem("auth_token");
+      if (!token) return;
+
+      // Exports what the page shows; rfd shows the save dialog
+      await tauriInvoke<string>("export_events_ics", {
+        token,
+        start_date: null,
+        end_date: null,
+        project_id: filterProjectId || null,
+        path: null,
+      });
+    } catch (err) {
+      if (isCancelled(err)) return;
+      console.error("Error exporting events:", err);
+      alert(errorMessage(err, "Failed to export events"));
+    }
+  };
+
+  const handleImport = async () => {
+    try {
+      const token = localStorage.getItem("auth_token");
+      if (!token) return;
+
+      // Imports into the selected project, if any; rfd shows the open dialog
+      const responseJson = await tauriInvoke<string>("import_events_ics", {
+        token,
+        project_id: filterProjectId || null,
+        path: null,
+      });
+      const response = JSON.parse(responseJson);
+      if (response.success) {
+        fetchEvents();
+      }
+    } catch (err) {
+      if (isCancelled(err)) return;
+      console.error("Error importing events:", err);
+      alert(errorMessage(err, "Failed to import events"));
+    }
+  };
+
+  // Bucketed by the day the backend computed in the user's zone
   const groupedEvents = events.reduce((acc, event) => {
-    const date = new Date(event.start_time * 1000);
-    const dateKey = format(date, "yyyy-MM-dd");
+    const dateKey = event.day;
     if (!acc[dateKey]) {
       acc[dateKey] = [];
     }
@@ -187,6 +239,14 @@ export default function EventsPage() {
               </NativeSelectOption>
             ))}
           </NativeSelect>
+          <Button variant="outline" onClick={handleImport}>
+            <Upload className="mr-2 h-4 w-4" />
+            Import
+          </Button>
+          <Button variant="outline" onClick={handleExport}>
+            <Download className="mr-2 h-4 w-4" />
+            Export
+          </Button>
           <NewEventDialog
             trigger={
               <Button>
@@ -243,7 +303,7 @@ export default function EventsPage() {
                   <div className="space-y-3">
                     {dateEvents.map((event) => (
                       <div
-                        key={event.id}
+                        key={`${event.id}-${event.occurrence_start ?? ""}`}
                         className="flex items-start justify-between p-4 border rounded-lg hover:bg-accent/50 transition-colors"
                       >
                         <div className="flex-1">
@@ -283,6 +343,12 @@ export default function EventsPage() {
                                     📁 {event.project_name}
                                   </span>
                                 )}
+                                {event.recurrence_rule && (
+                                  <span className="flex items-center gap-1">
+                                    <Repeat className="h-3 w-3" />
+                                    Repeats
+                                  </span>
+                                )}
                               </div>
                             </div>
                           </div>
@@ -334,7 +400,7 @@ export default function EventsPage() {
           <AlertDialogHeader>
             <AlertDialogTitle>Delete Event</AlertDialogTitle>
             <AlertDialogDescription>
-              Are you sure you want to delete this event? This action cannot be undone.
+              Are you sure you want to delete this event? It will be moved to the trash, where you can restore it.
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
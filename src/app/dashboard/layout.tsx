@@ -8,6 +8,7 @@ import AppSideBar from "@/components/layout/AppSidebar";
 import Navbar from "@/components/layout/AppNavbar";
 import { ThemeProvider } from "@/components/providers/ThemeProvider";
 import { SidebarInset, SidebarProvider } from "@/components/ui/sidebar";
+import { ReminderToasts } from "@/components/events/ReminderToasts";
 
 // Helper to safely invoke Tauri commands
 async function tauriInvoke<T = any>(cmd: string, args?: any): Promise<T> {
@@ -43,6 +44,7 @@ export default function DashboardLayout({
   const router = useRouter();
   const [defaultOpen, setDefaultOpen] = useState(true);
   const [isAuthenticated, setIsAuthenticated] = useState<boolean | null>(null);
+  const [userId, setUserId] = useState<string | null>(null);
 
   useEffect(() => {
     // Check authentication
@@ -60,7 +62,14 @@ export default function DashboardLayout({
         const userInfo = responseJson && responseJson !== "null" ? JSON.parse(responseJson) : null;
 
         if (userInfo && userInfo.id) {
+          // Days and recurrences are computed in the zone the user is currently in,
+          // so save it before any page loads events
+          await tauriInvoke<string>("set_time_zone", {
+            token,
+            time_zone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+          }).catch((err) => console.error("Error saving time zone:", err));
           // Token is valid
+          setUserId(userInfo.id);
           setIsAuthenticated(true);
         } else {
           // Token is invalid, clear and redirect
@@ -121,6 +130,9 @@ export default function DashboardLayout({
             {/* Page Content */}
             <div>{children}</div>
           </SidebarInset>
+
+          {/* Reminders from the background scheduler */}
+          {userId && <ReminderToasts userId={userId} />}
         </SidebarProvider>
       </ThemeProvider>
     </div>
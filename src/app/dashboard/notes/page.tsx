@@ -1,3 +1,7 @@
+"use client";
+
+import * as React from "react";
+import { Box, Database, Loader2, Plus, Trash2 } from "lucide-react";
 import {
   Breadcrumb,
   BreadcrumbItem,
@@ -6,22 +10,193 @@ import {
   BreadcrumbPage,
   BreadcrumbSeparator,
 } from "@/components/ui/breadcrumb";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
+import { errorMessage } from "@/lib/errors";
+
+async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  return await invoke<T>(cmd, args);
+}
+
+// Matches NoteInfo in src-tauri/src/notes.rs
+interface Note {
+  id: string;
+  project_id?: string | null;
+  project_title?: string | null;
+  area_id?: string | null;
+  area_name?: string | null;
+  title: string;
+  description?: string | null;
+  content?: string | null;
+  status: "Inbox" | "Draft" | "Final";
+  created_at: number;
+  updated_at: number;
+}
+
+const STATUSES = ["Inbox", "Draft", "Final"] as const;
+
 export default function Notes() {
+  const [notes, setNotes] = React.useState<Note[]>([]);
+  const [title, setTitle] = React.useState("");
+  const [loading, setLoading] = React.useState(true);
+  const [error, setError] = React.useState<string | null>(null);
+
+  const fetchNotes = React.useCallback(async () => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    try {
+      const response = JSON.parse(await tauriInvoke<string>("get_notes", { token, json: "{}" }));
+      setNotes(response.notes || []);
+    } catch (err) {
+      console.error("Error fetching notes:", err);
+      setError(errorMessage(err, "Failed to load notes"));
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  React.useEffect(() => {
+    fetchNotes();
+  }, [fetchNotes]);
+
+  const run = async (cmd: string, args: Record<string, unknown>, fallback: string) => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    setError(null);
+    try {
+      await tauriInvoke<string>(cmd, { token, ...args });
+      await fetchNotes();
+    } catch (err) {
+      setError(errorMessage(err, fallback));
+    }
+  };
+
+  const addNote = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!title.trim()) return;
+    await run(
+      "create_note",
+      {
+        json: JSON.stringify({
+          project_id: null,
+          area_id: null,
+          title,
+          description: null,
+          content: null,
+          status: null,
+        }),
+      },
+      "Failed to add the note"
+    );
+    setTitle("");
+  };
+
+  const place = (note: Note) =>
+    note.project_title
+      ? `${note.area_name} · ${note.project_title}`
+      : note.area_name || "Unfiled";
+
   return (
-    <Breadcrumb>
-      <BreadcrumbList>
-        <BreadcrumbItem>
-          <BreadcrumbLink href="/dashboard">Dashboard</BreadcrumbLink>
-        </BreadcrumbItem>
-        <BreadcrumbSeparator />
-        <BreadcrumbItem>
-          <BreadcrumbPage>
+    <div className="flex flex-col gap-6">
+      <Breadcrumb>
+        <BreadcrumbList>
+          <BreadcrumbItem>
+            <BreadcrumbLink href="/dashboard">Dashboard</BreadcrumbLink>
+          </BreadcrumbItem>
+          <BreadcrumbSeparator />
+          <BreadcrumbItem>
             <BreadcrumbPage>
-              <BreadcrumbLink href="/dashboard/noes">Notes</BreadcrumbLink>
+              <BreadcrumbLink href="/dashboard/notes">Notes</BreadcrumbLink>
             </BreadcrumbPage>
-          </BreadcrumbPage>
-        </BreadcrumbItem>
-      </BreadcrumbList>
-    </Breadcrumb>
+          </BreadcrumbItem>
+        </BreadcrumbList>
+      </Breadcrumb>
+
+      <form onSubmit={addNote} className="flex max-w-xl items-center gap-2">
+        <Input value={title} onChange={(e) => setTitle(e.target.value)} placeholder="Jot down a note" />
+        <Button type="submit" disabled={!title.trim()}>
+          <Plus className="mr-2 h-4 w-4" />
+          Add
+        </Button>
+      </form>
+
+      {error && (
+        <div className="text-sm text-destructive bg-destructive/10 p-2 rounded">{error}</div>
+      )}
+
+      {loading ? (
+        <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+      ) : (
+        <div className="grid gap-4 md:grid-cols-3">
+          {STATUSES.map((status) => (
+            <div key={status} className="flex flex-col gap-2">
+              <h2 className="text-sm font-medium text-muted-foreground">{status}</h2>
+              {notes
+                .filter((note) => note.status === status)
+                .map((note) => (
+                  <Card key={note.id} className="gap-2 py-4">
+                    <CardHeader className="px-4">
+                      <CardTitle className="text-base">{note.title}</CardTitle>
+                      <CardDescription>{place(note)}</CardDescription>
+                    </CardHeader>
+                    <CardContent className="flex flex-col gap-3 px-4">
+                      {note.description && <p className="text-sm">{note.description}</p>}
+                      <div className="flex flex-wrap items-center gap-1">
+                        <select
+                          className="rounded-md border bg-background px-2 py-1 text-xs"
+                          value={note.status}
+                          onChange={(e) =>
+                            run("move_note", { id: note.id, new_status: e.target.value }, "Failed to move the note")
+                          }
+                        >
+                          {STATUSES.map((s) => (
+                            <option key={s} value={s}>
+                              {s}
+                            </option>
+                          ))}
+                        </select>
+                        {note.project_id && (
+                          <Button
+                            size="sm"
+                            variant="ghost"
+                            title="Turn into a resource"
+                            onClick={() =>
+                              run("promote_note_to_resource", { id: note.id, project_id: null }, "Failed to turn the note into a resource")
+                            }
+                          >
+                            <Database className="h-4 w-4" />
+                          </Button>
+                        )}
+                        {(note.area_id || note.project_id) && (
+                          <Button
+                            size="sm"
+                            variant="ghost"
+                            title="Turn into a project"
+                            onClick={() =>
+                              run("promote_note_to_project", { id: note.id, area_id: null }, "Failed to turn the note into a project")
+                            }
+                          >
+                            <Box className="h-4 w-4" />
+                          </Button>
+                        )}
+                        <Button
+                          size="sm"
+                          variant="ghost"
+                          title="Delete"
+                          onClick={() => run("delete_note", { id: note.id }, "Failed to delete the note")}
+                        >
+                          <Trash2 className="h-4 w-4" />
+                        </Button>
+                      </div>
+                    </CardContent>
+                  </Card>
+                ))}
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
   );
 }
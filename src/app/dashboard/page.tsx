@@ -81,24 +81,13 @@ export default function Dashboard() {
 
       // Fetch resources count
       try {
-        const resourcesJson = await tauriInvoke<string>("get_resources", {
+        // Only the total is needed, so ask for the smallest page
+        const resourcesPage = await tauriInvoke<{ total: number }>("list_resources", {
           token,
           project_id: null,
+          limit: 1,
         });
-        let resourcesData: unknown[] = [];
-        if (typeof resourcesJson === "string") {
-          if (resourcesJson.trim() === "") {
-            resourcesData = [];
-          } else {
-            const parsed = JSON.parse(resourcesJson);
-            resourcesData = Array.isArray(parsed) ? parsed : [];
-          }
-        } else if (Array.isArray(resourcesJson)) {
-          resourcesData = resourcesJson;
-        } else {
-          resourcesData = [];
-        }
-        setResourcesCount(resourcesData.length);
+        setResourcesCount(resourcesPage.total);
       } catch (err) {
         console.error("Error fetching resources:", err);
       }
@@ -26,6 +26,9 @@ import {
     type JSONContent,
 } from "@/components/ui/shadcn-io/editor";
 import { EditorContent, useCurrentEditor } from "@tiptap/react";
+import { errorMessage } from "@/lib/errors";
+import { RevisionHistoryDialog } from "@/components/resources/RevisionHistoryDialog";
+import { Backlinks } from "@/components/resources/Backlinks";
 
 function EditorContentWrapper({ className }: { className?: string }) {
     const { editor } = useCurrentEditor();
@@ -45,8 +48,10 @@ async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Prom
 
 interface Resource {
     id: string;
-    project_id: string;
+    project_id?: string | null;
     project_name?: string | null;
+    area_id?: string | null;
+    area_name?: string | null;
     name: string;
     content?: string | null;
     file_type?: string | null;
@@ -65,6 +70,8 @@ export default function ResourceEditor() {
     const [loading, setLoading] = React.useState(true);
     const [saving, setSaving] = React.useState(false);
     const [error, setError] = React.useState<string | null>(null);
+    // Bumped to remount the editor when its content is replaced, e.g. by restoring a revision
+    const [editorKey, setEditorKey] = React.useState(0);
 
     React.useEffect(() => {
         const fetchResource = async () => {
@@ -113,7 +120,7 @@ export default function ResourceEditor() {
                 }
             } catch (err) {
                 console.error("Error fetching resource:", err);
-                setError(err instanceof Error ? err.message : "Failed to fetch resource");
+                setError(errorMessage(err, "Failed to fetch resource"));
             } finally {
                 setLoading(false);
             }
@@ -168,7 +175,7 @@ export default function ResourceEditor() {
             }
         } catch (err) {
             console.error("Error saving resource:", err);
-            setError(err instanceof Error ? err.message : "An error occurred");
+            setError(errorMessage(err, "An error occurred"));
         } finally {
             setSaving(false);
         }
@@ -219,17 +226,34 @@ export default function ResourceEditor() {
                         </Button>
                         <div>
                             <h1 className="text-3xl font-bold">{resource.name}</h1>
-                            {resource.project_name && (
+                            {resource.project_name ? (
                                 <p className="text-sm text-muted-foreground">
                                     Project: {resource.project_name}
                                 </p>
+                            ) : resource.area_name && (
+                                <p className="text-sm text-muted-foreground">
+                                    Area: {resource.area_name}
+                                </p>
                             )}
                         </div>
                     </div>
-                    <Button onClick={handleSave} disabled={saving}>
-                        <Save className="mr-2 h-4 w-4" />
-                        {saving ? "Saving..." : "Save"}
-                    </Button>
+                    <div className="flex items-center gap-2">
+                        <RevisionHistoryDialog
+                            resourceId={resource.id}
+                            onRestore={(restored) => {
+                                try {
+                                    setContent(restored ? JSON.parse(restored) : null);
+                                } catch {
+                                    setContent(null);
+                                }
+                                setEditorKey((key) => key + 1);
+                            }}
+                        />
+                        <Button onClick={handleSave} disabled={saving}>
+                            <Save className="mr-2 h-4 w-4" />
+                            {saving ? "Saving..." : "Save"}
+                        </Button>
+                    </div>
                 </div>
 
                 {error && (
@@ -240,6 +264,7 @@ export default function ResourceEditor() {
 
                 <div className="border rounded-lg bg-background shadow-sm relative overflow-hidden">
                     <EditorProvider
+                        key={editorKey}
                         className="w-full"
                         content={content || undefined}
                         onUpdate={({ editor }) => {
@@ -298,6 +323,10 @@ export default function ResourceEditor() {
                         </div>
                     </EditorProvider>
                 </div>
+
+                <div className="mt-6">
+                    <Backlinks kind="resource" id={resource.id} refreshKey={resource.updated_at} />
+                </div>
             </section>
         </main>
     );
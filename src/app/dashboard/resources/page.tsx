@@ -7,6 +7,7 @@ import { Card, CardContent } from "@/components/ui/card";
 import { NewResourceDialog } from "@/components/resources/NewResourceDialog";
 import { ResourceCard } from "@/components/resources/ResourceCard";
 import ResourceEditor from "./editor/page";
+import { errorMessage } from "@/lib/errors";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   try {
@@ -18,77 +19,87 @@ async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Prom
   }
 }
 
-interface Resource {
+interface ResourceSummary {
   id: string;
-  project_id: string;
+  project_id?: string | null;
   project_name?: string | null;
+  area_id?: string | null;
+  area_name?: string | null;
   name: string;
-  content?: string | null;
+  snippet?: string | null;
+  has_file: boolean;
   file_type?: string | null;
   file_size?: number | null;
   created_at: number;
   updated_at: number;
 }
 
+interface ResourceSummaryPage {
+  resources: ResourceSummary[];
+  total: number;
+  limit: number;
+  offset: number;
+}
+
+const PAGE_SIZE = 50;
+
 export default function Resources() {
   const searchParams = useSearchParams();
   const resourceId = searchParams.get("resource");
-  const [resources, setResources] = React.useState<Resource[]>([]);
+  // ?area=<id> lists an area's resources, including its projects'; each ?tag=<id> narrows further
+  const areaId = searchParams.get("area");
+  const tagKey = searchParams.getAll("tag").join(",");
+  const [resources, setResources] = React.useState<ResourceSummary[]>([]);
+  const [total, setTotal] = React.useState(0);
   const [loading, setLoading] = React.useState(true);
+  const [loadingMore, setLoadingMore] = React.useState(false);
   const [error, setError] = React.useState<string | null>(null);
 
+  // Listings carry summaries only; bodies are fetched when a resource is opened
+  const fetchPage = React.useCallback(async (offset: number) => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) {
+      throw new Error("Not authenticated");
+    }
+    return await tauriInvoke<ResourceSummaryPage>("list_resources", {
+      token,
+      project_id: null,
+      area_id: areaId,
+      tag_ids: tagKey ? tagKey.split(",") : null,
+      sort_by: "created_at",
+      sort_order: "desc",
+      limit: PAGE_SIZE,
+      offset,
+    });
+  }, [areaId, tagKey]);
+
   const fetchResources = React.useCallback(async () => {
     try {
-      const token = localStorage.getItem("auth_token");
-      if (!token) {
-        setError("Not authenticated");
-        return;
-      }
-
-      const responseJson = await tauriInvoke<string>("get_resources", {
-        token,
-        project_id: null,
-      });
-      try {
-        // Handle empty or null responses
-        if (!responseJson) {
-          setResources([]);
-          setError(null);
-          return;
-        }
-        // Check if it's already an array (parsed by Tauri)
-        if (Array.isArray(responseJson)) {
-          setResources(responseJson);
-          setError(null);
-          return;
-        }
-        // If it's a string, parse it
-        if (typeof responseJson === "string") {
-          if (responseJson.trim() === "") {
-            setResources([]);
-            setError(null);
-            return;
-          }
-          const resourcesData = JSON.parse(responseJson);
-          setResources(resourcesData);
-          setError(null);
-        } else {
-          // If it's already an object/array, use it directly
-          setResources(responseJson as Resource[]);
-          setError(null);
-        }
-      } catch (err) {
-        console.error("Error parsing resources:", err);
-        setError("Failed to parse resources data");
-        setResources([]);
-      }
+      const page = await fetchPage(0);
+      setResources(page.resources);
+      setTotal(page.total);
+      setError(null);
     } catch (err) {
       console.error("Error fetching resources:", err);
-      setError(err instanceof Error ? err.message : "Failed to fetch resources");
+      setError(errorMessage(err, "Failed to fetch resources"));
     } finally {
       setLoading(false);
     }
-  }, []);
+  }, [fetchPage]);
+
+  const loadMore = async () => {
+    setLoadingMore(true);
+    try {
+      const page = await fetchPage(resources.length);
+      setResources((current) => [...current, ...page.resources]);
+      setTotal(page.total);
+    } catch (err) {
+      console.error("Error fetching resources:", err);
+      setError(errorMessage(err, "Failed to fetch resources"));
+    } finally {
+      setLoadingMore(false);
+    }
+  };
 
   React.useEffect(() => {
     fetchResources();
@@ -154,15 +165,24 @@ export default function Resources() {
             </CardContent>
           </Card>
         ) : (
-          <div className="grid gap-4 md:grid-cols-2 lg:grid-cols-3">
-            {resources.map((resource) => (
-              <ResourceCard
-                key={resource.id}
-                resource={resource}
-                onUpdate={fetchResources}
-              />
-            ))}
-          </div>
+          <>
+            <div className="grid gap-4 md:grid-cols-2 lg:grid-cols-3">
+              {resources.map((resource) => (
+                <ResourceCard
+                  key={resource.id}
+                  resource={resource}
+                  onUpdate={fetchResources}
+                />
+              ))}
+            </div>
+            {resources.length < total && (
+              <div className="flex justify-center mt-6">
+                <Button variant="outline" onClick={loadMore} disabled={loadingMore}>
+                  {loadingMore ? "Loading..." : `Load more (${total - resources.length} left)`}
+                </Button>
+              </div>
+            )}
+          </>
         )}
       </section>
     </main>
@@ -0,0 +1,236 @@
+"use client";
+
+import * as React from "react";
+import { Calendar, Database, Folder, FolderKanban, GitMerge, Loader2, Trash2 } from "lucide-react";
+import {
+  Breadcrumb,
+  BreadcrumbItem,
+  BreadcrumbLink,
+  BreadcrumbList,
+  BreadcrumbPage,
+  BreadcrumbSeparator,
+} from "@/components/ui/breadcrumb";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
+import { errorMessage } from "@/lib/errors";
+
+async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  return await invoke<T>(cmd, args);
+}
+
+// Matches TagInfo and TaggedItem in src-tauri/src/tags.rs
+interface Tag {
+  id: string;
+  name: string;
+  color?: string | null;
+  item_count: number;
+  created_at: number;
+  updated_at: number;
+}
+
+interface TaggedItem {
+  kind: "area" | "project" | "resource" | "event";
+  id: string;
+  title: string;
+  parent_title?: string | null;
+}
+
+const ICONS = {
+  area: Folder,
+  project: FolderKanban,
+  resource: Database,
+  event: Calendar,
+};
+
+export default function Tags() {
+  const [tags, setTags] = React.useState<Tag[]>([]);
+  const [selected, setSelected] = React.useState<Tag | null>(null);
+  const [items, setItems] = React.useState<TaggedItem[]>([]);
+  const [name, setName] = React.useState("");
+  const [color, setColor] = React.useState("#6b7280");
+  const [mergeInto, setMergeInto] = React.useState("");
+  const [loading, setLoading] = React.useState(true);
+  const [error, setError] = React.useState<string | null>(null);
+
+  const fetchTags = React.useCallback(async () => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    try {
+      const response = JSON.parse(await tauriInvoke<string>("get_tags", { token }));
+      setTags(response.tags || []);
+    } catch (err) {
+      console.error("Error fetching tags:", err);
+      setError(errorMessage(err, "Failed to load tags"));
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  React.useEffect(() => {
+    fetchTags();
+  }, [fetchTags]);
+
+  const select = async (tag: Tag | null) => {
+    setSelected(tag);
+    setItems([]);
+    setMergeInto("");
+    if (!tag) return;
+    setName(tag.name);
+    setColor(tag.color || "#6b7280");
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    try {
+      const response = JSON.parse(await tauriInvoke<string>("get_tagged_items", { token, id: tag.id }));
+      setItems(response.items || []);
+    } catch (err) {
+      setError(errorMessage(err, "Failed to load the tagged items"));
+    }
+  };
+
+  // Run a change to the selected tag, then reload and select the tag it returns
+  const change = async (cmd: string, args: Record<string, unknown>, fallback: string) => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    setError(null);
+    try {
+      const response = JSON.parse(await tauriInvoke<string>(cmd, { token, ...args }));
+      await fetchTags();
+      await select(response.tag ?? null);
+    } catch (err) {
+      setError(errorMessage(err, fallback));
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-6">
+      <Breadcrumb>
+        <BreadcrumbList>
+          <BreadcrumbItem>
+            <BreadcrumbLink href="/dashboard">Dashboard</BreadcrumbLink>
+          </BreadcrumbItem>
+          <BreadcrumbSeparator />
+          <BreadcrumbItem>
+            <BreadcrumbPage>
+              <BreadcrumbLink href="/dashboard/tags">Tags</BreadcrumbLink>
+            </BreadcrumbPage>
+          </BreadcrumbItem>
+        </BreadcrumbList>
+      </Breadcrumb>
+
+      {error && (
+        <div className="text-sm text-destructive bg-destructive/10 p-2 rounded">{error}</div>
+      )}
+
+      {loading ? (
+        <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+      ) : tags.length === 0 ? (
+        <p className="text-sm text-muted-foreground">
+          No tags yet. Tag an area, project, resource or event to see it here.
+        </p>
+      ) : (
+        <div className="flex flex-wrap gap-2">
+          {tags.map((tag) => (
+            <button
+              key={tag.id}
+              onClick={() => select(tag)}
+              className={`flex items-center gap-2 rounded-full border px-3 py-1 text-sm hover:bg-muted ${
+                tag.id === selected?.id ? "bg-muted font-medium" : ""
+              }`}
+            >
+              <span className="h-2.5 w-2.5 rounded-full" style={{ background: tag.color || "#6b7280" }} />
+              {tag.name}
+              <span className="text-muted-foreground">{tag.item_count}</span>
+            </button>
+          ))}
+        </div>
+      )}
+
+      {selected && (
+        <Card>
+          <CardHeader>
+            <CardTitle>{selected.name}</CardTitle>
+            <CardDescription>
+              {selected.item_count === 1 ? "1 item" : `${selected.item_count} items`}
+            </CardDescription>
+          </CardHeader>
+          <CardContent className="flex flex-col gap-4">
+            <div className="flex flex-wrap items-center gap-2">
+              <Input className="max-w-xs" value={name} onChange={(e) => setName(e.target.value)} />
+              <input
+                type="color"
+                className="h-9 w-12 rounded-md border bg-background"
+                value={color}
+                onChange={(e) => setColor(e.target.value)}
+              />
+              <Button
+                variant="outline"
+                onClick={() =>
+                  change(
+                    "update_tag",
+                    { id: selected.id, json: JSON.stringify({ name, color }) },
+                    "Failed to save the tag"
+                  )
+                }
+              >
+                Save
+              </Button>
+            </div>
+            <div className="flex flex-wrap items-center gap-2">
+              <select
+                className="rounded-md border bg-background px-3 py-2 text-sm"
+                value={mergeInto}
+                onChange={(e) => setMergeInto(e.target.value)}
+              >
+                <option value="">Merge into…</option>
+                {tags
+                  .filter((tag) => tag.id !== selected.id)
+                  .map((tag) => (
+                    <option key={tag.id} value={tag.id}>
+                      {tag.name}
+                    </option>
+                  ))}
+              </select>
+              <Button
+                variant="outline"
+                disabled={!mergeInto}
+                onClick={() =>
+                  change(
+                    "merge_tags",
+                    { source_id: selected.id, target_id: mergeInto },
+                    "Failed to merge the tags"
+                  )
+                }
+              >
+                <GitMerge className="mr-2 h-4 w-4" />
+                Merge
+              </Button>
+              <Button
+                variant="destructive"
+                onClick={() => change("delete_tag", { id: selected.id }, "Failed to delete the tag")}
+              >
+                <Trash2 className="mr-2 h-4 w-4" />
+                Delete
+              </Button>
+            </div>
+            <div className="flex flex-col gap-2">
+              {items.map((item) => {
+                const Icon = ICONS[item.kind];
+                return (
+                  <div key={`${item.kind}-${item.id}`} className="flex items-center gap-3">
+                    <Icon className="h-4 w-4 text-primary" />
+                    <span className="truncate">{item.title}</span>
+                    {item.parent_title && (
+                      <span className="text-sm text-muted-foreground">{item.parent_title}</span>
+                    )}
+                  </div>
+                );
+              })}
+            </div>
+          </CardContent>
+        </Card>
+      )}
+    </div>
+  );
+}
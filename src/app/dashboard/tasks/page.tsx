@@ -1,14 +1,181 @@
+"use client";
+
+import * as React from "react";
+import { format } from "date-fns";
+import { CornerDownRight, Loader2, Plus, Trash2 } from "lucide-react";
 import {
   Breadcrumb,
   BreadcrumbItem,
   BreadcrumbLink,
   BreadcrumbList,
   BreadcrumbPage,
-  BreadcrumbSeparator,
 } from "@/components/ui/breadcrumb";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { errorMessage } from "@/lib/errors";
+import { cn } from "@/lib/utils";
+
+async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  return await invoke<T>(cmd, args);
+}
+
+interface Project {
+  id: string;
+  title: string;
+  task_count: number;
+  done_task_count: number;
+  progress?: number | null;
+}
+
+// Matches TaskInfo in src-tauri/src/tasks.rs
+interface Task {
+  id: string;
+  project_id: string;
+  parent_id?: string | null;
+  title: string;
+  notes?: string | null;
+  done: boolean;
+  due_date?: number | null;
+  priority?: string | null;
+  position: number;
+  completed_at?: number | null;
+  created_at: number;
+  updated_at: number;
+}
+
 export default function Tasks() {
+  const [projects, setProjects] = React.useState<Project[]>([]);
+  const [projectId, setProjectId] = React.useState("");
+  const [tasks, setTasks] = React.useState<Task[]>([]);
+  const [newTitle, setNewTitle] = React.useState("");
+  // Task the next new task goes under, if any
+  const [parentId, setParentId] = React.useState<string | null>(null);
+  const [loading, setLoading] = React.useState(true);
+  const [error, setError] = React.useState<string | null>(null);
+
+  const fetchProjects = React.useCallback(async () => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    try {
+      const list: Project[] = JSON.parse(
+        await tauriInvoke<string>("get_projects", { token, area_id: null })
+      );
+      setProjects(list);
+      setProjectId((current) => current || list[0]?.id || "");
+    } catch (err) {
+      setError(errorMessage(err, "Failed to load projects"));
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  const fetchTasks = React.useCallback(async () => {
+    const token = localStorage.getItem("auth_token");
+    if (!token || !projectId) return;
+    try {
+      const response = JSON.parse(
+        await tauriInvoke<string>("get_tasks", { token, project_id: projectId })
+      );
+      setTasks(response.tasks || []);
+    } catch (err) {
+      setError(errorMessage(err, "Failed to load tasks"));
+    }
+  }, [projectId]);
+
+  React.useEffect(() => {
+    fetchProjects();
+  }, [fetchProjects]);
+
+  React.useEffect(() => {
+    setParentId(null);
+    fetchTasks();
+  }, [fetchTasks]);
+
+  // Run a command, then reload the tasks and the project progress
+  const run = async (cmd: string, args: Record<string, unknown>, fallback: string) => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    setError(null);
+    try {
+      await tauriInvoke<string>(cmd, { token, ...args });
+      await Promise.all([fetchTasks(), fetchProjects()]);
+    } catch (err) {
+      setError(errorMessage(err, fallback));
+    }
+  };
+
+  const addTask = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!newTitle.trim()) return;
+    await run(
+      "create_task",
+      {
+        json: JSON.stringify({
+          project_id: projectId,
+          parent_id: parentId,
+          title: newTitle,
+          notes: null,
+          due_date: null,
+          priority: null,
+        }),
+      },
+      "Failed to add the task"
+    );
+    setNewTitle("");
+  };
+
+  const children = (parent: string | null) =>
+    tasks.filter((task) => (task.parent_id ?? null) === parent);
+
+  const renderTasks = (parent: string | null, depth: number): React.ReactNode =>
+    children(parent).map((task) => (
+      <React.Fragment key={task.id}>
+        <div
+          className="group flex items-center gap-3 rounded-md px-2 py-1.5 hover:bg-muted"
+          style={{ paddingLeft: `${depth * 1.5 + 0.5}rem` }}
+        >
+          <input
+            type="checkbox"
+            className="h-4 w-4"
+            checked={task.done}
+            onChange={(e) => run("set_task_done", { id: task.id, done: e.target.checked }, "Failed to update the task")}
+          />
+          <span className={cn("flex-1", task.done && "text-muted-foreground line-through")}>{task.title}</span>
+          {task.due_date && (
+            <span className="text-xs text-muted-foreground">
+              {format(new Date(task.due_date * 1000), "d MMM")}
+            </span>
+          )}
+          {task.priority && <span className="text-xs text-muted-foreground">{task.priority}</span>}
+          <Button
+            size="sm"
+            variant="ghost"
+            className="opacity-0 group-hover:opacity-100"
+            title="Add a subtask"
+            onClick={() => setParentId(task.id)}
+          >
+            <CornerDownRight className="h-4 w-4" />
+          </Button>
+          <Button
+            size="sm"
+            variant="ghost"
+            className="opacity-0 group-hover:opacity-100"
+            title="Delete"
+            onClick={() => run("delete_task", { id: task.id }, "Failed to delete the task")}
+          >
+            <Trash2 className="h-4 w-4" />
+          </Button>
+        </div>
+        {renderTasks(task.id, depth + 1)}
+      </React.Fragment>
+    ));
+
+  const project = projects.find((p) => p.id === projectId);
+  const parent = tasks.find((task) => task.id === parentId);
+
   return (
-    <div className="ml-4">
+    <div className="ml-4 flex flex-col gap-6">
       <Breadcrumb>
         <BreadcrumbList className="text-2xl">
           <BreadcrumbItem>
@@ -18,6 +185,56 @@ export default function Tasks() {
           </BreadcrumbItem>
         </BreadcrumbList>
       </Breadcrumb>
+
+      {error && (
+        <div className="text-sm text-destructive bg-destructive/10 p-2 rounded">{error}</div>
+      )}
+
+      {loading ? (
+        <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+      ) : projects.length === 0 ? (
+        <p className="text-sm text-muted-foreground">Create a project to add tasks to it.</p>
+      ) : (
+        <>
+          <div className="flex items-center gap-4">
+            <select
+              className="rounded-md border bg-background px-3 py-2 text-sm"
+              value={projectId}
+              onChange={(e) => setProjectId(e.target.value)}
+            >
+              {projects.map((p) => (
+                <option key={p.id} value={p.id}>
+                  {p.title}
+                </option>
+              ))}
+            </select>
+            {project?.progress != null && (
+              <span className="text-sm text-muted-foreground">
+                {project.done_task_count} of {project.task_count} done ({project.progress}%)
+              </span>
+            )}
+          </div>
+
+          <div className="flex flex-col">{renderTasks(null, 0)}</div>
+
+          <form onSubmit={addTask} className="flex max-w-xl items-center gap-2">
+            <Input
+              value={newTitle}
+              onChange={(e) => setNewTitle(e.target.value)}
+              placeholder={parent ? `New subtask of ${parent.title}` : "New task"}
+            />
+            {parent && (
+              <Button type="button" variant="ghost" onClick={() => setParentId(null)}>
+                Cancel
+              </Button>
+            )}
+            <Button type="submit" disabled={!newTitle.trim()}>
+              <Plus className="mr-2 h-4 w-4" />
+              Add
+            </Button>
+          </form>
+        </>
+      )}
     </div>
   );
 }
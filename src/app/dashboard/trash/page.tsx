@@ -0,0 +1,231 @@
+"use client";
+
+import * as React from "react";
+import { format } from "date-fns";
+import { ArchiveRestore, Calendar, Database, Folder, FolderKanban, Loader2, Trash2 } from "lucide-react";
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+import {
+  Breadcrumb,
+  BreadcrumbItem,
+  BreadcrumbLink,
+  BreadcrumbList,
+  BreadcrumbPage,
+  BreadcrumbSeparator,
+} from "@/components/ui/breadcrumb";
+import { Button } from "@/components/ui/button";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { errorMessage } from "@/lib/errors";
+
+async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  return await invoke<T>(cmd, args);
+}
+
+// Matches TrashedItem in src-tauri/src/trash.rs
+interface TrashedItem {
+  kind: "area" | "project" | "resource" | "event";
+  id: string;
+  title: string;
+  parent_title?: string | null;
+  deleted_at: number;
+  purge_at: number;
+}
+
+const ICONS = {
+  area: Folder,
+  project: FolderKanban,
+  resource: Database,
+  event: Calendar,
+};
+
+const RETENTION_OPTIONS = [
+  { value: "7", label: "1 week" },
+  { value: "30", label: "30 days" },
+  { value: "90", label: "90 days" },
+  { value: "365", label: "1 year" },
+];
+
+export default function Trash() {
+  const [items, setItems] = React.useState<TrashedItem[]>([]);
+  const [retentionDays, setRetentionDays] = React.useState("30");
+  const [loading, setLoading] = React.useState(true);
+  const [showEmptyDialog, setShowEmptyDialog] = React.useState(false);
+  const [emptying, setEmptying] = React.useState(false);
+  const [error, setError] = React.useState<string | null>(null);
+
+  const fetchTrash = React.useCallback(async () => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    try {
+      const response = JSON.parse(await tauriInvoke<string>("get_trash", { token }));
+      if (response.success) {
+        setItems(response.items || []);
+        setRetentionDays(String(response.retention_days));
+      }
+    } catch (err) {
+      console.error("Error fetching trash:", err);
+      setError(errorMessage(err, "Failed to load the trash"));
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  React.useEffect(() => {
+    fetchTrash();
+  }, [fetchTrash]);
+
+  const restore = async (item: TrashedItem) => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    setError(null);
+    try {
+      await tauriInvoke<string>("restore_item", { token, kind: item.kind, id: item.id });
+      await fetchTrash();
+    } catch (err) {
+      setError(errorMessage(err, "Failed to restore"));
+    }
+  };
+
+  const emptyTrash = async () => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    setError(null);
+    setEmptying(true);
+    try {
+      await tauriInvoke<string>("empty_trash", { token });
+      setShowEmptyDialog(false);
+      await fetchTrash();
+    } catch (err) {
+      setError(errorMessage(err, "Failed to empty the trash"));
+    } finally {
+      setEmptying(false);
+    }
+  };
+
+  const changeRetention = async (value: string) => {
+    const token = localStorage.getItem("auth_token");
+    if (!token) return;
+    setError(null);
+    try {
+      await tauriInvoke<string>("set_trash_retention", { token, days: Number(value) });
+      await fetchTrash();
+    } catch (err) {
+      setError(errorMessage(err, "Failed to save the setting"));
+    }
+  };
+
+  const day = (timestamp: number) => format(new Date(timestamp * 1000), "d MMM yyyy");
+
+  return (
+    <div className="flex flex-col gap-6">
+      <Breadcrumb>
+        <BreadcrumbList>
+          <BreadcrumbItem>
+            <BreadcrumbLink href="/dashboard">Dashboard</BreadcrumbLink>
+          </BreadcrumbItem>
+          <BreadcrumbSeparator />
+          <BreadcrumbItem>
+            <BreadcrumbPage>
+              <BreadcrumbLink href="/dashboard/trash">Trash</BreadcrumbLink>
+            </BreadcrumbPage>
+          </BreadcrumbItem>
+        </BreadcrumbList>
+      </Breadcrumb>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>Keep deleted items for</CardTitle>
+          <CardDescription>After this they are deleted for good.</CardDescription>
+        </CardHeader>
+        <CardContent className="flex items-center justify-between gap-4">
+          <select
+            className="rounded-md border bg-background px-3 py-2 text-sm"
+            value={retentionDays}
+            onChange={(e) => changeRetention(e.target.value)}
+          >
+            {/* Keep a value set elsewhere selectable */}
+            {!RETENTION_OPTIONS.some((o) => o.value === retentionDays) && (
+              <option value={retentionDays}>{retentionDays} days</option>
+            )}
+            {RETENTION_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+          <Button
+            variant="destructive"
+            disabled={items.length === 0}
+            onClick={() => setShowEmptyDialog(true)}
+          >
+            <Trash2 className="mr-2 h-4 w-4" />
+            Empty Trash
+          </Button>
+        </CardContent>
+      </Card>
+
+      {error && (
+        <div className="text-sm text-destructive bg-destructive/10 p-2 rounded">{error}</div>
+      )}
+
+      {loading ? (
+        <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+      ) : items.length === 0 ? (
+        <p className="text-sm text-muted-foreground">The trash is empty.</p>
+      ) : (
+        <div className="flex flex-col gap-2">
+          {items.map((item) => {
+            const Icon = ICONS[item.kind];
+            return (
+              <Card key={`${item.kind}-${item.id}`} className="flex-row items-center gap-3 p-4">
+                <Icon className="h-5 w-5 text-primary" />
+                <div className="min-w-0 flex-1">
+                  <div className="truncate font-medium">{item.title}</div>
+                  <div className="text-sm text-muted-foreground">
+                    {item.parent_title ? `${item.parent_title} · ` : ""}
+                    Deleted {day(item.deleted_at)} · Deleted for good {day(item.purge_at)}
+                  </div>
+                </div>
+                <Button size="sm" variant="outline" onClick={() => restore(item)}>
+                  <ArchiveRestore className="mr-2 h-4 w-4" />
+                  Restore
+                </Button>
+              </Card>
+            );
+          })}
+        </div>
+      )}
+
+      <AlertDialog open={showEmptyDialog} onOpenChange={setShowEmptyDialog}>
+        <AlertDialogContent>
+          <AlertDialogHeader>
+            <AlertDialogTitle>Empty Trash</AlertDialogTitle>
+            <AlertDialogDescription>
+              Everything in the trash will be deleted for good. This action cannot be undone.
+            </AlertDialogDescription>
+          </AlertDialogHeader>
+          <AlertDialogFooter>
+            <AlertDialogCancel disabled={emptying}>Cancel</AlertDialogCancel>
+            <AlertDialogAction
+              onClick={emptyTrash}
+              disabled={emptying}
+              className="bg-destructive text-destructive-foreground hover:bg-destructive/90"
+            >
+              {emptying && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+              Empty Trash
+            </AlertDialogAction>
+          </AlertDialogFooter>
+        </AlertDialogContent>
+      </AlertDialog>
+    </div>
+  );
+}
@@ -8,6 +8,8 @@ import {
   Folder,
   LayoutDashboard,
   CalendarDays,
+  Tag,
+  Trash2,
 } from "lucide-react";
 
 export const menu = [
@@ -25,6 +27,8 @@ export const menu = [
   },
   { title: "Calendar", icon: CalendarDays, url: "/dashboard/calendar" },
   { title: "Archive", icon: Archive, url: "/dashboard/archive" },
+  { title: "Tags", icon: Tag, url: "/dashboard/tags" },
+  { title: "Trash", icon: Trash2, url: "/dashboard/trash" },
 ];
 
 export const sample = {
@@ -83,6 +83,31 @@ const AreaCard = ({ id, title, image, href, onUpdate }: AreaCardProps) => {
     }
   };
 
+  const handleArchive = async () => {
+    try {
+      const token = localStorage.getItem("auth_token");
+      if (!token) {
+        console.error("Not authenticated");
+        return;
+      }
+
+      const responseJson = await tauriInvoke<string>("archive_area", {
+        token,
+        id,
+      });
+      const response = JSON.parse(responseJson);
+      if (response.success) {
+        if (onUpdate) {
+          onUpdate();
+        }
+      } else {
+        console.error("Failed to archive area:", response.message);
+      }
+    } catch (err) {
+      console.error("Error archiving area:", err);
+    }
+  };
+
   // Use gradient fallback if no image provided
   const hasImage = image && image.trim() !== "";
 
@@ -137,6 +162,15 @@ const AreaCard = ({ id, title, image, href, onUpdate }: AreaCardProps) => {
                   >
                     Edit
                   </DropdownMenuItem>
+                  <DropdownMenuItem
+                    onClick={(e) => {
+                      e.preventDefault();
+                      e.stopPropagation();
+                      handleArchive();
+                    }}
+                  >
+                    Archive
+                  </DropdownMenuItem>
                   <DropdownMenuItem
                     onClick={(e) => {
                       e.preventDefault();
@@ -184,7 +218,7 @@ const AreaCard = ({ id, title, image, href, onUpdate }: AreaCardProps) => {
           <AlertDialogHeader>
             <AlertDialogTitle>Delete Area</AlertDialogTitle>
             <AlertDialogDescription>
-              Are you sure you want to delete "{title}"? This action cannot be undone.
+              Are you sure you want to delete "{title}"? It will be moved to the trash, where you can restore it.
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
@@ -13,6 +13,7 @@ import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
 import { Folder, ArrowLeft, Box, Loader2, Plus } from "lucide-react";
 import { NewProjectDialog } from "@/components/projects/NewProjectDialog";
+import { errorMessage } from "@/lib/errors";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
     try {
@@ -79,8 +80,7 @@ export default function AreaDetailView({ area }: AreaDetailViewProps) {
             setProjects(projectsData);
         } catch (err: unknown) {
             console.error("Error fetching projects:", err);
-            const errorMessage = err instanceof Error ? err.message : "Failed to fetch projects";
-            setError(errorMessage);
+            setError(errorMessage(err, "Failed to fetch projects"));
         } finally {
             setLoadingProjects(false);
         }
@@ -101,24 +101,13 @@ export default function AreaDetailView({ area }: AreaDetailViewProps) {
             // Fetch all resources for all projects in this area
             for (const projectId of projectIds) {
                 try {
-                    const responseJson = await tauriInvoke<string>("get_resources", {
+                    // Only the total is needed, so ask for the smallest page
+                    const page = await tauriInvoke<{ total: number }>("list_resources", {
                         token,
                         project_id: projectId,
+                        limit: 1,
                     });
-                    let resourcesData: unknown[] = [];
-                    if (typeof responseJson === "string") {
-                        if (responseJson.trim() === "") {
-                            resourcesData = [];
-                        } else {
-                            const parsed = JSON.parse(responseJson);
-                            resourcesData = Array.isArray(parsed) ? parsed : [];
-                        }
-                    } else if (Array.isArray(responseJson)) {
-                        resourcesData = responseJson;
-                    } else {
-                        resourcesData = [];
-                    }
-                    counts[projectId] = resourcesData.length;
+                    counts[projectId] = page.total;
                 } catch (err) {
                     console.error(`Error fetching resources for project ${projectId}:`, err);
                     counts[projectId] = 0;
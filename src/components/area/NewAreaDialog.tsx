@@ -13,6 +13,7 @@ import {
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Loader2 } from "lucide-react";
+import { errorMessage } from "@/lib/errors";
 
 async function tauriInvoke<T = any>(cmd: string, args?: any): Promise<T> {
     try {
@@ -142,7 +143,7 @@ export function NewAreaDialog({ trigger, onSuccess, area, open: controlledOpen,
             }
         } catch (err: any) {
             console.error("Error saving area:", err);
-            setError(err.message || "An error occurred");
+            setError(errorMessage(err, "An error occurred"));
         } finally {
             setLoading(false);
         }
@@ -8,6 +8,7 @@ import { Dialog, DialogContent, DialogHeader, DialogTitle } from "@/components/u
 import { XIcon } from "lucide-react";
 import { SmoothLink } from "@/components/ui/smooth-link";
 import { useRouter } from "next/navigation";
+import { errorMessage } from "@/lib/errors";
 
 // Helper to safely invoke Tauri commands
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
@@ -126,8 +127,7 @@ export function AuthForm({ mode, title, description, switchText, switchHref, swi
             }
         } catch (err) {
             console.error("Auth error:", err);
-            const errorMessage = err instanceof Error ? err.message : "An error occurred";
-            setError(errorMessage);
+            setError(errorMessage(err, "An error occurred"));
         } finally {
             setLoading(false);
         }
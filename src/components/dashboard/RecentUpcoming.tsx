@@ -81,33 +81,20 @@ export default function RecentUpcoming() {
     }
 
     try {
-      const responseJson = await tauriInvoke<string>("get_resources", {
+      const page = await tauriInvoke<{ resources: Resource[] }>("list_resources", {
         token,
         project_id: null,
+        sort_by: "updated_at",
+        sort_order: "desc",
+        limit: 5,
       });
-      let resourcesData: Resource[] = [];
-      if (typeof responseJson === "string") {
-        if (responseJson.trim() === "") {
-          resourcesData = [];
-        } else {
-          const parsed = JSON.parse(responseJson);
-          resourcesData = Array.isArray(parsed) ? parsed : [];
-        }
-      } else if (Array.isArray(responseJson)) {
-        resourcesData = responseJson;
-      } else {
-        resourcesData = [];
-      }
-
-      const formattedResources: TabData[] = resourcesData
-        .sort((a, b) => b.updated_at - a.updated_at)
-        .slice(0, 5)
-        .map((resource) => ({
-          title: resource.name,
-          icon: <Database size={17} />,
-          date: `Updated ${format(new Date(resource.updated_at * 1000), "MMM d, yyyy")}`,
-          url: "/dashboard/resources",
-        }));
+
+      const formattedResources: TabData[] = page.resources.map((resource) => ({
+        title: resource.name,
+        icon: <Database size={17} />,
+        date: `Updated ${format(new Date(resource.updated_at * 1000), "MMM d, yyyy")}`,
+        url: "/dashboard/resources",
+      }));
 
       setResources(formattedResources);
     } catch (err) {
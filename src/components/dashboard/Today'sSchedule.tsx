@@ -2,7 +2,7 @@
 
 import { useState, useEffect, useCallback } from "react";
 import { Card } from "../ui/card";
-import { format, isToday, isSameDay } from "date-fns";
+import { format, isSameDay } from "date-fns";
 import { Calendar as CalendarIcon } from "lucide-react";
 import Link from "next/link";
 
@@ -24,6 +24,7 @@ interface Event {
   end_time?: number | null;
   location?: string | null;
   all_day: boolean;
+  day: string;
   project_name?: string | null;
 }
 
@@ -53,13 +54,10 @@ export default function TodaysSchedule() {
       });
       const response = JSON.parse(responseJson);
       if (response.success) {
-        // Filter to only today's events and sort by start time
-        const todaysEvents = (response.events || [])
-          .filter((event: Event) => {
-            const eventDate = new Date(event.start_time * 1000);
-            return isToday(eventDate);
-          })
-          .sort((a: Event, b: Event) => a.start_time - b.start_time);
+        // Everything overlapping today, including events that started earlier
+        const todaysEvents = (response.events || []).sort(
+          (a: Event, b: Event) => a.start_time - b.start_time
+        );
         setEvents(todaysEvents);
       }
     } catch (err) {
@@ -16,6 +16,7 @@ import { Label } from "@/components/ui/label";
 import { NativeSelect, NativeSelectOption } from "@/components/ui/native-select";
 import { Textarea } from "@/components/ui/textarea";
 import { DatePicker } from "@/components/projects/DatePicker";
+import { errorMessage } from "@/lib/errors";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
     try {
@@ -33,6 +34,26 @@ interface Project {
     area_id: string;
 }
 
+// Simple repeat choices; anything else is kept as a custom rule
+const REPEAT_OPTIONS = [
+    { value: "", label: "Does not repeat" },
+    { value: "FREQ=DAILY", label: "Daily" },
+    { value: "FREQ=WEEKLY", label: "Weekly" },
+    { value: "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR", label: "Every weekday" },
+    { value: "FREQ=MONTHLY", label: "Monthly" },
+    { value: "FREQ=YEARLY", label: "Yearly" },
+];
+
+// Minutes before the event
+const REMINDER_OPTIONS = [
+    { value: "", label: "No reminder" },
+    { value: "0", label: "At start time" },
+    { value: "5", label: "5 minutes before" },
+    { value: "15", label: "15 minutes before" },
+    { value: "60", label: "1 hour before" },
+    { value: "1440", label: "1 day before" },
+];
+
 interface NewEventDialogProps {
     trigger?: React.ReactNode;
     defaultProjectId?: string;
@@ -46,6 +67,8 @@ interface NewEventDialogProps {
         end_time?: number | null;
         location?: string | null;
         all_day: boolean;
+        recurrence_rule?: string | null;
+        reminders?: number[];
     } | null;
     open?: boolean;
     onOpenChange?: (open: boolean) => void;
@@ -68,6 +91,10 @@ export function NewEventDialog({
     const [projectId, setProjectId] = React.useState(event?.project_id || defaultProjectId || "");
     const [location, setLocation] = React.useState(event?.location || "");
     const [allDay, setAllDay] = React.useState(event?.all_day || false);
+    const [recurrenceRule, setRecurrenceRule] = React.useState(event?.recurrence_rule || "");
+    const [reminder, setReminder] = React.useState(
+        event?.reminders?.length ? String(event.reminders[0]) : ""
+    );
     const [startDate, setStartDate] = React.useState<Date | undefined>(
         event?.start_time ? new Date(event.start_time * 1000) : new Date()
     );
@@ -90,6 +117,7 @@ export function NewEventDialog({
     });
     const [loading, setLoading] = React.useState(false);
     const [error, setError] = React.useState<string | null>(null);
+    const [conflicts, setConflicts] = React.useState<string[]>([]);
 
     // Validate end date is on or after start date
     React.useEffect(() => {
@@ -124,6 +152,47 @@ export function NewEventDialog({
         }
     }, [startDate, endDate, startTime, endTime, allDay]);
 
+    // Warn about timed events the proposed slot overlaps; saving is still allowed
+    React.useEffect(() => {
+        const token = localStorage.getItem("auth_token");
+        if (!open || !token || allDay || !startDate || !startTime) {
+            setConflicts([]);
+            return;
+        }
+        const at = (date: Date, time: string) => {
+            const [hours, minutes] = time.split(":").map(Number);
+            const dateTime = new Date(date);
+            dateTime.setHours(hours, minutes, 0, 0);
+            return Math.floor(dateTime.getTime() / 1000);
+        };
+        const start = at(startDate, startTime);
+        const end = endDate && endTime ? at(endDate, endTime) : null;
+        if (end !== null && end <= start) {
+            setConflicts([]);
+            return;
+        }
+
+        let cancelled = false;
+        tauriInvoke<string>("check_event_conflicts", {
+            token,
+            start_time: start,
+            end_time: end,
+            exclude_id: event?.id ?? null,
+        })
+            .then((responseJson) => {
+                const response = JSON.parse(responseJson);
+                if (!cancelled && response.success) {
+                    setConflicts((response.conflicts || []).map((c: { title: string }) => c.title));
+                }
+            })
+            .catch(() => {
+                if (!cancelled) setConflicts([]);
+            });
+        return () => {
+            cancelled = true;
+        };
+    }, [open, allDay, startDate, startTime, endDate, endTime, event?.id]);
+
     React.useEffect(() => {
         const fetchProjects = async () => {
             try {
@@ -213,6 +282,10 @@ export function NewEventDialog({
                 end_time: endTimestamp || null,
                 location: location.trim() || null,
                 all_day: allDay,
+                time_zone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+                recurrence_rule: recurrenceRule || null,
+                // Only the first reminder is editable here; any others are kept
+                reminders: reminder === "" ? [] : [Number(reminder), ...(event?.reminders?.slice(1) ?? [])],
             };
 
             if (event) {
@@ -248,8 +321,7 @@ export function NewEventDialog({
             }
         } catch (err: unknown) {
             console.error("Error saving event:", err);
-            const errorMessage = err instanceof Error ? err.message : "Failed to save event";
-            setError(errorMessage);
+            setError(errorMessage(err, "Failed to save event"));
         } finally {
             setLoading(false);
         }
@@ -312,6 +384,48 @@ export function NewEventDialog({
                             />
                         </div>
 
+                        <div className="grid gap-2">
+                            <Label htmlFor="repeat">Repeat</Label>
+                            <NativeSelect
+                                id="repeat"
+                                value={recurrenceRule}
+                                onChange={(e) => setRecurrenceRule(e.target.value)}
+                            >
+                                {REPEAT_OPTIONS.map((option) => (
+                                    <NativeSelectOption key={option.value} value={option.value}>
+                                        {option.label}
+                                    </NativeSelectOption>
+                                ))}
+                                {recurrenceRule &&
+                                    !REPEAT_OPTIONS.some((option) => option.value === recurrenceRule) && (
+                                        <NativeSelectOption value={recurrenceRule}>
+                                            Custom ({recurrenceRule})
+                                        </NativeSelectOption>
+                                    )}
+                            </NativeSelect>
+                        </div>
+
+                        <div className="grid gap-2">
+                            <Label htmlFor="reminder">Reminder</Label>
+                            <NativeSelect
+                                id="reminder"
+                                value={reminder}
+                                onChange={(e) => setReminder(e.target.value)}
+                            >
+                                {REMINDER_OPTIONS.map((option) => (
+                                    <NativeSelectOption key={option.value} value={option.value}>
+                                        {option.label}
+                                    </NativeSelectOption>
+                                ))}
+                                {reminder &&
+                                    !REMINDER_OPTIONS.some((option) => option.value === reminder) && (
+                                        <NativeSelectOption value={reminder}>
+                                            {reminder} minutes before
+                                        </NativeSelectOption>
+                                    )}
+                            </NativeSelect>
+                        </div>
+
                         <div className="flex items-center gap-2">
                             <input
                                 type="checkbox"
@@ -367,6 +481,12 @@ export function NewEventDialog({
                             </div>
                         </div>
 
+                        {conflicts.length > 0 && (
+                            <div className="text-sm text-amber-700 bg-amber-500/10 p-2 rounded">
+                                Overlaps with {conflicts.join(", ")}
+                            </div>
+                        )}
+
                         {error && (
                             <div className="text-sm text-destructive bg-destructive/10 p-2 rounded">
                                 {error}
@@ -0,0 +1,130 @@
+"use client";
+
+import * as React from "react";
+import { format } from "date-fns";
+import { Bell, X } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Card } from "@/components/ui/card";
+import { errorMessage } from "@/lib/errors";
+
+// Helper to safely invoke Tauri commands
+async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+    const { invoke } = await import("@tauri-apps/api/core");
+    return await invoke<T>(cmd, args);
+}
+
+// Matches ReminderInfo in src-tauri/src/reminders.rs
+interface Reminder {
+    user_id: string;
+    kind: "event" | "project";
+    source_id: string;
+    occurrence_start: number;
+    minutes_before: number;
+    title: string;
+    due_at: number;
+}
+
+const SNOOZE_MINUTES = 10;
+
+function reminderKey(reminder: Reminder) {
+    return JSON.stringify({
+        kind: reminder.kind,
+        source_id: reminder.source_id,
+        occurrence_start: reminder.occurrence_start,
+        minutes_before: reminder.minutes_before,
+    });
+}
+
+/**
+ * Shows reminders pushed by the backend scheduler, plus any still waiting from
+ * before the app was restarted, each with snooze and dismiss actions.
+ */
+export function ReminderToasts({ userId }: { userId: string }) {
+    const [reminders, setReminders] = React.useState<Reminder[]>([]);
+
+    React.useEffect(() => {
+        const token = localStorage.getItem("auth_token");
+        if (!token) return;
+
+        let unlisten: (() => void) | undefined;
+        let cancelled = false;
+
+        tauriInvoke<string>("get_pending_reminders", { token })
+            .then((responseJson) => {
+                const response = JSON.parse(responseJson);
+                if (!cancelled && response.success) {
+                    setReminders(response.reminders || []);
+                }
+            })
+            .catch((err) => console.error("Error fetching reminders:", err));
+
+        import("@tauri-apps/api/event").then(({ listen }) =>
+            listen<Reminder>("reminder", ({ payload }) => {
+                // Reminders for other accounts on this machine are not ours to show
+                if (payload.user_id !== userId) return;
+                setReminders((current) => [
+                    ...current.filter((r) => reminderKey(r) !== reminderKey(payload)),
+                    payload,
+                ]);
+            }).then((stop) => {
+                if (cancelled) stop();
+                else unlisten = stop;
+            })
+        );
+
+        return () => {
+            cancelled = true;
+            unlisten?.();
+        };
+    }, [userId]);
+
+    const act = async (reminder: Reminder, command: "snooze_reminder" | "dismiss_reminder") => {
+        const token = localStorage.getItem("auth_token");
+        if (!token) return;
+        try {
+            await tauriInvoke<string>(command, {
+                token,
+                json: reminderKey(reminder),
+                ...(command === "snooze_reminder" ? { minutes: SNOOZE_MINUTES } : {}),
+            });
+            setReminders((current) => current.filter((r) => reminderKey(r) !== reminderKey(reminder)));
+        } catch (err) {
+            console.error(`Error with ${command}:`, err);
+            alert(errorMessage(err, "Failed to update reminder"));
+        }
+    };
+
+    if (reminders.length === 0) return null;
+
+    return (
+        <div className="fixed bottom-4 right-4 z-50 flex w-80 flex-col gap-2">
+            {reminders.map((reminder) => (
+                <Card key={reminderKey(reminder)} className="gap-2 p-4 shadow-lg">
+                    <div className="flex items-start gap-2">
+                        <Bell className="mt-0.5 h-4 w-4 shrink-0 text-primary" />
+                        <div className="min-w-0 flex-1">
+                            <div className="truncate font-medium">{reminder.title}</div>
+                            <div className="text-sm text-muted-foreground">
+                                {reminder.kind === "project" ? "Ends " : "Starts "}
+                                {format(new Date(reminder.occurrence_start * 1000), "EEE d MMM, h:mm a")}
+                            </div>
+                        </div>
+                        <button
+                            type="button"
+                            aria-label="Dismiss"
+                            className="text-muted-foreground hover:text-foreground"
+                            onClick={() => act(reminder, "dismiss_reminder")}
+                        >
+                            <X className="h-4 w-4" />
+                        </button>
+                    </div>
+                    <div className="flex justify-end">
+                        <Button size="sm" variant="outline" onClick={() => act(reminder, "snooze_reminder")}>
+                            Snooze {SNOOZE_MINUTES} min
+                        </Button>
+                    </div>
+                </Card>
+            ))}
+        </div>
+    );
+}
@@ -138,24 +138,13 @@ export default function AppSidebar() {
       if (!token) return;
 
       try {
-        const responseJson = await tauriInvoke<string>("get_resources", {
+        // Only the total is needed, so ask for the smallest page
+        const page = await tauriInvoke<{ total: number }>("list_resources", {
           token,
           project_id: null,
+          limit: 1,
         });
-        let resourcesData: unknown[] = [];
-        if (typeof responseJson === "string") {
-          if (responseJson.trim() === "") {
-            resourcesData = [];
-          } else {
-            const parsed = JSON.parse(responseJson);
-            resourcesData = Array.isArray(parsed) ? parsed : [];
-          }
-        } else if (Array.isArray(responseJson)) {
-          resourcesData = responseJson;
-        } else {
-          resourcesData = [];
-        }
-        setResourcesCount(resourcesData.length);
+        setResourcesCount(page.total);
       } catch (err) {
         console.error("Error fetching resources:", err);
       }
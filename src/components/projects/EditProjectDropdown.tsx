@@ -16,7 +16,7 @@ import {
   AlertDialogHeader,
   AlertDialogTitle,
 } from "@/components/ui/alert-dialog";
-import { Edit, Trash, Loader2 } from "lucide-react";
+import { Archive, Edit, Trash, Loader2 } from "lucide-react";
 import { NewProjectDialog } from "./NewProjectDialog";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
@@ -84,6 +84,31 @@ export default function EditProjectDropdown({
     }
   };
 
+  const handleArchive = async () => {
+    try {
+      const token = localStorage.getItem("auth_token");
+      if (!token) {
+        console.error("Not authenticated");
+        return;
+      }
+
+      const responseJson = await tauriInvoke<string>("archive_project", {
+        token,
+        id: project.id,
+      });
+      const response = JSON.parse(responseJson);
+      if (response.success) {
+        if (onUpdate) {
+          onUpdate();
+        }
+      } else {
+        console.error("Failed to archive project:", response.message);
+      }
+    } catch (err) {
+      console.error("Error archiving project:", err);
+    }
+  };
+
   return (
     <>
       <DropdownMenu>
@@ -100,6 +125,15 @@ export default function EditProjectDropdown({
             <Edit className="mr-2 h-4 w-4" />
             Edit Project
           </DropdownMenuItem>
+          <DropdownMenuItem
+            onClick={(e) => {
+              e.stopPropagation();
+              handleArchive();
+            }}
+          >
+            <Archive className="mr-2 h-4 w-4" />
+            Archive Project
+          </DropdownMenuItem>
           <DropdownMenuItem
             onClick={(e) => {
               e.stopPropagation();
@@ -130,7 +164,7 @@ export default function EditProjectDropdown({
           <AlertDialogHeader>
             <AlertDialogTitle>Delete Project</AlertDialogTitle>
             <AlertDialogDescription>
-              Are you sure you want to delete "{project.title}"? This action cannot be undone.
+              Are you sure you want to delete "{project.title}"? It will be moved to the trash, where you can restore it.
             </AlertDialogDescription>
           </AlertDialogHeader>
           <AlertDialogFooter>
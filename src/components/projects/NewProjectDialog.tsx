@@ -19,6 +19,7 @@ import {
 } from "@/components/ui/native-select";
 import { Textarea } from "@/components/ui/textarea";
 import { DatePicker } from "./DatePicker";
+import { errorMessage } from "@/lib/errors";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   try {
@@ -57,7 +58,8 @@ interface NewProjectDialogProps {
   onOpenChange?: (open: boolean) => void;
 }
 
-const STATUSES = ["Inbox", "Planned", "Progress", "Done"] as const;
+// Used until the area's workflow loads
+const STATUSES = ["Inbox", "Planned", "Progress", "Done"];
 const PRIORITIES = ["High", "Medium", "Low"] as const;
 
 export function NewProjectDialog({ trigger, defaultStatus = "Inbox", defaultAreaId, onSuccess, project, open: controlledOpen, onOpenChange: controlledOnOpenChange }: NewProjectDialogProps) {
@@ -69,6 +71,7 @@ export function NewProjectDialog({ trigger, defaultStatus = "Inbox", defaultArea
   const [description, setDescription] = React.useState(project?.description || "");
   const [areaId, setAreaId] = React.useState(project?.area_id || defaultAreaId || "");
   const [status, setStatus] = React.useState(project?.status || defaultStatus);
+  const [statuses, setStatuses] = React.useState<string[]>(STATUSES);
   const [priority, setPriority] = React.useState(project?.priority || "");
   const [startDate, setStartDate] = React.useState<Date | undefined>(
     project?.start_date ? new Date(project.start_date * 1000) : undefined
@@ -129,6 +132,28 @@ export function NewProjectDialog({ trigger, defaultStatus = "Inbox", defaultArea
     }
   }, [open, project, defaultStatus, defaultAreaId]);
 
+  // Status choices follow the workflow of the chosen area
+  React.useEffect(() => {
+    const fetchWorkflow = async () => {
+      const token = localStorage.getItem("auth_token");
+      if (!token || !open) return;
+      try {
+        const responseJson = await tauriInvoke<string>("get_workflow", {
+          token,
+          area_id: areaId || null,
+        });
+        const names: string[] = JSON.parse(responseJson).workflow.statuses.map(
+          (s: { name: string }) => s.name
+        );
+        setStatuses(names);
+        setStatus((current) => (names.includes(current) ? current : names[0]));
+      } catch (err) {
+        console.error("Error fetching workflow:", err);
+      }
+    };
+    fetchWorkflow();
+  }, [open, areaId]);
+
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
     setLoading(true);
@@ -187,8 +212,7 @@ export function NewProjectDialog({ trigger, defaultStatus = "Inbox", defaultArea
       }
     } catch (err: unknown) {
       console.error("Error saving project:", err);
-      const errorMessage = err instanceof Error ? err.message : "An error occurred";
-      setError(errorMessage);
+      setError(errorMessage(err, "An error occurred"));
     } finally {
       setLoading(false);
     }
@@ -259,7 +283,7 @@ export function NewProjectDialog({ trigger, defaultStatus = "Inbox", defaultArea
                   required
                   disabled={loading}
                 >
-                  {STATUSES.map((s) => (
+                  {statuses.map((s) => (
                     <NativeSelectOption key={s} value={s}>
                       {s === "Progress" ? "In progress" : s}
                     </NativeSelectOption>
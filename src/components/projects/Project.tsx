@@ -14,6 +14,7 @@ import {
   KanbanProvider,
   type DragEndEvent,
 } from "@/components/ui/shadcn-io/kanban";
+import { errorMessage } from "@/lib/errors";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   try {
@@ -35,11 +36,23 @@ interface Project {
   priority?: string | null;
   start_date?: number | null;
   end_date?: number | null;
+  task_count: number;
+  done_task_count: number;
+  progress?: number | null; // Percentage of tasks done, null without tasks
+  position: number; // Order within its area and status column
   created_at: number;
   updated_at: number;
 }
 
-const STATUSES = ["Inbox", "Planned", "Progress", "Done"] as const;
+// Matches WorkflowInfo in src-tauri/src/workflows.rs
+interface Workflow {
+  name: string;
+  statuses: { name: string; done: boolean }[];
+  transitions: { from: string; to: string }[];
+}
+
+// Used until the user's workflow loads
+const STATUSES = ["Inbox", "Planned", "Progress", "Done"];
 
 // Map status to display name
 const getStatusDisplayName = (status: string) => {
@@ -64,13 +77,14 @@ const getStatusColor = (status: string) => {
 
 /**
  * Project component renders a horizontal scrollable kanban board with project cards
- * organized by status (Inbox, Planned, Progress, Done).
+ * organized by the statuses of the user's workflow.
  */
 export default function Project() {
   const [projects, setProjects] = useState<Project[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [projectResourceCounts, setProjectResourceCounts] = useState<Record<string, number>>({});
+  const [statuses, setStatuses] = useState<string[]>(STATUSES);
 
   const fetchProjects = useCallback(async () => {
     setLoading(true);
@@ -88,10 +102,13 @@ export default function Project() {
       });
       const projectsData = JSON.parse(responseJson);
       setProjects(projectsData);
+
+      const workflowJson = await tauriInvoke<string>("get_workflow", { token, area_id: null });
+      const workflow: Workflow = JSON.parse(workflowJson).workflow;
+      setStatuses(workflow.statuses.map((s) => s.name));
     } catch (err: unknown) {
       console.error("Error fetching projects:", err);
-      const errorMessage = err instanceof Error ? err.message : "Failed to fetch projects";
-      setError(errorMessage);
+      setError(errorMessage(err, "Failed to fetch projects"));
     } finally {
       setLoading(false);
     }
@@ -112,24 +129,13 @@ export default function Project() {
       // Fetch all resources for all projects
       for (const projectId of projectIds) {
         try {
-          const responseJson = await tauriInvoke<string>("get_resources", {
+          // Only the total is needed, so ask for the smallest page
+          const page = await tauriInvoke<{ total: number }>("list_resources", {
             token,
             project_id: projectId,
+            limit: 1,
           });
-          let resourcesData: unknown[] = [];
-          if (typeof responseJson === "string") {
-            if (responseJson.trim() === "") {
-              resourcesData = [];
-            } else {
-              const parsed = JSON.parse(responseJson);
-              resourcesData = Array.isArray(parsed) ? parsed : [];
-            }
-          } else if (Array.isArray(responseJson)) {
-            resourcesData = responseJson;
-          } else {
-            resourcesData = [];
-          }
-          counts[projectId] = resourcesData.length;
+          counts[projectId] = page.total;
         } catch (err) {
           console.error(`Error fetching resources for project ${projectId}:`, err);
           counts[projectId] = 0;
@@ -160,8 +166,11 @@ export default function Project() {
     project, // Include full project data for access in render
   }));
 
-  // Create columns from statuses
-  const columns = STATUSES.map((status) => ({
+  // Columns follow the user's workflow, plus any statuses only an area's own workflow has
+  const extraStatuses = Array.from(new Set(projects.map((p) => p.status))).filter(
+    (status) => !statuses.includes(status)
+  );
+  const columns = [...statuses, ...extraStatuses].map((status) => ({
     id: status,
     name: getStatusDisplayName(status),
     color: getStatusColor(status),
@@ -195,11 +204,20 @@ export default function Project() {
         }
       }
 
-      if (!targetColumn || targetColumn === project.status) {
+      if (!targetColumn) {
         return;
       }
 
-      // Update project status via backend
+      // The optimistic update has already put the card in place; its neighbours there
+      // tell the backend where it goes
+      const columnProjects = projects.filter(
+        (p) => p.id === project.id || (p.status === targetColumn && p.area_id === project.area_id)
+      );
+      const index = columnProjects.findIndex((p) => p.id === project.id);
+      const before = columnProjects[index - 1];
+      const after = columnProjects[index + 1];
+
+      // Update project status and position via backend
       try {
         const token = localStorage.getItem("auth_token");
         if (!token) {
@@ -210,6 +228,8 @@ export default function Project() {
           token,
           id: project.id,
           new_status: targetColumn,
+          before_id: before?.id ?? null,
+          after_id: after?.id ?? null,
         });
         const response = JSON.parse(responseJson);
         if (response.success) {
@@ -409,6 +429,18 @@ export default function Project() {
                           </div>
                         )}
                       </div>
+
+                      {/* Task progress */}
+                      {project.progress != null && (
+                        <div className="flex items-center gap-2 text-xs text-muted-foreground">
+                          <div className="h-1.5 flex-1 overflow-hidden rounded-full bg-muted">
+                            <div className="h-full bg-primary" style={{ width: `${project.progress}%` }} />
+                          </div>
+                          <span>
+                            {project.done_task_count}/{project.task_count}
+                          </span>
+                        </div>
+                      )}
                     </KanbanCard>
                   );
                 }}
@@ -0,0 +1,79 @@
+"use client";
+import * as React from "react";
+import Link from "next/link";
+import { FileText } from "lucide-react";
+import { errorMessage } from "@/lib/errors";
+
+async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+    const { invoke } = await import("@tauri-apps/api/core");
+    return await invoke<T>(cmd, args);
+}
+
+// Matches Backlink in src-tauri/src/links.rs
+interface Backlink {
+    id: string;
+    name: string;
+    project_id?: string | null;
+    project_title?: string | null;
+    snippet?: string | null;
+    updated_at: number;
+}
+
+interface BacklinksProps {
+    kind: "area" | "project" | "resource" | "event";
+    id: string;
+    // Changes whenever the item may have gained or lost links, to fetch them again
+    refreshKey?: unknown;
+}
+
+// Documents linking here with [[Title]], or [[project:Title]] and the like for other kinds
+export function Backlinks({ kind, id, refreshKey }: BacklinksProps) {
+    const [backlinks, setBacklinks] = React.useState<Backlink[]>([]);
+    const [error, setError] = React.useState<string | null>(null);
+
+    React.useEffect(() => {
+        const token = localStorage.getItem("auth_token");
+        if (!token) return;
+        tauriInvoke<string>("get_backlinks", { token, kind, id })
+            .then((json) => {
+                setBacklinks(JSON.parse(json).backlinks || []);
+                setError(null);
+            })
+            .catch((err) => setError(errorMessage(err, "Failed to load backlinks")));
+    }, [kind, id, refreshKey]);
+
+    if (error) {
+        return <p className="text-sm text-destructive">{error}</p>;
+    }
+    if (backlinks.length === 0) {
+        return null;
+    }
+
+    return (
+        <div className="flex flex-col gap-2">
+            <h2 className="text-sm font-medium text-muted-foreground">Linked from</h2>
+            {backlinks.map((backlink) => (
+                <Link
+                    key={backlink.id}
+                    href={`/dashboard/resources/editor?resource=${backlink.id}`}
+                    className="flex items-start gap-3 rounded-md border p-3 hover:bg-muted"
+                >
+                    <FileText className="mt-0.5 h-4 w-4 text-primary" />
+                    <div className="min-w-0">
+                        <div className="font-medium">
+                            {backlink.name}
+                            {backlink.project_title && (
+                                <span className="ml-2 text-sm font-normal text-muted-foreground">
+                                    {backlink.project_title}
+                                </span>
+                            )}
+                        </div>
+                        {backlink.snippet && (
+                            <div className="truncate text-sm text-muted-foreground">{backlink.snippet}</div>
+                        )}
+                    </div>
+                </Link>
+            ))}
+        </div>
+    );
+}
@@ -15,6 +15,7 @@ import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import {
     NativeSelect,
+    NativeSelectOptGroup,
     NativeSelectOption,
 } from "@/components/ui/native-select";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
@@ -24,6 +25,7 @@ import {
     DropzoneEmptyState,
 } from "@/components/ui/shadcn-io/dropzone";
 import { useRouter } from "next/navigation";
+import { errorMessage } from "@/lib/errors";
 
 async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
     try {
@@ -41,15 +43,36 @@ interface Project {
     area_id: string;
 }
 
+interface Area {
+    id: string;
+    name: string;
+}
+
+// Where a resource lives, as "project:<id>", "area:<id>" or "" when on its own
+function placeOf(resource?: { project_id?: string | null; area_id?: string | null } | null) {
+    if (resource?.project_id) return `project:${resource.project_id}`;
+    if (resource?.area_id) return `area:${resource.area_id}`;
+    return "";
+}
+
+function splitPlace(place: string) {
+    const [kind, id] = place.split(":");
+    return {
+        project_id: kind === "project" ? id : null,
+        area_id: kind === "area" ? id : null,
+    };
+}
+
 interface NewResourceDialogProps {
     trigger?: React.ReactNode;
     defaultProjectId?: string;
     onSuccess?: () => void;
     resource?: {
         id: string;
-        project_id: string;
+        project_id?: string | null;
+        area_id?: string | null;
         name: string;
-        content?: string | null;
+        has_file?: boolean;
     } | null;
     open?: boolean;
     onOpenChange?: (open: boolean) => void;
@@ -67,12 +90,14 @@ export function NewResourceDialog({
     const open = controlledOpen !== undefined ? controlledOpen : internalOpen;
     const setOpen = controlledOnOpenChange || setInternalOpen;
     const [projects, setProjects] = React.useState<Project[]>([]);
+    const [areas, setAreas] = React.useState<Area[]>([]);
     const [name, setName] = React.useState(resource?.name || "");
-    const [projectId, setProjectId] = React.useState(
-        resource?.project_id || defaultProjectId || ""
+    const [place, setPlace] = React.useState(
+        resource ? placeOf(resource) : defaultProjectId ? `project:${defaultProjectId}` : ""
     );
     const [resourceType, setResourceType] = React.useState<"file" | "text">("text");
     const [selectedFile, setSelectedFile] = React.useState<File | null>(null);
+    const [removeFile, setRemoveFile] = React.useState(false);
     const [loading, setLoading] = React.useState(false);
     const [error, setError] = React.useState<string | null>(null);
     const router = useRouter();
@@ -98,9 +123,10 @@ export function NewResourceDialog({
                 }
                 if (projectsData.length > 0 && !resource && !hasInitializedProjectId.current) {
                     const initialProjectId = defaultProjectId || projectsData[0].id;
-                    setProjectId(initialProjectId);
+                    setPlace(`project:${initialProjectId}`);
                     hasInitializedProjectId.current = true;
                 }
+                setAreas(JSON.parse(await tauriInvoke<string>("get_areas", { token })));
             } catch (err) {
                 console.error("Error fetching projects:", err);
             }
@@ -110,15 +136,16 @@ export function NewResourceDialog({
             fetchProjects();
             if (resource) {
                 setName(resource.name);
-                setProjectId(resource.project_id);
+                setPlace(placeOf(resource));
                 hasInitializedProjectId.current = true;
             } else {
                 setName("");
-                setProjectId(defaultProjectId || "");
+                setPlace(defaultProjectId ? `project:${defaultProjectId}` : "");
                 setResourceType("text");
                 setSelectedFile(null);
                 hasInitializedProjectId.current = false;
             }
+            setRemoveFile(false);
             setError(null);
         } else {
             hasInitializedProjectId.current = false;
@@ -137,11 +164,6 @@ export function NewResourceDialog({
                 return;
             }
 
-            if (!projectId) {
-                setError("Please select a project");
-                return;
-            }
-
             if (!name.trim()) {
                 setError("Please enter a name");
                 return;
@@ -156,9 +178,9 @@ export function NewResourceDialog({
                 // For text resources, create with empty content - user will edit in the editor page
                 contentJson = null;
             } else if (selectedFile) {
-                // Validate file size (5MB max)
-                if (selectedFile.size > 5 * 1024 * 1024) {
-                    setError("File size exceeds maximum of 5MB");
+                // Validate file size (256MB max)
+                if (selectedFile.size > 256 * 1024 * 1024) {
+                    setError("File size exceeds maximum of 256MB");
                     setLoading(false);
                     return;
                 }
@@ -183,7 +205,7 @@ export function NewResourceDialog({
             }
 
             const request = {
-                project_id: projectId,
+                ...splitPlace(place),
                 name: name.trim(),
                 content: contentJson,
                 file_data: fileDataBase64,
@@ -192,11 +214,12 @@ export function NewResourceDialog({
             };
 
             if (resource) {
-                // Update existing resource
+                // Update existing resource. Leaving out content and file_data keeps what is
+                // stored; the dialog only renames, moves and optionally drops the file.
                 const responseJson = await tauriInvoke<string>("update_resource", {
                     token,
                     id: resource.id,
-                    json: JSON.stringify(request),
+                    json: JSON.stringify({ name: name.trim(), remove_file: removeFile }),
                 });
                 try {
                     const response = JSON.parse(responseJson);
@@ -209,6 +232,13 @@ export function NewResourceDialog({
                     setError("Failed to update resource");
                     return;
                 }
+                if (place !== placeOf(resource)) {
+                    await tauriInvoke<string>("move_resource", {
+                        token,
+                        id: resource.id,
+                        ...splitPlace(place),
+                    });
+                }
             } else {
                 // Create new resource
                 const responseJson = await tauriInvoke<string>("create_resource", {
@@ -243,8 +273,7 @@ export function NewResourceDialog({
             }
         } catch (err: unknown) {
             console.error("Error saving resource:", err);
-            const errorMessage = err instanceof Error ? err.message : "An error occurred";
-            setError(errorMessage);
+            setError(errorMessage(err, "An error occurred"));
         } finally {
             setLoading(false);
         }
@@ -270,19 +299,27 @@ export function NewResourceDialog({
                     </div>
 
                     <div className="space-y-2">
-                        <Label htmlFor="project">Project</Label>
+                        <Label htmlFor="project">Belongs to</Label>
                         <NativeSelect
                             id="project"
-                            value={projectId}
-                            onChange={(e) => setProjectId(e.target.value)}
-                            required
+                            value={place}
+                            onChange={(e) => setPlace(e.target.value)}
                         >
-                            <NativeSelectOption value="">Select a project</NativeSelectOption>
-                            {projects.map((project) => (
-                                <NativeSelectOption key={project.id} value={project.id}>
-                                    {project.title}
-                                </NativeSelectOption>
-                            ))}
+                            <NativeSelectOption value="">Nothing, on its own</NativeSelectOption>
+                            <NativeSelectOptGroup label="Projects">
+                                {projects.map((project) => (
+                                    <NativeSelectOption key={project.id} value={`project:${project.id}`}>
+                                        {project.title}
+                                    </NativeSelectOption>
+                                ))}
+                            </NativeSelectOptGroup>
+                            <NativeSelectOptGroup label="Areas">
+                                {areas.map((area) => (
+                                    <NativeSelectOption key={area.id} value={`area:${area.id}`}>
+                                        {area.name}
+                                    </NativeSelectOption>
+                                ))}
+                            </NativeSelectOptGroup>
                         </NativeSelect>
                     </div>
 
@@ -309,7 +346,7 @@ export function NewResourceDialog({
                                 </TabsContent>
                                 <TabsContent value="file" className="mt-4">
                                     <Dropzone
-                                        maxSize={5 * 1024 * 1024} // 5MB
+                                        maxSize={256 * 1024 * 1024} // 256MB
                                         maxFiles={1}
                                         onDrop={(acceptedFiles) => {
                                             if (acceptedFiles.length > 0) {
@@ -332,20 +369,32 @@ export function NewResourceDialog({
                                         </DropzoneContent>
                                     </Dropzone>
                                     <p className="text-sm text-muted-foreground mt-2">
-                                        Upload a file (max 5MB). Files are stored as base64.
+                                        Upload a file (max 256MB).
                                     </p>
                                 </TabsContent>
                             </Tabs>
                         </div>
                     )}
-                    {resource?.content && (
+                    {resource && !resource.has_file && (
                         <div className="space-y-2">
                             <Label>Content</Label>
                             <p className="text-sm text-muted-foreground">
-                                This resource has content. Edit it in the full editor page.
+                                Edit this resource's content in the full editor page.
                             </p>
                         </div>
                     )}
+                    {resource?.has_file && (
+                        <div className="flex items-center gap-2">
+                            <input
+                                id="remove-file"
+                                type="checkbox"
+                                checked={removeFile}
+                                onChange={(e) => setRemoveFile(e.target.checked)}
+                                className="h-4 w-4"
+                            />
+                            <Label htmlFor="remove-file">Remove the attached file</Label>
+                        </div>
+                    )}
 
                     {error && (
                         <div className="text-sm text-destructive bg-destructive/10 p-3 rounded-md">
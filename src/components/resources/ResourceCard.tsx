@@ -1,6 +1,7 @@
 "use client";
 import * as React from "react";
 import { useRouter } from "next/navigation";
+import { save } from "@tauri-apps/plugin-dialog";
 import { FileText, MoreVertical, Trash2, Edit, ExternalLink, Download, Eye } from "lucide-react";
 import { Card, CardContent, CardDescription, CardFooter, CardHeader, CardTitle } from "@/components/ui/card";
 import {
@@ -38,13 +39,16 @@ async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Prom
     }
 }
 
-interface Resource {
+// Listing summary; the body is fetched with get_resource_by_id when viewed or opened
+interface ResourceSummary {
     id: string;
-    project_id: string;
+    project_id?: string | null;
     project_name?: string | null;
+    area_id?: string | null;
+    area_name?: string | null;
     name: string;
-    content?: string | null;
-    file_data?: string | null;
+    snippet?: string | null;
+    has_file: boolean;
     file_type?: string | null;
     file_size?: number | null;
     created_at: number;
@@ -52,7 +56,7 @@ interface Resource {
 }
 
 interface ResourceCardProps {
-    resource: Resource;
+    resource: ResourceSummary;
     onUpdate?: () => void;
 }
 
@@ -81,21 +85,25 @@ export function ResourceCard({ resource, onUpdate }: ResourceCardProps) {
                 return;
             }
 
-            // Download file using Rust command (rfd handles the dialog)
-            await tauriInvoke<string>("download_resource_file", {
+            const extension = resource.file_type?.split("/").pop();
+            const path = await save({
+                defaultPath: resource.name,
+                filters: extension ? [{ name: "File", extensions: [extension] }] : [],
+            });
+            // Cancelled
+            if (!path) return;
+
+            await tauriInvoke<string>("export_resource_file", {
                 token,
                 id: resource.id,
+                path,
             });
         } catch (err) {
-            // User might have cancelled, which is fine
-            if (err instanceof Error && err.message.includes("cancelled")) {
-                return;
-            }
             console.error("Error downloading resource:", err);
         }
     };
 
-    const isFileResource = resource.file_data !== null && resource.file_data !== undefined;
+    const isFileResource = resource.has_file;
 
     const isViewableFile = isFileResource && resource.file_type && (
         resource.file_type.startsWith("image/") ||
@@ -227,39 +235,7 @@ export function ResourceCard({ resource, onUpdate }: ResourceCardProps) {
         }
     };
 
-    const preview = resource.content
-        ? (() => {
-            if (!resource.content || resource.content.trim() === "") return null;
-            try {
-                const json = JSON.parse(resource.content);
-                // Extract text from TipTap JSON
-                const extractText = (node: unknown): string => {
-                    if (typeof node !== "object" || node === null) return "";
-                    if (Array.isArray(node)) {
-                        return node.map(extractText).join(" ");
-                    }
-                    const obj = node as Record<string, unknown>;
-                    if (obj.type === "text" && typeof obj.text === "string") {
-                        return obj.text;
-                    }
-                    if (Array.isArray(obj.content)) {
-                        return obj.content.map(extractText).join(" ");
-                    }
-                    return "";
-                };
-                const text = extractText(json);
-                return text.length > 150 ? `${text.substring(0, 150)}...` : text;
-            } catch {
-                // If parsing fails, try to show as plain text if it's not too long
-                if (typeof resource.content === "string" && resource.content.length > 0) {
-                    return resource.content.length > 150
-                        ? `${resource.content.substring(0, 150)}...`
-                        : resource.content;
-                }
-                return null;
-            }
-        })()
-        : null;
+    const preview = resource.snippet;
 
     return (
         <>
@@ -272,7 +248,7 @@ export function ResourceCard({ resource, onUpdate }: ResourceCardProps) {
                             </div>
                             <div className="flex-1 min-w-0">
                                 <CardTitle className="text-base">
-                                    {resource.content ? (
+                                    {!isFileResource ? (
                                         <button
                                             type="button"
                                             onClick={handleOpenEditor}
@@ -284,10 +260,14 @@ export function ResourceCard({ resource, onUpdate }: ResourceCardProps) {
                                         resource.name
                                     )}
                                 </CardTitle>
-                                {resource.project_name && (
+                                {resource.project_name ? (
                                     <CardDescription className="mt-1">
                                         Project: {resource.project_name}
                                     </CardDescription>
+                                ) : resource.area_name && (
+                                    <CardDescription className="mt-1">
+                                        Area: {resource.area_name}
+                                    </CardDescription>
                                 )}
                             </div>
                         </div>
@@ -298,7 +278,7 @@ export function ResourceCard({ resource, onUpdate }: ResourceCardProps) {
                                 </button>
                             </DropdownMenuTrigger>
                             <DropdownMenuContent align="end">
-                                {resource.content && (
+                                {!isFileResource && (
                                     <DropdownMenuItem onClick={handleOpenEditor}>
                                         <ExternalLink className="mr-2 h-4 w-4" />
                                         Open in Editor
@@ -354,8 +334,9 @@ export function ResourceCard({ resource, onUpdate }: ResourceCardProps) {
                 resource={{
                     id: resource.id,
                     project_id: resource.project_id,
+                    area_id: resource.area_id,
                     name: resource.name,
-                    content: resource.content,
+                    has_file: resource.has_file,
                 }}
                 open={showEditDialog}
                 onOpenChange={setShowEditDialog}
@@ -389,7 +370,7 @@ export function ResourceCard({ resource, onUpdate }: ResourceCardProps) {
                     <AlertDialogHeader>
                         <AlertDialogTitle>Delete Resource</AlertDialogTitle>
                         <AlertDialogDescription>
-                            Are you sure you want to delete "{resource.name}"? This action cannot be undone.
+                            Are you sure you want to delete "{resource.name}"? It will be moved to the trash, where you can restore it.
                         </AlertDialogDescription>
                     </AlertDialogHeader>
                     <AlertDialogFooter>
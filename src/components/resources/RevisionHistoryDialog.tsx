@@ -0,0 +1,181 @@
+"use client";
+import * as React from "react";
+import { format } from "date-fns";
+import { History, Loader2, RotateCcw } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import {
+    Dialog,
+    DialogContent,
+    DialogDescription,
+    DialogHeader,
+    DialogTitle,
+    DialogTrigger,
+} from "@/components/ui/dialog";
+import { errorMessage } from "@/lib/errors";
+
+async function tauriInvoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+    const { invoke } = await import("@tauri-apps/api/core");
+    return await invoke<T>(cmd, args);
+}
+
+// Matches RevisionSummary and DiffLine in src-tauri/src/revisions.rs
+interface RevisionSummary {
+    id: number;
+    name: string;
+    snippet?: string | null;
+    content_size: number;
+    saved_at: number;
+    created_at: number;
+}
+
+interface DiffLine {
+    change: "equal" | "insert" | "delete";
+    text: string;
+}
+
+interface RevisionHistoryDialogProps {
+    resourceId: string;
+    // Called with the resource's content after a revision is restored
+    onRestore: (content: string | null) => void;
+}
+
+export function RevisionHistoryDialog({ resourceId, onRestore }: RevisionHistoryDialogProps) {
+    const [open, setOpen] = React.useState(false);
+    const [revisions, setRevisions] = React.useState<RevisionSummary[]>([]);
+    const [selected, setSelected] = React.useState<number | null>(null);
+    const [changes, setChanges] = React.useState<DiffLine[]>([]);
+    const [loading, setLoading] = React.useState(false);
+    const [restoring, setRestoring] = React.useState(false);
+    const [error, setError] = React.useState<string | null>(null);
+
+    React.useEffect(() => {
+        if (!open) return;
+        const token = localStorage.getItem("auth_token");
+        if (!token) return;
+        setLoading(true);
+        setError(null);
+        tauriInvoke<string>("get_resource_revisions", { token, resource_id: resourceId })
+            .then((json) => {
+                const response = JSON.parse(json);
+                setRevisions(response.revisions || []);
+                setSelected(response.revisions?.[0]?.id ?? null);
+            })
+            .catch((err) => setError(errorMessage(err, "Failed to load the history")))
+            .finally(() => setLoading(false));
+    }, [open, resourceId]);
+
+    // Compare the selected revision with what is saved now
+    React.useEffect(() => {
+        if (selected === null) {
+            setChanges([]);
+            return;
+        }
+        const token = localStorage.getItem("auth_token");
+        if (!token) return;
+        tauriInvoke<string>("diff_resource_revisions", {
+            token,
+            resource_id: resourceId,
+            from: selected,
+            to: null,
+        })
+            .then((json) => setChanges(JSON.parse(json).changes || []))
+            .catch((err) => setError(errorMessage(err, "Failed to compare")));
+    }, [selected, resourceId]);
+
+    const restore = async () => {
+        const token = localStorage.getItem("auth_token");
+        if (!token || selected === null) return;
+        setRestoring(true);
+        setError(null);
+        try {
+            const response = JSON.parse(
+                await tauriInvoke<string>("restore_resource_revision", {
+                    token,
+                    resource_id: resourceId,
+                    id: selected,
+                })
+            );
+            onRestore(response.resource?.content ?? null);
+            setOpen(false);
+        } catch (err) {
+            setError(errorMessage(err, "Failed to restore"));
+        } finally {
+            setRestoring(false);
+        }
+    };
+
+    return (
+        <Dialog open={open} onOpenChange={setOpen}>
+            <DialogTrigger asChild>
+                <Button variant="outline">
+                    <History className="mr-2 h-4 w-4" />
+                    History
+                </Button>
+            </DialogTrigger>
+            <DialogContent className="max-w-4xl max-h-[90vh]">
+                <DialogHeader>
+                    <DialogTitle>History</DialogTitle>
+                    <DialogDescription>
+                        Earlier versions of this document, compared with the saved one.
+                    </DialogDescription>
+                </DialogHeader>
+
+                {error && (
+                    <div className="text-sm text-destructive bg-destructive/10 p-2 rounded">{error}</div>
+                )}
+
+                {loading ? (
+                    <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+                ) : revisions.length === 0 ? (
+                    <p className="text-sm text-muted-foreground">No earlier versions yet.</p>
+                ) : (
+                    <div className="grid grid-cols-[14rem_1fr] gap-4 min-h-0">
+                        <div className="flex flex-col gap-1 overflow-y-auto max-h-[60vh]">
+                            {revisions.map((revision) => (
+                                <button
+                                    key={revision.id}
+                                    onClick={() => setSelected(revision.id)}
+                                    className={`rounded-md px-3 py-2 text-left text-sm hover:bg-muted ${
+                                        revision.id === selected ? "bg-muted font-medium" : ""
+                                    }`}
+                                >
+                                    <div>{format(new Date(revision.saved_at * 1000), "d MMM yyyy, HH:mm")}</div>
+                                    <div className="truncate text-xs text-muted-foreground">
+                                        {revision.snippet || "Empty"}
+                                    </div>
+                                </button>
+                            ))}
+                        </div>
+                        <div className="flex flex-col gap-3 min-w-0">
+                            <div className="overflow-y-auto max-h-[55vh] rounded-md border p-3 font-mono text-sm">
+                                {changes.map((line, i) => (
+                                    <div
+                                        key={i}
+                                        className={
+                                            line.change === "insert"
+                                                ? "bg-green-500/10 text-green-700 dark:text-green-400"
+                                                : line.change === "delete"
+                                                  ? "bg-red-500/10 text-red-700 line-through dark:text-red-400"
+                                                  : ""
+                                        }
+                                    >
+                                        {line.change === "insert" ? "+ " : line.change === "delete" ? "- " : "  "}
+                                        {line.text}
+                                    </div>
+                                ))}
+                            </div>
+                            <Button className="self-end" onClick={restore} disabled={restoring || selected === null}>
+                                {restoring ? (
+                                    <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+                                ) : (
+                                    <RotateCcw className="mr-2 h-4 w-4" />
+                                )}
+                                Restore this version
+                            </Button>
+                        </div>
+                    </div>
+                )}
+            </DialogContent>
+        </Dialog>
+    );
+}
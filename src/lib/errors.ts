@@ -0,0 +1,37 @@
+// Error codes returned by every Tauri command (see src-tauri/src/error.rs)
+export type ErrorCode =
+  | "NOT_FOUND"
+  | "FORBIDDEN"
+  | "VALIDATION"
+  | "CONFLICT"
+  | "UNAUTHENTICATED"
+  | "STORAGE";
+
+// Shape a rejected command resolves to: { success: false, code, message }
+export type CommandError = {
+  success: false;
+  code: ErrorCode;
+  message: string;
+};
+
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    "code" in err &&
+    "message" in err &&
+    (err as { success?: unknown }).success === false
+  );
+}
+
+export function hasErrorCode(err: unknown, code: ErrorCode): boolean {
+  return isCommandError(err) && err.code === code;
+}
+
+// Human-readable message for anything a command (or fetch) can throw
+export function errorMessage(err: unknown, fallback: string): string {
+  if (isCommandError(err)) return err.message || fallback;
+  if (err instanceof Error) return err.message || fallback;
+  if (typeof err === "string" && err.length > 0) return err;
+  return fallback;
+}